@@ -2,10 +2,10 @@
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use flowcore::{ExecutionEvent, Value, Workflow};
-use flowruntime::FlowRuntime;
+use flowcore::{Environment, ExecutionEvent, Value, Workflow, WorkflowFormat};
+use flowruntime::{Diagnostic, FlowRuntime, Severity};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing_subscriber;
 
 #[derive(Parser)]
@@ -20,7 +20,7 @@ struct Cli {
 enum Commands {
     /// Execute a workflow file
     Run {
-        /// Path to workflow JSON file
+        /// Path to workflow JSON, YAML or TOML file
         #[arg(short, long)]
         file: PathBuf,
         
@@ -28,15 +28,46 @@ enum Commands {
         #[arg(short, long)]
         input: Option<String>,
         
+        /// Environment profile: a file, or a name looked up as
+        /// environments/<name>.{yaml,yml,toml,json} next to the workflow
+        /// or in the current directory
+        #[arg(short, long)]
+        env: Option<String>,
+        
+        /// Override a variable, as NAME=VALUE (VALUE is read as JSON if it
+        /// parses, as text otherwise); may be repeated
+        #[arg(long = "var", value_name = "NAME=VALUE")]
+        vars: Vec<String>,
+        
         /// Show verbose output
         #[arg(short, long)]
         verbose: bool,
     },
     
-    /// Validate a workflow file
+    /// Lint workflow files: unknown node types, dangling connections,
+    /// undefined ports, missing or unused config and more
     Validate {
-        /// Path to workflow JSON file
+        /// Paths to workflow JSON, YAML or TOML files
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        
+        /// Output: text, or json for editors and CI
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    
+    /// Convert a workflow file between the named, plain and tagged formats
+    Convert {
+        /// Path to workflow JSON, YAML or TOML file
         file: PathBuf,
+        
+        /// Form to write: named (nodes keyed by name), plain or tagged
+        #[arg(long, default_value = "plain")]
+        to: String,
+        
+        /// Output file (stdout if omitted); YAML for .yaml/.yml, TOML for .toml
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
     
     /// List available node types
@@ -50,38 +81,12 @@ enum Commands {
     },
 }
 
-/// Convert a serde_json::Value to flowcore::Value
-fn json_to_value(json: serde_json::Value) -> Value {
-    match json {
-        serde_json::Value::Null => Value::Null,
-        serde_json::Value::Bool(b) => Value::Bool(b),
-        serde_json::Value::Number(n) => {
-            if let Some(f) = n.as_f64() {
-                Value::Number(f)
-            } else {
-                Value::Number(n.as_i64().unwrap_or(0) as f64)
-            }
-        }
-        serde_json::Value::String(s) => Value::String(s),
-        serde_json::Value::Array(arr) => {
-            Value::Array(arr.into_iter().map(json_to_value).collect())
-        }
-        serde_json::Value::Object(obj) => {
-            let map: HashMap<String, Value> = obj
-                .into_iter()
-                .map(|(k, v)| (k, json_to_value(v)))
-                .collect();
-            Value::Object(map)
-        }
-    }
-}
-
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
     
     match cli.command {
-        Commands::Run { file, input, verbose } => {
+        Commands::Run { file, input, env, vars, verbose } => {
             // Initialize logging
             if verbose {
                 tracing_subscriber::fmt()
@@ -93,11 +98,17 @@ async fn main() -> Result<()> {
                     .init();
             }
             
-            run_workflow(file, input).await?;
+            run_workflow(file, input, env, vars).await?;
+        }
+        
+        Commands::Validate { files, format } => {
+            if !validate_workflows(files, &format)? {
+                std::process::exit(1);
+            }
         }
         
-        Commands::Validate { file } => {
-            validate_workflow(file)?;
+        Commands::Convert { file, to, output } => {
+            convert_workflow(file, &to, output)?;
         }
         
         Commands::Nodes => {
@@ -112,16 +123,38 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-async fn run_workflow(file: PathBuf, input: Option<String>) -> Result<()> {
+async fn run_workflow(
+    file: PathBuf,
+    input: Option<String>,
+    env: Option<String>,
+    vars: Vec<String>,
+) -> Result<()> {
     println!("🚀 Loading workflow from: {}", file.display());
     
     // Load workflow
-    let workflow_json = std::fs::read_to_string(&file)?;
-    let workflow: Workflow = serde_json::from_str(&workflow_json)?;
+    let workflow = load_workflow(&file)?;
     
     println!("📋 Workflow: {}", workflow.name);
     println!("   Nodes: {}", workflow.nodes.len());
     println!("   Connections: {}", workflow.connections.len());
+    
+    // Environment variables, then --var overrides on top
+    let mut variables = HashMap::new();
+    if let Some(env) = env {
+        let environment = load_environment(&env, &file)?;
+        println!("   Environment: {}", environment.name);
+        variables.extend(environment.variables);
+    }
+    for var in vars {
+        let (name, value) = var
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid --var '{}': expected NAME=VALUE", var))?;
+        let value = serde_json::from_str(value)
+            .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+        variables.insert(name.to_string(), Value::from_json(value));
+    }
+    // Fail on undefined variables before anything runs
+    workflow.with_variables(&variables)?;
     println!();
     
     // Parse input data - convert plain JSON to Value types
@@ -132,7 +165,7 @@ async fn run_workflow(file: PathBuf, input: Option<String>) -> Result<()> {
         // Convert to HashMap<String, Value>
         if let serde_json::Value::Object(obj) = json {
             obj.into_iter()
-                .map(|(k, v)| (k, json_to_value(v)))
+                .map(|(k, v)| (k, Value::from_json(v)))
                 .collect()
         } else {
             return Err(anyhow::anyhow!("Input must be a JSON object"));
@@ -190,9 +223,27 @@ async fn run_workflow(file: PathBuf, input: Option<String>) -> Result<()> {
                         flowcore::NodeEvent::StderrLine { line } => {
                             eprintln!("     📤 [{}] {}", node_id, line);
                         }
+                        flowcore::NodeEvent::Retrying { attempt, max_attempts, error, .. } => {
+                            println!("     🔁 [{}] retrying ({}/{}): {}", node_id, attempt, max_attempts, error);
+                        }
+                        flowcore::NodeEvent::Custom { name, .. } => {
+                            println!("     📣 [{}] event {}", node_id, name);
+                        }
+                        flowcore::NodeEvent::WaitingForSignal { name, .. } => {
+                            println!("     ⏸️  [{}] waiting for signal '{}'", node_id, name);
+                        }
+                        flowcore::NodeEvent::SignalReceived { name } => {
+                            println!("     ▶️  [{}] received signal '{}'", node_id, name);
+                        }
                         _ => {}
                     }
                 }
+                ExecutionEvent::WorkflowPaused { .. } => {
+                    println!("⏸️  Workflow paused");
+                }
+                ExecutionEvent::WorkflowResumed { .. } => {
+                    println!("▶️  Workflow resumed");
+                }
                 ExecutionEvent::WorkflowCompleted { success, duration_ms, .. } => {
                     if success {
                         println!("✨ Workflow completed successfully in {}ms", duration_ms);
@@ -205,7 +256,9 @@ async fn run_workflow(file: PathBuf, input: Option<String>) -> Result<()> {
     });
     
     // Execute workflow
-    let result = runtime.execute(&workflow, inputs).await?;
+    let result = runtime
+        .execute_with_variables(&workflow, inputs, variables)
+        .await?;
     
     // Wait for events to finish printing
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
@@ -232,22 +285,127 @@ async fn run_workflow(file: PathBuf, input: Option<String>) -> Result<()> {
     Ok(())
 }
 
-fn validate_workflow(file: PathBuf) -> Result<()> {
-    println!("🔍 Validating workflow: {}", file.display());
+/// Read a workflow file, JSON, YAML or TOML by extension, in any of the
+/// named, plain or tagged forms
+fn load_workflow(file: &Path) -> Result<Workflow> {
+    let text = std::fs::read_to_string(file)?;
+    Ok(Workflow::parse(&text, WorkflowFormat::from_path(file))?)
+}
+
+/// An environment profile: `env` itself if it is a file, otherwise
+/// `environments/<env>.{yaml,yml,toml,json}` next to the workflow or in the
+/// current directory
+fn load_environment(env: &str, workflow_file: &Path) -> Result<Environment> {
+    let path = Path::new(env);
+    if path.is_file() {
+        return Ok(Environment::load(path)?);
+    }
     
-    let workflow_json = std::fs::read_to_string(&file)?;
-    let workflow: Workflow = serde_json::from_str(&workflow_json)?;
+    let dirs = [
+        workflow_file.parent().unwrap_or(Path::new(".")),
+        Path::new("."),
+    ];
+    for dir in dirs {
+        for extension in ["yaml", "yml", "toml", "json"] {
+            let candidate = dir
+                .join("environments")
+                .join(format!("{}.{}", env, extension));
+            if candidate.is_file() {
+                return Ok(Environment::load(candidate)?);
+            }
+        }
+    }
+    anyhow::bail!(
+        "Environment '{}' not found: not a file, and no environments/{}.yaml (or .yml, .toml, .json) next to the workflow or in the current directory",
+        env,
+        env
+    )
+}
+
+fn convert_workflow(file: PathBuf, to: &str, output: Option<PathBuf>) -> Result<()> {
+    let workflow = load_workflow(&file)?;
+    let document = match to {
+        "named" => workflow.to_named_document(),
+        "plain" => workflow.to_document(true),
+        "tagged" => workflow.to_document(false),
+        other => anyhow::bail!("Unknown format '{}': expected named, plain or tagged", other),
+    };
     
-    println!("✅ Workflow is valid:");
-    println!("   Name: {}", workflow.name);
-    println!("   Nodes: {}", workflow.nodes.len());
-    println!("   Connections: {}", workflow.connections.len());
+    let format = WorkflowFormat::from_path(output.as_deref().unwrap_or(&file));
+    let text = format.render(&document)?;
     
-    // TODO: Add more validation (check for cycles, unknown node types, etc.)
+    match output {
+        Some(path) => {
+            std::fs::write(&path, text)?;
+            eprintln!("✨ Wrote {}", path.display());
+        }
+        None => println!("{}", text.trim_end()),
+    }
     
     Ok(())
 }
 
+/// Lint `files` against the standard nodes and report in `format`.
+/// Returns whether all of them are valid.
+fn validate_workflows(files: Vec<PathBuf>, format: &str) -> Result<bool> {
+    if !matches!(format, "text" | "json") {
+        anyhow::bail!("Unknown format '{}': expected text or json", format);
+    }
+    
+    let mut registry = flowruntime::NodeRegistry::new();
+    flownodes::register_all(&mut registry);
+    
+    let mut all_valid = true;
+    let mut reports = Vec::new();
+    for file in files {
+        let (workflow, diagnostics) = match load_workflow(&file) {
+            Ok(workflow) => {
+                let diagnostics = registry.lint_workflow(&workflow);
+                (Some(workflow), diagnostics)
+            }
+            Err(e) => (None, vec![Diagnostic::error("parse", e.to_string())]),
+        };
+        let valid = !diagnostics.iter().any(Diagnostic::is_error);
+        
+        if format == "text" {
+            println!("🔍 Validating workflow: {}", file.display());
+            for diagnostic in &diagnostics {
+                let (icon, severity) = match diagnostic.severity {
+                    Severity::Error => ("❌", "error"),
+                    Severity::Warning => ("⚠️ ", "warning"),
+                };
+                println!("   {} {}[{}]: {}", icon, severity, diagnostic.code, diagnostic.message);
+            }
+            match workflow.filter(|_| valid) {
+                Some(workflow) => {
+                    println!("✅ Workflow is valid:");
+                    println!("   Name: {}", workflow.name);
+                    println!("   Nodes: {}", workflow.nodes.len());
+                    println!("   Connections: {}", workflow.connections.len());
+                    if !diagnostics.is_empty() {
+                        println!("   Warnings: {}", diagnostics.len());
+                    }
+                }
+                None => {
+                    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+                    println!("💥 Workflow is invalid: {} error(s)", errors);
+                }
+            }
+        }
+        all_valid &= valid;
+        reports.push(serde_json::json!({
+            "file": file,
+            "valid": valid,
+            "diagnostics": diagnostics,
+        }));
+    }
+    
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    }
+    Ok(all_valid)
+}
+
 fn list_nodes() {
     println!("📦 Available Node Types:");
     println!();
@@ -288,8 +446,8 @@ fn create_example_workflow(output: PathBuf) -> Result<()> {
     workflow.connect(http_id, "body", debug_id, "message");
     
     // Save to file
-    let json = serde_json::to_string_pretty(&workflow)?;
-    std::fs::write(&output, json)?;
+    let format = WorkflowFormat::from_path(&output);
+    std::fs::write(&output, format.render(&workflow.to_document(true))?)?;
     
     println!("✨ Created example workflow: {}", output.display());
     println!();
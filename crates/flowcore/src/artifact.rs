@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncWriteExt;
+
+/// Reference to file data kept in an [`ArtifactStore`] instead of inline
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BlobRef {
+    /// Lowercase hex SHA-256 of the contents
+    pub hash: String,
+    pub size: u64,
+    pub media_type: String,
+}
+
+/// What a garbage collection pass did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GcReport {
+    pub removed: usize,
+    pub freed_bytes: u64,
+    pub kept: usize,
+}
+
+/// Local content-addressed store for blob data
+///
+/// Blobs live at `<root>/sha256/<first two hex digits>/<hash>`, with their
+/// media type next to them in `<hash>.type`. Identical contents are stored
+/// once.
+#[derive(Debug, Clone)]
+pub struct ArtifactStore {
+    root: Arc<PathBuf>,
+}
+
+impl ArtifactStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: Arc::new(root.into()),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Where the blob with `hash` is stored, whether or not it exists.
+    /// `None` if `hash` is not a SHA-256 hex digest.
+    pub fn path(&self, hash: &str) -> Option<PathBuf> {
+        let valid = hash.len() == 64
+            && hash.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+        valid.then(|| self.root.join("sha256").join(&hash[..2]).join(hash))
+    }
+
+    /// Store `data` and return a reference to it
+    pub async fn put(&self, data: &[u8], media_type: impl Into<String>) -> io::Result<BlobRef> {
+        let blob = BlobRef {
+            hash: hex::encode(Sha256::digest(data)),
+            size: data.len() as u64,
+            media_type: media_type.into(),
+        };
+        let path = self.path(&blob.hash).expect("SHA-256 digests are valid hashes");
+
+        if tokio::fs::try_exists(&path).await? {
+            // Stored before: only mark it as recently used for gc
+            let touched = path.clone();
+            tokio::task::spawn_blocking(move || {
+                let file = std::fs::File::options().write(true).open(&touched)?;
+                file.set_modified(SystemTime::now())
+            })
+            .await
+            .map_err(io::Error::other)??;
+        } else {
+            let tmp_dir = self.root.join("tmp");
+            tokio::fs::create_dir_all(&tmp_dir).await?;
+            tokio::fs::create_dir_all(path.parent().unwrap_or(&self.root)).await?;
+
+            // Written aside and renamed so readers never see partial blobs
+            let tmp = tmp_dir.join(uuid::Uuid::new_v4().to_string());
+            let mut file = tokio::fs::File::create(&tmp).await?;
+            file.write_all(data).await?;
+            file.sync_all().await?;
+            drop(file);
+            tokio::fs::rename(&tmp, &path).await?;
+        }
+
+        let type_path = path.with_extension("type");
+        if !tokio::fs::try_exists(&type_path).await? {
+            tokio::fs::write(&type_path, &blob.media_type).await?;
+        }
+        Ok(blob)
+    }
+
+    /// Read a blob's contents
+    pub async fn load(&self, blob: &BlobRef) -> io::Result<Vec<u8>> {
+        let path = self.path(&blob.hash).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("invalid blob hash '{}'", blob.hash))
+        })?;
+        tokio::fs::read(path).await
+    }
+
+    /// Media type the blob with `hash` was first stored with
+    pub async fn media_type(&self, hash: &str) -> Option<String> {
+        let path = self.path(hash)?.with_extension("type");
+        tokio::fs::read_to_string(path).await.ok()
+    }
+
+    /// Delete blobs not in `referenced`, except those stored or re-stored
+    /// within `grace` (a running node may not have reported them yet)
+    pub async fn gc(&self, referenced: &HashSet<String>, grace: Duration) -> io::Result<GcReport> {
+        let mut report = GcReport::default();
+        let cutoff = SystemTime::now() - grace;
+        let is_stale = |metadata: &std::fs::Metadata| {
+            metadata.modified().is_ok_and(|modified| modified < cutoff)
+        };
+
+        let mut shards = match tokio::fs::read_dir(self.root.join("sha256")).await {
+            Ok(shards) => shards,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(report),
+            Err(e) => return Err(e),
+        };
+        while let Some(shard) = shards.next_entry().await? {
+            if !shard.file_type().await?.is_dir() {
+                continue;
+            }
+            let mut entries = tokio::fs::read_dir(shard.path()).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                if path.extension().is_some() {
+                    continue;
+                }
+                let hash = entry.file_name().to_string_lossy().into_owned();
+                let metadata = entry.metadata().await?;
+                if referenced.contains(&hash) || !is_stale(&metadata) {
+                    report.kept += 1;
+                    continue;
+                }
+
+                tokio::fs::remove_file(&path).await?;
+                let _ = tokio::fs::remove_file(path.with_extension("type")).await;
+                report.removed += 1;
+                report.freed_bytes += metadata.len();
+            }
+        }
+
+        // Leftovers of writes that never finished
+        if let Ok(mut tmp) = tokio::fs::read_dir(self.root.join("tmp")).await {
+            while let Some(entry) = tmp.next_entry().await? {
+                if entry.metadata().await.is_ok_and(|m| is_stale(&m)) {
+                    let _ = tokio::fs::remove_file(entry.path()).await;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+impl Default for ArtifactStore {
+    /// A store in the system's temporary directory
+    fn default() -> Self {
+        Self::new(std::env::temp_dir().join("flowengine-artifacts"))
+    }
+}
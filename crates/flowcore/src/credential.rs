@@ -0,0 +1,130 @@
+//! References to stored credentials
+//!
+//! Node config names a credential instead of embedding it:
+//!
+//! ```yaml
+//! env:
+//!   GITHUB_TOKEN: { $credential: github }
+//!   JIRA_USER: { $credential: jira, field: username }
+//! ```
+//!
+//! The executor swaps each reference for the credential just before the
+//! node runs, using the runtime's [`CredentialResolver`]. Secret parts
+//! arrive as [`Value::Secret`], so they are masked like any other secret.
+
+use crate::{NodeError, Value};
+use async_trait::async_trait;
+use std::collections::hash_map::{Entry, HashMap};
+
+/// Key of a credential reference object
+pub const CREDENTIAL_KEY: &str = "$credential";
+
+/// A `{"$credential": name}` reference, optionally to one `field` of it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CredentialRef {
+    pub name: String,
+    pub field: Option<String>,
+}
+
+impl CredentialRef {
+    /// The reference `value` is, if it is an object (native or JSON) of
+    /// `$credential` and optionally `field`, both strings
+    pub fn from_value(value: &Value) -> Option<CredentialRef> {
+        let map = match value {
+            Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| Some((k.as_str(), v.as_str()?)))
+                .collect::<Option<HashMap<_, _>>>()?,
+            Value::Json(serde_json::Value::Object(map)) => map
+                .iter()
+                .map(|(k, v)| Some((k.as_str(), v.as_str()?)))
+                .collect::<Option<HashMap<_, _>>>()?,
+            _ => return None,
+        };
+        let name = map.get(CREDENTIAL_KEY)?;
+        if map.keys().any(|k| *k != CREDENTIAL_KEY && *k != "field") {
+            return None;
+        }
+        Some(CredentialRef {
+            name: name.to_string(),
+            field: map.get("field").map(|f| f.to_string()),
+        })
+    }
+}
+
+/// Looks up referenced credentials
+#[async_trait]
+pub trait CredentialResolver: Send + Sync {
+    /// The credential's value, or the value of its `field`, with secret
+    /// parts as [`Value::Secret`]
+    async fn resolve(&self, reference: &CredentialRef) -> Result<Value, NodeError>;
+}
+
+impl Value {
+    /// Every credential reference in this value, including nested ones
+    pub fn credential_refs(&self) -> Vec<CredentialRef> {
+        if let Some(reference) = CredentialRef::from_value(self) {
+            return vec![reference];
+        }
+        match self {
+            Value::Array(items) => items.iter().flat_map(Value::credential_refs).collect(),
+            Value::Object(map) => map.values().flat_map(Value::credential_refs).collect(),
+            Value::Json(json @ (serde_json::Value::Array(_) | serde_json::Value::Object(_))) => {
+                Value::from_json(json.clone()).credential_refs()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// This value with every reference replaced by its entry in `resolved`.
+    /// JSON holding references becomes native values, as secrets can't be
+    /// JSON.
+    fn with_credentials(&self, resolved: &HashMap<CredentialRef, Value>) -> Value {
+        if let Some(value) = CredentialRef::from_value(self).and_then(|r| resolved.get(&r)) {
+            return value.clone();
+        }
+        match self {
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|v| v.with_credentials(resolved)).collect())
+            }
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), v.with_credentials(resolved)))
+                    .collect(),
+            ),
+            Value::Json(json) if !self.credential_refs().is_empty() => {
+                Value::from_json(json.clone()).with_credentials(resolved)
+            }
+            other => other.clone(),
+        }
+    }
+}
+
+/// `config` with every credential reference replaced by the credential.
+/// Without a resolver any reference is a configuration error.
+pub async fn resolve_credentials(
+    config: &HashMap<String, Value>,
+    resolver: Option<&dyn CredentialResolver>,
+) -> Result<HashMap<String, Value>, NodeError> {
+    let refs: Vec<CredentialRef> = config.values().flat_map(Value::credential_refs).collect();
+    if refs.is_empty() {
+        return Ok(config.clone());
+    }
+    let Some(resolver) = resolver else {
+        return Err(NodeError::Configuration(format!(
+            "credential '{}' is referenced, but no credential store is configured",
+            refs[0].name
+        )));
+    };
+    let mut resolved = HashMap::new();
+    for reference in refs {
+        if let Entry::Vacant(entry) = resolved.entry(reference) {
+            let value = resolver.resolve(entry.key()).await?;
+            entry.insert(value);
+        }
+    }
+    Ok(config
+        .iter()
+        .map(|(k, v)| (k.clone(), v.with_credentials(&resolved)))
+        .collect())
+}
@@ -0,0 +1,123 @@
+//! Structural diff between two workflow definitions
+//!
+//! Nodes are matched by id and connections by value, so the diff describes
+//! what an editor actually changed rather than a textual JSON delta.
+
+use crate::{Connection, NodeId, NodeSpec, Workflow};
+use serde::{Deserialize, Serialize};
+
+/// Changes needed to turn one workflow definition into another
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkflowDiff {
+    pub from_revision: u32,
+    pub to_revision: u32,
+    /// Top-level fields that differ ("name", "description", "triggers", "settings")
+    pub changed_fields: Vec<String>,
+    pub nodes_added: Vec<NodeSpec>,
+    pub nodes_removed: Vec<NodeId>,
+    pub nodes_changed: Vec<NodeChange>,
+    pub connections_added: Vec<Connection>,
+    pub connections_removed: Vec<Connection>,
+}
+
+/// A node present in both definitions whose spec differs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeChange {
+    pub node_id: NodeId,
+    /// Spec fields that differ ("node_type", "name", "config", "position", "retry_policy")
+    pub fields: Vec<String>,
+}
+
+impl WorkflowDiff {
+    pub fn between(from: &Workflow, to: &Workflow) -> Self {
+        let mut diff = WorkflowDiff {
+            from_revision: from.revision,
+            to_revision: to.revision,
+            ..Default::default()
+        };
+
+        if from.name != to.name {
+            diff.changed_fields.push("name".to_string());
+        }
+        if from.description != to.description {
+            diff.changed_fields.push("description".to_string());
+        }
+        if !same_json(&from.triggers, &to.triggers) {
+            diff.changed_fields.push("triggers".to_string());
+        }
+        if !same_json(&from.settings, &to.settings) {
+            diff.changed_fields.push("settings".to_string());
+        }
+
+        for node in &to.nodes {
+            match from.find_node(node.id) {
+                None => diff.nodes_added.push(node.clone()),
+                Some(old) => {
+                    let fields = changed_node_fields(old, node);
+                    if !fields.is_empty() {
+                        diff.nodes_changed.push(NodeChange {
+                            node_id: node.id,
+                            fields,
+                        });
+                    }
+                }
+            }
+        }
+        diff.nodes_removed = from
+            .nodes
+            .iter()
+            .filter(|n| to.find_node(n.id).is_none())
+            .map(|n| n.id)
+            .collect();
+
+        diff.connections_added = to
+            .connections
+            .iter()
+            .filter(|c| !from.connections.contains(c))
+            .cloned()
+            .collect();
+        diff.connections_removed = from
+            .connections
+            .iter()
+            .filter(|c| !to.connections.contains(c))
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    /// True when the two definitions are structurally identical
+    pub fn is_empty(&self) -> bool {
+        self.changed_fields.is_empty()
+            && self.nodes_added.is_empty()
+            && self.nodes_removed.is_empty()
+            && self.nodes_changed.is_empty()
+            && self.connections_added.is_empty()
+            && self.connections_removed.is_empty()
+    }
+}
+
+fn changed_node_fields(old: &NodeSpec, new: &NodeSpec) -> Vec<String> {
+    let mut fields = Vec::new();
+    if old.node_type != new.node_type {
+        fields.push("node_type".to_string());
+    }
+    if old.name != new.name {
+        fields.push("name".to_string());
+    }
+    if old.config != new.config {
+        fields.push("config".to_string());
+    }
+    if !same_json(&old.position, &new.position) {
+        fields.push("position".to_string());
+    }
+    if !same_json(&old.retry_policy, &new.retry_policy) {
+        fields.push("retry_policy".to_string());
+    }
+    fields
+}
+
+/// Compare types that don't implement `PartialEq` via their serialized form
+fn same_json<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
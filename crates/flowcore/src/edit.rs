@@ -0,0 +1,343 @@
+//! Partial workflow edits
+//!
+//! A JSON-Patch-style list of operations that an editor can send instead of
+//! the whole definition. Edits are applied in order and all-or-nothing.
+
+use crate::{Connection, NodeId, NodeSpec, Position, Value, Workflow, WorkflowError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One edit operation, tagged by `op`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum WorkflowEdit {
+    AddNode {
+        node: NodeSpec,
+    },
+    /// Also removes every connection to or from the node
+    RemoveNode {
+        node_id: NodeId,
+    },
+    MoveNode {
+        node_id: NodeId,
+        position: Position,
+    },
+    /// Merge `config` into the node's config (`null` removes a key) and
+    /// optionally rename it
+    UpdateNode {
+        node_id: NodeId,
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(default)]
+        config: HashMap<String, Value>,
+    },
+    Connect {
+        connection: Connection,
+    },
+    Disconnect {
+        connection: Connection,
+    },
+    /// Replace one connection with another
+    Rewire {
+        from: Connection,
+        to: Connection,
+    },
+}
+
+impl Workflow {
+    /// Apply edits in order. On error the workflow is left unchanged.
+    pub fn apply_edits(&mut self, edits: &[WorkflowEdit]) -> Result<(), WorkflowError> {
+        let mut edited = self.clone();
+        for edit in edits {
+            edited.apply_edit(edit)?;
+        }
+        *self = edited;
+        Ok(())
+    }
+
+    fn apply_edit(&mut self, edit: &WorkflowEdit) -> Result<(), WorkflowError> {
+        match edit {
+            WorkflowEdit::AddNode { node } => {
+                if self.find_node(node.id).is_some() {
+                    return Err(WorkflowError::Invalid(format!(
+                        "node {} already exists",
+                        node.id
+                    )));
+                }
+                self.nodes.push(node.clone());
+            }
+            WorkflowEdit::RemoveNode { node_id } => {
+                self.node_mut(*node_id)?;
+                self.nodes.retain(|n| n.id != *node_id);
+                self.connections
+                    .retain(|c| c.from_node != *node_id && c.to_node != *node_id);
+            }
+            WorkflowEdit::MoveNode { node_id, position } => {
+                self.node_mut(*node_id)?.position = Some(*position);
+            }
+            WorkflowEdit::UpdateNode {
+                node_id,
+                name,
+                config,
+            } => {
+                let node = self.node_mut(*node_id)?;
+                if let Some(name) = name {
+                    node.name = Some(name.clone());
+                }
+                for (key, value) in config {
+                    if value.is_null() {
+                        node.config.remove(key);
+                    } else {
+                        node.config.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            WorkflowEdit::Connect { connection } => self.add_connection(connection)?,
+            WorkflowEdit::Disconnect { connection } => self.remove_connection(connection)?,
+            WorkflowEdit::Rewire { from, to } => {
+                self.remove_connection(from)?;
+                self.add_connection(to)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Result<&mut NodeSpec, WorkflowError> {
+        self.nodes
+            .iter_mut()
+            .find(|n| n.id == id)
+            .ok_or_else(|| WorkflowError::NodeNotFound(id.to_string()))
+    }
+
+    fn add_connection(&mut self, connection: &Connection) -> Result<(), WorkflowError> {
+        for node_id in [connection.from_node, connection.to_node] {
+            if self.find_node(node_id).is_none() {
+                return Err(WorkflowError::NodeNotFound(node_id.to_string()));
+            }
+        }
+        if self.connections.contains(connection) {
+            return Err(WorkflowError::InvalidConnection(format!(
+                "{}.{} -> {}.{} already exists",
+                connection.from_node, connection.from_port, connection.to_node, connection.to_port
+            )));
+        }
+        self.connections.push(connection.clone());
+        Ok(())
+    }
+
+    fn remove_connection(&mut self, connection: &Connection) -> Result<(), WorkflowError> {
+        let before = self.connections.len();
+        self.connections.retain(|c| c != connection);
+        if self.connections.len() == before {
+            return Err(WorkflowError::InvalidConnection(format!(
+                "{}.{} -> {}.{} does not exist",
+                connection.from_node, connection.from_port, connection.to_node, connection.to_port
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection(from: NodeId, to: NodeId) -> Connection {
+        Connection {
+            from_node: from,
+            from_port: "out".to_string(),
+            to_node: to,
+            to_port: "in".to_string(),
+        }
+    }
+
+    /// `a -> b`
+    fn pipeline() -> (Workflow, NodeId, NodeId) {
+        let mut workflow = Workflow::new("edited");
+        let a = workflow.add_node(NodeSpec::new("debug.log"));
+        let b = workflow.add_node(NodeSpec::new("debug.log"));
+        workflow.connect(a, "out", b, "in");
+        (workflow, a, b)
+    }
+
+    #[test]
+    fn test_add_move_and_update_node() {
+        let (mut workflow, a, _) = pipeline();
+        let node = NodeSpec::new("shell.exec").with_config("command", "echo hi");
+        let c = node.id;
+
+        workflow
+            .apply_edits(&[
+                WorkflowEdit::AddNode { node },
+                WorkflowEdit::MoveNode {
+                    node_id: c,
+                    position: Position { x: 10.0, y: 20.0 },
+                },
+                WorkflowEdit::UpdateNode {
+                    node_id: a,
+                    name: Some("first".to_string()),
+                    config: HashMap::from([("level".to_string(), Value::from("warn"))]),
+                },
+            ])
+            .unwrap();
+
+        assert_eq!(workflow.nodes.len(), 3);
+        let added = workflow.find_node(c).unwrap();
+        assert_eq!(added.position.map(|p| (p.x, p.y)), Some((10.0, 20.0)));
+        let first = workflow.find_node(a).unwrap();
+        assert_eq!(first.name.as_deref(), Some("first"));
+        assert_eq!(first.config["level"], Value::from("warn"));
+
+        // Adding a node that already exists fails
+        let duplicate = workflow.find_node(c).unwrap().clone();
+        let err = workflow
+            .apply_edits(&[WorkflowEdit::AddNode { node: duplicate }])
+            .unwrap_err();
+        assert!(matches!(err, WorkflowError::Invalid(_)), "{}", err);
+    }
+
+    #[test]
+    fn test_update_node_merges_config() {
+        let mut workflow = Workflow::new("edited");
+        let a = workflow.add_node(
+            NodeSpec::new("debug.log")
+                .with_config("level", "info")
+                .with_config("prefix", ">"),
+        );
+
+        // `Null` removes a key, others are set, the rest are kept
+        let edit: WorkflowEdit = serde_json::from_value(serde_json::json!({
+            "op": "update_node",
+            "node_id": a,
+            "config": {
+                "prefix": {"type": "Null"},
+                "format": {"type": "String", "value": "json"},
+            },
+        }))
+        .unwrap();
+        workflow.apply_edits(&[edit]).unwrap();
+
+        let config = &workflow.find_node(a).unwrap().config;
+        assert_eq!(config.len(), 2);
+        assert_eq!(config["level"], Value::from("info"));
+        assert_eq!(config["format"], Value::from("json"));
+        assert_eq!(workflow.find_node(a).unwrap().name, None);
+    }
+
+    #[test]
+    fn test_remove_node_drops_its_connections() {
+        let (mut workflow, a, b) = pipeline();
+        let c = workflow.add_node(NodeSpec::new("debug.log"));
+        workflow.connect(a, "out", c, "in");
+
+        workflow
+            .apply_edits(&[WorkflowEdit::RemoveNode { node_id: b }])
+            .unwrap();
+
+        assert!(workflow.find_node(b).is_none());
+        assert_eq!(workflow.connections, vec![connection(a, c)]);
+    }
+
+    #[test]
+    fn test_connect_disconnect_and_rewire() {
+        let (mut workflow, a, b) = pipeline();
+        let c = workflow.add_node(NodeSpec::new("debug.log"));
+
+        workflow
+            .apply_edits(&[WorkflowEdit::Rewire {
+                from: connection(a, b),
+                to: connection(a, c),
+            }])
+            .unwrap();
+        assert_eq!(workflow.connections, vec![connection(a, c)]);
+
+        workflow
+            .apply_edits(&[
+                WorkflowEdit::Connect {
+                    connection: connection(c, b),
+                },
+                WorkflowEdit::Disconnect {
+                    connection: connection(a, c),
+                },
+            ])
+            .unwrap();
+        assert_eq!(workflow.connections, vec![connection(c, b)]);
+
+        // Connections are not duplicated, and only existing ones come off
+        let err = workflow
+            .apply_edits(&[WorkflowEdit::Connect {
+                connection: connection(c, b),
+            }])
+            .unwrap_err();
+        assert!(matches!(err, WorkflowError::InvalidConnection(_)), "{}", err);
+        let err = workflow
+            .apply_edits(&[WorkflowEdit::Rewire {
+                from: connection(a, b),
+                to: connection(b, c),
+            }])
+            .unwrap_err();
+        assert!(matches!(err, WorkflowError::InvalidConnection(_)), "{}", err);
+    }
+
+    #[test]
+    fn test_unknown_node_rejected() {
+        let (mut workflow, a, _) = pipeline();
+        let unknown = NodeId::new_v4();
+
+        for edit in [
+            WorkflowEdit::RemoveNode { node_id: unknown },
+            WorkflowEdit::MoveNode {
+                node_id: unknown,
+                position: Position { x: 0.0, y: 0.0 },
+            },
+            WorkflowEdit::UpdateNode {
+                node_id: unknown,
+                name: Some("ghost".to_string()),
+                config: HashMap::new(),
+            },
+            WorkflowEdit::Connect {
+                connection: connection(a, unknown),
+            },
+        ] {
+            let err = workflow.apply_edits(&[edit]).unwrap_err();
+            assert!(
+                matches!(&err, WorkflowError::NodeNotFound(id) if *id == unknown.to_string()),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_connection_to_removed_node_rejects_the_whole_batch() {
+        let (mut workflow, a, b) = pipeline();
+        let before = serde_json::to_value(&workflow).unwrap();
+
+        // `b` is gone by the time `a -> b` is connected again
+        let err = workflow
+            .apply_edits(&[
+                WorkflowEdit::MoveNode {
+                    node_id: a,
+                    position: Position { x: 1.0, y: 1.0 },
+                },
+                WorkflowEdit::RemoveNode { node_id: b },
+                WorkflowEdit::Connect {
+                    connection: connection(a, b),
+                },
+            ])
+            .unwrap_err();
+        assert!(matches!(err, WorkflowError::NodeNotFound(_)), "{}", err);
+        assert_eq!(serde_json::to_value(&workflow).unwrap(), before);
+
+        let err = workflow
+            .apply_edits(&[
+                WorkflowEdit::RemoveNode { node_id: b },
+                WorkflowEdit::Disconnect {
+                    connection: connection(a, b),
+                },
+            ])
+            .unwrap_err();
+        assert!(matches!(err, WorkflowError::InvalidConnection(_)), "{}", err);
+        assert_eq!(serde_json::to_value(&workflow).unwrap(), before);
+    }
+}
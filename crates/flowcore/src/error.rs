@@ -46,6 +46,21 @@ pub enum NodeError {
     Cancelled,
 }
 
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum PathError {
+    #[error("Invalid path '{path}': {reason}")]
+    Syntax { path: String, reason: String },
+    
+    #[error("Cannot set '{path}': {reason}")]
+    Set { path: String, reason: String },
+}
+
+impl From<PathError> for NodeError {
+    fn from(e: PathError) -> Self {
+        NodeError::Configuration(e.to_string())
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum WorkflowError {
     #[error("Workflow not found: {0}")]
@@ -1,7 +1,7 @@
-use crate::{NodeId, Value};
+use crate::{NodeId, SecretMask, Value};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 use uuid::Uuid;
 
 pub type ExecutionId = Uuid;
@@ -21,6 +21,15 @@ pub enum ExecutionEvent {
         duration_ms: u64,
         timestamp: DateTime<Utc>,
     },
+    /// No further nodes start until the execution is resumed
+    WorkflowPaused {
+        execution_id: ExecutionId,
+        timestamp: DateTime<Utc>,
+    },
+    WorkflowResumed {
+        execution_id: ExecutionId,
+        timestamp: DateTime<Utc>,
+    },
     NodeStarted {
         execution_id: ExecutionId,
         node_id: NodeId,
@@ -48,6 +57,48 @@ pub enum ExecutionEvent {
     },
 }
 
+impl ExecutionEvent {
+    /// Execution this event belongs to
+    pub fn execution_id(&self) -> ExecutionId {
+        match self {
+            ExecutionEvent::WorkflowStarted { execution_id, .. }
+            | ExecutionEvent::WorkflowCompleted { execution_id, .. }
+            | ExecutionEvent::WorkflowPaused { execution_id, .. }
+            | ExecutionEvent::WorkflowResumed { execution_id, .. }
+            | ExecutionEvent::NodeStarted { execution_id, .. }
+            | ExecutionEvent::NodeCompleted { execution_id, .. }
+            | ExecutionEvent::NodeFailed { execution_id, .. }
+            | ExecutionEvent::NodeEvent { execution_id, .. } => *execution_id,
+        }
+    }
+    
+    /// Serialized `type` tag, e.g. `"NodeCompleted"`
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ExecutionEvent::WorkflowStarted { .. } => "WorkflowStarted",
+            ExecutionEvent::WorkflowCompleted { .. } => "WorkflowCompleted",
+            ExecutionEvent::WorkflowPaused { .. } => "WorkflowPaused",
+            ExecutionEvent::WorkflowResumed { .. } => "WorkflowResumed",
+            ExecutionEvent::NodeStarted { .. } => "NodeStarted",
+            ExecutionEvent::NodeCompleted { .. } => "NodeCompleted",
+            ExecutionEvent::NodeFailed { .. } => "NodeFailed",
+            ExecutionEvent::NodeEvent { .. } => "NodeEvent",
+        }
+    }
+}
+
+/// An execution event as carried on a shared bus such as Iggy, where
+/// other engines read it without access to this engine's state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusEvent {
+    #[serde(flatten)]
+    pub event: ExecutionEvent,
+    /// Executions started by event triggers before this one, in a row.
+    /// Missing from events published without one.
+    #[serde(default)]
+    pub chain_depth: u32,
+}
+
 /// Events specific to node execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "event_type")]
@@ -58,6 +109,64 @@ pub enum NodeEvent {
     Data { port: String, value: Value },
     StdoutLine { line: String },
     StderrLine { line: String },
+    /// Emitted by the executor before re-running a failed node
+    Retrying { attempt: u32, max_attempts: u32, delay_ms: u64, error: String },
+    /// Named application event, e.g. for event triggers of other workflows
+    Custom { name: String, payload: Value },
+    /// The node is paused until a signal named `name` is sent to the execution
+    WaitingForSignal { name: String, timeout_ms: Option<u64> },
+    /// The signal the node waited for arrived
+    SignalReceived { name: String },
+}
+
+impl NodeEvent {
+    /// Serialized `event_type` tag, e.g. `"StdoutLine"`
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            NodeEvent::Info { .. } => "Info",
+            NodeEvent::Warning { .. } => "Warning",
+            NodeEvent::Progress { .. } => "Progress",
+            NodeEvent::Data { .. } => "Data",
+            NodeEvent::StdoutLine { .. } => "StdoutLine",
+            NodeEvent::StderrLine { .. } => "StderrLine",
+            NodeEvent::Retrying { .. } => "Retrying",
+            NodeEvent::Custom { .. } => "Custom",
+            NodeEvent::WaitingForSignal { .. } => "WaitingForSignal",
+            NodeEvent::SignalReceived { .. } => "SignalReceived",
+        }
+    }
+
+    /// This event with known secrets masked in its text and values
+    pub fn masked(self, mask: &SecretMask) -> NodeEvent {
+        if mask.is_empty() {
+            return self;
+        }
+        match self {
+            NodeEvent::Info { message } => NodeEvent::Info { message: mask.mask(&message) },
+            NodeEvent::Warning { message } => NodeEvent::Warning { message: mask.mask(&message) },
+            NodeEvent::Progress { percent, message } => NodeEvent::Progress {
+                percent,
+                message: message.map(|m| mask.mask(&m)),
+            },
+            NodeEvent::Data { port, value } => NodeEvent::Data {
+                port,
+                value: mask.mask_value(&value),
+            },
+            NodeEvent::StdoutLine { line } => NodeEvent::StdoutLine { line: mask.mask(&line) },
+            NodeEvent::StderrLine { line } => NodeEvent::StderrLine { line: mask.mask(&line) },
+            NodeEvent::Retrying { attempt, max_attempts, delay_ms, error } => NodeEvent::Retrying {
+                attempt,
+                max_attempts,
+                delay_ms,
+                error: mask.mask(&error),
+            },
+            NodeEvent::Custom { name, payload } => NodeEvent::Custom {
+                name,
+                payload: mask.mask_value(&payload),
+            },
+            other => other,
+        }
+    }
 }
 
 /// Event emitter for nodes to send real-time updates
@@ -66,6 +175,8 @@ pub struct EventEmitter {
     execution_id: ExecutionId,
     node_id: NodeId,
     sender: broadcast::Sender<ExecutionEvent>,
+    mask: SecretMask,
+    tap: Option<mpsc::UnboundedSender<ExecutionEvent>>,
 }
 
 impl EventEmitter {
@@ -78,17 +189,37 @@ impl EventEmitter {
             execution_id,
             node_id,
             sender,
+            mask: SecretMask::default(),
+            tap: None,
         }
     }
+
+    /// Mask these secrets out of everything the node emits, e.g. a
+    /// process printing its API key
+    pub fn with_mask(mut self, mask: SecretMask) -> Self {
+        self.mask = mask;
+        self
+    }
+    
+    /// Also send everything the node emits to `tap`, which unlike a bus
+    /// subscriber never falls behind and misses events
+    pub fn with_tap(mut self, tap: mpsc::UnboundedSender<ExecutionEvent>) -> Self {
+        self.tap = Some(tap);
+        self
+    }
     
     /// Emit a node-specific event
     pub fn emit(&self, event: NodeEvent) {
-        let _ = self.sender.send(ExecutionEvent::NodeEvent {
+        let event = ExecutionEvent::NodeEvent {
             execution_id: self.execution_id,
             node_id: self.node_id.clone(),
-            event,
+            event: event.masked(&self.mask),
             timestamp: Utc::now(),
-        });
+        };
+        if let Some(tap) = &self.tap {
+            let _ = tap.send(event.clone());
+        }
+        let _ = self.sender.send(event);
     }
     
     /// Emit info message
@@ -131,6 +262,14 @@ impl EventEmitter {
             line: line.into(),
         });
     }
+
+    /// Emit a named custom event
+    pub fn custom(&self, name: impl Into<String>, payload: Value) {
+        self.emit(NodeEvent::Custom {
+            name: name.into(),
+            payload,
+        });
+    }
 }
 
 /// Global event bus
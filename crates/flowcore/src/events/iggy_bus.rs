@@ -10,7 +10,7 @@ use std::fmt;
 use std::sync::Arc;
 use futures_util::StreamExt;
 
-use crate::{ExecutionEvent};
+use crate::{BusEvent, ExecutionEvent};
 
 /// Configuration for Iggy event bus
 #[derive(Debug, Clone)]
@@ -171,7 +171,16 @@ impl IggyEventBus {
     
     /// Publish an event to the bus using low-level client API
     pub async fn publish(&self, event: ExecutionEvent) -> Result<(), IggyEventBusError> {
-        let payload = serde_json::to_vec(&event)
+        self.publish_at_depth(event, 0).await
+    }
+
+    /// Publish an event of an execution `chain_depth` event triggers deep
+    pub async fn publish_at_depth(
+        &self,
+        event: ExecutionEvent,
+        chain_depth: u32,
+    ) -> Result<(), IggyEventBusError> {
+        let payload = serde_json::to_vec(&BusEvent { event, chain_depth })
             .map_err(|e| {
                 tracing::error!("Failed to serialize event: {:?}", e);
                 IggyEventBusError::SerializationFailed(e.to_string())
@@ -263,6 +272,12 @@ pub struct IggyEventSubscription {
 impl IggyEventSubscription {
     /// Poll for new events using high-level consumer
     pub async fn poll(&self) -> Result<Vec<ExecutionEvent>, IggyEventBusError> {
+        let events = self.poll_with_depth().await?;
+        Ok(events.into_iter().map(|e| e.event).collect())
+    }
+
+    /// Poll for new events along with the chain depth they were published at
+    pub async fn poll_with_depth(&self) -> Result<Vec<BusEvent>, IggyEventBusError> {
         tracing::debug!("Polling for events from consumer group: {}", self.consumer_id);
         
         // Create consumer using consumer_group
@@ -296,7 +311,7 @@ impl IggyEventSubscription {
             match result {
                 Ok(received_message) => {
                     tracing::debug!("Received message with {} bytes", received_message.message.payload.len());
-                    match serde_json::from_slice::<ExecutionEvent>(&received_message.message.payload) {
+                    match serde_json::from_slice::<BusEvent>(&received_message.message.payload) {
                         Ok(event) => {
                             tracing::debug!("Successfully deserialized event");
                             events.push(event);
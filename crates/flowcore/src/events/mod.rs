@@ -3,5 +3,5 @@
 mod base;
 mod iggy_bus;
 
-pub use base::{EventEmitter, EventBus, ExecutionEvent, NodeEvent, ExecutionId};
+pub use base::{BusEvent, EventEmitter, EventBus, ExecutionEvent, NodeEvent, ExecutionId};
 pub use iggy_bus::{IggyEventBus, IggyEventBusConfig, IggyEventBusError, IggyEventSubscription};
\ No newline at end of file
@@ -0,0 +1,297 @@
+//! Plain authoring format for workflow files
+//!
+//! Stored workflows use the tagged [`Value`] encoding
+//! (`{"type": "String", "value": "..."}`) for node config. Workflow files may
+//! instead write config values as natural JSON or YAML; the tagged form is
+//! still recognised wherever a value appears, so both styles can be mixed.
+
+use crate::{Value, Workflow, WorkflowError};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// `type` names of the tagged [`Value`] encoding
+const VALUE_TYPES: &[&str] = &[
+    "Null",
+    "Bool",
+    "Number",
+    "Integer",
+    "Timestamp",
+    "Decimal",
+    "String",
+    "Secret",
+    "Bytes",
+    "Blob",
+    "Json",
+    "Array",
+    "Object",
+];
+
+/// Serialization of a workflow file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkflowFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl WorkflowFormat {
+    /// YAML for `.yaml`/`.yml` files, TOML for `.toml`, JSON otherwise
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        let ext = path
+            .as_ref()
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        match ext.to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => WorkflowFormat::Yaml,
+            "toml" => WorkflowFormat::Toml,
+            _ => WorkflowFormat::Json,
+        }
+    }
+
+    /// Format named `json`, `yaml`/`yml` or `toml`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(WorkflowFormat::Json),
+            "yaml" | "yml" => Some(WorkflowFormat::Yaml),
+            "toml" => Some(WorkflowFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Media type of documents in this format
+    pub fn content_type(self) -> &'static str {
+        match self {
+            WorkflowFormat::Json => "application/json",
+            WorkflowFormat::Yaml => "application/yaml",
+            WorkflowFormat::Toml => "application/toml",
+        }
+    }
+
+    /// YAML for `application/yaml`, `application/x-yaml` and `text/yaml`,
+    /// TOML for `application/toml`, JSON otherwise
+    pub fn from_content_type(content_type: &str) -> Self {
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        match mime.to_ascii_lowercase().as_str() {
+            "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => {
+                WorkflowFormat::Yaml
+            }
+            "application/toml" | "text/toml" => WorkflowFormat::Toml,
+            _ => WorkflowFormat::Json,
+        }
+    }
+
+    /// Parse a document of this format into a JSON tree
+    pub fn parse(self, text: &str) -> Result<serde_json::Value, WorkflowError> {
+        match self {
+            WorkflowFormat::Json => {
+                serde_json::from_str(text).map_err(|e| WorkflowError::Invalid(e.to_string()))
+            }
+            WorkflowFormat::Yaml => {
+                serde_yaml::from_str(text).map_err(|e| WorkflowError::Invalid(e.to_string()))
+            }
+            WorkflowFormat::Toml => {
+                toml::from_str(text).map_err(|e| WorkflowError::Invalid(e.to_string()))
+            }
+        }
+    }
+
+    /// Render a JSON tree in this format
+    pub fn render(self, document: &serde_json::Value) -> Result<String, WorkflowError> {
+        match self {
+            WorkflowFormat::Json => serde_json::to_string_pretty(document)
+                .map_err(|e| WorkflowError::Invalid(e.to_string())),
+            WorkflowFormat::Yaml => {
+                serde_yaml::to_string(document).map_err(|e| WorkflowError::Invalid(e.to_string()))
+            }
+            // TOML has no null, so unset fields must already be left out
+            WorkflowFormat::Toml => {
+                toml::to_string(document).map_err(|e| WorkflowError::Invalid(e.to_string()))
+            }
+        }
+    }
+}
+
+/// Whether `map` has the shape of a tagged value rather than a plain object
+fn is_tagged(map: &serde_json::Map<String, serde_json::Value>) -> bool {
+    let known_type = map
+        .get("type")
+        .and_then(|t| t.as_str())
+        .is_some_and(|t| VALUE_TYPES.contains(&t));
+    known_type && map.keys().all(|k| k == "type" || k == "value")
+}
+
+impl Value {
+    /// Value from natural JSON as [`Value::from_json`] reads it, except that
+    /// objects shaped `{"type": <variant>, "value": ...}` are read as tagged
+    /// values.
+    pub fn from_plain(json: serde_json::Value) -> Result<Value, String> {
+        Ok(match json {
+            serde_json::Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .map(Value::from_plain)
+                    .collect::<Result<_, _>>()?,
+            ),
+            serde_json::Value::Object(mut map) if is_tagged(&map) => {
+                let kind = map["type"].as_str().unwrap_or_default().to_string();
+                // Containers may hold plain or tagged items
+                match (kind.as_str(), map.remove("value")) {
+                    ("Array", Some(serde_json::Value::Array(items))) => {
+                        Value::from_plain(items.into())?
+                    }
+                    ("Object", Some(serde_json::Value::Object(fields))) => {
+                        Value::Object(plain_fields(fields)?)
+                    }
+                    (_, value) => {
+                        if let Some(value) = value {
+                            map.insert("value".to_string(), value);
+                        }
+                        serde_json::from_value(map.into())
+                            .map_err(|e| format!("invalid {} value: {}", kind, e))?
+                    }
+                }
+            }
+            serde_json::Value::Object(map) => Value::Object(plain_fields(map)?),
+            scalar => Value::from_json(scalar),
+        })
+    }
+
+    /// Natural JSON for this value, the inverse of [`Value::from_plain`].
+    /// Variants JSON has no native form for (timestamps, decimals, bytes,
+    /// blobs, raw JSON) and objects that would read back as tagged values
+    /// keep the tagged encoding.
+    pub fn to_plain(&self) -> serde_json::Value {
+        match self {
+            Value::Null => serde_json::Value::Null,
+            Value::Bool(b) => (*b).into(),
+            Value::Number(n) if n.is_finite() => (*n).into(),
+            Value::Integer(i) => (*i).into(),
+            Value::String(s) => s.clone().into(),
+            Value::Array(items) => items.iter().map(Value::to_plain).collect(),
+            Value::Object(map) => {
+                let fields: serde_json::Map<_, _> =
+                    map.iter().map(|(k, v)| (k.clone(), v.to_plain())).collect();
+                if is_tagged(&fields) {
+                    serde_json::json!({ "type": "Object", "value": fields })
+                } else {
+                    fields.into()
+                }
+            }
+            _ => serde_json::to_value(self).unwrap_or_default(),
+        }
+    }
+}
+
+fn drop_nulls(object: &mut serde_json::Value) {
+    if let Some(map) = object.as_object_mut() {
+        map.retain(|_, v| !v.is_null());
+    }
+}
+
+fn plain_fields(
+    map: serde_json::Map<String, serde_json::Value>,
+) -> Result<HashMap<String, Value>, String> {
+    map.into_iter()
+        .map(|(k, v)| {
+            let value = Value::from_plain(v).map_err(|e| format!("{}: {}", k, e))?;
+            Ok((k, value))
+        })
+        .collect()
+}
+
+impl Workflow {
+    /// Read a workflow file whose node config may be plain or tagged
+    pub fn parse(text: &str, format: WorkflowFormat) -> Result<Workflow, WorkflowError> {
+        Workflow::from_document(format.parse(text)?)
+    }
+
+    /// Workflow from a JSON tree whose node config may be plain or tagged,
+    /// with nodes in a list or keyed by name
+    pub fn from_document(mut document: serde_json::Value) -> Result<Workflow, WorkflowError> {
+        if crate::named::is_named(&document) {
+            return Workflow::from_named_document(document);
+        }
+        // Config is parsed apart from the rest, since secrets would not
+        // survive a round trip through the tagged encoding
+        let mut configs = Vec::new();
+        let nodes = document
+            .get_mut("nodes")
+            .and_then(|n| n.as_array_mut())
+            .into_iter()
+            .flatten();
+        for (index, node) in nodes.enumerate() {
+            let Some(config) = node.get_mut("config").and_then(|c| c.as_object_mut()) else {
+                continue;
+            };
+            let parsed = std::mem::take(config)
+                .into_iter()
+                .map(|(key, value)| {
+                    let parsed = Value::from_plain(value).map_err(|e| {
+                        WorkflowError::Invalid(format!("nodes[{}].config.{}: {}", index, key, e))
+                    })?;
+                    Ok((key, parsed))
+                })
+                .collect::<Result<HashMap<_, _>, WorkflowError>>()?;
+            configs.push((index, parsed));
+        }
+        let variables = match document.as_object_mut().and_then(|d| d.remove("variables")) {
+            Some(variables) => plain_variables(variables)
+                .map_err(|e| WorkflowError::Invalid(format!("variables.{}", e)))?,
+            None => HashMap::new(),
+        };
+        let mut workflow: Workflow =
+            serde_json::from_value(document).map_err(|e| WorkflowError::Invalid(e.to_string()))?;
+        for (index, config) in configs {
+            workflow.nodes[index].config = config;
+        }
+        workflow.variables = variables;
+        Ok(workflow)
+    }
+
+    /// JSON tree of this workflow with node config in the plain form
+    /// (`plain`, which also leaves out unset optional fields) or the tagged
+    /// form the API stores
+    pub fn to_document(&self, plain: bool) -> serde_json::Value {
+        let mut document = serde_json::to_value(self).unwrap_or_default();
+        if !plain {
+            return document;
+        }
+        if let Some(nodes) = document.get_mut("nodes").and_then(|n| n.as_array_mut()) {
+            for (node, spec) in nodes.iter_mut().zip(&self.nodes) {
+                let config: serde_json::Map<_, _> = spec
+                    .config
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.to_plain()))
+                    .collect();
+                node["config"] = config.into();
+                drop_nulls(node);
+            }
+        }
+        if !self.variables.is_empty() {
+            document["variables"] = plain_document(&self.variables);
+        }
+        drop_nulls(&mut document);
+        document
+    }
+}
+
+/// Variables whose values may be plain or tagged
+pub(crate) fn plain_variables(
+    document: serde_json::Value,
+) -> Result<HashMap<String, Value>, String> {
+    match document {
+        serde_json::Value::Null => Ok(HashMap::new()),
+        serde_json::Value::Object(map) => plain_fields(map),
+        _ => Err("variables must map names to values".to_string()),
+    }
+}
+
+/// Plain JSON object of `values`
+pub(crate) fn plain_document(values: &HashMap<String, Value>) -> serde_json::Value {
+    values
+        .iter()
+        .map(|(k, v)| (k.clone(), v.to_plain()))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
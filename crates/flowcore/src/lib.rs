@@ -3,20 +3,39 @@
 //! This crate provides the fundamental types and traits that all other
 //! components depend on. It has no runtime dependencies.
 
+mod artifact;
+mod credential;
+pub mod diff;
+mod edit;
 mod error;
+mod format;
+mod named;
 pub mod events;
 mod node;
+mod path;
+mod secret;
+mod signal;
 mod value;
+mod variables;
 mod workflow;
 
-pub use error::{FlowError, NodeError, WorkflowError};
+pub use artifact::{ArtifactStore, BlobRef, GcReport};
+pub use credential::{resolve_credentials, CredentialRef, CredentialResolver, CREDENTIAL_KEY};
+pub use error::{FlowError, NodeError, PathError, WorkflowError};
+pub use format::WorkflowFormat;
 pub use node::{Node, NodeContext, NodeOutput, NodeMetadata, NodeState};
+pub use secret::{Secret, SecretMask, REDACTED};
+pub use signal::Signals;
 pub use workflow::{
     Workflow, WorkflowId, NodeId, NodeSpec, Connection, 
-    TriggerSpec, TriggerType, ErrorHandling,
+    TriggerSpec, TriggerType, CatchUpPolicy, WebhookResponse, FileEvent, ErrorHandling,
     Position, RetryPolicy, WorkflowSettings,
 };
 pub use value::Value;
+pub use variables::{Environment, VARIABLE_PREFIX};
+pub use bigdecimal::BigDecimal;
+pub use diff::{NodeChange, WorkflowDiff};
+pub use edit::WorkflowEdit;
 pub use events::*;
 
 /// Result type for flow operations
@@ -0,0 +1,352 @@
+//! Name-keyed workflow documents
+//!
+//! Hand-written workflow files key nodes (and triggers) by name instead of
+//! UUID and connect them with `"node.port -> node.port"` strings:
+//!
+//! ```yaml
+//! name: Fetch and log
+//! nodes:
+//!   fetch:
+//!     type: http.request
+//!     config: { method: GET }
+//!   log:
+//!     type: debug.log
+//! connections:
+//!   - fetch.body -> log.message
+//! ```
+//!
+//! Ids left out are derived from the workflow name and node keys, so loading
+//! the same file twice yields the same workflow and node ids. Different
+//! files with the same name get the same workflow id too unless one sets
+//! `id`; the server refuses to create the second.
+
+use crate::{
+    Connection, NodeId, NodeSpec, TriggerSpec, Value, Workflow, WorkflowError, WorkflowSettings,
+};
+use serde_json::{Map, Value as Json};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Id derived from `parts`, stable across loads of the same document
+fn stable_id(parts: &[&str]) -> Uuid {
+    let digest = Sha256::digest(parts.join("\n"));
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Builder::from_custom_bytes(bytes).into_uuid()
+}
+
+fn workflow_id(name: &str) -> Uuid {
+    stable_id(&["workflow", name])
+}
+
+fn node_id(workflow_id: Uuid, key: &str) -> NodeId {
+    stable_id(&["node", &workflow_id.to_string(), key])
+}
+
+fn trigger_id(workflow_id: Uuid, key: &str) -> Uuid {
+    stable_id(&["trigger", &workflow_id.to_string(), key])
+}
+
+/// `base`, suffixed `_2`, `_3`, ... until it is not yet in `taken`
+fn unique_key(taken: &mut HashSet<String>, base: String) -> String {
+    let mut key = base.clone();
+    let mut n = 2;
+    while !taken.insert(key.clone()) {
+        key = format!("{}_{}", base, n);
+        n += 1;
+    }
+    key
+}
+
+fn invalid(message: impl Into<String>) -> WorkflowError {
+    WorkflowError::Invalid(message.into())
+}
+
+/// Optional field deserialized from `map`, with the field name in errors
+fn field<T: serde::de::DeserializeOwned>(
+    map: &mut Map<String, Json>,
+    name: &str,
+    context: &str,
+) -> Result<Option<T>, WorkflowError> {
+    match map.remove(name) {
+        None | Some(Json::Null) => Ok(None),
+        Some(value) => serde_json::from_value(value)
+            .map(Some)
+            .map_err(|e| invalid(format!("{}.{}: {}", context, name, e))),
+    }
+}
+
+/// Whether a document keys its nodes by name
+pub(crate) fn is_named(document: &Json) -> bool {
+    document.get("nodes").is_some_and(|nodes| nodes.is_object())
+}
+
+/// `node.port` split at the first dot; ports may contain dots, keys may not
+fn endpoint(text: &str) -> Result<(&str, &str), WorkflowError> {
+    match text.trim().split_once('.') {
+        Some((node, port)) if !node.is_empty() && !port.is_empty() => Ok((node, port)),
+        _ => Err(WorkflowError::InvalidConnection(format!(
+            "'{}' is not of the form node.port",
+            text
+        ))),
+    }
+}
+
+impl Workflow {
+    /// Workflow from a name-keyed document (see the module docs)
+    pub(crate) fn from_named_document(document: Json) -> Result<Workflow, WorkflowError> {
+        let Json::Object(mut doc) = document else {
+            return Err(invalid("workflow document must be an object"));
+        };
+        let name: String =
+            field(&mut doc, "name", "workflow")?.ok_or_else(|| invalid("missing field `name`"))?;
+        let id = field(&mut doc, "id", "workflow")?.unwrap_or_else(|| workflow_id(&name));
+
+        let mut nodes = Vec::new();
+        let mut keys: HashMap<String, NodeId> = HashMap::new();
+        let Some(Json::Object(entries)) = doc.remove("nodes") else {
+            unreachable!("named documents have a nodes object")
+        };
+        for (key, entry) in entries {
+            if key.is_empty() || key.contains('.') {
+                return Err(invalid(format!(
+                    "node key '{}' must be non-empty and contain no '.'",
+                    key
+                )));
+            }
+            let context = format!("nodes.{}", key);
+            let mut entry = match entry {
+                Json::Object(entry) => entry,
+                _ => return Err(invalid(format!("{} must be an object", context))),
+            };
+            let node_type: String = field(&mut entry, "type", &context)?
+                .ok_or_else(|| invalid(format!("{}: missing field `type`", context)))?;
+            let config = match entry.remove("config") {
+                None | Some(Json::Null) => HashMap::new(),
+                Some(Json::Object(config)) => config
+                    .into_iter()
+                    .map(|(k, v)| {
+                        let value = Value::from_plain(v)
+                            .map_err(|e| invalid(format!("{}.config.{}: {}", context, k, e)))?;
+                        Ok((k, value))
+                    })
+                    .collect::<Result<_, WorkflowError>>()?,
+                Some(_) => return Err(invalid(format!("{}.config must be an object", context))),
+            };
+            let spec = NodeSpec {
+                id: field(&mut entry, "id", &context)?.unwrap_or_else(|| node_id(id, &key)),
+                node_type,
+                name: Some(field(&mut entry, "name", &context)?.unwrap_or_else(|| key.clone())),
+                config,
+                position: field(&mut entry, "position", &context)?,
+                retry_policy: field(&mut entry, "retry_policy", &context)?,
+            };
+            keys.insert(key, spec.id);
+            nodes.push(spec);
+        }
+
+        // Node keys, or raw ids for nodes an export couldn't name
+        let resolve = |node: &str| -> Result<NodeId, WorkflowError> {
+            keys.get(node)
+                .copied()
+                .or_else(|| {
+                    Uuid::parse_str(node)
+                        .ok()
+                        .filter(|id| nodes.iter().any(|n| n.id == *id))
+                })
+                .ok_or_else(|| WorkflowError::NodeNotFound(node.to_string()))
+        };
+        let mut connections = Vec::new();
+        for entry in field::<Vec<Json>>(&mut doc, "connections", "workflow")?.unwrap_or_default() {
+            let (from, to) = match &entry {
+                Json::String(text) => text.split_once("->").ok_or_else(|| {
+                    WorkflowError::InvalidConnection(format!(
+                        "'{}' is not of the form a.port -> b.port",
+                        text
+                    ))
+                })?,
+                Json::Object(map) => match (
+                    map.get("from").and_then(Json::as_str),
+                    map.get("to").and_then(Json::as_str),
+                ) {
+                    (Some(from), Some(to)) => (from, to),
+                    _ => {
+                        return Err(WorkflowError::InvalidConnection(format!(
+                            "{} needs string `from` and `to`",
+                            entry
+                        )))
+                    }
+                },
+                _ => return Err(WorkflowError::InvalidConnection(entry.to_string())),
+            };
+            let (from_node, from_port) = endpoint(from)?;
+            let (to_node, to_port) = endpoint(to)?;
+            connections.push(Connection {
+                from_node: resolve(from_node)?,
+                from_port: from_port.to_string(),
+                to_node: resolve(to_node)?,
+                to_port: to_port.to_string(),
+            });
+        }
+
+        let mut triggers = Vec::new();
+        for (key, entry) in
+            field::<Map<String, Json>>(&mut doc, "triggers", "workflow")?.unwrap_or_default()
+        {
+            let context = format!("triggers.{}", key);
+            let Json::Object(mut entry) = entry else {
+                return Err(invalid(format!("{} must be an object", context)));
+            };
+            let trigger_id =
+                field(&mut entry, "id", &context)?.unwrap_or_else(|| trigger_id(id, &key));
+            let enabled = field(&mut entry, "enabled", &context)?.unwrap_or(true);
+            let trigger_type = serde_json::from_value(entry.into())
+                .map_err(|e| invalid(format!("{}: {}", context, e)))?;
+            triggers.push(TriggerSpec {
+                id: trigger_id,
+                trigger_type,
+                enabled,
+            });
+        }
+
+        Ok(Workflow {
+            id,
+            revision: field(&mut doc, "revision", "workflow")?.unwrap_or_default(),
+            name,
+            description: field(&mut doc, "description", "workflow")?,
+            nodes,
+            connections,
+            triggers,
+            settings: field(&mut doc, "settings", "workflow")?.unwrap_or_default(),
+            variables: match doc.remove("variables") {
+                Some(variables) => crate::format::plain_variables(variables)
+                    .map_err(|e| invalid(format!("variables.{}", e)))?,
+                None => HashMap::new(),
+            },
+        })
+    }
+
+    /// Name-keyed document for this workflow, the inverse of loading one.
+    /// Node keys come from node names (made unique, with `.` replaced), and
+    /// ids are only written where they differ from the derived ones.
+    pub fn to_named_document(&self) -> Json {
+        let mut doc = Map::new();
+        doc.insert("name".to_string(), self.name.clone().into());
+        if self.id != workflow_id(&self.name) {
+            doc.insert("id".to_string(), self.id.to_string().into());
+        }
+        if let Some(description) = &self.description {
+            doc.insert("description".to_string(), description.clone().into());
+        }
+        if !self.variables.is_empty() {
+            doc.insert(
+                "variables".to_string(),
+                crate::format::plain_document(&self.variables),
+            );
+        }
+
+        let mut taken = HashSet::new();
+        let mut keys: HashMap<NodeId, String> = HashMap::new();
+        let mut nodes = Map::new();
+        for node in &self.nodes {
+            let base = node
+                .name
+                .as_deref()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .unwrap_or(&node.node_type)
+                .replace('.', "_");
+            let key = unique_key(&mut taken, base);
+
+            let mut entry = Map::new();
+            entry.insert("type".to_string(), node.node_type.clone().into());
+            if node.name.as_deref() != Some(key.as_str()) {
+                if let Some(name) = &node.name {
+                    entry.insert("name".to_string(), name.clone().into());
+                }
+            }
+            if node.id != node_id(self.id, &key) {
+                entry.insert("id".to_string(), node.id.to_string().into());
+            }
+            if !node.config.is_empty() {
+                let config: Map<_, _> = node
+                    .config
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.to_plain()))
+                    .collect();
+                entry.insert("config".to_string(), config.into());
+            }
+            if let Some(position) = &node.position {
+                entry.insert(
+                    "position".to_string(),
+                    serde_json::to_value(position).unwrap_or_default(),
+                );
+            }
+            if let Some(retry) = &node.retry_policy {
+                entry.insert(
+                    "retry_policy".to_string(),
+                    serde_json::to_value(retry).unwrap_or_default(),
+                );
+            }
+            keys.insert(node.id, key.clone());
+            nodes.insert(key, entry.into());
+        }
+        doc.insert("nodes".to_string(), nodes.into());
+
+        if !self.connections.is_empty() {
+            let reference = |id: &NodeId| keys.get(id).cloned().unwrap_or_else(|| id.to_string());
+            let connections: Vec<Json> = self
+                .connections
+                .iter()
+                .map(|c| {
+                    format!(
+                        "{}.{} -> {}.{}",
+                        reference(&c.from_node),
+                        c.from_port,
+                        reference(&c.to_node),
+                        c.to_port
+                    )
+                    .into()
+                })
+                .collect();
+            doc.insert("connections".to_string(), connections.into());
+        }
+
+        if !self.triggers.is_empty() {
+            let mut taken = HashSet::new();
+            let mut triggers = Map::new();
+            for trigger in &self.triggers {
+                let Ok(Json::Object(mut entry)) = serde_json::to_value(&trigger.trigger_type)
+                else {
+                    continue;
+                };
+                let kind = entry
+                    .get("type")
+                    .and_then(Json::as_str)
+                    .unwrap_or("trigger")
+                    .to_lowercase();
+                let key = unique_key(&mut taken, kind);
+                if trigger.id != trigger_id(self.id, &key) {
+                    entry.insert("id".to_string(), trigger.id.to_string().into());
+                }
+                if !trigger.enabled {
+                    entry.insert("enabled".to_string(), false.into());
+                }
+                triggers.insert(key, entry.into());
+            }
+            doc.insert("triggers".to_string(), triggers.into());
+        }
+
+        let settings = serde_json::to_value(&self.settings).unwrap_or_default();
+        if settings != serde_json::to_value(WorkflowSettings::default()).unwrap_or_default() {
+            let mut settings = settings;
+            if let Some(map) = settings.as_object_mut() {
+                map.retain(|_, v| !v.is_null());
+            }
+            doc.insert("settings".to_string(), settings);
+        }
+
+        doc.into()
+    }
+}
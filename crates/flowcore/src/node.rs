@@ -1,7 +1,8 @@
-use crate::{NodeError, Value, events::EventEmitter};
+use crate::{ArtifactStore, NodeError, Signals, Value, events::EventEmitter};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
@@ -53,6 +54,20 @@ pub struct NodeContext {
     
     /// Cancellation token for graceful shutdown
     pub cancellation: tokio_util::sync::CancellationToken,
+    
+    /// Signals sent to the execution from outside
+    pub signals: Signals,
+    
+    /// Where blob values are stored
+    pub artifacts: ArtifactStore,
+    
+    /// Scratch directory shared by all nodes of the execution, removed
+    /// when it finishes (`None` outside a runtime)
+    pub workspace: Option<PathBuf>,
+    
+    /// Workflow variables in effect for the execution, with the selected
+    /// environment applied
+    pub variables: HashMap<String, Value>,
 }
 
 impl NodeContext {
@@ -64,6 +79,10 @@ impl NodeContext {
             state: Arc::new(RwLock::new(NodeState::default())),
             events,
             cancellation: tokio_util::sync::CancellationToken::new(),
+            signals: Signals::new(),
+            artifacts: ArtifactStore::default(),
+            workspace: None,
+            variables: HashMap::new(),
         }
     }
     
@@ -73,6 +92,85 @@ impl NodeContext {
             .ok_or_else(|| NodeError::MissingInput(name.to_string()))
     }
     
+    /// Value of a workflow variable
+    pub fn variable(&self, name: &str) -> Option<&Value> {
+        self.variables.get(name)
+    }
+    
+    /// Contents of a bytes, string or blob input, loading blobs from the
+    /// artifact store only now. `None` if the input is missing.
+    pub async fn input_bytes(&self, name: &str) -> Result<Option<Vec<u8>>, NodeError> {
+        match self.inputs.get(name) {
+            None => Ok(None),
+            Some(value) => self.value_bytes(value).await.map(Some),
+        }
+    }
+    
+    /// Contents of a bytes, string or blob value; other values as text
+    pub async fn value_bytes(&self, value: &Value) -> Result<Vec<u8>, NodeError> {
+        match value {
+            Value::Blob(blob) => self.artifacts.load(blob).await.map_err(|e| {
+                NodeError::ExecutionFailed(format!("Failed to load blob {}: {}", blob.hash, e))
+            }),
+            Value::Bytes(b) => Ok(b.clone()),
+            other => Ok(other.to_string().into_bytes()),
+        }
+    }
+    
+    /// Keep `data` in the artifact store and return a blob value for it
+    pub async fn store_blob(&self, data: &[u8], media_type: &str) -> Result<Value, NodeError> {
+        self.artifacts
+            .put(data, media_type)
+            .await
+            .map(Value::Blob)
+            .map_err(|e| NodeError::ExecutionFailed(format!("Failed to store blob: {}", e)))
+    }
+    
+    /// Absolute path of `relative` inside the execution's workspace.
+    /// Paths leaving the workspace are rejected.
+    pub fn workspace_path(&self, relative: &str) -> Result<PathBuf, NodeError> {
+        let workspace = self.workspace.as_ref().ok_or_else(|| {
+            NodeError::ExecutionFailed("Execution has no workspace".to_string())
+        })?;
+        let relative = Path::new(relative);
+        if !relative.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+            return Err(NodeError::InvalidInputType {
+                field: "path".to_string(),
+                expected: "relative path inside the workspace".to_string(),
+                actual: relative.display().to_string(),
+            });
+        }
+        Ok(workspace.join(relative))
+    }
+    
+    /// Write each value (bytes, blob or text) to its path in the workspace
+    pub async fn write_files(&self, files: &HashMap<String, Value>) -> Result<Vec<PathBuf>, NodeError> {
+        let mut written = Vec::with_capacity(files.len());
+        for (relative, value) in files {
+            let path = self.workspace_path(relative)?;
+            let data = self.value_bytes(value).await?;
+            let write = async {
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(&path, data).await
+            };
+            write.await.map_err(|e| {
+                NodeError::ExecutionFailed(format!("Failed to write {}: {}", path.display(), e))
+            })?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+    
+    /// Inputs named `file:<path>`, keyed by path
+    pub fn file_inputs(&self) -> HashMap<String, Value> {
+        self.inputs
+            .iter()
+            .filter_map(|(key, value)| Some((key.strip_prefix("file:")?.to_string(), value.clone())))
+            .collect()
+    }
+    
     /// Get config value or return error
     pub fn require_config(&self, name: &str) -> Result<&Value, NodeError> {
         self.config.get(name)
@@ -0,0 +1,348 @@
+//! Paths into values
+//!
+//! JSON Pointer (RFC 6901) and a JSONPath subset (`$`, `.key`, `['key']`,
+//! `[0]`, `[-1]`, `[*]`, `.*`, `..key`, `..*`) address nested values the same
+//! way whether they are native `Object`/`Array` values or `Json` trees.
+//! Values read from inside a `Json` tree come back as native values (see
+//! [`Value::from_json`]); values written into one are stored as JSON.
+
+use crate::value::json_type_name;
+use crate::{PathError, Value};
+use serde_json::Value as Json;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    /// Object key, or array index when the key is a non-negative number
+    Key(String),
+    /// Array index, negative from the end
+    Index(i64),
+    /// Every child
+    Wildcard,
+    /// The key (or every child, for `None`) at any depth
+    Descend(Option<String>),
+}
+
+fn syntax(path: &str, reason: impl Into<String>) -> PathError {
+    PathError::Syntax {
+        path: path.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// RFC 6901 reference tokens; `~1` is `/` and `~0` is `~`
+fn parse_pointer(pointer: &str) -> Result<Vec<Step>, PathError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(syntax(pointer, "a JSON Pointer starts with '/'"));
+    };
+    Ok(rest
+        .split('/')
+        .map(|token| Step::Key(token.replace("~1", "/").replace("~0", "~")))
+        .collect())
+}
+
+fn parse_jsonpath(path: &str) -> Result<Vec<Step>, PathError> {
+    let Some(rest) = path.trim().strip_prefix('$') else {
+        return Err(syntax(path, "a JSONPath starts with '$'"));
+    };
+    let chars: Vec<char> = rest.chars().collect();
+    let mut steps = Vec::new();
+    let mut i = 0;
+
+    // Member name after `.` or `..`, up to the next `.` or `[`
+    let name = |i: &mut usize| {
+        let start = *i;
+        while *i < chars.len() && chars[*i] != '.' && chars[*i] != '[' {
+            *i += 1;
+        }
+        chars[start..*i].iter().collect::<String>()
+    };
+
+    while i < chars.len() {
+        match chars[i] {
+            '.' if chars.get(i + 1) == Some(&'.') => {
+                i += 2;
+                let member = name(&mut i);
+                match member.as_str() {
+                    "" => return Err(syntax(path, "'..' must be followed by a name or '*'")),
+                    "*" => steps.push(Step::Descend(None)),
+                    _ => steps.push(Step::Descend(Some(member))),
+                }
+            }
+            '.' => {
+                i += 1;
+                let member = name(&mut i);
+                match member.as_str() {
+                    "" => return Err(syntax(path, "'.' must be followed by a name or '*'")),
+                    "*" => steps.push(Step::Wildcard),
+                    _ => steps.push(Step::Key(member)),
+                }
+            }
+            '[' => {
+                let close = (i..chars.len())
+                    .find(|&j| chars[j] == ']' && !in_quotes(&chars[i + 1..j]))
+                    .ok_or_else(|| syntax(path, "unclosed '['"))?;
+                let inner: String = chars[i + 1..close].iter().collect();
+                let inner = inner.trim();
+                steps.push(bracket(path, inner)?);
+                i = close + 1;
+            }
+            c => return Err(syntax(path, format!("unexpected '{}'", c))),
+        }
+    }
+    Ok(steps)
+}
+
+/// Whether `chars` ends inside an unterminated quoted string
+fn in_quotes(chars: &[char]) -> bool {
+    let mut quote = None;
+    let mut escaped = false;
+    for &c in chars {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None => {}
+        }
+    }
+    quote.is_some()
+}
+
+fn bracket(path: &str, inner: &str) -> Result<Step, PathError> {
+    if inner == "*" {
+        return Ok(Step::Wildcard);
+    }
+    for quote in ['\'', '"'] {
+        if let Some(key) = inner.strip_prefix(quote).and_then(|s| s.strip_suffix(quote)) {
+            let mut unescaped = String::new();
+            let mut chars = key.chars();
+            while let Some(c) = chars.next() {
+                if c == '\\' {
+                    unescaped.extend(chars.next());
+                } else {
+                    unescaped.push(c);
+                }
+            }
+            return Ok(Step::Key(unescaped));
+        }
+    }
+    inner
+        .parse()
+        .map(Step::Index)
+        .map_err(|_| syntax(path, format!("'[{}]' is not an index, '*' or a quoted name", inner)))
+}
+
+/// Position of `index` in a list of `len` items; negative counts from the end
+fn position(index: i64, len: usize) -> Option<usize> {
+    let position = if index < 0 { len as i64 + index } else { index };
+    (0..len as i64).contains(&position).then_some(position as usize)
+}
+
+/// A value reached by a path, native or inside a `Json` tree
+#[derive(Clone, Copy)]
+enum Node<'a> {
+    Value(&'a Value),
+    Json(&'a Json),
+}
+
+impl<'a> Node<'a> {
+    fn key(self, key: &str) -> Option<Node<'a>> {
+        match self {
+            Node::Value(Value::Object(map)) => map.get(key).map(Node::Value),
+            Node::Value(Value::Array(items)) => items.get(key.parse::<usize>().ok()?).map(Node::Value),
+            Node::Value(Value::Json(json)) | Node::Json(json) => match json {
+                Json::Object(map) => map.get(key).map(Node::Json),
+                Json::Array(items) => items.get(key.parse::<usize>().ok()?).map(Node::Json),
+                _ => None,
+            },
+            Node::Value(_) => None,
+        }
+    }
+
+    fn index(self, index: i64) -> Option<Node<'a>> {
+        match self {
+            Node::Value(Value::Array(items)) => items.get(position(index, items.len())?).map(Node::Value),
+            Node::Value(Value::Json(Json::Array(items))) | Node::Json(Json::Array(items)) => {
+                items.get(position(index, items.len())?).map(Node::Json)
+            }
+            _ => None,
+        }
+    }
+
+    fn children(self) -> Vec<Node<'a>> {
+        match self {
+            Node::Value(Value::Object(map)) => map.values().map(Node::Value).collect(),
+            Node::Value(Value::Array(items)) => items.iter().map(Node::Value).collect(),
+            Node::Value(Value::Json(json)) | Node::Json(json) => match json {
+                Json::Object(map) => map.values().map(Node::Json).collect(),
+                Json::Array(items) => items.iter().map(Node::Json).collect(),
+                _ => Vec::new(),
+            },
+            Node::Value(_) => Vec::new(),
+        }
+    }
+
+    /// This node followed by all of its descendants, depth first
+    fn descendants(self) -> Vec<Node<'a>> {
+        let mut found = Vec::new();
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            found.push(node);
+            let mut children = node.children();
+            children.reverse();
+            stack.extend(children);
+        }
+        found
+    }
+
+    fn to_value(self) -> Value {
+        match self {
+            Node::Value(value) => value.clone(),
+            Node::Json(json) => Value::from_json(json.clone()),
+        }
+    }
+}
+
+fn select<'a>(root: &'a Value, steps: &[Step]) -> Vec<Node<'a>> {
+    let mut nodes = vec![Node::Value(root)];
+    for step in steps {
+        nodes = match step {
+            Step::Key(key) => nodes.into_iter().filter_map(|n| n.key(key)).collect(),
+            Step::Index(index) => nodes.into_iter().filter_map(|n| n.index(*index)).collect(),
+            Step::Wildcard => nodes.into_iter().flat_map(Node::children).collect(),
+            Step::Descend(None) => nodes
+                .into_iter()
+                .flat_map(|n| n.descendants().into_iter().skip(1))
+                .collect(),
+            Step::Descend(Some(key)) => nodes
+                .into_iter()
+                .flat_map(Node::descendants)
+                .filter_map(|n| n.key(key))
+                .collect(),
+        };
+    }
+    nodes
+}
+
+/// Where `step` points in an array of `len` items when writing; `len` appends
+fn write_position(path: &str, step: &Step, len: usize) -> Result<usize, PathError> {
+    let index = match step {
+        Step::Key(key) if key == "-" => return Ok(len),
+        Step::Key(key) => key.parse::<usize>().ok().map(|index| index as i64),
+        Step::Index(index) => Some(*index),
+        _ => None,
+    };
+    match index {
+        Some(index) if index == len as i64 => Ok(len),
+        Some(index) => position(index, len).ok_or_else(|| PathError::Set {
+            path: path.to_string(),
+            reason: format!("index {} is out of bounds for {} items", index, len),
+        }),
+        None => Err(PathError::Set {
+            path: path.to_string(),
+            reason: "arrays are indexed by number".to_string(),
+        }),
+    }
+}
+
+fn set_value(target: &mut Value, steps: &[Step], value: Value, path: &str) -> Result<(), PathError> {
+    let Some((step, rest)) = steps.split_first() else {
+        *target = value;
+        return Ok(());
+    };
+    if target.is_null() && matches!(step, Step::Key(_)) {
+        *target = Value::Object(Default::default());
+    }
+    match target {
+        Value::Object(map) => {
+            let Step::Key(key) = step else {
+                return Err(PathError::Set {
+                    path: path.to_string(),
+                    reason: "objects are indexed by name".to_string(),
+                });
+            };
+            set_value(map.entry(key.clone()).or_insert(Value::Null), rest, value, path)
+        }
+        Value::Array(items) => {
+            let index = write_position(path, step, items.len())?;
+            if index == items.len() {
+                items.push(Value::Null);
+            }
+            set_value(&mut items[index], rest, value, path)
+        }
+        Value::Json(json) => set_json(json, steps, value.to_json(), path),
+        other => Err(PathError::Set {
+            path: path.to_string(),
+            reason: format!("cannot index into {}", other.type_name()),
+        }),
+    }
+}
+
+fn set_json(target: &mut Json, steps: &[Step], value: Json, path: &str) -> Result<(), PathError> {
+    let Some((step, rest)) = steps.split_first() else {
+        *target = value;
+        return Ok(());
+    };
+    if target.is_null() && matches!(step, Step::Key(_)) {
+        *target = Json::Object(Default::default());
+    }
+    match target {
+        Json::Object(map) => {
+            let Step::Key(key) = step else {
+                return Err(PathError::Set {
+                    path: path.to_string(),
+                    reason: "objects are indexed by name".to_string(),
+                });
+            };
+            set_json(map.entry(key.clone()).or_insert(Json::Null), rest, value, path)
+        }
+        Json::Array(items) => {
+            let index = write_position(path, step, items.len())?;
+            if index == items.len() {
+                items.push(Json::Null);
+            }
+            set_json(&mut items[index], rest, value, path)
+        }
+        other => Err(PathError::Set {
+            path: path.to_string(),
+            reason: format!("cannot index into {}", json_type_name(other)),
+        }),
+    }
+}
+
+impl Value {
+    /// Value at a JSON Pointer such as `/items/0/name`; `""` is the whole value
+    pub fn pointer(&self, pointer: &str) -> Option<Value> {
+        let steps = parse_pointer(pointer).ok()?;
+        select(self, &steps).first().map(|node| node.to_value())
+    }
+
+    /// Set the value at a JSON Pointer, creating missing objects on the way.
+    /// An index equal to the length (or `-`) appends to an array.
+    pub fn set_pointer(&mut self, pointer: &str, value: impl Into<Value>) -> Result<(), PathError> {
+        let steps = parse_pointer(pointer)?;
+        set_value(self, &steps, value.into(), pointer)
+    }
+
+    /// Every value matched by a JSONPath such as `$.items[*].name`
+    pub fn query(&self, path: &str) -> Result<Vec<Value>, PathError> {
+        let steps = parse_jsonpath(path)?;
+        Ok(select(self, &steps).into_iter().map(Node::to_value).collect())
+    }
+
+    /// Set the value at a JSONPath made of names and indexes only
+    pub fn set_path(&mut self, path: &str, value: impl Into<Value>) -> Result<(), PathError> {
+        let steps = parse_jsonpath(path)?;
+        if steps.iter().any(|s| matches!(s, Step::Wildcard | Step::Descend(_))) {
+            return Err(PathError::Set {
+                path: path.to_string(),
+                reason: "wildcards and '..' select more than one place".to_string(),
+            });
+        }
+        set_value(self, &steps, value.into(), path)
+    }
+}
@@ -0,0 +1,142 @@
+//! Secret values
+//!
+//! A [`Secret`] is a string nodes can read (see [`Value::reveal_str`]) that
+//! never leaves the process in clear text: it serializes, prints and
+//! displays as [`REDACTED`], so events, logs, stored workflows and API
+//! responses only ever see the placeholder. Copies that end up inside plain
+//! strings, such as a process echoing its API key to stdout, are masked by
+//! a [`SecretMask`].
+
+use crate::Value;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::sync::Arc;
+
+/// What a secret reads as wherever it is serialized or displayed
+pub const REDACTED: &str = "********";
+
+/// Secrets shorter than this are not masked in text, where they would
+/// match all over ordinary output
+const MIN_MASKED_LEN: usize = 4;
+
+/// String whose contents are only available through [`Secret::expose`]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Secret(value.into())
+    }
+
+    /// The secret in clear text, for handing to a process or request.
+    /// Never log it.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Secret)
+    }
+}
+
+/// Known secret strings to mask out of text, longest first so a secret
+/// containing another is masked whole
+#[derive(Clone, Default)]
+pub struct SecretMask {
+    secrets: Arc<Vec<String>>,
+}
+
+impl SecretMask {
+    pub fn new<'a>(secrets: impl IntoIterator<Item = &'a Secret>) -> Self {
+        let mut mask = SecretMask::default();
+        mask.add(secrets);
+        mask
+    }
+
+    /// Also mask `secrets`
+    pub fn add<'a>(&mut self, secrets: impl IntoIterator<Item = &'a Secret>) {
+        let mut added = false;
+        for secret in secrets {
+            let text = secret.expose();
+            if text.len() >= MIN_MASKED_LEN && !self.secrets.iter().any(|s| s == text) {
+                Arc::make_mut(&mut self.secrets).push(text.to_string());
+                added = true;
+            }
+        }
+        if added {
+            Arc::make_mut(&mut self.secrets).sort_by_key(|s| std::cmp::Reverse(s.len()));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secrets.is_empty()
+    }
+
+    /// `text` with every known secret replaced by [`REDACTED`]
+    pub fn mask(&self, text: &str) -> String {
+        let mut masked = text.to_string();
+        for secret in self.secrets.iter() {
+            if masked.contains(secret.as_str()) {
+                masked = masked.replace(secret.as_str(), REDACTED);
+            }
+        }
+        masked
+    }
+
+    /// `value` with known secrets masked in every string it holds
+    pub fn mask_value(&self, value: &Value) -> Value {
+        if self.is_empty() {
+            return value.clone();
+        }
+        match value {
+            Value::String(s) => Value::String(self.mask(s)),
+            Value::Json(j) => Value::Json(self.mask_json(j)),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.mask_value(v)).collect()),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.mask_value(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    fn mask_json(&self, json: &serde_json::Value) -> serde_json::Value {
+        match json {
+            serde_json::Value::String(s) => self.mask(s).into(),
+            serde_json::Value::Array(items) => items.iter().map(|v| self.mask_json(v)).collect(),
+            serde_json::Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| (k.clone(), self.mask_json(v)))
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+            other => other.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for SecretMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretMask({} secrets)", self.secrets.len())
+    }
+}
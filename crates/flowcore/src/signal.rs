@@ -0,0 +1,91 @@
+use crate::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// Named signals sent to one execution from outside, e.g. a human approval.
+/// Signals sent before anything waits for them are kept until taken.
+#[derive(Clone, Default)]
+pub struct Signals {
+    inner: Arc<SignalsInner>,
+}
+
+#[derive(Default)]
+struct SignalsInner {
+    pending: Mutex<HashMap<String, VecDeque<Value>>>,
+    arrived: Notify,
+}
+
+impl Signals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signals that were sent but not taken yet, e.g. restored from a checkpoint
+    pub fn with_pending(pending: HashMap<String, Vec<Value>>) -> Self {
+        let signals = Self::new();
+        for (name, payloads) in pending {
+            for payload in payloads {
+                signals.send(name.clone(), payload);
+            }
+        }
+        signals
+    }
+
+    /// Deliver a signal to whoever waits for `name`, now or later
+    pub fn send(&self, name: impl Into<String>, payload: Value) {
+        self.inner
+            .pending
+            .lock()
+            .unwrap()
+            .entry(name.into())
+            .or_default()
+            .push_back(payload);
+        self.inner.arrived.notify_waiters();
+    }
+
+    /// Take the oldest pending signal named `name` without waiting
+    pub fn try_take(&self, name: &str) -> Option<Value> {
+        let mut pending = self.inner.pending.lock().unwrap();
+        let queue = pending.get_mut(name)?;
+        let payload = queue.pop_front();
+        if queue.is_empty() {
+            pending.remove(name);
+        }
+        payload
+    }
+
+    /// Wait for a signal named `name` and take it
+    pub async fn wait(&self, name: &str) -> Value {
+        loop {
+            // Register before checking so a send in between isn't missed
+            let arrived = self.inner.arrived.notified();
+            tokio::pin!(arrived);
+            arrived.as_mut().enable();
+
+            if let Some(payload) = self.try_take(name) {
+                return payload;
+            }
+            arrived.await;
+        }
+    }
+
+    /// Snapshot of the signals not taken yet
+    pub fn pending(&self) -> HashMap<String, Vec<Value>> {
+        self.inner
+            .pending
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, payloads)| (name.clone(), payloads.iter().cloned().collect()))
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for Signals {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signals")
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
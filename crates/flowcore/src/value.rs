@@ -1,3 +1,8 @@
+use crate::secret::REDACTED;
+use crate::{BlobRef, NodeError, Secret};
+use base64::Engine;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -8,8 +13,19 @@ pub enum Value {
     Null,
     Bool(bool),
     Number(f64),
+    /// Exact 64-bit integer, for ids and counters `f64` would round
+    Integer(i64),
+    /// Point in time, RFC 3339 in JSON
+    Timestamp(DateTime<Utc>),
+    /// Arbitrary-precision decimal, a string in JSON so no digit is lost
+    Decimal(BigDecimal),
     String(String),
+    /// String nodes can read but that is redacted wherever it is
+    /// serialized or displayed
+    Secret(Secret),
     Bytes(Vec<u8>),
+    /// File data kept in an [`ArtifactStore`](crate::ArtifactStore)
+    Blob(BlobRef),
     Json(serde_json::Value),
     Array(Vec<Value>),
     Object(HashMap<String, Value>),
@@ -23,9 +39,55 @@ impl Value {
         }
     }
 
+    pub fn as_secret(&self) -> Option<&Secret> {
+        match self {
+            Value::Secret(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// String or secret in clear text, for values handed to a process or
+    /// request (environment variables, credentials); never log the result
+    pub fn reveal_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            Value::Secret(s) => Some(s.expose()),
+            _ => None,
+        }
+    }
+
+    /// Any numeric value as `f64`, possibly rounded
     pub fn as_f64(&self) -> Option<f64> {
         match self {
             Value::Number(n) => Some(*n),
+            Value::Integer(i) => Some(*i as f64),
+            Value::Decimal(d) => d.to_f64(),
+            _ => None,
+        }
+    }
+
+    /// Numeric values that are whole numbers within `i64`
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Integer(i) => Some(*i),
+            Value::Number(n) if n.fract() == 0.0 && *n >= i64::MIN as f64 && *n < i64::MAX as f64 => {
+                Some(*n as i64)
+            }
+            Value::Decimal(d) if d.is_integer() => d.to_i64(),
+            _ => None,
+        }
+    }
+
+    pub fn as_timestamp(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Value::Timestamp(t) => Some(*t),
+            _ => None,
+        }
+    }
+
+    pub fn as_decimal(&self) -> Option<&BigDecimal> {
+        match self {
+            Value::Decimal(d) => Some(d),
             _ => None,
         }
     }
@@ -37,6 +99,43 @@ impl Value {
         }
     }
 
+    pub fn as_blob(&self) -> Option<&BlobRef> {
+        match self {
+            Value::Blob(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Every blob referenced in this value, including nested ones
+    pub fn blob_refs(&self) -> Vec<&BlobRef> {
+        let mut blobs = Vec::new();
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            match value {
+                Value::Blob(b) => blobs.push(b),
+                Value::Array(items) => stack.extend(items),
+                Value::Object(map) => stack.extend(map.values()),
+                _ => {}
+            }
+        }
+        blobs
+    }
+
+    /// Every secret in this value, including nested ones
+    pub fn secrets(&self) -> Vec<&Secret> {
+        let mut secrets = Vec::new();
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            match value {
+                Value::Secret(s) => secrets.push(s),
+                Value::Array(items) => stack.extend(items),
+                Value::Object(map) => stack.extend(map.values()),
+                _ => {}
+            }
+        }
+        secrets
+    }
+
     pub fn as_json(&self) -> Option<&serde_json::Value> {
         match self {
             Value::Json(j) => Some(j),
@@ -50,8 +149,13 @@ impl Value {
             Value::Null => "null".to_string(),
             Value::Bool(b) => b.to_string(),
             Value::Number(n) => n.to_string(),
+            Value::Integer(i) => i.to_string(),
+            Value::Timestamp(t) => t.to_rfc3339(),
+            Value::Decimal(d) => d.to_string(),
             Value::String(s) => s.clone(),
+            Value::Secret(_) => REDACTED.to_string(),
             Value::Bytes(b) => format!("<{} bytes>", b.len()),
+            Value::Blob(b) => format!("<{} blob, {} bytes, sha256:{}>", b.media_type, b.size, b.hash),
             Value::Json(j) => j.to_string(),
             Value::Array(arr) => {
                 let items: Vec<String> = arr.iter().map(|v| v.to_string()).collect();
@@ -70,6 +174,201 @@ impl Value {
         matches!(self, Value::Null)
     }
 
+    /// Whether this is an object, native or JSON
+    pub fn is_object(&self) -> bool {
+        matches!(self, Value::Object(_) | Value::Json(serde_json::Value::Object(_)))
+    }
+
+    /// Name of the value's type for messages; `Json` values report the
+    /// type of the JSON they hold
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "bool",
+            Value::Number(_) => "number",
+            Value::Integer(_) => "integer",
+            Value::Timestamp(_) => "timestamp",
+            Value::Decimal(_) => "decimal",
+            Value::String(_) => "string",
+            Value::Secret(_) => "secret",
+            Value::Bytes(_) => "bytes",
+            Value::Blob(_) => "blob",
+            Value::Json(j) => json_type_name(j),
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    /// Native value for a JSON tree. Integers stay exact: `Integer` within
+    /// `i64`, `Decimal` above it; other numbers become `Number`.
+    pub fn from_json(json: serde_json::Value) -> Value {
+        match json {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Bool(b),
+            serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => Value::Integer(i),
+                (None, Some(u)) => Value::Decimal(BigDecimal::from(u)),
+                _ => Value::Number(n.as_f64().unwrap_or_default()),
+            },
+            serde_json::Value::String(s) => Value::String(s),
+            serde_json::Value::Array(items) => {
+                Value::Array(items.into_iter().map(Value::from_json).collect())
+            }
+            serde_json::Value::Object(map) => {
+                Value::Object(map.into_iter().map(|(k, v)| (k, Value::from_json(v))).collect())
+            }
+        }
+    }
+
+    /// This value as natural JSON without losing precision: integers stay
+    /// integers, decimals become strings with every digit, timestamps
+    /// RFC 3339 strings, bytes base64, blobs their reference and secrets
+    /// the redacted placeholder. Use
+    /// [`Value::to_plain`] to keep the variants themselves.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::Null => serde_json::Value::Null,
+            Value::Bool(b) => (*b).into(),
+            Value::Number(n) => (*n).into(),
+            Value::Integer(i) => (*i).into(),
+            Value::Timestamp(t) => t.to_rfc3339().into(),
+            Value::Decimal(d) => d.to_string().into(),
+            Value::String(s) => s.clone().into(),
+            Value::Secret(_) => REDACTED.into(),
+            Value::Bytes(b) => base64::engine::general_purpose::STANDARD.encode(b).into(),
+            Value::Blob(blob) => serde_json::to_value(blob).unwrap_or_default(),
+            Value::Json(j) => j.clone(),
+            Value::Array(items) => items.iter().map(Value::to_json).collect(),
+            Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_json()))
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+        }
+    }
+
+    /// The same value with every `Json` tree turned into native values, so
+    /// objects are always `Object` and arrays always `Array`
+    pub fn normalize(self) -> Value {
+        match self {
+            Value::Json(j) => Value::from_json(j),
+            Value::Array(items) => Value::Array(items.into_iter().map(Value::normalize).collect()),
+            Value::Object(map) => {
+                Value::Object(map.into_iter().map(|(k, v)| (k, v.normalize())).collect())
+            }
+            other => other,
+        }
+    }
+
+    /// Deep-merge `other` into this value. Objects (native or JSON) merge
+    /// key by key; anything else, arrays included, is replaced by `other`.
+    pub fn merge(&mut self, other: Value) {
+        match (self, other) {
+            (Value::Json(base), Value::Json(over)) if base.is_object() && over.is_object() => {
+                merge_json(base, over)
+            }
+            (base, over) if base.is_object() && over.is_object() => {
+                if let Value::Json(j) = base {
+                    *base = Value::from_json(j.take());
+                }
+                let over = match over {
+                    Value::Json(j) => Value::from_json(j),
+                    native => native,
+                };
+                if let (Value::Object(base), Value::Object(over)) = (base, over) {
+                    for (key, value) in over {
+                        match base.get_mut(&key) {
+                            Some(existing) => existing.merge(value),
+                            None => {
+                                base.insert(key, value);
+                            }
+                        }
+                    }
+                }
+            }
+            (base, over) => *base = over,
+        }
+    }
+
+    fn mismatch(&self, field: &str, expected: &str) -> NodeError {
+        NodeError::InvalidInputType {
+            field: field.to_string(),
+            expected: expected.to_string(),
+            actual: self.type_name().to_string(),
+        }
+    }
+
+    /// String (native or JSON), or an `InvalidInputType` error for `field`
+    pub fn require_str(&self, field: &str) -> Result<&str, NodeError> {
+        match self {
+            Value::String(s) | Value::Json(serde_json::Value::String(s)) => Ok(s),
+            other => Err(other.mismatch(field, "string")),
+        }
+    }
+
+    /// Boolean (native or JSON), or an `InvalidInputType` error for `field`
+    pub fn require_bool(&self, field: &str) -> Result<bool, NodeError> {
+        match self {
+            Value::Bool(b) | Value::Json(serde_json::Value::Bool(b)) => Ok(*b),
+            other => Err(other.mismatch(field, "bool")),
+        }
+    }
+
+    /// Any number as `f64`, or an `InvalidInputType` error for `field`
+    pub fn require_f64(&self, field: &str) -> Result<f64, NodeError> {
+        match self {
+            Value::Json(serde_json::Value::Number(n)) => n.as_f64(),
+            other => other.as_f64(),
+        }
+        .ok_or_else(|| self.mismatch(field, "number"))
+    }
+
+    /// A whole number within `i64`, or an `InvalidInputType` error for `field`
+    pub fn require_i64(&self, field: &str) -> Result<i64, NodeError> {
+        match self {
+            Value::Json(serde_json::Value::Number(n)) => {
+                Value::from_json(serde_json::Value::Number(n.clone())).as_i64()
+            }
+            other => other.as_i64(),
+        }
+        .ok_or_else(|| self.mismatch(field, "integer"))
+    }
+
+    /// Timestamp, or a string holding an RFC 3339 one
+    pub fn require_timestamp(&self, field: &str) -> Result<DateTime<Utc>, NodeError> {
+        match self {
+            Value::Timestamp(t) => Some(*t),
+            Value::String(s) | Value::Json(serde_json::Value::String(s)) => {
+                DateTime::parse_from_rfc3339(s).ok().map(|t| t.with_timezone(&Utc))
+            }
+            _ => None,
+        }
+        .ok_or_else(|| self.mismatch(field, "timestamp"))
+    }
+
+    /// Items of an array (native or JSON), or an `InvalidInputType` error for `field`
+    pub fn require_array(&self, field: &str) -> Result<Vec<Value>, NodeError> {
+        match self {
+            Value::Array(items) => Ok(items.clone()),
+            Value::Json(serde_json::Value::Array(items)) => {
+                Ok(items.iter().cloned().map(Value::from_json).collect())
+            }
+            other => Err(other.mismatch(field, "array")),
+        }
+    }
+
+    /// Fields of an object (native or JSON), or an `InvalidInputType` error for `field`
+    pub fn require_object(&self, field: &str) -> Result<HashMap<String, Value>, NodeError> {
+        match self {
+            Value::Object(map) => Ok(map.clone()),
+            Value::Json(serde_json::Value::Object(map)) => Ok(map
+                .iter()
+                .map(|(k, v)| (k.clone(), Value::from_json(v.clone())))
+                .collect()),
+            other => Err(other.mismatch(field, "object")),
+        }
+    }
+
     pub fn as_bytes(&self) -> Option<&[u8]> {
         match self {
             Value::Bytes(b) => Some(b),
@@ -87,6 +386,35 @@ impl Value {
     }
 }
 
+/// Name of a JSON value's type, as [`Value::type_name`] reports it
+pub(crate) fn json_type_name(json: &serde_json::Value) -> &'static str {
+    match json {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "bool",
+        serde_json::Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+fn merge_json(base: &mut serde_json::Value, over: serde_json::Value) {
+    match (base, over) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(over)) => {
+            for (key, value) in over {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, over) => *base = over,
+    }
+}
+
 impl From<String> for Value {
     fn from(s: String) -> Self {
         Value::String(s)
@@ -107,7 +435,31 @@ impl From<f64> for Value {
 
 impl From<i64> for Value {
     fn from(n: i64) -> Self {
-        Value::Number(n as f64)
+        Value::Integer(n)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(n: i32) -> Self {
+        Value::Integer(n.into())
+    }
+}
+
+impl From<u32> for Value {
+    fn from(n: u32) -> Self {
+        Value::Integer(n.into())
+    }
+}
+
+impl From<DateTime<Utc>> for Value {
+    fn from(t: DateTime<Utc>) -> Self {
+        Value::Timestamp(t)
+    }
+}
+
+impl From<BigDecimal> for Value {
+    fn from(d: BigDecimal) -> Self {
+        Value::Decimal(d)
     }
 }
 
@@ -135,6 +487,18 @@ impl From<&[u8]> for Value {
     }
 }
 
+impl From<Secret> for Value {
+    fn from(s: Secret) -> Self {
+        Value::Secret(s)
+    }
+}
+
+impl From<BlobRef> for Value {
+    fn from(b: BlobRef) -> Self {
+        Value::Blob(b)
+    }
+}
+
 impl From<HashMap<String, Value>> for Value {
     fn from(m: HashMap<String, Value>) -> Self {
         Value::Object(m)
@@ -0,0 +1,229 @@
+//! Workflow variables and environment profiles
+//!
+//! Node config refers to variables as `${vars.name}` inside strings:
+//!
+//! ```yaml
+//! variables:
+//!   api: http://localhost:8080
+//!   timeout: 30
+//! nodes:
+//!   fetch:
+//!     type: http.request
+//!     config:
+//!       url: ${vars.api}/items
+//!       timeout: ${vars.timeout}
+//! ```
+//!
+//! A string that is exactly one reference takes the variable's value,
+//! type included, so `timeout` above stays a number and a variable holding
+//! `{"$credential": ...}` still refers to a credential. Elsewhere the value
+//! is spliced in as text. The workflow's `variables` are defaults; an
+//! [`Environment`] such as `prod` overrides them for one execution.
+
+use crate::{Value, Workflow, WorkflowError, WorkflowFormat};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Start of a variable reference; the name runs to the next `}`
+pub const VARIABLE_PREFIX: &str = "${vars.";
+
+/// Named set of variables overriding a workflow's defaults, e.g. the URLs
+/// and limits of `staging` or `prod`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Environment {
+    pub name: String,
+    #[serde(default)]
+    pub variables: HashMap<String, Value>,
+}
+
+impl Environment {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            variables: HashMap::new(),
+        }
+    }
+
+    pub fn with_variable(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.variables.insert(name.into(), value.into());
+        self
+    }
+
+    /// Profile from a document mapping variable names to plain or tagged
+    /// values
+    pub fn from_document(
+        name: impl Into<String>,
+        document: serde_json::Value,
+    ) -> Result<Self, WorkflowError> {
+        let name = name.into();
+        let variables = crate::format::plain_variables(document)
+            .map_err(|e| WorkflowError::Invalid(format!("environment '{}': {}", name, e)))?;
+        Ok(Self { name, variables })
+    }
+
+    /// Profile from a JSON, YAML or TOML file, named after the file
+    /// (`environments/prod.yaml` is `prod`)
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WorkflowError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            WorkflowError::Invalid(format!("cannot read {}: {}", path.display(), e))
+        })?;
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        Environment::from_document(name, WorkflowFormat::from_path(path).parse(&text)?)
+    }
+
+    /// The variables as a plain document, the inverse of
+    /// [`Environment::from_document`]
+    pub fn to_document(&self) -> serde_json::Value {
+        crate::format::plain_document(&self.variables)
+    }
+}
+
+/// Names referenced in `text`, in order
+fn references(text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(VARIABLE_PREFIX) {
+        let after = &rest[start + VARIABLE_PREFIX.len()..];
+        let Some(end) = after.find('}') else { break };
+        names.push(&after[..end]);
+        rest = &after[end + 1..];
+    }
+    names
+}
+
+/// Whether `json` holds a reference anywhere
+fn json_has_references(json: &serde_json::Value) -> bool {
+    match json {
+        serde_json::Value::String(s) => !references(s).is_empty(),
+        serde_json::Value::Array(items) => items.iter().any(json_has_references),
+        serde_json::Value::Object(map) => map.values().any(json_has_references),
+        _ => false,
+    }
+}
+
+/// `text` with its references replaced. `Err` names the variable that is
+/// unknown or can't be spliced into text.
+fn interpolate(text: &str, variables: &HashMap<String, Value>) -> Result<Value, String> {
+    let refs = references(text);
+    if let [name] = refs[..] {
+        let whole = text.len() == VARIABLE_PREFIX.len() + name.len() + 1;
+        if whole && text.starts_with(VARIABLE_PREFIX) {
+            return variables
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("unknown variable '{}'", name));
+        }
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut secret = false;
+    let mut rest = text;
+    for name in refs {
+        let start = rest.find(VARIABLE_PREFIX).unwrap_or_default();
+        out.push_str(&rest[..start]);
+        let value = variables
+            .get(name)
+            .ok_or_else(|| format!("unknown variable '{}'", name))?;
+        match value {
+            Value::Secret(s) => {
+                secret = true;
+                out.push_str(s.expose());
+            }
+            Value::String(s) => out.push_str(s),
+            Value::Json(serde_json::Value::String(s)) => out.push_str(s),
+            Value::Array(_) | Value::Object(_) | Value::Bytes(_) | Value::Blob(_) => {
+                return Err(format!(
+                    "variable '{}' is a {} and can only be used as a whole value",
+                    name,
+                    value.type_name()
+                ))
+            }
+            Value::Json(json) if json.is_array() || json.is_object() => {
+                return Err(format!(
+                    "variable '{}' is JSON and can only be used as a whole value",
+                    name
+                ))
+            }
+            other => out.push_str(&other.to_string()),
+        }
+        rest = &rest[start + VARIABLE_PREFIX.len() + name.len() + 1..];
+    }
+    out.push_str(rest);
+    // Text holding a secret is a secret
+    Ok(if secret {
+        Value::Secret(crate::Secret::new(out))
+    } else {
+        Value::String(out)
+    })
+}
+
+impl Value {
+    /// Names of the variables this value refers to, including in nested
+    /// values
+    pub fn variable_refs(&self) -> Vec<String> {
+        match self {
+            Value::String(s) => references(s).into_iter().map(String::from).collect(),
+            Value::Array(items) => items.iter().flat_map(Value::variable_refs).collect(),
+            Value::Object(map) => map.values().flat_map(Value::variable_refs).collect(),
+            Value::Json(json) if json_has_references(json) => {
+                Value::from_json(json.clone()).variable_refs()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// This value with every `${vars.name}` reference replaced. JSON
+    /// holding references becomes native values. `Err` explains the first
+    /// reference that can't be replaced.
+    pub fn with_variables(&self, variables: &HashMap<String, Value>) -> Result<Value, String> {
+        Ok(match self {
+            Value::String(s) if !references(s).is_empty() => interpolate(s, variables)?,
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|v| v.with_variables(variables))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| Ok((k.clone(), v.with_variables(variables)?)))
+                    .collect::<Result<_, String>>()?,
+            ),
+            Value::Json(json) if json_has_references(json) => {
+                Value::from_json(json.clone()).with_variables(variables)?
+            }
+            other => other.clone(),
+        })
+    }
+}
+
+impl Workflow {
+    /// This workflow with `overrides` (e.g. an environment's variables)
+    /// applied over its own variables, and every reference in node config
+    /// replaced. The result's `variables` are the ones in effect.
+    pub fn with_variables(
+        &self,
+        overrides: &HashMap<String, Value>,
+    ) -> Result<Workflow, WorkflowError> {
+        let mut workflow = self.clone();
+        workflow.variables.extend(overrides.clone());
+        for node in &mut workflow.nodes {
+            for (key, value) in node.config.iter_mut() {
+                *value = value.with_variables(&workflow.variables).map_err(|e| {
+                    WorkflowError::Invalid(format!(
+                        "node {} config.{}: {}",
+                        node.name.as_deref().unwrap_or(&node.id.to_string()),
+                        key,
+                        e
+                    ))
+                })?;
+            }
+        }
+        Ok(workflow)
+    }
+}
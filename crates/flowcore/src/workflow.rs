@@ -10,24 +10,36 @@ pub type NodeId = Uuid;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workflow {
     pub id: WorkflowId,
+    /// Stored revision this definition came from (0 = never saved)
+    #[serde(default)]
+    pub revision: u32,
     pub name: String,
     pub description: Option<String>,
     pub nodes: Vec<NodeSpec>,
+    #[serde(default)]
     pub connections: Vec<Connection>,
+    #[serde(default)]
     pub triggers: Vec<TriggerSpec>,
+    #[serde(default)]
     pub settings: WorkflowSettings,
+    /// Defaults for `${vars.name}` references in node config, overridden
+    /// per execution by an [`Environment`](crate::Environment)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, Value>,
 }
 
 impl Workflow {
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             id: Uuid::new_v4(),
+            revision: 0,
             name: name.into(),
             description: None,
             nodes: Vec::new(),
             connections: Vec::new(),
             triggers: Vec::new(),
             settings: WorkflowSettings::default(),
+            variables: HashMap::new(),
         }
     }
     
@@ -63,6 +75,7 @@ pub struct NodeSpec {
     pub id: NodeId,
     pub node_type: String,
     pub name: Option<String>,
+    #[serde(default)]
     pub config: HashMap<String, Value>,
     pub position: Option<Position>,
     pub retry_policy: Option<RetryPolicy>,
@@ -108,7 +121,7 @@ impl NodeSpec {
 }
 
 /// Connection between nodes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Connection {
     pub from_node: NodeId,
     pub from_port: String,
@@ -170,9 +183,91 @@ pub struct TriggerSpec {
 #[serde(tag = "type")]
 pub enum TriggerType {
     Manual,
-    Cron { expression: String },
-    Webhook { path: String },
-    Event { event_type: String },
+    /// Standard 5-field cron expression (`min hour dom mon dow`), or 6/7
+    /// fields with leading seconds and trailing year
+    Cron {
+        expression: String,
+        /// IANA timezone the expression is evaluated in (default UTC)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timezone: Option<String>,
+        #[serde(default)]
+        catch_up: CatchUpPolicy,
+    },
+    /// HTTP requests to `/hooks/{path}`, any method
+    Webhook {
+        path: String,
+        /// Shared secret; when set, requests must carry a hex HMAC-SHA256
+        /// of the body in `signature_header`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        secret: Option<String>,
+        /// Defaults to `X-Signature-256`; a `sha256=` prefix is accepted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature_header: Option<String>,
+        #[serde(default)]
+        response: WebhookResponse,
+    },
+    /// Another workflow finishing (`workflow.completed`, `workflow.failed`,
+    /// `workflow.cancelled`) or a custom event emitted by a node
+    Event {
+        event_type: String,
+        /// Only events from this workflow (default: any)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source_workflow: Option<Uuid>,
+        /// Workflow input name to JSON Pointer into the event payload; the
+        /// whole payload is always available as the `event` input
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        inputs: HashMap<String, String>,
+    },
+    /// Files appearing, changing or disappearing under a local directory.
+    /// Changes within `debounce_ms` of each other start a single run.
+    FileWatch {
+        /// Directory to watch, recursively
+        path: String,
+        /// Only files whose path relative to `path` matches, e.g. `**/*.csv`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        glob: Option<String>,
+        #[serde(default = "default_file_events")]
+        events: Vec<FileEvent>,
+        /// Quiet period before a batch of changes starts a run (default 500)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        debounce_ms: Option<u64>,
+    },
+}
+
+/// Kinds of file changes a file-watch trigger reacts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FileEvent {
+    Created,
+    Modified,
+    Removed,
+}
+
+fn default_file_events() -> Vec<FileEvent> {
+    vec![FileEvent::Created, FileEvent::Modified]
+}
+
+/// How a webhook request is answered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookResponse {
+    /// `202 Accepted` with the execution id as soon as the run starts
+    #[default]
+    Immediate,
+    /// Wait for a `webhook.respond` node and reply with its status,
+    /// headers and body
+    ResponseNode,
+}
+
+/// What a cron trigger does about fire times missed while no scheduler
+/// was running
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CatchUpPolicy {
+    /// Drop missed runs and wait for the next fire time
+    #[default]
+    Skip,
+    /// Run once for the most recent missed fire time
+    Latest,
+    /// Run once for every missed fire time, oldest first (bounded)
+    All,
 }
 
 /// Global workflow settings
@@ -181,6 +276,9 @@ pub struct WorkflowSettings {
     pub max_execution_time_ms: Option<u64>,
     pub max_parallel_nodes: usize,
     pub on_error: ErrorHandling,
+    /// Workflow started with the failure context when an execution fails
+    #[serde(default)]
+    pub on_failure: Option<Uuid>,
 }
 
 impl Default for WorkflowSettings {
@@ -189,6 +287,7 @@ impl Default for WorkflowSettings {
             max_execution_time_ms: None,
             max_parallel_nodes: 10,
             on_error: ErrorHandling::StopWorkflow,
+            on_failure: None,
         }
     }
 }
@@ -0,0 +1,156 @@
+use flowcore::{NodeSpec, Value, Workflow, WorkflowFormat};
+use serde_json::json;
+
+#[test]
+fn test_plain_and_tagged_config_mix() {
+    let yaml = r#"
+id: 6f1c8a52-3b1e-4c1e-9a7e-2d3c4b5a6f70
+name: Plain
+nodes:
+  - id: 0b7e3c1a-8f2d-4e5b-9c6a-1d2e3f4a5b6c
+    node_type: shell.exec
+    config:
+      command: echo
+      args: [hello, 2]
+      timeout: 1.5
+      shell: false
+      env:
+        MODE: { type: String, value: fast }
+      since: { type: Timestamp, value: "2026-01-02T03:04:05Z" }
+      token: { type: Secret, value: s3cret }
+"#;
+    let workflow = Workflow::parse(yaml, WorkflowFormat::Yaml).unwrap();
+    let config = &workflow.nodes[0].config;
+
+    assert_eq!(config["command"], Value::String("echo".into()));
+    assert_eq!(
+        config["args"],
+        Value::Array(vec![Value::String("hello".into()), Value::Integer(2)])
+    );
+    assert_eq!(config["timeout"], Value::Number(1.5));
+    assert_eq!(config["shell"], Value::Bool(false));
+    assert_eq!(
+        config["env"],
+        Value::Object([("MODE".to_string(), Value::String("fast".into()))].into())
+    );
+    assert!(config["since"].as_timestamp().is_some());
+    assert_eq!(config["token"].reveal_str(), Some("s3cret"));
+    assert!(workflow.connections.is_empty());
+}
+
+#[test]
+fn test_plain_round_trip() {
+    let mut workflow = Workflow::new("round trip");
+    workflow.add_node(
+        NodeSpec::new("shell.exec")
+            .with_config("command", "ls")
+            .with_config("count", 3i64)
+            .with_config("ratio", 2.0)
+            .with_config("body", Value::Json(json!({"a": 1})))
+            .with_config(
+                "lookalike",
+                Value::Object([("type".to_string(), Value::String("String".into()))].into()),
+            ),
+    );
+
+    for format in [WorkflowFormat::Json, WorkflowFormat::Yaml] {
+        let text = format.render(&workflow.to_document(true)).unwrap();
+        let parsed = Workflow::parse(&text, format).unwrap();
+        assert_eq!(parsed.nodes[0].config, workflow.nodes[0].config);
+    }
+
+    let plain = workflow.to_document(true);
+    assert_eq!(plain["nodes"][0]["config"]["command"], json!("ls"));
+    assert_eq!(plain["nodes"][0]["config"]["count"], json!(3));
+}
+
+#[test]
+fn test_invalid_tagged_value_reports_path() {
+    let document = json!({
+        "id": "6f1c8a52-3b1e-4c1e-9a7e-2d3c4b5a6f70",
+        "name": "bad",
+        "nodes": [{
+            "id": "0b7e3c1a-8f2d-4e5b-9c6a-1d2e3f4a5b6c",
+            "node_type": "shell.exec",
+            "config": {"timeout": {"type": "Number", "value": "soon"}}
+        }]
+    });
+    let err = Workflow::from_document(document).unwrap_err().to_string();
+    assert!(err.contains("nodes[0].config.timeout"), "{}", err);
+}
+
+const NAMED_YAML: &str = r#"
+name: Fetch and log
+nodes:
+  fetch:
+    type: http.request
+    config: { method: GET }
+  log:
+    type: debug.log
+    name: Log Result
+connections:
+  - fetch.body -> log.message
+  - from: fetch.headers
+    to: log.file:out/headers.json
+triggers:
+  nightly:
+    type: Cron
+    expression: "0 3 * * *"
+    enabled: false
+"#;
+
+#[test]
+fn test_named_document_resolves_references() {
+    let workflow = Workflow::parse(NAMED_YAML, WorkflowFormat::Yaml).unwrap();
+    let fetch = workflow.nodes.iter().find(|n| n.node_type == "http.request").unwrap();
+    let log = workflow.nodes.iter().find(|n| n.node_type == "debug.log").unwrap();
+
+    assert_eq!(fetch.name.as_deref(), Some("fetch"));
+    assert_eq!(log.name.as_deref(), Some("Log Result"));
+    assert_eq!(fetch.config["method"], Value::String("GET".into()));
+    assert_eq!(workflow.connections[0].from_node, fetch.id);
+    assert_eq!(workflow.connections[0].to_node, log.id);
+    assert_eq!(workflow.connections[1].to_port, "file:out/headers.json");
+    assert!(!workflow.triggers[0].enabled);
+
+    // Ids are derived, so loading again gives the same ones
+    let again = Workflow::parse(NAMED_YAML, WorkflowFormat::Yaml).unwrap();
+    assert_eq!(again.id, workflow.id);
+    assert_eq!(again.connections, workflow.connections);
+    assert_eq!(again.triggers[0].id, workflow.triggers[0].id);
+}
+
+#[test]
+fn test_named_document_round_trip() {
+    let workflow = Workflow::parse(NAMED_YAML, WorkflowFormat::Yaml).unwrap();
+    let document = workflow.to_named_document();
+    assert!(document.get("id").is_none());
+    assert_eq!(document["connections"][0], json!("fetch.body -> Log Result.message"));
+
+    for format in [WorkflowFormat::Json, WorkflowFormat::Yaml, WorkflowFormat::Toml] {
+        let text = format.render(&document).unwrap();
+        let parsed = Workflow::parse(&text, format).unwrap();
+        assert_eq!(parsed.id, workflow.id);
+        assert_eq!(parsed.connections, workflow.connections);
+        let ids = |w: &Workflow| {
+            let mut ids: Vec<_> = w.nodes.iter().map(|n| (n.id, n.name.clone())).collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(ids(&parsed), ids(&workflow));
+        assert_eq!(parsed.triggers[0].id, workflow.triggers[0].id);
+    }
+}
+
+#[test]
+fn test_named_document_unknown_node() {
+    let toml = r#"
+name = "broken"
+connections = ["a.out -> missing.in"]
+
+[nodes.a]
+type = "debug.log"
+"#;
+    let err = Workflow::parse(toml, WorkflowFormat::Toml).unwrap_err();
+    assert!(err.to_string().contains("missing"), "{}", err);
+}
@@ -0,0 +1,153 @@
+use flowcore::{BigDecimal, NodeError, PathError, Secret, SecretMask, Value};
+use serde_json::json;
+use std::collections::HashMap;
+
+fn order() -> Value {
+    Value::Object(HashMap::from([
+        ("id".to_string(), Value::Integer(7)),
+        (
+            "items".to_string(),
+            Value::Json(json!([
+                {"sku": "a-1", "qty": 2, "tags": {"color": "red"}},
+                {"sku": "b/2", "qty": 1.5}
+            ])),
+        ),
+    ]))
+}
+
+#[test]
+fn test_pointer_crosses_representations() {
+    let order = order();
+    assert_eq!(order.pointer("/id"), Some(Value::Integer(7)));
+    assert_eq!(order.pointer("/items/0/qty"), Some(Value::Integer(2)));
+    assert_eq!(order.pointer("/items/1/sku"), Some(Value::String("b/2".into())));
+    assert_eq!(order.pointer("/items/0/tags"), Some(Value::Object(HashMap::from([(
+        "color".to_string(),
+        Value::String("red".into())
+    )]))));
+    assert_eq!(order.pointer("/items/5"), None);
+    assert_eq!(order.pointer("items"), None);
+    assert_eq!(order.pointer(""), Some(order.clone()));
+}
+
+#[test]
+fn test_jsonpath_query() {
+    let order = order();
+    assert_eq!(
+        order.query("$.items[*].sku").unwrap(),
+        vec![Value::String("a-1".into()), Value::String("b/2".into())]
+    );
+    assert_eq!(order.query("$.items[-1].qty").unwrap(), vec![Value::Number(1.5)]);
+    assert_eq!(order.query("$..color").unwrap(), vec![Value::String("red".into())]);
+    assert_eq!(order.query("$['items'][0]['sku']").unwrap(), vec![Value::String("a-1".into())]);
+    assert!(order.query("$.missing").unwrap().is_empty());
+    assert!(matches!(order.query("items"), Err(PathError::Syntax { .. })));
+    assert!(matches!(order.query("$.items[x]"), Err(PathError::Syntax { .. })));
+}
+
+#[test]
+fn test_set_pointer_and_path() {
+    let mut order = order();
+    order.set_pointer("/items/0/qty", 3i64).unwrap();
+    order.set_pointer("/items/-", json!({"sku": "c"})).unwrap();
+    order.set_pointer("/shipping/address/city", "Oslo").unwrap();
+    order.set_path("$.items[1].sku", "b-2").unwrap();
+
+    assert_eq!(order.pointer("/items/0/qty"), Some(Value::Integer(3)));
+    assert_eq!(order.pointer("/items/2/sku"), Some(Value::String("c".into())));
+    assert_eq!(order.pointer("/items/1/sku"), Some(Value::String("b-2".into())));
+    assert_eq!(order.pointer("/shipping/address/city"), Some(Value::String("Oslo".into())));
+    // Writes into a JSON tree keep it JSON
+    assert!(matches!(order.pointer("/items"), Some(Value::Json(_))));
+
+    assert!(matches!(order.set_pointer("/id/x", 1i64), Err(PathError::Set { .. })));
+    assert!(matches!(order.set_pointer("/items/9", 1i64), Err(PathError::Set { .. })));
+    assert!(matches!(order.set_path("$.items[*].sku", "x"), Err(PathError::Set { .. })));
+}
+
+#[test]
+fn test_json_conversions() {
+    let json = json!({"n": 1, "big": u64::MAX, "f": 0.5, "list": [true, null, "s"]});
+    let value = Value::from_json(json.clone());
+    assert_eq!(value.pointer("/n"), Some(Value::Integer(1)));
+    assert_eq!(value.pointer("/big"), Some(Value::Decimal(BigDecimal::from(u64::MAX))));
+    assert_eq!(value.pointer("/f"), Some(Value::Number(0.5)));
+
+    assert_eq!(Value::Json(json.clone()).normalize(), value);
+    assert_eq!(Value::Bytes(b"hi".to_vec()).to_json(), json!("aGk="));
+    assert_eq!(
+        Value::Decimal("12345678901234567890.123".parse().unwrap()).to_json(),
+        json!("12345678901234567890.123")
+    );
+    assert_eq!(Value::from_json(value.to_json()).pointer("/list/2"), Some(Value::String("s".into())));
+}
+
+#[test]
+fn test_typed_extractors_report_actual_type() {
+    assert_eq!(Value::Json(json!("x")).require_str("name").unwrap(), "x");
+    assert_eq!(Value::Json(json!(3)).require_i64("count").unwrap(), 3);
+    assert_eq!(Value::Number(4.0).require_i64("count").unwrap(), 4);
+    assert_eq!(Value::Integer(2).require_f64("ratio").unwrap(), 2.0);
+    assert!(Value::String("2026-01-02T03:04:05Z".into()).require_timestamp("at").is_ok());
+    assert_eq!(Value::Json(json!([1, 2])).require_array("list").unwrap().len(), 2);
+    assert!(order().pointer("/items/0").unwrap().require_object("item").is_ok());
+
+    match Value::Integer(5).require_str("url") {
+        Err(NodeError::InvalidInputType { field, expected, actual }) => {
+            assert_eq!((field.as_str(), expected.as_str(), actual.as_str()), ("url", "string", "integer"));
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    match Value::Json(json!({"a": 1})).require_bool("flag") {
+        Err(NodeError::InvalidInputType { actual, .. }) => assert_eq!(actual, "object"),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_deep_merge() {
+    let mut base = Value::Object(HashMap::from([
+        ("name".to_string(), Value::String("a".into())),
+        ("opts".to_string(), Value::Json(json!({"retries": 1, "tags": ["x"], "nested": {"keep": true}}))),
+    ]));
+    base.merge(Value::Json(json!({"opts": {"retries": 3, "tags": ["y"], "nested": {"add": 1}}, "extra": null})));
+
+    assert_eq!(base.pointer("/name"), Some(Value::String("a".into())));
+    assert_eq!(base.pointer("/opts/retries"), Some(Value::Integer(3)));
+    assert_eq!(base.pointer("/opts/tags"), Some(Value::Array(vec![Value::String("y".into())])));
+    assert_eq!(base.pointer("/opts/nested/keep"), Some(Value::Bool(true)));
+    assert_eq!(base.pointer("/opts/nested/add"), Some(Value::Integer(1)));
+    assert_eq!(base.pointer("/extra"), Some(Value::Null));
+
+    let mut json = Value::Json(json!({"a": {"b": 1}}));
+    json.merge(Value::Json(json!({"a": {"c": 2}})));
+    assert_eq!(json, Value::Json(json!({"a": {"b": 1, "c": 2}})));
+
+    let mut scalar = Value::Integer(1);
+    scalar.merge(Value::String("replaced".into()));
+    assert_eq!(scalar, Value::String("replaced".into()));
+}
+
+#[test]
+fn test_secret_redacted_outside_the_process() {
+    let secret = Value::Secret(Secret::new("hunter22"));
+    let config = Value::Object(HashMap::from([("token".to_string(), secret.clone())]));
+
+    assert_eq!(secret.reveal_str(), Some("hunter22"));
+    assert_eq!(secret.as_str(), None);
+    assert_eq!(secret.to_string(), "********");
+    assert_eq!(format!("{:?}", secret), "Secret(********)");
+    assert_eq!(config.to_json(), json!({"token": "********"}));
+    assert_eq!(
+        serde_json::to_value(&config).unwrap(),
+        json!({"type": "Object", "value": {"token": {"type": "Secret", "value": "********"}}})
+    );
+    assert_eq!(config.secrets().len(), 1);
+
+    let mask = SecretMask::new(config.secrets());
+    assert_eq!(mask.mask("Authorization: hunter22"), "Authorization: ********");
+    assert_eq!(
+        mask.mask_value(&Value::Json(json!({"echo": ["x hunter22"]}))),
+        Value::Json(json!({"echo": ["x ********"]}))
+    );
+}
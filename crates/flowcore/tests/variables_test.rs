@@ -0,0 +1,84 @@
+use flowcore::{Environment, Value, Workflow, WorkflowFormat};
+use serde_json::json;
+use std::collections::HashMap;
+
+const WORKFLOW: &str = r#"
+name: Deploy
+variables:
+  zypi_url: http://localhost:8080
+  memory_mb: 512
+nodes:
+  deploy:
+    type: http.request
+    config:
+      url: ${vars.zypi_url}/vms
+      memory: ${vars.memory_mb}
+      label: ${vars.memory_mb} MB
+      headers:
+        Authorization: Bearer ${vars.token}
+"#;
+
+#[test]
+fn test_defaults_and_environment_overrides() {
+    let workflow = Workflow::parse(WORKFLOW, WorkflowFormat::Yaml).unwrap();
+    assert_eq!(workflow.variables["memory_mb"], Value::Integer(512));
+
+    // Without a token the reference can't be resolved
+    let err = workflow.with_variables(&HashMap::new()).unwrap_err();
+    assert!(err.to_string().contains("node deploy config.headers: unknown variable 'token'"));
+
+    let prod = Environment::from_document(
+        "prod",
+        json!({
+            "zypi_url": "https://zypi.prod.example.com",
+            "memory_mb": 2048,
+            "token": {"type": "Secret", "value": "s3cret"},
+        }),
+    )
+    .unwrap();
+    let resolved = workflow.with_variables(&prod.variables).unwrap();
+    let config = &resolved.nodes[0].config;
+
+    assert_eq!(config["url"], Value::String("https://zypi.prod.example.com/vms".into()));
+    // A whole reference keeps the variable's type, a partial one is text
+    assert_eq!(config["memory"], Value::Integer(2048));
+    assert_eq!(config["label"], Value::String("2048 MB".into()));
+    // Text holding a secret stays secret
+    let auth = config["headers"].pointer("/Authorization").unwrap();
+    assert!(matches!(auth, Value::Secret(_)));
+    assert_eq!(auth.reveal_str(), Some("Bearer s3cret"));
+    assert_eq!(resolved.variables["memory_mb"], Value::Integer(2048));
+}
+
+#[test]
+fn test_containers_only_as_whole_values() {
+    let variables = HashMap::from([(
+        "hosts".to_string(),
+        Value::Array(vec![Value::String("a".into()), Value::String("b".into())]),
+    )]);
+
+    let whole = Value::String("${vars.hosts}".into());
+    assert_eq!(whole.with_variables(&variables).unwrap(), variables["hosts"]);
+    assert_eq!(whole.variable_refs(), ["hosts"]);
+
+    let spliced = Value::String("hosts: ${vars.hosts}".into());
+    assert!(spliced.with_variables(&variables).is_err());
+}
+
+#[test]
+fn test_variables_round_trip() {
+    let workflow = Workflow::parse(WORKFLOW, WorkflowFormat::Yaml).unwrap();
+    for (document, format) in [
+        (workflow.to_document(true), WorkflowFormat::Yaml),
+        (workflow.to_named_document(), WorkflowFormat::Toml),
+    ] {
+        let text = format.render(&document).unwrap();
+        let parsed = Workflow::parse(&text, format).unwrap();
+        assert_eq!(parsed.variables, workflow.variables);
+    }
+
+    let prod = Environment::new("prod").with_variable("memory_mb", 2048i64);
+    let document = prod.to_document();
+    assert_eq!(document, json!({"memory_mb": 2048}));
+    assert_eq!(Environment::from_document("prod", document).unwrap(), prod);
+}
@@ -13,7 +13,7 @@
 
 use async_trait::async_trait;
 use flowcore::{Node, NodeContext, NodeError, NodeOutput, Value};
-use flowruntime::{NodeFactory, NodeMetadata, PortDefinition};
+use flowruntime::{ConfigDefinition, NodeFactory, NodeMetadata, PortDefinition};
 use std::collections::HashMap;
 
 pub struct ApiCallNode {
@@ -166,7 +166,7 @@ impl Node for ApiCallNode {
         let mut env = serde_json::Map::new();
         if let Some(Value::Object(config_env)) = ctx.config.get("env") {
             for (k, v) in config_env {
-                if let Some(s) = v.as_str() {
+                if let Some(s) = v.reveal_str() {
                     env.insert(k.clone(), serde_json::Value::String(s.to_string()));
                 }
             }
@@ -175,11 +175,12 @@ impl Node for ApiCallNode {
         for (key, value) in &ctx.inputs {
             let env_key = key.to_uppercase();
             match value {
-                Value::String(s) => {
-                    env.insert(env_key, serde_json::Value::String(s.clone()));
+                Value::String(_) | Value::Secret(_) => {
+                    let s = value.reveal_str().unwrap_or_default();
+                    env.insert(env_key, serde_json::Value::String(s.to_string()));
                 }
-                Value::Number(n) => {
-                    env.insert(env_key, serde_json::Value::String(n.to_string()));
+                Value::Number(_) | Value::Integer(_) | Value::Decimal(_) | Value::Timestamp(_) => {
+                    env.insert(env_key, serde_json::Value::String(value.to_string()));
                 }
                 _ => {}
             }
@@ -272,8 +273,9 @@ impl NodeFactory for ApiCallNodeFactory {
                     .to_string(),
             category: "api".to_string(),
             inputs: vec![PortDefinition {
-                name: "stdin".to_string(),
-                description: "Data piped to script's stdin".to_string(),
+                name: "<name>".to_string(),
+                description: "Passed to the script as the environment variable <NAME>"
+                    .to_string(),
                 required: false,
             }],
             outputs: vec![
@@ -288,6 +290,26 @@ impl NodeFactory for ApiCallNodeFactory {
                     description: "Raw stdout".to_string(),
                     required: false,
                 },
+                PortDefinition {
+                    name: "stderr".to_string(),
+                    description: "Raw stderr".to_string(),
+                    required: false,
+                },
+                PortDefinition {
+                    name: "duration_ms".to_string(),
+                    description: "Execution time in milliseconds".to_string(),
+                    required: false,
+                },
+            ],
+            config: vec![
+                ConfigDefinition::new("script", "Python script to run", true),
+                ConfigDefinition::new("packages", "pip packages to install first, as a list or comma-separated", false),
+                ConfigDefinition::new("image", "Sandbox image (default ubuntu:24.04)", false),
+                ConfigDefinition::new("timeout", "Timeout in seconds (default 60)", false),
+                ConfigDefinition::new("memory_mb", "VM memory in MB (default 256)", false),
+                ConfigDefinition::new("zypi_url", "Zypi server URL (default http://localhost:4000)", false),
+                ConfigDefinition::new("name", "Name used in log messages", false),
+                ConfigDefinition::new("env", "Environment variables for the script, e.g. API keys", false),
             ],
         }
     }
@@ -13,7 +13,7 @@
 
 use async_trait::async_trait;
 use flowcore::{Node, NodeContext, NodeError, NodeOutput, Value};
-use flowruntime::{NodeFactory, NodeMetadata, PortDefinition};
+use flowruntime::{ConfigDefinition, NodeFactory, NodeMetadata, PortDefinition};
 use std::collections::HashMap;
 
 pub struct BrowserRenderNode;
@@ -260,6 +260,21 @@ impl NodeFactory for BrowserRenderNodeFactory {
                     description: "Raw stdout from chromium".to_string(),
                     required: false,
                 },
+                PortDefinition {
+                    name: "duration_ms".to_string(),
+                    description: "Render time in milliseconds".to_string(),
+                    required: false,
+                },
+            ],
+            config: vec![
+                ConfigDefinition::new("url", "URL to render", false),
+                ConfigDefinition::new("html", "Inline HTML to render instead of a URL", false),
+                ConfigDefinition::new("mode", "dom, text or screenshot (default dom)", false),
+                ConfigDefinition::new("wait_ms", "Extra time for JavaScript to run (default 1000)", false),
+                ConfigDefinition::new("memory_mb", "VM memory in MB (default 512)", false),
+                ConfigDefinition::new("timeout", "Timeout in seconds (default 30)", false),
+                ConfigDefinition::new("zypi_url", "Zypi server URL (default http://localhost:4000)", false),
+                ConfigDefinition::new("image", "Sandbox image (default ubuntu:24.04)", false),
             ],
         }
     }
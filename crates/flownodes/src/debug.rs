@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use flowcore::{Node, NodeContext, NodeError, NodeOutput, Value};
-use flowruntime::{NodeFactory, NodeMetadata};
+use flowruntime::{NodeFactory, NodeMetadata, PortDefinition};
 use std::collections::HashMap;
 
 /// Simple debug node that logs its inputs
@@ -44,8 +44,26 @@ impl NodeFactory for DebugNodeFactory {
         NodeMetadata {
             description: "Logs input values for debugging".to_string(),
             category: "debug".to_string(),
-            inputs: vec![],
-            outputs: vec![],
+            inputs: vec![
+                PortDefinition {
+                    name: "message".to_string(),
+                    description: "Message to log".to_string(),
+                    required: false,
+                },
+                PortDefinition {
+                    name: "<name>".to_string(),
+                    description: "Logged with its value".to_string(),
+                    required: false,
+                },
+            ],
+            outputs: vec![
+                PortDefinition {
+                    name: "message".to_string(),
+                    description: "Message that was logged".to_string(),
+                    required: false,
+                },
+            ],
+            config: vec![],
         }
     }
 }
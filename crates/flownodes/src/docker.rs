@@ -3,12 +3,15 @@
 
 use async_trait::async_trait;
 use flowcore::{Node, NodeContext, NodeError, NodeOutput, Value};
-use flowruntime::{NodeFactory, NodeMetadata, PortDefinition};
+use flowruntime::{ConfigDefinition, NodeFactory, NodeMetadata, PortDefinition};
 use std::collections::HashMap;
 use std::process::Stdio;
 use tokio::process::Command;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
+/// Where containers and sandboxes see the execution's workspace
+pub(crate) const SANDBOX_WORKSPACE: &str = "/workspace";
+
 /// Node that executes Docker containers with extensive configuration options
 pub struct DockerNode;
 
@@ -88,7 +91,7 @@ impl DockerNode {
         let mut env = HashMap::new();
         if let Some(Value::Object(env_obj)) = ctx.config.get("env") {
             for (key, value) in env_obj {
-                if let Some(val_str) = value.as_str() {
+                if let Some(val_str) = value.reveal_str() {
                     env.insert(key.clone(), val_str.to_string());
                 } else if let Value::Json(json_val) = value {
                     env.insert(key.clone(), json_val.to_string());
@@ -245,13 +248,11 @@ impl DockerNode {
             StdinMode::None => Ok(Vec::new()),
             StdinMode::Raw => {
                 // Get raw data from input
-                ctx.inputs.get("data")
-                    .and_then(|v| match v {
-                        Value::String(s) => Some(s.as_bytes().to_vec()),
-                        Value::Bytes(b) => Some(b.clone()),
-                        _ => None,
-                    })
-                    .ok_or_else(|| NodeError::MissingInput("data".to_string()))
+                match ctx.inputs.get("data") {
+                    Some(Value::String(s)) => Ok(s.as_bytes().to_vec()),
+                    Some(value @ (Value::Bytes(_) | Value::Blob(_))) => ctx.value_bytes(value).await,
+                    _ => Err(NodeError::MissingInput("data".to_string())),
+                }
             }
             StdinMode::Json => {
                 // Serialize all inputs as JSON
@@ -319,6 +320,18 @@ impl Node for DockerNode {
             ctx.events.info(format!("  📂 Volume: {}", mount_str));
             cmd.arg("-v").arg(mount_str);
         }
+
+        // The execution's workspace, shared with its other nodes
+        if let Some(ref workspace) = ctx.workspace {
+            if !config.volumes.iter().any(|v| v.container_path == SANDBOX_WORKSPACE) {
+                let files = ctx.file_inputs();
+                if !files.is_empty() {
+                    ctx.write_files(&files).await?;
+                }
+                cmd.arg("-v").arg(format!("{}:{}", workspace.display(), SANDBOX_WORKSPACE));
+                cmd.arg("-e").arg(format!("FLOW_WORKSPACE={}", SANDBOX_WORKSPACE));
+            }
+        }
         
         // Working directory
         if let Some(ref workdir) = config.working_dir {
@@ -512,6 +525,16 @@ impl NodeFactory for DockerNodeFactory {
                     name: "data".to_string(),
                     description: "Data to pass to container (mode depends on stdin_mode config)".to_string(),
                     required: false,
+                },
+                PortDefinition {
+                    name: "<name>".to_string(),
+                    description: "Sent to the container as a field of the JSON on stdin (stdin_mode json)".to_string(),
+                    required: false,
+                },
+                PortDefinition {
+                    name: "file:<path>".to_string(),
+                    description: "File written to the workspace mounted at /workspace".to_string(),
+                    required: false,
                 }
             ],
             outputs: vec![
@@ -541,6 +564,24 @@ impl NodeFactory for DockerNodeFactory {
                     required: false,
                 }
             ],
+            config: vec![
+                ConfigDefinition::new("image", "Image to run", true),
+                ConfigDefinition::new("command", "Command, as a list or a shell-quoted string", false),
+                ConfigDefinition::new("entrypoint", "Entrypoint override", false),
+                ConfigDefinition::new("env", "Environment variables", false),
+                ConfigDefinition::new("volumes", "Mounts, as host:container[:ro] strings", false),
+                ConfigDefinition::new("workdir", "Working directory in the container", false),
+                ConfigDefinition::new("user", "User to run as", false),
+                ConfigDefinition::new("network", "Network to attach to", false),
+                ConfigDefinition::new("cpu_limit", "CPU limit, e.g. \"1.5\"", false),
+                ConfigDefinition::new("memory_limit", "Memory limit, e.g. \"512m\"", false),
+                ConfigDefinition::new("stdin_mode", "none, raw, json or text (default json)", false),
+                ConfigDefinition::new("output_mode", "auto, json or text (default auto)", false),
+                ConfigDefinition::new("auto_pull", "Pull the image if it is missing (default true)", false),
+                ConfigDefinition::new("detached", "Run in the background (default false)", false),
+                ConfigDefinition::new("remove", "Remove the container afterwards (default true)", false),
+                ConfigDefinition::new("timeout", "Timeout in seconds", false),
+            ],
         }
     }
 }
@@ -3,12 +3,13 @@
 
 use async_trait::async_trait;
 use flowcore::{Node, NodeContext, NodeError, NodeOutput, Value};
-use flowruntime::{NodeFactory, NodeMetadata, PortDefinition};
+use flowruntime::{ConfigDefinition, NodeFactory, NodeMetadata, PortDefinition};
 use std::collections::HashMap;
 use std::process::Stdio;
 use tokio::process::Command;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use serde_json::json;
+use crate::docker::SANDBOX_WORKSPACE;
 
 /// Enhanced Docker node with flexible I/O modes
 pub struct DockerNodeV2;
@@ -100,7 +101,7 @@ impl DockerNodeV2 {
         let mut env = HashMap::new();
         if let Some(Value::Object(env_obj)) = ctx.config.get("env") {
             for (key, value) in env_obj {
-                if let Some(val_str) = value.as_str() {
+                if let Some(val_str) = value.reveal_str() {
                     env.insert(key.clone(), val_str.to_string());
                 } else if let Value::Json(json_val) = value {
                     env.insert(key.clone(), json_val.to_string());
@@ -232,7 +233,13 @@ impl DockerNodeV2 {
             Value::Null => json!(null),
             Value::Bool(b) => json!(b),
             Value::Number(n) => json!(n),
+            Value::Integer(i) => json!(i),
+            Value::Timestamp(t) => json!(t.to_rfc3339()),
+            // A JSON number would round it to f64
+            Value::Decimal(d) => json!(d.to_string()),
             Value::String(s) => json!(s),
+            // Input for the container itself, which needs the clear text
+            Value::Secret(s) => json!(s.expose()),
             Value::Json(j) => j.clone(),
             Value::Array(arr) => {
                 json!(arr.iter().map(|v| Self::value_to_json_flat(v)).collect::<Vec<_>>())
@@ -245,6 +252,7 @@ impl DockerNodeV2 {
                 json!(map)
             }
             Value::Bytes(_) => json!(null), // Can't represent bytes in JSON
+            Value::Blob(blob) => json!(blob),
         }
     }
     
@@ -308,13 +316,11 @@ impl DockerNodeV2 {
         match stdin_mode {
             StdinMode::None => Ok(Vec::new()),
             StdinMode::Raw => {
-                ctx.inputs.get("data")
-                    .and_then(|v| match v {
-                        Value::String(s) => Some(s.as_bytes().to_vec()),
-                        Value::Bytes(b) => Some(b.clone()),
-                        _ => None,
-                    })
-                    .ok_or_else(|| NodeError::MissingInput("data".to_string()))
+                match ctx.inputs.get("data") {
+                    Some(Value::String(s)) => Ok(s.as_bytes().to_vec()),
+                    Some(value @ (Value::Bytes(_) | Value::Blob(_))) => ctx.value_bytes(value).await,
+                    _ => Err(NodeError::MissingInput("data".to_string())),
+                }
             }
             StdinMode::Json => {
                 // NEW: Use io_mode to control serialization
@@ -374,6 +380,18 @@ impl Node for DockerNodeV2 {
             ctx.events.info(format!("  📂 Volume: {}", mount_str));
             cmd.arg("-v").arg(mount_str);
         }
+
+        // The execution's workspace, shared with its other nodes
+        if let Some(ref workspace) = ctx.workspace {
+            if !config.volumes.iter().any(|v| v.container_path == SANDBOX_WORKSPACE) {
+                let files = ctx.file_inputs();
+                if !files.is_empty() {
+                    ctx.write_files(&files).await?;
+                }
+                cmd.arg("-v").arg(format!("{}:{}", workspace.display(), SANDBOX_WORKSPACE));
+                cmd.arg("-e").arg(format!("FLOW_WORKSPACE={}", SANDBOX_WORKSPACE));
+            }
+        }
         
         if let Some(ref workdir) = config.working_dir {
             cmd.arg("-w").arg(workdir);
@@ -547,6 +565,16 @@ impl NodeFactory for DockerNodeV2Factory {
                     name: "data".to_string(),
                     description: "Data to pass to container (mode depends on stdin_mode config)".to_string(),
                     required: false,
+                },
+                PortDefinition {
+                    name: "<name>".to_string(),
+                    description: "Sent to the container as a field of the JSON on stdin (stdin_mode json)".to_string(),
+                    required: false,
+                },
+                PortDefinition {
+                    name: "file:<path>".to_string(),
+                    description: "File written to the workspace mounted at /workspace".to_string(),
+                    required: false,
                 }
             ],
             outputs: vec![
@@ -576,6 +604,25 @@ impl NodeFactory for DockerNodeV2Factory {
                     required: false,
                 }
             ],
+            config: vec![
+                ConfigDefinition::new("image", "Image to run", true),
+                ConfigDefinition::new("command", "Command, as a list or a shell-quoted string", false),
+                ConfigDefinition::new("entrypoint", "Entrypoint override", false),
+                ConfigDefinition::new("env", "Environment variables", false),
+                ConfigDefinition::new("volumes", "Mounts, as host:container[:ro] strings", false),
+                ConfigDefinition::new("workdir", "Working directory in the container", false),
+                ConfigDefinition::new("user", "User to run as", false),
+                ConfigDefinition::new("network", "Network to attach to", false),
+                ConfigDefinition::new("cpu_limit", "CPU limit, e.g. \"1.5\"", false),
+                ConfigDefinition::new("memory_limit", "Memory limit, e.g. \"512m\"", false),
+                ConfigDefinition::new("stdin_mode", "none, raw, json or text (default json)", false),
+                ConfigDefinition::new("output_mode", "auto, json or text (default auto)", false),
+                ConfigDefinition::new("io_mode", "auto, flat or wrapped JSON for stdin (default auto)", false),
+                ConfigDefinition::new("auto_pull", "Pull the image if it is missing (default true)", false),
+                ConfigDefinition::new("detached", "Run in the background (default false)", false),
+                ConfigDefinition::new("remove", "Remove the container afterwards (default true)", false),
+                ConfigDefinition::new("timeout", "Timeout in seconds", false),
+            ],
         }
     }
 }
@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use flowcore::{Node, NodeContext, NodeError, NodeOutput, Value};
+use flowruntime::{ConfigDefinition, NodeFactory, NodeMetadata, PortDefinition};
+use std::collections::HashMap;
+
+/// Prefix of the events the engine emits itself
+const RESERVED_PREFIX: &str = "workflow.";
+
+/// Emit a named custom event that event triggers of other workflows can
+/// react to
+///
+/// The payload is the `payload` input, else the `payload` config, else all
+/// inputs as an object.
+pub struct EmitEventNode {
+    name: String,
+}
+
+#[async_trait]
+impl Node for EmitEventNode {
+    fn node_type(&self) -> &str {
+        "event.emit"
+    }
+
+    async fn execute(&self, ctx: NodeContext) -> Result<NodeOutput, NodeError> {
+        let payload = ctx
+            .inputs
+            .get("payload")
+            .or_else(|| ctx.config.get("payload"))
+            .cloned()
+            .unwrap_or_else(|| Value::Object(ctx.inputs.clone()));
+
+        ctx.events.custom(self.name.clone(), payload.clone());
+
+        Ok(NodeOutput::new().with_output("payload", payload))
+    }
+}
+
+pub struct EmitEventNodeFactory;
+
+impl NodeFactory for EmitEventNodeFactory {
+    fn create(&self, config: &HashMap<String, Value>) -> Result<Box<dyn Node>, NodeError> {
+        let name = config
+            .get("name")
+            .and_then(|v| v.as_str())
+            .filter(|n| !n.is_empty())
+            .ok_or_else(|| NodeError::Configuration("event.emit needs a 'name'".to_string()))?;
+        if name.starts_with(RESERVED_PREFIX) {
+            return Err(NodeError::Configuration(format!(
+                "event names starting with '{}' are reserved",
+                RESERVED_PREFIX
+            )));
+        }
+
+        Ok(Box::new(EmitEventNode {
+            name: name.to_string(),
+        }))
+    }
+
+    fn node_type(&self) -> &str {
+        "event.emit"
+    }
+
+    fn metadata(&self) -> NodeMetadata {
+        NodeMetadata {
+            description: "Emit a named event for event triggers".to_string(),
+            category: "event".to_string(),
+            inputs: vec![
+                PortDefinition {
+                    name: "payload".to_string(),
+                    description: "Event payload (default: all inputs)".to_string(),
+                    required: false,
+                },
+                PortDefinition {
+                    name: "<name>".to_string(),
+                    description: "Field of the payload if no payload input is connected"
+                        .to_string(),
+                    required: false,
+                }
+            ],
+            outputs: vec![
+                PortDefinition {
+                    name: "payload".to_string(),
+                    description: "Payload that was emitted".to_string(),
+                    required: false,
+                }
+            ],
+            config: vec![
+                ConfigDefinition::new("name", "Event name event triggers match on", true),
+                ConfigDefinition::new("payload", "Payload if no payload input is connected", false),
+            ],
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use flowcore::{Node, NodeContext, NodeError, NodeOutput, Value};
-use flowruntime::{NodeFactory, NodeMetadata};
+use flowruntime::{ConfigDefinition, NodeFactory, NodeMetadata, PortDefinition};
 use std::collections::HashMap;
 
 /// HTTP request node
@@ -23,13 +23,7 @@ impl Node for HttpRequestNode {
     }
     
     async fn execute(&self, ctx: NodeContext) -> Result<NodeOutput, NodeError> {
-        let url = ctx.require_input("url")?
-            .as_str()
-            .ok_or_else(|| NodeError::InvalidInputType {
-                field: "url".to_string(),
-                expected: "string".to_string(),
-                actual: "other".to_string(),
-            })?;
+        let url = ctx.require_input("url")?.require_str("url")?;
         let method_value = ctx.get_config_or("method", Value::String("GET".to_string()));
         let method = method_value.as_str().unwrap_or("GET");        
         
@@ -115,8 +109,19 @@ impl NodeFactory for HttpRequestNodeFactory {
         NodeMetadata {
             description: "Make HTTP requests".to_string(),
             category: "http".to_string(),
-            inputs: vec![],
-            outputs: vec![],
+            inputs: vec![
+                PortDefinition::new("url", "URL to request", true),
+                PortDefinition::new("body", "Body of POST and PUT requests; JSON or text", false),
+            ],
+            outputs: vec![
+                PortDefinition::new("status", "Response status code", false),
+                PortDefinition::new("body", "Response body as text", false),
+                PortDefinition::new("headers", "Response headers", false),
+            ],
+            config: vec![
+                ConfigDefinition::new("method", "GET, POST, PUT or DELETE (default GET)", false),
+                ConfigDefinition::new("headers", "Request headers", false),
+            ],
         }
     }
 }
@@ -7,10 +7,13 @@ mod browser;
 mod debug;
 mod docker;
 mod docker_v2;
+mod event;
 mod http;
 mod shell;
+mod signal;
 mod time;
 mod transform;
+mod webhook;
 mod zypi;
 mod zypi_grpc;
 
@@ -19,12 +22,15 @@ pub use browser::BrowserRenderNode;
 pub use debug::DebugNode;
 pub use docker::{DockerNode, DockerNodeFactory};
 pub use docker_v2::{DockerNodeV2, DockerNodeV2Factory};
+pub use event::{EmitEventNode, EmitEventNodeFactory};
 pub use http::HttpRequestNode;
 pub use shell::ShellExecNode;
+pub use signal::{WaitForSignalNode, WaitForSignalNodeFactory};
 pub use time::DelayNode;
 pub use transform::{JsonParseNode, JsonStringifyNode};
+pub use webhook::{WebhookRespondNode, WEBHOOK_RESPOND};
 pub use zypi::{ZypiExecNode, ZypiSessionCreateNode};
-pub use zypi_grpc::ZypiGrpcClient;
+pub use zypi_grpc::{SandboxExec, ZypiGrpcClient};
 use flowruntime::NodeRegistry;
 
 use std::sync::Arc;
@@ -36,11 +42,14 @@ pub fn register_all(registry: &mut NodeRegistry) {
     registry.register(Arc::new(debug::DebugNodeFactory));
     registry.register(Arc::new(docker::DockerNodeFactory));
     registry.register(Arc::new(docker_v2::DockerNodeV2Factory));
+    registry.register(Arc::new(event::EmitEventNodeFactory));
     registry.register(Arc::new(http::HttpRequestNodeFactory));
     registry.register(Arc::new(shell::ShellExecNodeFactory));
+    registry.register(Arc::new(signal::WaitForSignalNodeFactory));
     registry.register(Arc::new(time::DelayNodeFactory));
     registry.register(Arc::new(transform::JsonParseNodeFactory));
     registry.register(Arc::new(transform::JsonStringifyNodeFactory));
+    registry.register(Arc::new(webhook::WebhookRespondNodeFactory));
     registry.register(Arc::new(zypi::ZypiExecNodeFactory));
     registry.register(Arc::new(zypi::ZypiSessionCreateNodeFactory));
 }
@@ -3,11 +3,12 @@
 //! Runs local processes with full configuration:
 //! - command, args, env, workdir, stdin, timeout
 //! - Streaming stdout/stderr via events
-//! - File injection from Blob inputs
+//! - File injection from `file:<path>` inputs into the execution workspace,
+//!   which is also the default working directory (`$FLOW_WORKSPACE`)
 
 use async_trait::async_trait;
 use flowcore::{Node, NodeContext, NodeError, NodeOutput, Value};
-use flowruntime::{NodeFactory, NodeMetadata};
+use flowruntime::{ConfigDefinition, NodeFactory, NodeMetadata};
 use std::collections::HashMap;
 use std::process::Stdio;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
@@ -58,7 +59,7 @@ impl ShellConfig {
         let mut env = HashMap::new();
         if let Some(Value::Object(env_obj)) = ctx.config.get("env") {
             for (key, value) in env_obj {
-                if let Some(val_str) = value.as_str() {
+                if let Some(val_str) = value.reveal_str() {
                     env.insert(key.clone(), val_str.to_string());
                 }
             }
@@ -147,6 +148,11 @@ impl Node for ShellExecNode {
             config.args.join(" ")
         ));
 
+        let files = ctx.file_inputs();
+        if !files.is_empty() {
+            ctx.write_files(&files).await?;
+        }
+
         let mut cmd = if config.shell {
             let mut c = Command::new("sh");
             c.arg("-c");
@@ -166,13 +172,18 @@ impl Node for ShellExecNode {
         };
 
         // Environment
+        if let Some(ref workspace) = ctx.workspace {
+            cmd.env("FLOW_WORKSPACE", workspace);
+        }
         for (key, value) in &config.env {
             cmd.env(key, value);
         }
 
-        // Working directory
+        // Working directory, by default the execution's workspace
         if let Some(ref wd) = config.workdir {
             cmd.current_dir(wd);
+        } else if let Some(ref workspace) = ctx.workspace {
+            cmd.current_dir(workspace);
         }
 
         // Stdio
@@ -191,7 +202,9 @@ impl Node for ShellExecNode {
         if let Some(stdin_data) = ctx.inputs.get("stdin") {
             let data = match stdin_data {
                 Value::String(s) => s.as_bytes().to_vec(),
+                Value::Secret(s) => s.expose().as_bytes().to_vec(),
                 Value::Bytes(b) => b.clone(),
+                Value::Blob(_) => ctx.value_bytes(stdin_data).await?,
                 Value::Json(j) => j.to_string().as_bytes().to_vec(),
                 other => other.to_string().as_bytes().to_vec(),
             };
@@ -324,6 +337,13 @@ impl Node for ShellExecNode {
                 .and_then(|v| v.as_bool())
                 .unwrap_or(true);
             if fail_on_error {
+                // The output port is lost with the error, so make stderr
+                // visible to event listeners (e.g. failure handling)
+                if !config.stream_output {
+                    for line in stderr_str.lines() {
+                        ctx.events.stderr_line(line);
+                    }
+                }
                 return Err(NodeError::ExecutionFailed(format!(
                     "Command exited with code {}",
                     exit_code
@@ -374,6 +394,11 @@ impl NodeFactory for ShellExecNodeFactory {
                     description: "Data to pipe to stdin".to_string(),
                     required: false,
                 },
+                flowruntime::PortDefinition {
+                    name: "file:<path>".to_string(),
+                    description: "File written to the workspace at <path>".to_string(),
+                    required: false,
+                },
             ],
             outputs: vec![
                 flowruntime::PortDefinition {
@@ -404,6 +429,20 @@ impl NodeFactory for ShellExecNodeFactory {
                     required: false,
                 },
             ],
+            config: vec![
+                ConfigDefinition::new("command", "Program to run, or the command line with `shell`", true),
+                ConfigDefinition::new("args", "Arguments, as a list or a space-separated string", false),
+                ConfigDefinition::new("env", "Environment variables", false),
+                ConfigDefinition::new("env_passthrough", "Names of the engine's environment variables to pass on", false),
+                ConfigDefinition::new("workdir", "Working directory (default: the execution's workspace)", false),
+                ConfigDefinition::new("timeout", "Timeout in seconds", false),
+                ConfigDefinition::new("shell", "Run through `sh -c` (default false)", false),
+                ConfigDefinition::new("capture_stdout", "Capture stdout (default true)", false),
+                ConfigDefinition::new("capture_stderr", "Capture stderr (default true)", false),
+                ConfigDefinition::new("stream_output", "Emit output lines as events while running (default false)", false),
+                ConfigDefinition::new("strip_trailing_newline", "Strip the trailing newline from output (default true)", false),
+                ConfigDefinition::new("fail_on_error", "Fail the node on a non-zero exit (default true)", false),
+            ],
         }
     }
 }
@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use flowcore::{Node, NodeContext, NodeError, NodeEvent, NodeOutput, Value};
+use flowruntime::{ConfigDefinition, NodeFactory, NodeMetadata, PortDefinition};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Pause this branch until a named signal is sent to the execution, e.g.
+/// through `POST /api/executions/{id}/signals/{name}`
+///
+/// The signal's payload becomes the `payload` output. A signal sent before
+/// the node starts waiting is not lost. With a `timeout` (seconds) the node
+/// fails if no signal arrives in time.
+pub struct WaitForSignalNode {
+    signal: String,
+    timeout: Option<Duration>,
+}
+
+#[async_trait]
+impl Node for WaitForSignalNode {
+    fn node_type(&self) -> &str {
+        "flow.wait_for_signal"
+    }
+
+    async fn execute(&self, ctx: NodeContext) -> Result<NodeOutput, NodeError> {
+        let payload = match ctx.signals.try_take(&self.signal) {
+            Some(payload) => payload,
+            None => {
+                ctx.events.emit(NodeEvent::WaitingForSignal {
+                    name: self.signal.clone(),
+                    timeout_ms: self.timeout.map(|t| t.as_millis() as u64),
+                });
+                let timeout = async {
+                    match self.timeout {
+                        Some(timeout) => tokio::time::sleep(timeout).await,
+                        None => std::future::pending().await,
+                    }
+                };
+                tokio::select! {
+                    payload = ctx.signals.wait(&self.signal) => payload,
+                    _ = timeout => {
+                        return Err(NodeError::Timeout {
+                            seconds: self.timeout.unwrap_or_default().as_secs_f64().ceil() as u64,
+                        });
+                    }
+                    _ = ctx.cancellation.cancelled() => return Err(NodeError::Cancelled),
+                }
+            }
+        };
+        ctx.events.emit(NodeEvent::SignalReceived {
+            name: self.signal.clone(),
+        });
+
+        Ok(NodeOutput::new()
+            .with_output("payload", payload)
+            .with_output("signal", Value::String(self.signal.clone())))
+    }
+}
+
+pub struct WaitForSignalNodeFactory;
+
+impl NodeFactory for WaitForSignalNodeFactory {
+    fn create(&self, config: &HashMap<String, Value>) -> Result<Box<dyn Node>, NodeError> {
+        let signal = config
+            .get("signal")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| {
+                NodeError::Configuration("flow.wait_for_signal needs a 'signal' name".to_string())
+            })?;
+        let timeout = match config.get("timeout") {
+            None => None,
+            Some(value) => match value.as_f64() {
+                Some(seconds) if seconds > 0.0 && seconds.is_finite() => {
+                    Some(Duration::from_secs_f64(seconds))
+                }
+                _ => {
+                    return Err(NodeError::Configuration(
+                        "'timeout' must be a positive number of seconds".to_string(),
+                    ))
+                }
+            },
+        };
+
+        Ok(Box::new(WaitForSignalNode {
+            signal: signal.to_string(),
+            timeout,
+        }))
+    }
+
+    fn node_type(&self) -> &str {
+        "flow.wait_for_signal"
+    }
+
+    fn metadata(&self) -> NodeMetadata {
+        NodeMetadata {
+            description: "Wait for a signal sent to the execution".to_string(),
+            category: "flow".to_string(),
+            inputs: vec![],
+            outputs: vec![
+                PortDefinition {
+                    name: "payload".to_string(),
+                    description: "Payload the signal was sent with".to_string(),
+                    required: false,
+                },
+                PortDefinition {
+                    name: "signal".to_string(),
+                    description: "Name of the signal".to_string(),
+                    required: false,
+                }
+            ],
+            config: vec![
+                ConfigDefinition::new("signal", "Name of the signal to wait for", true),
+                ConfigDefinition::new("timeout", "Seconds to wait before failing (default: forever)", false),
+            ],
+        }
+    }
+}
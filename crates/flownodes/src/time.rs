@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use flowcore::{Node, NodeContext, NodeError, NodeOutput, Value};
-use flowruntime::{NodeFactory, NodeMetadata};
+use flowruntime::{ConfigDefinition, NodeFactory, NodeMetadata, PortDefinition};
 use std::collections::HashMap;
 use tokio::time::{sleep, Duration};
 
@@ -46,8 +46,19 @@ impl NodeFactory for DelayNodeFactory {
         NodeMetadata {
             description: "Delay execution for specified milliseconds".to_string(),
             category: "time".to_string(),
-            inputs: vec![],
-            outputs: vec![],
+            inputs: vec![PortDefinition {
+                name: "<name>".to_string(),
+                description: "Passed through unchanged".to_string(),
+                required: false,
+            }],
+            outputs: vec![PortDefinition {
+                name: "<name>".to_string(),
+                description: "Each input, once the delay is over".to_string(),
+                required: false,
+            }],
+            config: vec![
+                ConfigDefinition::new("delay_ms", "Milliseconds to wait (default 1000)", false),
+            ],
         }
     }
 }
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use flowcore::{Node, NodeContext, NodeError, NodeOutput, Value};
-use flowruntime::{NodeFactory, NodeMetadata};
+use flowruntime::{NodeFactory, NodeMetadata, PortDefinition};
 use std::collections::HashMap;
 
 /// Parse JSON string to Value
@@ -13,13 +13,7 @@ impl Node for JsonParseNode {
     }
     
     async fn execute(&self, ctx: NodeContext) -> Result<NodeOutput, NodeError> {
-        let input = ctx.require_input("json")?
-            .as_str()
-            .ok_or_else(|| NodeError::InvalidInputType {
-                field: "json".to_string(),
-                expected: "string".to_string(),
-                actual: "other".to_string(),
-            })?;
+        let input = ctx.require_input("json")?.require_str("json")?;
         
         let parsed: serde_json::Value = serde_json::from_str(input)
             .map_err(|e| NodeError::ExecutionFailed(format!("JSON parse error: {}", e)))?;
@@ -44,8 +38,17 @@ impl NodeFactory for JsonParseNodeFactory {
         NodeMetadata {
             description: "Parse JSON string".to_string(),
             category: "transform".to_string(),
-            inputs: vec![],
-            outputs: vec![],
+            inputs: vec![PortDefinition {
+                name: "json".to_string(),
+                description: "JSON text".to_string(),
+                required: true,
+            }],
+            outputs: vec![PortDefinition {
+                name: "parsed".to_string(),
+                description: "Parsed value".to_string(),
+                required: false,
+            }],
+            config: vec![],
         }
     }
 }
@@ -85,8 +88,17 @@ impl NodeFactory for JsonStringifyNodeFactory {
         NodeMetadata {
             description: "Convert value to JSON string".to_string(),
             category: "transform".to_string(),
-            inputs: vec![],
-            outputs: vec![],
+            inputs: vec![PortDefinition {
+                name: "value".to_string(),
+                description: "Value to convert".to_string(),
+                required: true,
+            }],
+            outputs: vec![PortDefinition {
+                name: "json".to_string(),
+                description: "Pretty-printed JSON".to_string(),
+                required: false,
+            }],
+            config: vec![],
         }
     }
 }
@@ -0,0 +1,105 @@
+use async_trait::async_trait;
+use flowcore::{Node, NodeContext, NodeError, NodeOutput, Value};
+use flowruntime::{ConfigDefinition, NodeFactory, NodeMetadata, PortDefinition};
+use std::collections::HashMap;
+
+/// Node type the server waits for when a webhook trigger answers with
+/// the workflow's own response
+pub const WEBHOOK_RESPOND: &str = "webhook.respond";
+
+/// Produce the HTTP response to the webhook request that started the run
+///
+/// `status`, `headers` and `body` come from inputs, falling back to config.
+/// The server sends the first response produced; the workflow carries on
+/// after it.
+pub struct WebhookRespondNode;
+
+impl WebhookRespondNode {
+    fn setting<'a>(ctx: &'a NodeContext, key: &str) -> Option<&'a Value> {
+        ctx.inputs.get(key).or_else(|| ctx.config.get(key))
+    }
+}
+
+#[async_trait]
+impl Node for WebhookRespondNode {
+    fn node_type(&self) -> &str {
+        WEBHOOK_RESPOND
+    }
+
+    async fn execute(&self, ctx: NodeContext) -> Result<NodeOutput, NodeError> {
+        let status = match Self::setting(&ctx, "status") {
+            None => 200.0,
+            Some(value) => value
+                .as_f64()
+                .or_else(|| value.as_json().and_then(|j| j.as_f64()))
+                .filter(|s| (100.0..=599.0).contains(s))
+                .ok_or_else(|| NodeError::InvalidInputType {
+                    field: "status".to_string(),
+                    expected: "HTTP status code".to_string(),
+                    actual: value.to_string(),
+                })?,
+        };
+
+        let headers = match Self::setting(&ctx, "headers") {
+            None => HashMap::new(),
+            Some(Value::Object(headers)) => headers.clone(),
+            Some(Value::Json(serde_json::Value::Object(headers))) => headers
+                .iter()
+                .map(|(k, v)| {
+                    let v = v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string());
+                    (k.clone(), Value::String(v))
+                })
+                .collect(),
+            Some(other) => {
+                return Err(NodeError::InvalidInputType {
+                    field: "headers".to_string(),
+                    expected: "object".to_string(),
+                    actual: other.to_string(),
+                })
+            }
+        };
+
+        let body = Self::setting(&ctx, "body").cloned().unwrap_or(Value::Null);
+
+        ctx.events.info(format!("Responding to webhook with {}", status));
+
+        Ok(NodeOutput::new()
+            .with_output("status", status)
+            .with_output("headers", Value::Object(headers))
+            .with_output("body", body))
+    }
+}
+
+pub struct WebhookRespondNodeFactory;
+
+impl NodeFactory for WebhookRespondNodeFactory {
+    fn create(&self, _config: &HashMap<String, Value>) -> Result<Box<dyn Node>, NodeError> {
+        Ok(Box::new(WebhookRespondNode))
+    }
+
+    fn node_type(&self) -> &str {
+        WEBHOOK_RESPOND
+    }
+
+    fn metadata(&self) -> NodeMetadata {
+        NodeMetadata {
+            description: "Set the HTTP response of the webhook that started the workflow".to_string(),
+            category: "webhook".to_string(),
+            inputs: vec![
+                PortDefinition::new("status", "HTTP status code (default 200)", false),
+                PortDefinition::new("headers", "Response headers", false),
+                PortDefinition::new("body", "Response body; strings are sent as text, anything else as JSON", false),
+            ],
+            outputs: vec![
+                PortDefinition::new("status", "Status sent", false),
+                PortDefinition::new("headers", "Headers sent", false),
+                PortDefinition::new("body", "Body sent", false),
+            ],
+            config: vec![
+                ConfigDefinition::new("status", "HTTP status code if no status input is connected", false),
+                ConfigDefinition::new("headers", "Response headers if no headers input is connected", false),
+                ConfigDefinition::new("body", "Response body if no body input is connected", false),
+            ],
+        }
+    }
+}
@@ -18,6 +18,12 @@
 //!   workdir      - Working directory
 //!   timeout      - Execution timeout in seconds
 //!   files        - Files to inject into the sandbox (from Blob inputs)
+//!   sync_workspace - Copy the execution workspace to `/workspace` in the
+//!                  sandbox and the files found there back afterwards;
+//!                  the image needs `sh`, `find` and `base64` (default: false)
+//!
+//! Files are sent as strings with a `file_encodings` entry per path:
+//! `utf8` for text, `base64` for anything else.
 //!
 //! Session chaining:
 //!   Node 1: zypi.session_create → outputs session_id
@@ -26,9 +32,17 @@
 //!   Session auto-expires after 5min idle. Close explicitly for cleanup.
 
 use async_trait::async_trait;
+use base64::Engine;
 use flowcore::{Node, NodeContext, NodeError, NodeOutput, Value};
-use flowruntime::{NodeFactory, NodeMetadata, PortDefinition};
+use flowruntime::{ConfigDefinition, NodeFactory, NodeMetadata, PortDefinition};
 use std::collections::HashMap;
+use std::path::Path;
+
+use crate::docker::SANDBOX_WORKSPACE;
+
+/// Printed by the sandbox between the command's stdout and the workspace
+/// files it sends back
+const SYNC_MARKER: &str = "--flowengine-workspace-sync--";
 
 pub struct ZypiExecNode {
     client: reqwest::Client,
@@ -53,6 +67,7 @@ struct ZypiConfig {
     timeout_seconds: Option<u64>,
     memory_mb: Option<u64>,
     vcpus: Option<u64>,
+    sync_workspace: bool,
 }
 
 impl ZypiConfig {
@@ -97,7 +112,7 @@ impl ZypiConfig {
         let mut env = HashMap::new();
         if let Some(Value::Object(env_obj)) = ctx.config.get("env") {
             for (key, value) in env_obj {
-                if let Some(val_str) = value.as_str() {
+                if let Some(val_str) = value.reveal_str() {
                     env.insert(key.clone(), val_str.to_string());
                 }
             }
@@ -140,6 +155,12 @@ impl ZypiConfig {
             .and_then(|v| v.as_f64())
             .map(|f| f as u64);
 
+        let sync_workspace = ctx
+            .config
+            .get("sync_workspace")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
         Ok(Self {
             url,
             image,
@@ -150,10 +171,151 @@ impl ZypiConfig {
             timeout_seconds,
             memory_mb,
             vcpus,
+            sync_workspace,
         })
     }
 }
 
+/// A file for the sandbox, as Zypi's string map carries it
+#[derive(Debug, Clone)]
+struct SandboxFile {
+    content: String,
+    /// `"utf8"` or `"base64"`
+    encoding: &'static str,
+}
+
+impl SandboxFile {
+    fn text(content: String) -> Self {
+        Self {
+            content,
+            encoding: "utf8",
+        }
+    }
+
+    /// Text files as they are, anything else base64-encoded
+    fn encode(data: Vec<u8>) -> Self {
+        match String::from_utf8(data) {
+            Ok(text) => Self::text(text),
+            Err(e) => Self {
+                content: base64::engine::general_purpose::STANDARD.encode(e.into_bytes()),
+                encoding: "base64",
+            },
+        }
+    }
+}
+
+/// Contents and encodings of `files`, keyed by path
+fn split_files(files: HashMap<String, SandboxFile>) -> (HashMap<String, String>, HashMap<String, String>) {
+    let mut contents = HashMap::new();
+    let mut encodings = HashMap::new();
+    for (path, file) in files {
+        encodings.insert(path.clone(), file.encoding.to_string());
+        contents.insert(path, file.content);
+    }
+    (contents, encodings)
+}
+
+/// Files to put into the sandbox: `file:<path>` inputs, the `files` input
+/// and, when syncing, the workspace under `/workspace`
+async fn sandbox_files(
+    ctx: &NodeContext,
+    sync_workspace: bool,
+) -> Result<HashMap<String, SandboxFile>, NodeError> {
+    let mut files = HashMap::new();
+
+    if let Some(workspace) = ctx.workspace.as_deref().filter(|_| sync_workspace) {
+        workspace_files(workspace, &mut files).await.map_err(|e| {
+            NodeError::ExecutionFailed(format!("Failed to read workspace: {}", e))
+        })?;
+    }
+
+    for (path, value) in ctx.file_inputs() {
+        let file = match value {
+            Value::String(s) => SandboxFile::text(s),
+            Value::Bytes(_) | Value::Blob(_) => SandboxFile::encode(ctx.value_bytes(&value).await?),
+            other => SandboxFile::text(other.to_string()),
+        };
+        files.insert(path, file);
+    }
+
+    if let Some(Value::Object(file_map)) = ctx.inputs.get("files") {
+        for (path, content) in file_map {
+            let file = match content {
+                Value::String(s) => SandboxFile::text(s.clone()),
+                Value::Bytes(_) | Value::Blob(_) => SandboxFile::encode(ctx.value_bytes(content).await?),
+                _ => SandboxFile::text(content.to_string()),
+            };
+            files.insert(path.clone(), file);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Add every file under `workspace` as `/workspace/<relative path>`
+async fn workspace_files(workspace: &Path, files: &mut HashMap<String, SandboxFile>) -> std::io::Result<()> {
+    let mut pending = vec![workspace.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let relative = path.strip_prefix(workspace).unwrap_or(&path);
+            let data = tokio::fs::read(&path).await?;
+            files.insert(
+                format!("{}/{}", SANDBOX_WORKSPACE, relative.display()),
+                SandboxFile::encode(data),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// `command` followed by a listing of the sandbox workspace: each file's
+/// path and its base64 contents, one line each, after [`SYNC_MARKER`].
+/// The exit code stays the command's.
+fn with_workspace_sync(command: &[String]) -> Vec<String> {
+    let script = format!(
+        r#"mkdir -p {ws} && "$@"; status=$?; echo; echo '{marker}'; find {ws} -type f | while IFS= read -r f; do echo "$f"; base64 -w0 < "$f"; echo; done; exit $status"#,
+        ws = SANDBOX_WORKSPACE,
+        marker = SYNC_MARKER,
+    );
+    ["sh".to_string(), "-c".to_string(), script, "sh".to_string()]
+        .into_iter()
+        .chain(command.iter().cloned())
+        .collect()
+}
+
+/// Write the files listed by [`with_workspace_sync`] into the workspace
+/// and return the command's own stdout
+async fn sync_back(ctx: &NodeContext, mut stdout: String) -> Result<String, NodeError> {
+    let separator = format!("\n{}\n", SYNC_MARKER);
+    let Some(at) = stdout.rfind(&separator) else {
+        // The command never finished, e.g. it timed out
+        return Ok(stdout);
+    };
+
+    let mut files = HashMap::new();
+    let mut lines = stdout[at + separator.len()..].lines();
+    while let (Some(path), Some(data)) = (lines.next(), lines.next()) {
+        let Some(relative) = path.strip_prefix(SANDBOX_WORKSPACE) else {
+            continue;
+        };
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| NodeError::ExecutionFailed(format!("Bad sandbox file {}: {}", path, e)))?;
+        files.insert(relative.trim_start_matches('/').to_string(), Value::Bytes(data));
+    }
+    ctx.write_files(&files).await?;
+    ctx.events.info(format!("  📂 Synced {} files from the sandbox workspace", files.len()));
+
+    stdout.truncate(at);
+    Ok(stdout)
+}
+
 #[async_trait]
 impl Node for ZypiExecNode {
     fn node_type(&self) -> &str {
@@ -169,6 +331,14 @@ impl Node for ZypiExecNode {
             config.image
         ));
 
+        let sync_workspace = config.sync_workspace && ctx.workspace.is_some();
+        let (files, file_encodings) = split_files(sandbox_files(&ctx, sync_workspace).await?);
+        let command = if sync_workspace {
+            with_workspace_sync(&config.command)
+        } else {
+            config.command.clone()
+        };
+
         // ── Try gRPC first (port 4001), fall back to REST ───────────────
         let grpc_url = config.url.replace(":4000", ":4001");
         let grpc_client = crate::zypi_grpc::ZypiGrpcClient::new(&grpc_url);
@@ -181,38 +351,52 @@ impl Node for ZypiExecNode {
         for (key, value) in &ctx.inputs {
             let env_key = key.to_uppercase();
             match value {
-                Value::String(s) => { env_vars.insert(env_key, s.clone()); }
-                Value::Number(n) => { env_vars.insert(env_key, n.to_string()); }
+                Value::String(_) | Value::Secret(_) => {
+                    env_vars.insert(env_key, value.reveal_str().unwrap_or_default().to_string());
+                }
+                Value::Number(_) | Value::Integer(_) | Value::Decimal(_) | Value::Timestamp(_) => {
+                    env_vars.insert(env_key, value.to_string());
+                }
                 Value::Bool(b) => { env_vars.insert(env_key, b.to_string()); }
                 _ => {}
             }
         }
 
         let grpc_result = if let Some(ref sid) = config.session_id {
-            crate::zypi_grpc::try_grpc_or_err(
-                grpc_client.session_exec(
-                    sid,
-                    config.command.clone(),
-                    config.timeout_seconds.unwrap_or(300) as u32,
-                    env_vars.clone(),
-                    config.workdir.as_deref(),
-                )
-            ).await
+            if !files.is_empty() {
+                // Session exec over gRPC cannot carry files
+                Err("files need the REST API".to_string())
+            } else {
+                crate::zypi_grpc::try_grpc_or_err(
+                    grpc_client.session_exec(
+                        sid,
+                        command.clone(),
+                        config.timeout_seconds.unwrap_or(300) as u32,
+                        env_vars.clone(),
+                        config.workdir.as_deref(),
+                    )
+                ).await
+            }
         } else {
             crate::zypi_grpc::try_grpc_or_err(
-                grpc_client.execute(
-                    config.command.clone(),
-                    &config.image,
-                    config.timeout_seconds.unwrap_or(300) as u32,
-                    env_vars.clone(),
-                    config.workdir.as_deref(),
-                    config.memory_mb.map(|m| m as u32),
-                    config.vcpus.map(|c| c as u32),
-                )
+                grpc_client.execute(crate::zypi_grpc::SandboxExec {
+                    command: command.clone(),
+                    image: config.image.clone(),
+                    timeout_secs: config.timeout_seconds.unwrap_or(300) as u32,
+                    env: env_vars.clone(),
+                    workdir: config.workdir.clone(),
+                    memory_mb: config.memory_mb.map(|m| m as u32),
+                    vcpus: config.vcpus.map(|c| c as u32),
+                    files: files.clone(),
+                    file_encodings: file_encodings.clone(),
+                })
             ).await
         };
 
-        if let Ok(result) = grpc_result {
+        if let Ok(mut result) = grpc_result {
+            if sync_workspace {
+                result.stdout = sync_back(&ctx, result.stdout).await?;
+            }
             if result.exit_code != 0 {
                 ctx.events.warn(format!("  stderr: {}", result.stderr));
                 return Err(NodeError::ExecutionFailed(format!(
@@ -249,8 +433,7 @@ impl Node for ZypiExecNode {
         payload.insert(
             "cmd".to_string(),
             serde_json::Value::Array(
-                config
-                    .command
+                command
                     .iter()
                     .map(|s| serde_json::Value::String(s.clone()))
                     .collect(),
@@ -266,8 +449,12 @@ impl Node for ZypiExecNode {
         for (key, value) in &ctx.inputs {
             let env_key = key.to_uppercase();
             match value {
-                Value::String(s) => { env.insert(env_key, s.clone()); }
-                Value::Number(n) => { env.insert(env_key, n.to_string()); }
+                Value::String(_) | Value::Secret(_) => {
+                    env.insert(env_key, value.reveal_str().unwrap_or_default().to_string());
+                }
+                Value::Number(_) | Value::Integer(_) | Value::Decimal(_) | Value::Timestamp(_) => {
+                    env.insert(env_key, value.to_string());
+                }
                 Value::Bool(b) => { env.insert(env_key, b.to_string()); }
                 _ => {}
             }
@@ -307,45 +494,24 @@ impl Node for ZypiExecNode {
             );
         }
 
-        // Inject files from Blob inputs
-        let mut files: serde_json::Map<String, serde_json::Value> =
-            serde_json::Map::new();
-        for (key, value) in &ctx.inputs {
-            if key.starts_with("file:") {
-                let path = key.strip_prefix("file:").unwrap();
-                let content = match value {
-                    Value::String(s) => {
-                        serde_json::Value::String(s.clone())
-                    }
-                    Value::Bytes(b) => {
-                        // Base64 encode binary data
-                        use base64::Engine;
-                        serde_json::Value::String(
-                            base64::engine::general_purpose::STANDARD
-                                .encode(b),
-                        )
-                    }
-                    _ => serde_json::Value::String(value.to_string()),
-                };
-                files.insert(path.to_string(), content);
-            }
-        }
-
-        // Also support a "files" input object
-        if let Some(Value::Object(file_map)) = ctx.inputs.get("files") {
-            for (path, content) in file_map {
-                let content_str = match content {
-                    Value::String(s) => s.clone(),
-                    _ => content.to_string(),
-                };
-                files.insert(path.clone(), serde_json::Value::String(content_str));
-            }
-        }
-
         if !files.is_empty() {
             payload.insert(
                 "files".to_string(),
-                serde_json::Value::Object(files),
+                serde_json::Value::Object(
+                    files
+                        .into_iter()
+                        .map(|(path, content)| (path, serde_json::Value::String(content)))
+                        .collect(),
+                ),
+            );
+            payload.insert(
+                "file_encodings".to_string(),
+                serde_json::Value::Object(
+                    file_encodings
+                        .into_iter()
+                        .map(|(path, encoding)| (path, serde_json::Value::String(encoding)))
+                        .collect(),
+                ),
             );
         }
 
@@ -429,10 +595,14 @@ impl Node for ZypiExecNode {
             .get("exit_code")
             .and_then(|v| v.as_i64())
             .unwrap_or(-1);
-        let stdout = body
+        let mut stdout = body
             .get("stdout")
             .and_then(|v| v.as_str())
-            .unwrap_or("");
+            .unwrap_or("")
+            .to_string();
+        if sync_workspace {
+            stdout = sync_back(&ctx, stdout).await?;
+        }
         let stderr = body
             .get("stderr")
             .and_then(|v| v.as_str())
@@ -459,16 +629,16 @@ impl Node for ZypiExecNode {
 
         // Parse stdout as JSON if possible
         let output_value = if let Ok(json) =
-            serde_json::from_str::<serde_json::Value>(stdout)
+            serde_json::from_str::<serde_json::Value>(&stdout)
         {
             Value::Json(json)
         } else {
-            Value::String(stdout.to_string())
+            Value::String(stdout.clone())
         };
 
         let mut output = NodeOutput::new()
             .with_output("output", output_value)
-            .with_output("stdout", stdout.to_string())
+            .with_output("stdout", stdout)
             .with_output("stderr", stderr.to_string())
             .with_output("exit_code", exit_code as f64)
             .with_output("success", success)
@@ -526,6 +696,12 @@ impl NodeFactory for ZypiExecNodeFactory {
                             .to_string(),
                     required: false,
                 },
+                PortDefinition {
+                    name: "<name>".to_string(),
+                    description: "Passed to the command as the environment variable <NAME>"
+                        .to_string(),
+                    required: false,
+                },
             ],
             outputs: vec![
                 PortDefinition {
@@ -568,6 +744,18 @@ impl NodeFactory for ZypiExecNodeFactory {
                     required: false,
                 },
             ],
+            config: vec![
+                ConfigDefinition::new("command", "Command, as a list or a space-separated string", true),
+                ConfigDefinition::new("url", "Zypi server URL (default http://localhost:4000)", false),
+                ConfigDefinition::new("image", "VM image (default ubuntu:24.04)", false),
+                ConfigDefinition::new("env", "Environment variables", false),
+                ConfigDefinition::new("workdir", "Working directory in the VM", false),
+                ConfigDefinition::new("timeout", "Timeout in seconds", false),
+                ConfigDefinition::new("session_id", "Session to run in, instead of a new VM", false),
+                ConfigDefinition::new("memory_mb", "VM memory in MB", false),
+                ConfigDefinition::new("vcpus", "Number of vCPUs", false),
+                ConfigDefinition::new("sync_workspace", "Copy the execution's workspace in and out of the VM (needs sh, find and base64)", false),
+            ],
         }
     }
 }
@@ -830,6 +1018,13 @@ impl NodeFactory for ZypiSessionCreateNodeFactory {
                     required: false,
                 },
             ],
+            config: vec![
+                ConfigDefinition::new("url", "Zypi server URL (default http://localhost:4000)", false),
+                ConfigDefinition::new("image", "VM image (default ubuntu:24.04)", false),
+                ConfigDefinition::new("agent_id", "Agent ID for memory attribution", false),
+                ConfigDefinition::new("vcpus", "Number of vCPUs (default 1)", false),
+                ConfigDefinition::new("memory_mb", "VM memory in MB (default 256)", false),
+            ],
         }
     }
 }
@@ -6,7 +6,7 @@
 //! Usage:
 //! ```ignore
 //! let client = ZypiGrpcClient::new("http://localhost:4001");
-//! let result = client.execute(command, image, timeout, env).await;
+//! let result = client.execute(SandboxExec { command, image, ..Default::default() }).await;
 //! ```
 
 use flowproto::zypi::v1::{
@@ -15,9 +15,26 @@ use flowproto::zypi::v1::{
     SandboxExecRequest,
     SessionExecRequest,
 };
+use std::collections::HashMap;
 use tonic::transport::Channel;
 use tracing::{info, warn};
 
+/// A one-shot command for [`ZypiGrpcClient::execute`]
+#[derive(Debug, Clone, Default)]
+pub struct SandboxExec {
+    pub command: Vec<String>,
+    pub image: String,
+    pub timeout_secs: u32,
+    pub env: HashMap<String, String>,
+    pub workdir: Option<String>,
+    pub memory_mb: Option<u32>,
+    pub vcpus: Option<u32>,
+    /// Contents of files to create in the sandbox, by path
+    pub files: HashMap<String, String>,
+    /// How each file's content is encoded, `"utf8"` or `"base64"`
+    pub file_encodings: HashMap<String, String>,
+}
+
 /// Result from a sandbox execution (normalized across gRPC and REST)
 #[derive(Debug, Clone)]
 pub struct ExecResult {
@@ -69,29 +86,21 @@ impl ZypiGrpcClient {
     }
 
     /// Execute a one-shot command in a Firecracker sandbox.
-    pub async fn execute(
-        &self,
-        command: Vec<String>,
-        image: &str,
-        timeout_secs: u32,
-        env: std::collections::HashMap<String, String>,
-        workdir: Option<&str>,
-        memory_mb: Option<u32>,
-        vcpus: Option<u32>,
-    ) -> Result<ExecResult, String> {
+    pub async fn execute(&self, exec: SandboxExec) -> Result<ExecResult, String> {
         let mut client = self.connect().await.map_err(|e| {
             format!("gRPC connect failed: {}", e)
         })?;
 
         let request = SandboxExecRequest {
-            command,
-            image: image.to_string(),
-            timeout_secs,
-            memory_mb,
-            vcpus,
-            env,
-            workdir: workdir.map(|s| s.to_string()),
-            files: std::collections::HashMap::new(),
+            command: exec.command,
+            image: exec.image,
+            timeout_secs: exec.timeout_secs,
+            memory_mb: exec.memory_mb,
+            vcpus: exec.vcpus,
+            env: exec.env,
+            workdir: exec.workdir,
+            files: exec.files,
+            file_encodings: exec.file_encodings,
             agent_id: None,
             stream: false,
         };
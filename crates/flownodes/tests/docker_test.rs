@@ -22,6 +22,10 @@ fn create_test_context(
         state: Arc::new(tokio::sync::RwLock::new(flowcore::NodeState::default())),
         events: event_bus.create_emitter(execution_id, node_id),
         cancellation: tokio_util::sync::CancellationToken::new(),
+        signals: flowcore::Signals::new(),
+        artifacts: flowcore::ArtifactStore::default(),
+        workspace: None,
+        variables: HashMap::new(),
     }
 }
 
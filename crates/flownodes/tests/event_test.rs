@@ -0,0 +1,40 @@
+use flowcore::{EventBus, ExecutionEvent, ExecutionId, NodeContext, NodeEvent, Value};
+use flownodes::EmitEventNodeFactory;
+use flowruntime::NodeFactory;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn config(name: &str) -> HashMap<String, Value> {
+    HashMap::from([("name".to_string(), Value::String(name.to_string()))])
+}
+
+#[tokio::test]
+async fn test_emit_custom_event() {
+    let event_bus = Arc::new(EventBus::new(100));
+    let mut events = event_bus.subscribe();
+    let node_id = uuid::Uuid::new_v4();
+    let mut ctx = NodeContext::new(node_id, event_bus.create_emitter(ExecutionId::new_v4(), node_id));
+    ctx.inputs.insert("id".to_string(), Value::Number(7.0));
+
+    let node = EmitEventNodeFactory.create(&config("order.created")).unwrap();
+    let output = node.execute(ctx).await.unwrap();
+
+    let payload = Value::Object(HashMap::from([("id".to_string(), Value::Number(7.0))]));
+    assert_eq!(output.outputs.get("payload"), Some(&payload));
+    match events.recv().await.unwrap() {
+        ExecutionEvent::NodeEvent {
+            event: NodeEvent::Custom { name, payload: emitted },
+            ..
+        } => {
+            assert_eq!(name, "order.created");
+            assert_eq!(emitted, payload);
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
+#[test]
+fn test_emit_requires_unreserved_name() {
+    assert!(EmitEventNodeFactory.create(&HashMap::new()).is_err());
+    assert!(EmitEventNodeFactory.create(&config("workflow.completed")).is_err());
+}
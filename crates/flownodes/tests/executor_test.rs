@@ -0,0 +1,245 @@
+use flowcore::{ExecutionEvent, ExecutionId, FlowError, NodeSpec, Value, Workflow};
+use flowruntime::{
+    ExecutionCheckpoint, ExecutionListener, ExecutionResult, ExecutionState, ExecutionStatus,
+    FlowRuntime, NodeRegistry, NodeStatus, RuntimeConfig, SequencedEvent,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+fn runtime() -> FlowRuntime {
+    let mut registry = NodeRegistry::new();
+    flownodes::register_all(&mut registry);
+    FlowRuntime::with_registry(Arc::new(registry), RuntimeConfig::default())
+}
+
+fn delay(ms: i64) -> NodeSpec {
+    NodeSpec::new("time.delay").with_config("delay_ms", ms)
+}
+
+#[tokio::test]
+async fn test_parallel_branches_of_different_duration() {
+    // While the slow branch runs the fast ones finish, and the slow node
+    // must not be picked as ready again
+    let mut workflow = Workflow::new("uneven branches");
+    let start = workflow.add_node(delay(0));
+    let slow = workflow.add_node(delay(300));
+    let fast = workflow.add_node(delay(10));
+    let after_fast = workflow.add_node(delay(10));
+    workflow.connect(start, "out", slow, "in");
+    workflow.connect(start, "out", fast, "in");
+    workflow.connect(fast, "out", after_fast, "in");
+
+    let result = runtime().execute(&workflow, HashMap::new()).await.unwrap();
+    assert_eq!(result.completed_nodes, 4);
+    assert_eq!(result.total_nodes, 4);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_finished_executions_expire_when_idle() {
+    let mut registry = NodeRegistry::new();
+    flownodes::register_all(&mut registry);
+    let config = RuntimeConfig {
+        finished_execution_ttl: Some(Duration::from_secs(1)),
+        ..Default::default()
+    };
+    let runtime = FlowRuntime::with_registry(Arc::new(registry), config);
+
+    let mut workflow = Workflow::new("short lived");
+    workflow.add_node(delay(0));
+    runtime.register_workflow(workflow.clone()).await;
+    let result = runtime.execute_workflow(workflow.id, HashMap::new()).await.unwrap();
+    assert!(runtime.get_execution_status(result.execution_id).await.is_some());
+
+    // Nothing else finishes, so only the sweep (every second here) can
+    // evict it, once it is older than the TTL
+    tokio::time::sleep(Duration::from_millis(900)).await;
+    assert!(runtime.get_execution_status(result.execution_id).await.is_some());
+    tokio::time::sleep(Duration::from_millis(1600)).await;
+    assert!(runtime.get_execution_status(result.execution_id).await.is_none());
+}
+
+/// Keeps the checkpoints it is given
+#[derive(Default)]
+struct Checkpoints(std::sync::Mutex<Vec<ExecutionCheckpoint>>);
+
+#[async_trait::async_trait]
+impl ExecutionListener for Checkpoints {
+    async fn on_execution_finished(
+        &self,
+        _workflow: &Workflow,
+        _state: &ExecutionState,
+        _result: &Result<ExecutionResult, FlowError>,
+    ) {
+    }
+
+    async fn on_execution_checkpoint(&self, checkpoint: &ExecutionCheckpoint) {
+        self.0.lock().unwrap().push(checkpoint.clone());
+    }
+}
+
+#[tokio::test]
+async fn test_progress_and_checkpoints_survive_a_busy_bus() {
+    // Subscribers of a bus this small miss events as soon as they fall behind
+    let mut registry = NodeRegistry::new();
+    flownodes::register_all(&mut registry);
+    let config = RuntimeConfig {
+        event_buffer_size: 2,
+        ..Default::default()
+    };
+    let runtime = FlowRuntime::with_registry(Arc::new(registry), config);
+    let checkpoints = Arc::new(Checkpoints::default());
+    runtime.add_listener(checkpoints.clone());
+
+    let mut workflow = Workflow::new("noisy");
+    let noisy = workflow.add_node(
+        NodeSpec::new("shell.exec")
+            .with_config("command", "seq 1 2000")
+            .with_config("shell", true)
+            .with_config("stream_output", true),
+    );
+    let wait = workflow.add_node(NodeSpec::new("flow.wait_for_signal").with_config("signal", "go"));
+    workflow.connect(noisy, "stdout", wait, "in");
+    runtime.register_workflow(workflow.clone()).await;
+
+    let execution_id = runtime.start_workflow(workflow.id, HashMap::new()).await.unwrap();
+    wait_for_status(&runtime, execution_id, ExecutionStatus::Waiting).await;
+    let state = runtime.get_execution_status(execution_id).await.unwrap();
+    assert_eq!(state.node_progress[&noisy].status, NodeStatus::Succeeded);
+    let checkpoint = checkpoints.0.lock().unwrap().last().cloned().unwrap();
+    assert!(checkpoint.completed.contains_key(&noisy));
+
+    assert!(runtime.send_signal(execution_id, "go", Value::Null).await);
+    wait_for_status(&runtime, execution_id, ExecutionStatus::Completed).await;
+}
+
+#[tokio::test]
+async fn test_paused_execution_starts_no_new_nodes() {
+    let runtime = runtime();
+    let mut workflow = Workflow::new("paused");
+    let slow = workflow.add_node(delay(200));
+    let next = workflow.add_node(delay(0));
+    workflow.connect(slow, "out", next, "in");
+    runtime.register_workflow(workflow.clone()).await;
+
+    let execution_id = runtime.start_workflow(workflow.id, HashMap::new()).await.unwrap();
+    wait_for_node(&runtime, execution_id, slow, NodeStatus::Running).await;
+    assert!(runtime.pause_execution(execution_id).await);
+    assert!(!runtime.pause_execution(execution_id).await);
+
+    // The running node finishes, the one after it doesn't start
+    wait_for_node(&runtime, execution_id, slow, NodeStatus::Succeeded).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let state = runtime.get_execution_status(execution_id).await.unwrap();
+    assert_eq!(state.status, ExecutionStatus::Paused);
+    assert_eq!(state.node_progress[&next].status, NodeStatus::Pending);
+
+    assert!(runtime.resume_execution(execution_id).await);
+    assert!(!runtime.resume_execution(execution_id).await);
+    wait_for_status(&runtime, execution_id, ExecutionStatus::Completed).await;
+    let state = runtime.get_execution_status(execution_id).await.unwrap();
+    assert_eq!(state.node_progress[&next].status, NodeStatus::Succeeded);
+
+    // Finished and unknown executions can be neither paused nor resumed
+    assert!(!runtime.pause_execution(execution_id).await);
+    assert!(!runtime.resume_execution(execution_id).await);
+    assert!(!runtime.pause_execution(ExecutionId::new_v4()).await);
+    assert!(!runtime.resume_execution(ExecutionId::new_v4()).await);
+}
+
+#[tokio::test]
+async fn test_followers_resume_and_catch_up_from_the_journal() {
+    let mut registry = NodeRegistry::new();
+    flownodes::register_all(&mut registry);
+    let config = RuntimeConfig {
+        event_buffer_size: 16,
+        ..Default::default()
+    };
+    let runtime = FlowRuntime::with_registry(Arc::new(registry), config);
+
+    let mut workflow = Workflow::new("followed");
+    let start = workflow.add_node(delay(0));
+    let wait = workflow.add_node(NodeSpec::new("flow.wait_for_signal").with_config("signal", "go"));
+    workflow.connect(start, "out", wait, "in");
+    runtime.register_workflow(workflow.clone()).await;
+    let execution_id = runtime.start_workflow(workflow.id, HashMap::new()).await.unwrap();
+    wait_for_status(&runtime, execution_id, ExecutionStatus::Waiting).await;
+
+    let first = runtime.follow_execution(execution_id, None).await.unwrap();
+    let recorded = seqs(&first.replay);
+    assert!(recorded.len() > 2 && recorded.len() < 16, "{:?}", recorded);
+    assert_eq!(recorded, (1..=recorded.len() as u64).collect::<Vec<_>>());
+    assert!(matches!(first.replay[0].event, ExecutionEvent::WorkflowStarted { .. }));
+    let newest = recorded.len() as u64;
+
+    // Reconnecting with Last-Event-ID replays only what came after it
+    let resumed = runtime.follow_execution(execution_id, Some(2)).await.unwrap();
+    assert_eq!(seqs(&resumed.replay), recorded[2..]);
+    assert!(runtime.follow_execution(execution_id, Some(newest)).await.unwrap().replay.is_empty());
+
+    // A follower that stops reading falls behind the live feed...
+    let mut live = first.live.unwrap();
+    for _ in 0..20 {
+        assert!(runtime.pause_execution(execution_id).await);
+        assert!(runtime.resume_execution(execution_id).await);
+    }
+    assert!(matches!(live.try_recv(), Err(TryRecvError::Lagged(_))));
+
+    // ...and catches up from the journal after the last event it saw,
+    // with as much as the journal still holds
+    let caught_up = runtime.follow_execution(execution_id, Some(newest)).await.unwrap();
+    assert_eq!(seqs(&caught_up.replay), (newest + 25..=newest + 40).collect::<Vec<_>>());
+    assert!(matches!(
+        caught_up.replay.last().unwrap().event,
+        ExecutionEvent::WorkflowResumed { .. }
+    ));
+
+    // The new live feed carries on where the replay ends, up to the end
+    let mut live = caught_up.live.unwrap();
+    assert!(runtime.send_signal(execution_id, "go", Value::Null).await);
+    let mut rest = Vec::new();
+    loop {
+        match tokio::time::timeout(Duration::from_secs(5), live.recv()).await.unwrap() {
+            Ok(event) => rest.push(event),
+            Err(RecvError::Closed) => break,
+            Err(RecvError::Lagged(n)) => panic!("lagged by {}", n),
+        }
+    }
+    let next = newest + 41;
+    assert_eq!(seqs(&rest), (next..next + rest.len() as u64).collect::<Vec<_>>());
+    assert!(matches!(
+        rest.last().unwrap().event,
+        ExecutionEvent::WorkflowCompleted { .. }
+    ));
+}
+
+fn seqs(events: &[SequencedEvent]) -> Vec<u64> {
+    events.iter().map(|e| e.seq).collect()
+}
+
+async fn wait_for_node(
+    runtime: &FlowRuntime,
+    execution_id: ExecutionId,
+    node_id: flowcore::NodeId,
+    status: NodeStatus,
+) {
+    for _ in 0..500 {
+        let state = runtime.get_execution_status(execution_id).await;
+        if state.is_some_and(|s| s.node_progress.get(&node_id).is_some_and(|n| n.status == status)) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("node {} never became {:?}", node_id, status);
+}
+
+async fn wait_for_status(runtime: &FlowRuntime, execution_id: ExecutionId, status: ExecutionStatus) {
+    for _ in 0..500 {
+        if runtime.get_execution_status(execution_id).await.is_some_and(|s| s.status == status) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("execution {} never became {:?}", execution_id, status);
+}
@@ -0,0 +1,144 @@
+use flowcore::{NodeId, Workflow, WorkflowFormat};
+use flowruntime::{Diagnostic, NodeRegistry, Severity};
+
+fn lint(document: &str) -> (Workflow, Vec<Diagnostic>) {
+    let mut registry = NodeRegistry::new();
+    flownodes::register_all(&mut registry);
+    let workflow = Workflow::parse(document, WorkflowFormat::Yaml).unwrap();
+    let diagnostics = registry.lint_workflow(&workflow);
+    (workflow, diagnostics)
+}
+
+fn node(workflow: &Workflow, name: &str) -> NodeId {
+    workflow.nodes.iter().find(|n| n.name.as_deref() == Some(name)).unwrap().id
+}
+
+fn codes(diagnostics: &[Diagnostic]) -> Vec<&str> {
+    diagnostics.iter().map(|d| d.code).collect()
+}
+
+#[test]
+fn test_clean_workflow() {
+    let (_, diagnostics) = lint(
+        r#"
+name: Clean
+nodes:
+  fetch:
+    type: http.request
+    config:
+      method: GET
+  parse:
+    type: transform.json_parse
+  log:
+    type: debug.log
+connections:
+  - fetch.body -> parse.json
+  - parse.parsed -> log.message
+"#,
+    );
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+}
+
+#[test]
+fn test_node_and_config_problems() {
+    let (workflow, diagnostics) = lint(
+        r#"
+name: Config
+nodes:
+  build:
+    type: shell.exec
+    config:
+      script: make
+  later:
+    type: no.such.type
+connections:
+  - build.stdout -> later.input
+"#,
+    );
+    assert_eq!(codes(&diagnostics), ["missing-config", "unused-config", "unknown-node-type"]);
+
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(diagnostics[0].node, Some(node(&workflow, "build")));
+    assert_eq!(diagnostics[0].field.as_deref(), Some("config.command"));
+    assert_eq!(diagnostics[1].severity, Severity::Warning);
+    assert_eq!(diagnostics[1].field.as_deref(), Some("config.script"));
+}
+
+#[test]
+fn test_connection_problems() {
+    let (_, diagnostics) = lint(
+        r#"
+name: Connections
+nodes:
+  a:
+    type: shell.exec
+    config:
+      command: echo a
+  b:
+    type: shell.exec
+    config:
+      command: echo b
+  merge:
+    type: shell.exec
+    config:
+      command: cat
+connections:
+  - a.stdout -> merge.stdin
+  - b.stdout -> merge.stdin
+  - a.result -> merge.file:a.txt
+  - a.stdout -> merge.SENTIMENT
+"#,
+    );
+    assert_eq!(codes(&diagnostics), ["unknown-port", "unknown-port", "duplicate-input"]);
+    assert_eq!(diagnostics[0].connection, Some(2));
+    assert_eq!(diagnostics[0].field.as_deref(), Some("from_port"));
+    // `file:<path>` matches any file, not other names
+    assert_eq!(diagnostics[1].connection, Some(3));
+    assert_eq!(diagnostics[1].field.as_deref(), Some("to_port"));
+    // Reported at the connection whose value is kept
+    assert_eq!(diagnostics[2].connection, Some(1));
+    assert_eq!(diagnostics[2].field.as_deref(), Some("stdin"));
+}
+
+#[test]
+fn test_graph_problems() {
+    let (mut workflow, _) = lint(
+        r#"
+name: Graph
+nodes:
+  first:
+    type: debug.log
+  second:
+    type: debug.log
+  after:
+    type: debug.log
+  alone:
+    type: debug.log
+connections:
+  - first.message -> second.message
+  - second.message -> first.message
+  - second.message -> after.message
+"#,
+    );
+    let dangling = uuid::Uuid::new_v4();
+    workflow.connect(node(&workflow, "first"), "message", dangling, "message");
+
+    let mut registry = NodeRegistry::new();
+    flownodes::register_all(&mut registry);
+    let diagnostics = registry.lint_workflow(&workflow);
+    assert_eq!(
+        codes(&diagnostics),
+        ["dangling-connection", "cycle", "unreachable-node", "disconnected-node"]
+    );
+    assert_eq!(diagnostics[0].connection, Some(3));
+    assert_eq!(diagnostics[0].field.as_deref(), Some("to_node"));
+    assert!(diagnostics[1].message.contains("'first', 'second'"));
+    assert_eq!(diagnostics[2].node, Some(node(&workflow, "after")));
+    assert_eq!(diagnostics[3].node, Some(node(&workflow, "alone")));
+
+    // Errors and warnings serialize for editors
+    let json = serde_json::to_value(&diagnostics[1]).unwrap();
+    assert_eq!(json["severity"], "error");
+    assert_eq!(json["code"], "cycle");
+    assert!(json.get("connection").is_none());
+}
@@ -0,0 +1,69 @@
+use flowcore::{ExecutionEvent, NodeEvent, NodeSpec, Secret, Value, Workflow};
+use flowruntime::{FlowRuntime, NodeRegistry, RuntimeConfig};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const KEY: &str = "sk-test-0123456789";
+
+fn runtime() -> FlowRuntime {
+    let mut registry = NodeRegistry::new();
+    flownodes::register_all(&mut registry);
+    FlowRuntime::with_registry(Arc::new(registry), RuntimeConfig::default())
+}
+
+#[tokio::test]
+async fn test_secret_env_masked_in_events() {
+    let runtime = runtime();
+    let mut events = runtime.subscribe_events();
+
+    let mut workflow = Workflow::new("secret env");
+    let env = HashMap::from([("API_KEY".to_string(), Value::Secret(Secret::new(KEY)))]);
+    let node = workflow.add_node(
+        NodeSpec::new("shell.exec")
+            .with_config("command", "echo \"key=$API_KEY\"; echo \"$API_KEY\" >&2; echo ${#API_KEY}")
+            .with_config("shell", true)
+            .with_config("stream_output", true)
+            .with_config("env", Value::Object(env)),
+    );
+
+    // The process gets the clear text, the result only the masked one
+    let result = runtime.execute(&workflow, HashMap::new()).await.unwrap();
+    assert_eq!(
+        result.outputs[&node].get("stdout"),
+        Some(&Value::String(format!("key=********\n{}", KEY.len())))
+    );
+
+    let mut stdout = Vec::new();
+    let mut completed = None;
+    while let Ok(event) = events.try_recv() {
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(!json.contains(KEY), "secret leaked in {}", json);
+        match event {
+            ExecutionEvent::NodeEvent { event: NodeEvent::StdoutLine { line }, .. } => {
+                stdout.push(line)
+            }
+            ExecutionEvent::NodeCompleted { outputs, .. } => completed = Some(outputs),
+            _ => {}
+        }
+    }
+    assert_eq!(stdout, vec!["key=********".to_string(), KEY.len().to_string()]);
+    assert_eq!(completed.unwrap().get("stdout"), result.outputs[&node].get("stdout"));
+}
+
+#[tokio::test]
+async fn test_secret_inputs_reach_the_process() {
+    let runtime = runtime();
+    let mut workflow = Workflow::new("secret stdin");
+    let node = workflow.add_node(
+        NodeSpec::new("shell.exec")
+            .with_config("command", "cut -c1-7")
+            .with_config("shell", true),
+    );
+
+    let inputs = HashMap::from([("stdin".to_string(), Value::Secret(Secret::new(KEY)))]);
+    let result = runtime.execute(&workflow, inputs).await.unwrap();
+    assert_eq!(
+        result.outputs[&node].get("stdout"),
+        Some(&Value::String(KEY[..7].to_string()))
+    );
+}
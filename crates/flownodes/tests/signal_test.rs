@@ -0,0 +1,76 @@
+use flowcore::{EventBus, ExecutionEvent, ExecutionId, NodeContext, NodeError, NodeEvent, Value};
+use flownodes::WaitForSignalNodeFactory;
+use flowruntime::NodeFactory;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn config(signal: &str, timeout: Option<f64>) -> HashMap<String, Value> {
+    let mut config = HashMap::from([("signal".to_string(), Value::String(signal.to_string()))]);
+    if let Some(timeout) = timeout {
+        config.insert("timeout".to_string(), Value::Number(timeout));
+    }
+    config
+}
+
+fn context(event_bus: &EventBus) -> NodeContext {
+    let node_id = uuid::Uuid::new_v4();
+    NodeContext::new(node_id, event_bus.create_emitter(ExecutionId::new_v4(), node_id))
+}
+
+#[tokio::test]
+async fn test_waits_for_signal() {
+    let event_bus = Arc::new(EventBus::new(100));
+    let mut events = event_bus.subscribe();
+    let ctx = context(&event_bus);
+    let signals = ctx.signals.clone();
+
+    let node = WaitForSignalNodeFactory.create(&config("approved", None)).unwrap();
+    let waiting = tokio::spawn(async move { node.execute(ctx).await });
+
+    match events.recv().await.unwrap() {
+        ExecutionEvent::NodeEvent {
+            event: NodeEvent::WaitingForSignal { name, timeout_ms },
+            ..
+        } => {
+            assert_eq!(name, "approved");
+            assert_eq!(timeout_ms, None);
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+    signals.send("other", Value::Null);
+    signals.send("approved", Value::String("yes".to_string()));
+
+    let output = waiting.await.unwrap().unwrap();
+    assert_eq!(output.outputs.get("payload"), Some(&Value::String("yes".to_string())));
+    assert_eq!(output.outputs.get("signal"), Some(&Value::String("approved".to_string())));
+    assert!(matches!(
+        events.recv().await.unwrap(),
+        ExecutionEvent::NodeEvent { event: NodeEvent::SignalReceived { .. }, .. }
+    ));
+    assert_eq!(signals.pending().len(), 1);
+}
+
+#[tokio::test]
+async fn test_takes_signal_sent_earlier() {
+    let event_bus = EventBus::new(100);
+    let ctx = context(&event_bus);
+    ctx.signals.send("approved", Value::Bool(true));
+
+    let node = WaitForSignalNodeFactory.create(&config("approved", Some(1.0))).unwrap();
+    let output = node.execute(ctx).await.unwrap();
+    assert_eq!(output.outputs.get("payload"), Some(&Value::Bool(true)));
+}
+
+#[tokio::test]
+async fn test_times_out() {
+    let event_bus = EventBus::new(100);
+    let node = WaitForSignalNodeFactory.create(&config("approved", Some(0.05))).unwrap();
+    let result = node.execute(context(&event_bus)).await;
+    assert!(matches!(result, Err(NodeError::Timeout { .. })));
+}
+
+#[test]
+fn test_requires_signal_name() {
+    assert!(WaitForSignalNodeFactory.create(&HashMap::new()).is_err());
+    assert!(WaitForSignalNodeFactory.create(&config("approved", Some(-1.0))).is_err());
+}
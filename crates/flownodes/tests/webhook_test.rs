@@ -0,0 +1,67 @@
+use flowcore::{EventBus, ExecutionId, Node, NodeContext, Value};
+use flownodes::WebhookRespondNode;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn create_test_context(
+    config: HashMap<String, Value>,
+    inputs: HashMap<String, Value>,
+) -> NodeContext {
+    let event_bus = Arc::new(EventBus::new(100));
+    let node_id = uuid::Uuid::new_v4();
+    let mut ctx = NodeContext::new(node_id, event_bus.create_emitter(ExecutionId::new_v4(), node_id));
+    ctx.config = config;
+    ctx.inputs = inputs;
+    ctx
+}
+
+#[tokio::test]
+async fn test_respond_defaults() {
+    let ctx = create_test_context(HashMap::new(), HashMap::new());
+    let output = WebhookRespondNode.execute(ctx).await.unwrap();
+
+    assert_eq!(output.outputs.get("status"), Some(&Value::Number(200.0)));
+    assert_eq!(output.outputs.get("headers"), Some(&Value::Object(HashMap::new())));
+    assert_eq!(output.outputs.get("body"), Some(&Value::Null));
+}
+
+#[tokio::test]
+async fn test_respond_inputs_override_config() {
+    let config = HashMap::from([
+        ("status".to_string(), Value::Number(201.0)),
+        ("body".to_string(), Value::String("from config".to_string())),
+    ]);
+    let inputs = HashMap::from([
+        ("status".to_string(), Value::Json(serde_json::json!(404))),
+        (
+            "headers".to_string(),
+            Value::Json(serde_json::json!({ "x-request-id": "abc" })),
+        ),
+    ]);
+    let output = WebhookRespondNode
+        .execute(create_test_context(config, inputs))
+        .await
+        .unwrap();
+
+    assert_eq!(output.outputs.get("status"), Some(&Value::Number(404.0)));
+    assert_eq!(
+        output.outputs.get("headers"),
+        Some(&Value::Object(HashMap::from([(
+            "x-request-id".to_string(),
+            Value::String("abc".to_string())
+        )])))
+    );
+    assert_eq!(
+        output.outputs.get("body"),
+        Some(&Value::String("from config".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_respond_rejects_bad_status() {
+    let inputs = HashMap::from([("status".to_string(), Value::Number(42.0))]);
+    let result = WebhookRespondNode
+        .execute(create_test_context(HashMap::new(), inputs))
+        .await;
+    assert!(result.is_err());
+}
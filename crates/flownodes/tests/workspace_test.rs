@@ -0,0 +1,116 @@
+use flowcore::{EventBus, ExecutionId, Node, NodeContext, NodeSpec, Value, Workflow};
+use flownodes::ShellExecNode;
+use flowruntime::{FlowRuntime, NodeRegistry, RuntimeConfig};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+fn temp_dir(prefix: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{}-{}", prefix, uuid::Uuid::new_v4()))
+}
+
+fn runtime(workspace_root: &Path) -> FlowRuntime {
+    let mut registry = NodeRegistry::new();
+    flownodes::register_all(&mut registry);
+    let config = RuntimeConfig {
+        workspace_root: workspace_root.to_path_buf(),
+        ..Default::default()
+    };
+    FlowRuntime::with_registry(Arc::new(registry), config)
+}
+
+fn shell(command: &str) -> NodeSpec {
+    NodeSpec::new("shell.exec")
+        .with_config("command", command)
+        .with_config("shell", true)
+}
+
+#[tokio::test]
+async fn test_nodes_share_workspace() {
+    let root = temp_dir("workspaces");
+    let runtime = runtime(&root);
+
+    let mut workflow = Workflow::new("workspace");
+    let write = workflow.add_node(shell("mkdir out && echo hi > out/note.txt"));
+    let read = workflow.add_node(shell("cat \"$FLOW_WORKSPACE/out/note.txt\""));
+    workflow.connect(write, "stdout", read, "in");
+
+    let result = runtime.execute(&workflow, HashMap::new()).await.unwrap();
+    assert_eq!(
+        result.outputs[&read].get("stdout"),
+        Some(&Value::String("hi".to_string()))
+    );
+
+    // Removed once the execution is over
+    assert!(!root.join(result.execution_id.to_string()).exists());
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[tokio::test]
+async fn test_failed_execution_stops_nodes_before_removing_workspace() {
+    let root = temp_dir("workspaces");
+    let runtime = runtime(&root);
+
+    // The slow node keeps recreating the workspace for as long as it runs
+    let mut workflow = Workflow::new("sibling fails");
+    workflow.add_node(shell(
+        "while true; do mkdir -p \"$FLOW_WORKSPACE/out\" && date > \"$FLOW_WORKSPACE/out/tick\"; sleep 0.05; done",
+    ));
+    workflow.add_node(shell("sleep 0.2; exit 1"));
+
+    assert!(runtime.execute(&workflow, HashMap::new()).await.is_err());
+
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    let left = std::fs::read_dir(&root).map_or(0, |entries| entries.count());
+    assert_eq!(left, 0);
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[tokio::test]
+async fn test_file_inputs_written_to_workspace() {
+    let workspace = temp_dir("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+
+    let event_bus = EventBus::new(100);
+    let node_id = uuid::Uuid::new_v4();
+    let mut ctx = NodeContext::new(node_id, event_bus.create_emitter(ExecutionId::new_v4(), node_id));
+    ctx.workspace = Some(workspace.clone());
+    ctx.config.insert("command".to_string(), Value::String("cat data/in.txt".to_string()));
+    ctx.config.insert("shell".to_string(), Value::Bool(true));
+    ctx.inputs.insert("file:data/in.txt".to_string(), Value::Bytes(b"hello".to_vec()));
+
+    let output = ShellExecNode.execute(ctx).await.unwrap();
+    assert_eq!(output.outputs.get("stdout"), Some(&Value::String("hello".to_string())));
+    assert_eq!(std::fs::read(workspace.join("data/in.txt")).unwrap(), b"hello");
+
+    let _ = std::fs::remove_dir_all(&workspace);
+}
+
+#[tokio::test]
+async fn test_workspace_paths_stay_inside() {
+    let event_bus = EventBus::new(100);
+    let node_id = uuid::Uuid::new_v4();
+    let mut ctx = NodeContext::new(node_id, event_bus.create_emitter(ExecutionId::new_v4(), node_id));
+    assert!(ctx.workspace_path("a.txt").is_err());
+
+    ctx.workspace = Some(PathBuf::from("/srv/ws"));
+    assert_eq!(ctx.workspace_path("a/b.txt").unwrap(), PathBuf::from("/srv/ws/a/b.txt"));
+    assert!(ctx.workspace_path("../b.txt").is_err());
+    assert!(ctx.workspace_path("a/../../b.txt").is_err());
+    assert!(ctx.workspace_path("/etc/passwd").is_err());
+}
+
+#[tokio::test]
+async fn test_prune_removes_untracked_workspaces() {
+    let root = temp_dir("workspaces");
+    let stale = root.join(uuid::Uuid::new_v4().to_string());
+    std::fs::create_dir_all(stale.join("nested")).unwrap();
+    std::fs::create_dir_all(root.join("not-a-workspace")).unwrap();
+
+    let runtime = runtime(&root);
+    assert_eq!(runtime.prune_workspaces().await.unwrap(), 1);
+    assert!(!stale.exists());
+    assert!(root.join("not-a-workspace").exists());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,302 @@
+//! Encrypted credentials
+//!
+//! Credentials are sealed with AES-256-GCM before they reach a backend, so
+//! the database only ever holds ciphertext; the name is bound in as
+//! associated data, so a sealed value copied to another name won't open.
+//! A [`CredentialVault`] pairs a store with the [`CredentialKey`] and
+//! resolves `{"$credential": ...}` references in node config.
+
+use crate::{PersistentStore, StoreError, StoreResult};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use async_trait::async_trait;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use flowcore::{CredentialRef, CredentialResolver, NodeError, Secret, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Prefix of sealed values, for telling formats apart once there are more
+const SEALED_PREFIX: &str = "v1:";
+const NONCE_LEN: usize = 12;
+
+/// Contents of a credential
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CredentialData {
+    /// API key or bearer token
+    Token {
+        token: String,
+    },
+    BasicAuth {
+        username: String,
+        password: String,
+    },
+    /// Client of an OAuth2 client credentials grant
+    #[serde(rename = "oauth2_client_credentials")]
+    OAuth2ClientCredentials {
+        token_url: String,
+        client_id: String,
+        client_secret: String,
+        #[serde(default)]
+        scopes: Vec<String>,
+    },
+}
+
+impl CredentialData {
+    /// Serialized `type` tag, e.g. `"basic_auth"`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CredentialData::Token { .. } => "token",
+            CredentialData::BasicAuth { .. } => "basic_auth",
+            CredentialData::OAuth2ClientCredentials { .. } => "oauth2_client_credentials",
+        }
+    }
+
+    /// Name of the first required field left empty
+    pub fn missing_field(&self) -> Option<&'static str> {
+        let required: &[(&'static str, &str)] = match self {
+            CredentialData::Token { token } => &[("token", token)],
+            CredentialData::BasicAuth { username, password } => {
+                &[("username", username), ("password", password)]
+            }
+            CredentialData::OAuth2ClientCredentials {
+                token_url,
+                client_id,
+                client_secret,
+                ..
+            } => &[
+                ("token_url", token_url),
+                ("client_id", client_id),
+                ("client_secret", client_secret),
+            ],
+        };
+        required
+            .iter()
+            .find(|(_, value)| value.is_empty())
+            .map(|(name, _)| *name)
+    }
+
+    /// What a reference to the credential resolves to: the token itself,
+    /// or an object of its fields, with secret parts as [`Value::Secret`]
+    pub fn to_value(&self) -> Value {
+        match self {
+            CredentialData::Token { token } => Value::Secret(Secret::new(token)),
+            _ => Value::Object(self.fields()),
+        }
+    }
+
+    /// One field, e.g. the `password` of basic auth
+    pub fn field(&self, name: &str) -> Option<Value> {
+        self.fields().remove(name)
+    }
+
+    fn fields(&self) -> HashMap<String, Value> {
+        let secret = |s: &String| Value::Secret(Secret::new(s));
+        let fields = match self {
+            CredentialData::Token { token } => vec![("token", secret(token))],
+            CredentialData::BasicAuth { username, password } => vec![
+                ("username", Value::String(username.clone())),
+                ("password", secret(password)),
+            ],
+            CredentialData::OAuth2ClientCredentials {
+                token_url,
+                client_id,
+                client_secret,
+                scopes,
+            } => vec![
+                ("token_url", Value::String(token_url.clone())),
+                ("client_id", Value::String(client_id.clone())),
+                ("client_secret", secret(client_secret)),
+                (
+                    "scopes",
+                    Value::Array(scopes.iter().cloned().map(Value::String).collect()),
+                ),
+            ],
+        };
+        fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for CredentialData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CredentialData({})", self.kind())
+    }
+}
+
+/// A credential as backends keep it: metadata in clear, contents sealed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CredentialRecord {
+    pub name: String,
+    /// `type` of the [`CredentialData`]
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Contents sealed with the vault's key; never serialized
+    #[serde(default, skip_serializing)]
+    pub sealed: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// AES-256-GCM key credentials are sealed with
+#[derive(Clone)]
+pub struct CredentialKey {
+    cipher: Aes256Gcm,
+}
+
+impl CredentialKey {
+    /// Key from 32 base64-encoded bytes, e.g. the output of
+    /// `openssl rand -base64 32`
+    pub fn from_base64(encoded: &str) -> StoreResult<Self> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| StoreError::Crypto(format!("key is not base64: {}", e)))?;
+        let cipher = Aes256Gcm::new_from_slice(&bytes).map_err(|_| {
+            StoreError::Crypto(format!("key must be 32 bytes, got {}", bytes.len()))
+        })?;
+        Ok(Self { cipher })
+    }
+
+    /// A new random key
+    pub fn generate() -> Self {
+        Self {
+            cipher: Aes256Gcm::new(&Aes256Gcm::generate_key(OsRng)),
+        }
+    }
+
+    /// `data` encrypted for the credential `name`
+    pub fn seal(&self, name: &str, data: &CredentialData) -> StoreResult<String> {
+        let plaintext = serde_json::to_vec(data)?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plaintext,
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_| StoreError::Crypto(format!("cannot seal credential '{}'", name)))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        Ok(format!(
+            "{}{}",
+            SEALED_PREFIX,
+            base64::engine::general_purpose::STANDARD.encode(sealed)
+        ))
+    }
+
+    /// Contents of the credential `name` sealed with this key
+    pub fn open(&self, name: &str, sealed: &str) -> StoreResult<CredentialData> {
+        let unreadable = || {
+            StoreError::Crypto(format!(
+                "cannot open credential '{}' (sealed with another key?)",
+                name
+            ))
+        };
+        let bytes = sealed
+            .strip_prefix(SEALED_PREFIX)
+            .and_then(|b| base64::engine::general_purpose::STANDARD.decode(b).ok())
+            .filter(|b| b.len() > NONCE_LEN)
+            .ok_or_else(|| StoreError::Corrupt(format!("sealed credential '{}'", name)))?;
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_| unreadable())?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+}
+
+impl std::fmt::Debug for CredentialKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CredentialKey(********)")
+    }
+}
+
+/// The credentials of a store, sealed and opened with one key
+#[derive(Clone)]
+pub struct CredentialVault {
+    store: PersistentStore,
+    key: CredentialKey,
+}
+
+impl CredentialVault {
+    pub fn new(store: PersistentStore, key: CredentialKey) -> Self {
+        Self { store, key }
+    }
+
+    /// Store `data` as the credential `name`, replacing its contents if
+    /// it already exists
+    pub async fn put(&self, name: &str, data: &CredentialData) -> StoreResult<CredentialRecord> {
+        let backend = self.store.backend();
+        let now = Utc::now();
+        let created_at = backend
+            .get_credential(name)
+            .await?
+            .map_or(now, |existing| existing.created_at);
+        let record = CredentialRecord {
+            name: name.to_string(),
+            kind: data.kind().to_string(),
+            sealed: self.key.seal(name, data)?,
+            created_at,
+            updated_at: now,
+        };
+        backend.put_credential(&record).await?;
+        Ok(record)
+    }
+
+    /// Metadata of a credential, without opening it
+    pub async fn record(&self, name: &str) -> StoreResult<Option<CredentialRecord>> {
+        self.store.backend().get_credential(name).await
+    }
+
+    pub async fn get(&self, name: &str) -> StoreResult<Option<CredentialData>> {
+        match self.store.backend().get_credential(name).await? {
+            Some(record) => self.key.open(name, &record.sealed).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Metadata of every credential, by name
+    pub async fn list(&self) -> StoreResult<Vec<CredentialRecord>> {
+        self.store.backend().list_credentials().await
+    }
+
+    pub async fn delete(&self, name: &str) -> StoreResult<bool> {
+        self.store.backend().delete_credential(name).await
+    }
+}
+
+#[async_trait]
+impl CredentialResolver for CredentialVault {
+    async fn resolve(&self, reference: &CredentialRef) -> Result<Value, NodeError> {
+        let name = &reference.name;
+        let data = self
+            .get(name)
+            .await
+            .map_err(|e| NodeError::Configuration(format!("credential '{}': {}", name, e)))?
+            .ok_or_else(|| NodeError::Configuration(format!("credential '{}' not found", name)))?;
+        match &reference.field {
+            None => Ok(data.to_value()),
+            Some(field) => data.field(field).ok_or_else(|| {
+                NodeError::Configuration(format!(
+                    "{} credential '{}' has no field '{}'",
+                    data.kind(),
+                    name,
+                    field
+                ))
+            }),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use thiserror::Error;
+
+/// Errors returned by any storage backend
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[cfg(feature = "postgres")]
+    #[error("Postgres error: {0}")]
+    Postgres(#[from] tokio_postgres::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Migration {version} failed: {message}")]
+    Migration { version: u32, message: String },
+
+    #[error("Unsupported database URL: {0}")]
+    UnsupportedUrl(String),
+
+    #[error("Revision conflict: expected {expected}, current is {actual}")]
+    Conflict { expected: u32, actual: u32 },
+
+    #[error("Corrupt record: {0}")]
+    Corrupt(String),
+
+    #[error("Credential encryption error: {0}")]
+    Crypto(String),
+}
+
+pub type StoreResult<T> = std::result::Result<T, StoreError>;
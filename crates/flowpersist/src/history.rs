@@ -0,0 +1,64 @@
+//! Execution history recording, driven by the runtime's listener hook
+
+use crate::{ExecutionRecord, PersistentStore};
+use async_trait::async_trait;
+use chrono::Utc;
+use flowcore::{FlowError, Workflow};
+use flowruntime::{ExecutionCheckpoint, ExecutionListener, ExecutionResult, ExecutionState};
+
+impl ExecutionRecord {
+    /// Build a history record from a finished execution
+    pub fn from_execution(
+        workflow: &Workflow,
+        state: &ExecutionState,
+        result: &Result<ExecutionResult, FlowError>,
+    ) -> Self {
+        let completed_at = state.completed_at.unwrap_or_else(Utc::now);
+        let (completed_nodes, total_nodes) = match result {
+            Ok(r) => (r.completed_nodes, r.total_nodes),
+            Err(_) => (0, workflow.nodes.len()),
+        };
+
+        Self {
+            id: state.execution_id,
+            workflow_id: workflow.id,
+            workflow_name: workflow.name.clone(),
+            workflow_revision: workflow.revision,
+            started_at: state.started_at,
+            completed_at: Some(completed_at),
+            success: result.is_ok(),
+            status: state.status.clone(),
+            duration_ms: (completed_at - state.started_at).num_milliseconds().max(0) as u64,
+            completed_nodes,
+            total_nodes,
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutionListener for PersistentStore {
+    async fn on_execution_finished(
+        &self,
+        workflow: &Workflow,
+        state: &ExecutionState,
+        result: &Result<ExecutionResult, FlowError>,
+    ) {
+        let record = ExecutionRecord::from_execution(workflow, state, result);
+        if let Err(e) = self.record_execution(&record).await {
+            tracing::warn!("Failed to record execution {}: {}", record.id, e);
+        }
+        if let Err(e) = self.delete_checkpoint(record.id).await {
+            tracing::warn!("Failed to delete checkpoint of execution {}: {}", record.id, e);
+        }
+    }
+
+    async fn on_execution_checkpoint(&self, checkpoint: &ExecutionCheckpoint) {
+        if let Err(e) = self.put_checkpoint(checkpoint).await {
+            tracing::warn!(
+                "Failed to save checkpoint of execution {}: {}",
+                checkpoint.execution_id,
+                e
+            );
+        }
+    }
+}
@@ -1,18 +1,51 @@
-//! Workflow persistence with SQLite
+//! Workflow persistence
 //!
 //! Provides:
 //! - Store/load workflow definitions
 //! - Node-level result caching with content-fingerprint
 //! - Workflow execution history
 //! - Cache invalidation
+//! - Cron trigger fire times
+//! - Outbound webhook subscriptions
+//! - Checkpoints of executions waiting for a signal
+//! - Credentials, encrypted at rest
+//! - Environment profiles of workflow variables
+//! - Blob references kept by all of the above
+//!
+//! Storage is abstracted behind the [`WorkflowStore`], [`ExecutionStore`],
+//! [`CacheStore`], [`ScheduleStore`], [`SubscriptionStore`],
+//! [`CheckpointStore`], [`CredentialStore`] and [`EnvironmentStore`] traits with
+//! SQLite, Postgres (`postgres` feature) and
+//! in-memory backends. SQL backends apply versioned [`migrations`] on open.
+
+mod credentials;
+mod error;
+mod history;
+mod memory;
+pub mod migrations;
+#[cfg(feature = "postgres")]
+mod postgres;
+mod sqlite;
+mod store;
+
+pub use credentials::{CredentialData, CredentialKey, CredentialRecord, CredentialVault};
+pub use error::{StoreError, StoreResult};
+pub use memory::MemoryStore;
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresStore;
+pub use sqlite::SqliteStore;
+pub use store::{
+    CacheStore, CheckpointStore, CredentialStore, EnvironmentStore, ExecutionStore, ScheduleStore,
+    Store, SubscriptionStore, WorkflowStore,
+};
 
-use flowcore::{Value, Workflow};
-use rusqlite::{params, Connection};
+use chrono::{DateTime, Utc};
+use flowcore::{Environment, ExecutionId, Value, Workflow, WorkflowDiff};
+use flowruntime::{ExecutionCheckpoint, ExecutionStatus};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::Mutex;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,7 +54,7 @@ pub struct CacheEntry {
     pub config_hash: String,
     pub input_hash: String,
     pub outputs: HashMap<String, Value>,
-    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub created_at: DateTime<Utc>,
     pub ttl_seconds: Option<i64>,
 }
 
@@ -30,232 +63,338 @@ pub struct ExecutionRecord {
     pub id: Uuid,
     pub workflow_id: Uuid,
     pub workflow_name: String,
-    pub started_at: chrono::DateTime<chrono::Utc>,
-    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Revision of the definition that actually ran
+    #[serde(default)]
+    pub workflow_revision: u32,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
     pub success: bool,
+    /// How the execution ended: completed, failed or cancelled
+    pub status: ExecutionStatus,
     pub duration_ms: u64,
     pub completed_nodes: usize,
     pub total_nodes: usize,
 }
 
-/// Persistent store backed by SQLite
+/// Filter and page for execution history queries
+#[derive(Debug, Clone)]
+pub struct ExecutionQuery {
+    pub workflow_id: Option<Uuid>,
+    /// Only successful (`true`) or failed (`false`) executions
+    pub success: Option<bool>,
+    /// Only executions that ended with this status
+    pub status: Option<ExecutionStatus>,
+    pub limit: usize,
+    pub offset: usize,
+}
+
+impl Default for ExecutionQuery {
+    fn default() -> Self {
+        Self {
+            workflow_id: None,
+            success: None,
+            status: None,
+            limit: 50,
+            offset: 0,
+        }
+    }
+}
+
+/// Summary of one stored revision of a workflow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRevision {
+    pub workflow_id: Uuid,
+    pub revision: u32,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Where a cron trigger stands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerSchedule {
+    pub trigger_id: Uuid,
+    pub workflow_id: Uuid,
+    /// Timezone and expression the fire times were computed from
+    pub spec: String,
+    /// `None` once the expression has no future fire times
+    pub next_fire_at: Option<DateTime<Utc>>,
+    pub last_fire_at: Option<DateTime<Utc>>,
+}
+
+/// An outbound webhook notified of execution lifecycle events
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub url: String,
+    /// Event types to deliver, e.g. `execution.failed`; all when empty
+    pub events: Vec<String>,
+    /// Only events of this workflow's executions, or of all when `None`
+    pub workflow_id: Option<Uuid>,
+    /// Key deliveries are signed with (HMAC-SHA256); never serialized
+    #[serde(default, skip_serializing)]
+    pub secret: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WebhookSubscription {
+    /// Whether an event of `event_type` from `workflow_id` is wanted
+    pub fn wants(&self, event_type: &str, workflow_id: Uuid) -> bool {
+        (self.events.is_empty() || self.events.iter().any(|e| e == event_type))
+            && self.workflow_id.is_none_or(|id| id == workflow_id)
+    }
+}
+
+/// Persistent store — a cheap, cloneable handle over any [`Store`] backend
+#[derive(Clone)]
 pub struct PersistentStore {
-    db: Arc<Mutex<Connection>>,
+    backend: Arc<dyn Store>,
 }
 
 impl PersistentStore {
     /// Open or create a SQLite database
-    pub fn open(path: impl AsRef<Path>) -> Result<Self, rusqlite::Error> {
-        let conn = Connection::open(path)?;
-        let store = Self {
-            db: Arc::new(Mutex::new(conn)),
-        };
-        store.initialize_tables()?;
-        Ok(store)
+    pub fn open(path: impl AsRef<Path>) -> StoreResult<Self> {
+        Ok(Self::from_store(Arc::new(SqliteStore::open(path)?)))
     }
 
-    /// Create in-memory store (for testing)
-    pub fn in_memory() -> Result<Self, rusqlite::Error> {
-        let conn = Connection::open_in_memory()?;
-        let store = Self {
-            db: Arc::new(Mutex::new(conn)),
-        };
-        store.initialize_tables()?;
-        Ok(store)
-    }
-
-    fn initialize_tables(&self) -> Result<(), rusqlite::Error> {
-        let conn = self.db.blocking_lock();
-        conn.execute_batch(
-            "
-            CREATE TABLE IF NOT EXISTS workflows (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                description TEXT,
-                definition_json TEXT NOT NULL,
-                created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
-            );
+    /// Create in-memory SQLite store (for testing)
+    pub fn in_memory() -> StoreResult<Self> {
+        Ok(Self::from_store(Arc::new(SqliteStore::open_in_memory()?)))
+    }
 
-            CREATE TABLE IF NOT EXISTS executions (
-                id TEXT PRIMARY KEY,
-                workflow_id TEXT NOT NULL,
-                workflow_name TEXT NOT NULL,
-                started_at TEXT NOT NULL,
-                completed_at TEXT,
-                success INTEGER NOT NULL DEFAULT 0,
-                duration_ms INTEGER NOT NULL DEFAULT 0,
-                completed_nodes INTEGER NOT NULL DEFAULT 0,
-                total_nodes INTEGER NOT NULL DEFAULT 0,
-                FOREIGN KEY (workflow_id) REFERENCES workflows(id)
-            );
+    /// Open a store from a URL:
+    /// - `postgres://…` / `postgresql://…` — Postgres (requires the `postgres` feature)
+    /// - `memory://` — non-persistent in-memory store
+    /// - `sqlite://path`, `:memory:` or a plain file path — SQLite
+    pub async fn connect(url: &str) -> StoreResult<Self> {
+        if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            #[cfg(feature = "postgres")]
+            {
+                return Ok(Self::from_store(Arc::new(PostgresStore::connect(url).await?)));
+            }
+            #[cfg(not(feature = "postgres"))]
+            {
+                return Err(StoreError::UnsupportedUrl(format!(
+                    "{} (flowpersist was built without the `postgres` feature)",
+                    url
+                )));
+            }
+        }
 
-            CREATE TABLE IF NOT EXISTS node_cache (
-                id TEXT PRIMARY KEY,
-                node_type TEXT NOT NULL,
-                config_hash TEXT NOT NULL,
-                input_hash TEXT NOT NULL,
-                outputs_json TEXT NOT NULL,
-                created_at TEXT NOT NULL,
-                ttl_seconds INTEGER,
-                UNIQUE(node_type, config_hash, input_hash)
-            );
+        if url == "memory://" {
+            return Ok(Self::from_store(Arc::new(MemoryStore::new())));
+        }
+
+        if url == ":memory:" {
+            return Self::in_memory();
+        }
+
+        Self::open(url.strip_prefix("sqlite://").unwrap_or(url))
+    }
+
+    /// Wrap an already constructed backend
+    pub fn from_store(backend: Arc<dyn Store>) -> Self {
+        Self { backend }
+    }
+
+    /// The underlying backend
+    pub fn backend(&self) -> &Arc<dyn Store> {
+        &self.backend
+    }
 
-            CREATE INDEX IF NOT EXISTS idx_executions_workflow
-                ON executions(workflow_id);
-            CREATE INDEX IF NOT EXISTS idx_executions_started
-                ON executions(started_at);
-            CREATE INDEX IF NOT EXISTS idx_node_cache_lookup
-                ON node_cache(node_type, config_hash, input_hash);
-            ",
-        )?;
-        Ok(())
+    pub async fn schema_version(&self) -> StoreResult<u32> {
+        self.backend.schema_version().await
     }
 
     // ── Workflow persistence ──
 
-    pub fn save_workflow(&self, workflow: &Workflow) -> Result<(), rusqlite::Error> {
-        let conn = self.db.blocking_lock();
-        let json = serde_json::to_string(workflow).map_err(|e| {
-            rusqlite::Error::ToSqlConversionFailure(Box::new(e))
-        })?;
-        let now = chrono::Utc::now().to_rfc3339();
-
-        conn.execute(
-            "INSERT OR REPLACE INTO workflows (id, name, description, definition_json, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, COALESCE((SELECT created_at FROM workflows WHERE id = ?1), ?5), ?5)",
-            params![
-                workflow.id.to_string(),
-                workflow.name,
-                workflow.description,
-                json,
-                now,
-            ],
-        )?;
-        Ok(())
-    }
-
-    pub fn load_workflow(&self, id: Uuid) -> Result<Option<Workflow>, rusqlite::Error> {
-        let conn = self.db.blocking_lock();
-        let mut stmt =
-            conn.prepare("SELECT definition_json FROM workflows WHERE id = ?1")?;
-        let mut rows = stmt.query_map(params![id.to_string()], |row| {
-            row.get::<_, String>(0)
-        })?;
-
-        match rows.next() {
-            Some(Ok(json)) => {
-                let workflow: Workflow = serde_json::from_str(&json).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
-                })?;
-                Ok(Some(workflow))
-            }
-            _ => Ok(None),
-        }
+    /// Save a new revision unconditionally, returning its number
+    pub async fn save_workflow(&self, workflow: &Workflow) -> StoreResult<u32> {
+        self.backend.save_workflow(workflow, None).await
     }
 
-    pub fn list_workflows(&self) -> Result<Vec<(Uuid, String)>, rusqlite::Error> {
-        let conn = self.db.blocking_lock();
-        let mut stmt =
-            conn.prepare("SELECT id, name FROM workflows ORDER BY updated_at DESC")?;
-        let rows = stmt.query_map([], |row| {
-            let id: String = row.get(0)?;
-            let name: String = row.get(1)?;
-            Ok((Uuid::parse_str(&id).unwrap_or_default(), name))
-        })?;
+    /// Save a new revision only if the current head is `expected_revision`
+    pub async fn update_workflow(
+        &self,
+        workflow: &Workflow,
+        expected_revision: u32,
+    ) -> StoreResult<u32> {
+        self.backend
+            .save_workflow(workflow, Some(expected_revision))
+            .await
+    }
 
-        rows.collect()
+    pub async fn load_workflow(&self, id: Uuid) -> StoreResult<Option<Workflow>> {
+        self.backend.load_workflow(id).await
     }
 
-    pub fn delete_workflow(&self, id: Uuid) -> Result<bool, rusqlite::Error> {
-        let conn = self.db.blocking_lock();
-        let count = conn.execute(
-            "DELETE FROM workflows WHERE id = ?1",
-            params![id.to_string()],
-        )?;
-        Ok(count > 0)
+    pub async fn load_revision(&self, id: Uuid, revision: u32) -> StoreResult<Option<Workflow>> {
+        self.backend.load_revision(id, revision).await
     }
 
-    // ── Execution history ──
+    pub async fn list_revisions(&self, id: Uuid) -> StoreResult<Vec<WorkflowRevision>> {
+        self.backend.list_revisions(id).await
+    }
 
-    pub fn record_execution(
+    /// Make an old revision current again by saving a copy of it as a new
+    /// revision. Returns `None` if the revision doesn't exist.
+    pub async fn rollback_workflow(
         &self,
-        record: &ExecutionRecord,
-    ) -> Result<(), rusqlite::Error> {
-        let conn = self.db.blocking_lock();
-        conn.execute(
-            "INSERT INTO executions (id, workflow_id, workflow_name, started_at, completed_at, success, duration_ms, completed_nodes, total_nodes)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-            params![
-                record.id.to_string(),
-                record.workflow_id.to_string(),
-                record.workflow_name,
-                record.started_at.to_rfc3339(),
-                record.completed_at.map(|t| t.to_rfc3339()),
-                record.success as i32,
-                record.duration_ms as i64,
-                record.completed_nodes as i64,
-                record.total_nodes as i64,
-            ],
-        )?;
-        Ok(())
-    }
-
-    pub fn get_execution_history(
+        id: Uuid,
+        revision: u32,
+        expected_revision: Option<u32>,
+    ) -> StoreResult<Option<Workflow>> {
+        let Some(mut workflow) = self.backend.load_revision(id, revision).await? else {
+            return Ok(None);
+        };
+        workflow.revision = self
+            .backend
+            .save_workflow(&workflow, expected_revision)
+            .await?;
+        Ok(Some(workflow))
+    }
+
+    /// Structural diff between two revisions of the same workflow
+    pub async fn diff_revisions(
+        &self,
+        id: Uuid,
+        from: u32,
+        to: u32,
+    ) -> StoreResult<Option<WorkflowDiff>> {
+        let from = self.backend.load_revision(id, from).await?;
+        let to = self.backend.load_revision(id, to).await?;
+        Ok(match (from, to) {
+            (Some(from), Some(to)) => Some(WorkflowDiff::between(&from, &to)),
+            _ => None,
+        })
+    }
+
+    pub async fn list_workflows(&self) -> StoreResult<Vec<(Uuid, String)>> {
+        self.backend.list_workflows().await
+    }
+
+    pub async fn delete_workflow(&self, id: Uuid) -> StoreResult<bool> {
+        self.backend.delete_workflow(id).await
+    }
+
+    // ── Execution history ──
+
+    pub async fn record_execution(&self, record: &ExecutionRecord) -> StoreResult<()> {
+        self.backend.record_execution(record).await
+    }
+
+    pub async fn get_execution(&self, id: Uuid) -> StoreResult<Option<ExecutionRecord>> {
+        self.backend.get_execution(id).await
+    }
+
+    /// Most recent executions first, optionally filtered by workflow
+    pub async fn get_execution_history(
         &self,
         workflow_id: Option<Uuid>,
         limit: usize,
-    ) -> Result<Vec<ExecutionRecord>, rusqlite::Error> {
-        let conn = self.db.blocking_lock();
-        let query = if workflow_id.is_some() {
-            "SELECT id, workflow_id, workflow_name, started_at, completed_at, success, duration_ms, completed_nodes, total_nodes
-             FROM executions WHERE workflow_id = ?1 ORDER BY started_at DESC LIMIT ?2"
-        } else {
-            "SELECT id, workflow_id, workflow_name, started_at, completed_at, success, duration_ms, completed_nodes, total_nodes
-             FROM executions ORDER BY started_at DESC LIMIT ?1"
-        };
+    ) -> StoreResult<Vec<ExecutionRecord>> {
+        self.backend
+            .query_executions(&ExecutionQuery {
+                workflow_id,
+                limit,
+                ..Default::default()
+            })
+            .await
+    }
 
-        let mut stmt = conn.prepare(query)?;
+    pub async fn query_executions(&self, query: &ExecutionQuery) -> StoreResult<Vec<ExecutionRecord>> {
+        self.backend.query_executions(query).await
+    }
 
-        let rows: rusqlite::Result<Vec<ExecutionRecord>> = if let Some(wf_id) = workflow_id {
-            stmt.query_map(
-                params![wf_id.to_string(), limit as i64],
-                |row| Self::row_to_record(row),
-            )?
-            .collect()
-        } else {
-            stmt.query_map(params![limit as i64], |row| {
-                Self::row_to_record(row)
-            })?
-            .collect()
-        };
+    // ── Cron trigger schedules ──
 
-        Ok(rows?)
-    }
-
-    fn row_to_record(
-        row: &rusqlite::Row,
-    ) -> rusqlite::Result<ExecutionRecord> {
-        Ok(ExecutionRecord {
-            id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap_or_default(),
-            workflow_id: Uuid::parse_str(&row.get::<_, String>(1)?)
-                .unwrap_or_default(),
-            workflow_name: row.get(2)?,
-            started_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
-                .map(|t| t.with_timezone(&chrono::Utc))
-                .unwrap_or_default(),
-            completed_at: row
-                .get::<_, Option<String>>(4)?
-                .and_then(|s| {
-                    chrono::DateTime::parse_from_rfc3339(&s)
-                        .map(|t| t.with_timezone(&chrono::Utc))
-                        .ok()
-                }),
-            success: row.get::<_, i32>(5)? != 0,
-            duration_ms: row.get::<_, i64>(6)? as u64,
-            completed_nodes: row.get::<_, i64>(7)? as usize,
-            total_nodes: row.get::<_, i64>(8)? as usize,
-        })
+    pub async fn load_schedule(&self, trigger_id: Uuid) -> StoreResult<Option<TriggerSchedule>> {
+        self.backend.load_schedule(trigger_id).await
+    }
+
+    pub async fn list_schedules(&self, workflow_id: Option<Uuid>) -> StoreResult<Vec<TriggerSchedule>> {
+        self.backend.list_schedules(workflow_id).await
+    }
+
+    pub async fn put_schedule(&self, schedule: &TriggerSchedule) -> StoreResult<()> {
+        self.backend.put_schedule(schedule).await
+    }
+
+    /// Compare-and-swap on `next_fire_at`; see [`ScheduleStore::advance_schedule`]
+    pub async fn advance_schedule(
+        &self,
+        schedule: &TriggerSchedule,
+        expected_next: Option<DateTime<Utc>>,
+    ) -> StoreResult<bool> {
+        self.backend.advance_schedule(schedule, expected_next).await
+    }
+
+    // ── Webhook subscriptions ──
+
+    pub async fn list_subscriptions(&self) -> StoreResult<Vec<WebhookSubscription>> {
+        self.backend.list_subscriptions().await
+    }
+
+    pub async fn put_subscription(&self, subscription: &WebhookSubscription) -> StoreResult<()> {
+        self.backend.put_subscription(subscription).await
+    }
+
+    pub async fn delete_subscription(&self, id: Uuid) -> StoreResult<bool> {
+        self.backend.delete_subscription(id).await
+    }
+
+    // ── Credentials ──
+
+    /// This store's credentials, sealed and opened with `key`
+    pub fn credentials(&self, key: CredentialKey) -> CredentialVault {
+        CredentialVault::new(self.clone(), key)
+    }
+
+    // ── Environment profiles ──
+
+    pub async fn list_environments(&self) -> StoreResult<Vec<Environment>> {
+        self.backend.list_environments().await
+    }
+
+    pub async fn get_environment(&self, name: &str) -> StoreResult<Option<Environment>> {
+        self.backend.get_environment(name).await
+    }
+
+    /// Insert or replace
+    pub async fn put_environment(&self, environment: &Environment) -> StoreResult<()> {
+        self.backend.put_environment(environment).await
+    }
+
+    pub async fn delete_environment(&self, name: &str) -> StoreResult<bool> {
+        self.backend.delete_environment(name).await
+    }
+
+    // ── Execution checkpoints ──
+
+    /// Insert or replace. A checkpoint holding secrets is stored without
+    /// its values: restoring it fails the execution anyway, and text
+    /// copied from a secret stays out of the database.
+    pub async fn put_checkpoint(&self, checkpoint: &ExecutionCheckpoint) -> StoreResult<()> {
+        if checkpoint.holds_secrets {
+            let stripped = ExecutionCheckpoint {
+                inputs: HashMap::new(),
+                completed: HashMap::new(),
+                signals: HashMap::new(),
+                variables: HashMap::new(),
+                ..checkpoint.clone()
+            };
+            return self.backend.put_checkpoint(&stripped).await;
+        }
+        self.backend.put_checkpoint(checkpoint).await
+    }
+
+    pub async fn delete_checkpoint(&self, execution_id: ExecutionId) -> StoreResult<bool> {
+        self.backend.delete_checkpoint(execution_id).await
+    }
+
+    /// Checkpoints of executions that were waiting when the last process
+    /// stopped, oldest first
+    pub async fn list_checkpoints(&self) -> StoreResult<Vec<ExecutionCheckpoint>> {
+        self.backend.list_checkpoints().await
     }
 
     // ── Node result caching ──
@@ -278,155 +417,1005 @@ impl PersistentStore {
         format!("{:x}", hasher.finish())
     }
 
-    pub fn get_cached_result(
+    pub async fn get_cached_result(
         &self,
         node_type: &str,
         config_hash: &str,
         input_hash: &str,
-    ) -> Result<Option<HashMap<String, Value>>, rusqlite::Error> {
-        let conn = self.db.blocking_lock();
-        let mut stmt = conn.prepare(
-            "SELECT outputs_json, created_at, ttl_seconds FROM node_cache
-             WHERE node_type = ?1 AND config_hash = ?2 AND input_hash = ?3",
-        )?;
-
-        let mut rows = stmt.query_map(
-            params![node_type, config_hash, input_hash],
-            |row| {
-                let json: String = row.get(0)?;
-                let created_at_str: String = row.get(1)?;
-                let ttl: Option<i64> = row.get(2)?;
-                Ok((json, created_at_str, ttl))
-            },
-        )?;
-
-        if let Some(Ok((json_str, created_at_str, ttl_seconds))) = rows.next() {
-            // Check TTL
-            if let Some(ttl) = ttl_seconds {
-                let created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
-                    .map(|t| t.with_timezone(&chrono::Utc))
-                    .unwrap_or_default();
-                let age = chrono::Utc::now()
-                    .signed_duration_since(created_at)
-                    .num_seconds();
-                if age > ttl {
-                    return Ok(None); // Expired
-                }
-            }
-
-            let outputs: HashMap<String, Value> =
-                serde_json::from_str(&json_str).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        0,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                })?;
-            return Ok(Some(outputs));
-        }
-
-        Ok(None)
+    ) -> StoreResult<Option<HashMap<String, Value>>> {
+        self.backend
+            .get_cached_result(node_type, config_hash, input_hash)
+            .await
     }
 
-    pub fn cache_result(
+    pub async fn cache_result(
         &self,
         node_type: &str,
         config_hash: &str,
         input_hash: &str,
         outputs: &HashMap<String, Value>,
         ttl_seconds: Option<i64>,
-    ) -> Result<(), rusqlite::Error> {
-        let conn = self.db.blocking_lock();
-        let id = Uuid::new_v4().to_string();
-        let json = serde_json::to_string(outputs).map_err(|e| {
-            rusqlite::Error::ToSqlConversionFailure(Box::new(e))
-        })?;
-        let now = chrono::Utc::now().to_rfc3339();
-
-        conn.execute(
-            "INSERT OR REPLACE INTO node_cache (id, node_type, config_hash, input_hash, outputs_json, created_at, ttl_seconds)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![id, node_type, config_hash, input_hash, json, now, ttl_seconds],
-        )?;
-        Ok(())
-    }
-
-    pub fn invalidate_cache(
-        &self,
-        node_type: Option<&str>,
-    ) -> Result<usize, rusqlite::Error> {
-        let conn = self.db.blocking_lock();
-        if let Some(nt) = node_type {
-            conn.execute("DELETE FROM node_cache WHERE node_type = ?1", params![nt])
-        } else {
-            conn.execute("DELETE FROM node_cache", [])
+    ) -> StoreResult<()> {
+        self.backend
+            .cache_result(node_type, config_hash, input_hash, outputs, ttl_seconds)
+            .await
+    }
+
+    pub async fn invalidate_cache(&self, node_type: Option<&str>) -> StoreResult<usize> {
+        self.backend.invalidate_cache(node_type).await
+    }
+
+    pub async fn cache_stats(&self) -> StoreResult<(usize, String)> {
+        self.backend.cache_stats().await
+    }
+
+    // ── Artifacts ──
+
+    /// Hashes of the blobs stored data refers to: node configs and
+    /// variables of every workflow revision, environments, live cache
+    /// entries and checkpoints
+    pub async fn referenced_blobs(&self) -> StoreResult<HashSet<String>> {
+        let mut values: Vec<Value> = Vec::new();
+        for workflow in self.backend.all_revisions().await? {
+            values.extend(workflow.nodes.into_iter().flat_map(|node| node.config.into_values()));
+            values.extend(workflow.variables.into_values());
+        }
+        for environment in self.list_environments().await? {
+            values.extend(environment.variables.into_values());
+        }
+        for outputs in self.backend.cached_outputs().await? {
+            values.extend(outputs.into_values());
         }
+        for checkpoint in self.list_checkpoints().await? {
+            values.extend(checkpoint.inputs.into_values());
+            values.extend(checkpoint.completed.into_values().flat_map(HashMap::into_values));
+            values.extend(checkpoint.variables.into_values());
+        }
+
+        Ok(values
+            .iter()
+            .flat_map(Value::blob_refs)
+            .map(|blob| blob.hash.clone())
+            .collect())
     }
+}
 
-    pub fn cache_stats(&self) -> Result<(usize, String), rusqlite::Error> {
-        let conn = self.db.blocking_lock();
-        let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM node_cache",
-            [],
-            |row| row.get(0),
-        )?;
-        let newest: String = conn
-            .query_row(
-                "SELECT COALESCE(MAX(created_at), 'never') FROM node_cache",
-                [],
-                |row| row.get(0),
-            )
-            .unwrap_or_else(|_| "unknown".to_string());
-        Ok((count as usize, newest))
+/// Parse an RFC 3339 timestamp as stored by the SQL backends
+pub(crate) fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+}
+
+/// Deserialize a stored definition, trusting the row's revision number over
+/// the JSON (definitions saved before versioning don't carry one)
+pub(crate) fn parse_workflow(json: &str, revision: u32) -> StoreResult<Workflow> {
+    let mut workflow: Workflow = serde_json::from_str(json)?;
+    workflow.revision = revision;
+    Ok(workflow)
+}
+
+/// Whether a cache entry created at `created_at` has outlived its TTL
+pub(crate) fn is_expired(created_at: DateTime<Utc>, ttl_seconds: Option<i64>) -> bool {
+    match ttl_seconds {
+        Some(ttl) => Utc::now().signed_duration_since(created_at).num_seconds() > ttl,
+        None => false,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use flowcore::Workflow;
+    use flowcore::{NodeSpec, Workflow};
+
+    fn record(workflow: &Workflow, started_at: DateTime<Utc>) -> ExecutionRecord {
+        ExecutionRecord {
+            id: Uuid::new_v4(),
+            workflow_id: workflow.id,
+            workflow_name: workflow.name.clone(),
+            workflow_revision: workflow.revision,
+            started_at,
+            completed_at: Some(started_at),
+            success: true,
+            status: ExecutionStatus::Completed,
+            duration_ms: 5,
+            completed_nodes: 1,
+            total_nodes: 1,
+        }
+    }
+
+    /// A fresh, empty store and whatever it takes to throw it away
+    struct TestBackend {
+        store: PersistentStore,
+        cleanup: Cleanup,
+    }
+
+    enum Cleanup {
+        Nothing,
+        File(std::path::PathBuf),
+        /// Drop the schema; `url` connects to it
+        #[cfg(feature = "postgres")]
+        Schema {
+            client: tokio_postgres::Client,
+            name: String,
+            url: String,
+        },
+    }
+
+    impl TestBackend {
+        /// SQLite in memory and on disk, the plain in-memory store and, with
+        /// the `postgres` feature and `FLOWPERSIST_POSTGRES_URL` set, a
+        /// private schema in that database
+        async fn all() -> Vec<Self> {
+            let path = std::env::temp_dir().join(format!("flowpersist-{}.db", Uuid::new_v4()));
+            vec![
+                Self {
+                    store: PersistentStore::in_memory().unwrap(),
+                    cleanup: Cleanup::Nothing,
+                },
+                Self {
+                    store: PersistentStore::open(&path).unwrap(),
+                    cleanup: Cleanup::File(path),
+                },
+                Self {
+                    store: PersistentStore::connect("memory://").await.unwrap(),
+                    cleanup: Cleanup::Nothing,
+                },
+            ]
+            .into_iter()
+            .chain(Self::postgres().await)
+            .collect()
+        }
+
+        #[cfg(feature = "postgres")]
+        async fn postgres() -> Option<Self> {
+            let url = std::env::var("FLOWPERSIST_POSTGRES_URL").ok()?;
+            let schema = format!("flowpersist_test_{}", Uuid::new_v4().simple());
+            let (client, connection) = tokio_postgres::connect(&url, tokio_postgres::NoTls)
+                .await
+                .unwrap();
+            tokio::spawn(connection);
+            client
+                .batch_execute(&format!("CREATE SCHEMA {}", schema))
+                .await
+                .unwrap();
+
+            let separator = if url.contains('?') { '&' } else { '?' };
+            let url = format!("{}{}options=-c%20search_path%3D{}", url, separator, schema);
+            Some(Self {
+                store: PersistentStore::connect(&url).await.unwrap(),
+                cleanup: Cleanup::Schema {
+                    client,
+                    name: schema,
+                    url,
+                },
+            })
+        }
 
-    #[test]
-    fn test_workflow_persistence() {
+        #[cfg(not(feature = "postgres"))]
+        async fn postgres() -> Option<Self> {
+            None
+        }
+
+        async fn cleanup(self) {
+            drop(self.store);
+            match self.cleanup {
+                Cleanup::Nothing => {}
+                Cleanup::File(path) => {
+                    let _ = std::fs::remove_file(path);
+                }
+                #[cfg(feature = "postgres")]
+                Cleanup::Schema { client, name, .. } => {
+                    let _ = client
+                        .batch_execute(&format!("DROP SCHEMA {} CASCADE", name))
+                        .await;
+                }
+            }
+        }
+    }
+
+    /// Run `test` against a fresh store of every backend
+    async fn each_backend(test: impl AsyncFn(&PersistentStore)) {
+        for backend in TestBackend::all().await {
+            test(&backend.store).await;
+            backend.cleanup().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_workflow_persistence() {
         let store = PersistentStore::in_memory().unwrap();
         let workflow = Workflow::new("test");
 
-        store.save_workflow(&workflow).unwrap();
+        store.save_workflow(&workflow).await.unwrap();
 
-        let loaded = store.load_workflow(workflow.id).unwrap();
+        let loaded = store.load_workflow(workflow.id).await.unwrap();
         assert!(loaded.is_some());
         assert_eq!(loaded.unwrap().name, "test");
     }
 
-    #[test]
-    fn test_cache_hit() {
+    #[tokio::test]
+    async fn test_cache_hit() {
+        each_backend(async |store| {
+            let outputs: HashMap<String, Value> =
+                [("result".to_string(), Value::Number(42.0))]
+                    .into_iter()
+                    .collect();
+
+            store
+                .cache_result("shell.exec", "config_hash", "input_hash", &outputs, Some(3600))
+                .await
+                .unwrap();
+
+            let cached = store
+                .get_cached_result("shell.exec", "config_hash", "input_hash")
+                .await
+                .unwrap();
+            assert!(cached.is_some());
+            assert_eq!(
+                cached.unwrap().get("result"),
+                Some(&Value::Number(42.0))
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_typed_values_round_trip() {
+        let id: i64 = 1_234_567_890_123_456_789;
+        let at = DateTime::parse_from_rfc3339("2024-03-01T12:30:00.123456Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let amount: flowcore::BigDecimal = "12345678901234567890.000000001".parse().unwrap();
+        let outputs = HashMap::from([
+            ("id".to_string(), Value::from(id)),
+            ("at".to_string(), Value::from(at)),
+            ("amount".to_string(), Value::from(amount.clone())),
+        ]);
+
+        // Nothing goes through f64 on the way
+        let json = serde_json::to_value(&outputs).unwrap();
+        assert_eq!(json["id"]["value"], serde_json::json!(id));
+        assert_eq!(json["amount"]["value"], "12345678901234567890.000000001");
+
+        each_backend(async |store| {
+            store
+                .cache_result("shell.exec", "config_hash", "input_hash", &outputs, None)
+                .await
+                .unwrap();
+            let cached = store
+                .get_cached_result("shell.exec", "config_hash", "input_hash")
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(cached, outputs, "{}", store.backend().backend_name());
+            assert_eq!(cached["id"].as_i64(), Some(id));
+            assert_eq!(cached["at"].as_timestamp(), Some(at));
+            assert_eq!(cached["amount"].as_decimal(), Some(&amount));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_cache_miss() {
+        each_backend(async |store| {
+            let cached = store
+                .get_cached_result("shell.exec", "no_match", "no_match")
+                .await
+                .unwrap();
+            assert!(cached.is_none());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_migrations_applied_once() {
+        let dir = std::env::temp_dir().join(format!("flowpersist-{}.db", Uuid::new_v4()));
+
+        let store = PersistentStore::open(&dir).unwrap();
+        assert_eq!(store.schema_version().await.unwrap(), migrations::latest_version());
+        drop(store);
+
+        // Reopening must not re-run (or fail on) applied migrations
+        let store = PersistentStore::open(&dir).unwrap();
+        assert_eq!(store.schema_version().await.unwrap(), migrations::latest_version());
+
+        let _ = std::fs::remove_file(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_backends_agree() {
+        each_backend(async |store| {
+            let workflow = Workflow::new("history");
+            store.save_workflow(&workflow).await.unwrap();
+
+            let now = Utc::now();
+            let older = record(&workflow, now - chrono::Duration::seconds(10));
+            let newer = record(&workflow, now);
+            store.record_execution(&older).await.unwrap();
+            store.record_execution(&newer).await.unwrap();
+
+            let history = store
+                .get_execution_history(Some(workflow.id), 10)
+                .await
+                .unwrap();
+            assert_eq!(history.len(), 2, "{}", store.backend().backend_name());
+            assert_eq!(history[0].id, newer.id);
+            assert!(store.get_execution(older.id).await.unwrap().is_some());
+
+            // History outlives the workflow it belongs to
+            assert_eq!(store.list_workflows().await.unwrap().len(), 1);
+            assert!(store.delete_workflow(workflow.id).await.unwrap());
+            assert!(store.load_workflow(workflow.id).await.unwrap().is_none());
+            assert_eq!(
+                store
+                    .get_execution_history(Some(workflow.id), 10)
+                    .await
+                    .unwrap()
+                    .len(),
+                2
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_query_executions() {
+        each_backend(async |store| {
+            let workflow = Workflow::new("queried");
+            let other = Workflow::new("other");
+            let now = Utc::now();
+            let mut records = Vec::new();
+            for i in 0..4 {
+                let mut r = record(&workflow, now - chrono::Duration::seconds(i));
+                r.success = i % 2 == 0;
+                if !r.success {
+                    r.status = if i == 1 {
+                        ExecutionStatus::Cancelled
+                    } else {
+                        ExecutionStatus::Failed
+                    };
+                }
+                store.record_execution(&r).await.unwrap();
+                records.push(r);
+            }
+            store.record_execution(&record(&other, now)).await.unwrap();
+
+            let failed = store
+                .query_executions(&ExecutionQuery {
+                    workflow_id: Some(workflow.id),
+                    success: Some(false),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            let ids: Vec<_> = failed.iter().map(|r| r.id).collect();
+            assert_eq!(ids, [records[1].id, records[3].id], "{}", store.backend().backend_name());
+
+            let cancelled = store
+                .query_executions(&ExecutionQuery {
+                    status: Some(ExecutionStatus::Cancelled),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            assert_eq!(cancelled.len(), 1);
+            assert_eq!(cancelled[0].id, records[1].id);
+            let stored = store.get_execution(records[3].id).await.unwrap().unwrap();
+            assert_eq!(stored.status, ExecutionStatus::Failed);
+
+            let page = store
+                .query_executions(&ExecutionQuery {
+                    workflow_id: Some(workflow.id),
+                    limit: 2,
+                    offset: 1,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            let ids: Vec<_> = page.iter().map(|r| r.id).collect();
+            assert_eq!(ids, [records[1].id, records[2].id]);
+
+            let all = store.query_executions(&ExecutionQuery::default()).await.unwrap();
+            assert_eq!(all.len(), 5);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_schedule_advances_once() {
+        each_backend(async |store| {
+            let workflow = Workflow::new("scheduled");
+            store.save_workflow(&workflow).await.unwrap();
+
+            let due = Utc::now();
+            let schedule = TriggerSchedule {
+                trigger_id: Uuid::new_v4(),
+                workflow_id: workflow.id,
+                spec: "UTC * * * * *".to_string(),
+                next_fire_at: Some(due),
+                last_fire_at: None,
+            };
+            store.put_schedule(&schedule).await.unwrap();
+            assert_eq!(store.load_schedule(schedule.trigger_id).await.unwrap(), Some(schedule.clone()));
+
+            let advanced = TriggerSchedule {
+                next_fire_at: Some(due + chrono::Duration::minutes(1)),
+                last_fire_at: Some(due),
+                ..schedule.clone()
+            };
+            // Two schedulers racing for the same fire time: only one wins
+            assert!(store.advance_schedule(&advanced, Some(due)).await.unwrap());
+            assert!(!store.advance_schedule(&advanced, Some(due)).await.unwrap());
+            assert_eq!(
+                store.list_schedules(Some(workflow.id)).await.unwrap(),
+                vec![advanced],
+                "{}",
+                store.backend().backend_name()
+            );
+
+            // Schedules go with their workflow
+            store.delete_workflow(workflow.id).await.unwrap();
+            assert!(store.list_schedules(None).await.unwrap().is_empty());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions() {
+        each_backend(async |store| {
+            let workflow = Workflow::new("watched");
+            store.save_workflow(&workflow).await.unwrap();
+
+            let all = WebhookSubscription {
+                id: Uuid::new_v4(),
+                url: "https://example.com/all".to_string(),
+                events: Vec::new(),
+                workflow_id: None,
+                secret: Some("s3cret".to_string()),
+                created_at: parse_timestamp(&Utc::now().to_rfc3339()).unwrap(),
+            };
+            let failures = WebhookSubscription {
+                id: Uuid::new_v4(),
+                url: "https://example.com/failures".to_string(),
+                events: vec!["execution.failed".to_string()],
+                workflow_id: Some(workflow.id),
+                secret: None,
+                ..all.clone()
+            };
+            store.put_subscription(&all).await.unwrap();
+            store.put_subscription(&failures).await.unwrap();
+
+            let mut listed = store.list_subscriptions().await.unwrap();
+            listed.sort_by_key(|s| s.url.clone());
+            assert_eq!(listed, vec![all.clone(), failures.clone()], "{}", store.backend().backend_name());
+
+            assert!(failures.wants("execution.failed", workflow.id));
+            assert!(!failures.wants("execution.completed", workflow.id));
+            assert!(!failures.wants("execution.failed", Uuid::new_v4()));
+            assert!(all.wants("execution.started", Uuid::new_v4()));
+
+            // Subscriptions to one workflow go with it
+            store.delete_workflow(workflow.id).await.unwrap();
+            assert_eq!(store.list_subscriptions().await.unwrap(), vec![all.clone()]);
+            assert!(store.delete_subscription(all.id).await.unwrap());
+            assert!(!store.delete_subscription(all.id).await.unwrap());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_checkpoints() {
+        each_backend(async |store| {
+            let workflow = Workflow::new("waiting");
+            store.save_workflow(&workflow).await.unwrap();
+
+            let mut checkpoint = ExecutionCheckpoint {
+                execution_id: Uuid::new_v4(),
+                workflow_id: workflow.id,
+                workflow_revision: 1,
+                started_at: parse_timestamp(&Utc::now().to_rfc3339()).unwrap(),
+                inputs: HashMap::from([("n".to_string(), Value::Number(1.0))]),
+                completed: HashMap::from([(
+                    Uuid::new_v4(),
+                    HashMap::from([("out".to_string(), Value::String("done".to_string()))]),
+                )]),
+                signals: HashMap::new(),
+                variables: HashMap::from([(
+                    "api".to_string(),
+                    Value::String("https://staging.example.com".to_string()),
+                )]),
+                holds_secrets: false,
+            };
+            store.put_checkpoint(&checkpoint).await.unwrap();
+            checkpoint.signals.insert("go".to_string(), vec![Value::Bool(true)]);
+            store.put_checkpoint(&checkpoint).await.unwrap();
+            assert_eq!(
+                store.list_checkpoints().await.unwrap(),
+                vec![checkpoint.clone()],
+                "{}",
+                store.backend().backend_name()
+            );
+
+            assert!(store.delete_checkpoint(checkpoint.execution_id).await.unwrap());
+            assert!(!store.delete_checkpoint(checkpoint.execution_id).await.unwrap());
+
+            // Checkpoints go with their workflow
+            store.put_checkpoint(&checkpoint).await.unwrap();
+            store.delete_workflow(workflow.id).await.unwrap();
+            assert!(store.list_checkpoints().await.unwrap().is_empty());
+        })
+        .await;
+    }
+
+    /// Counts its runs, then waits for the "go" signal; outputs its
+    /// `token` input as plain text if it has one
+    struct CountThenWait(Arc<std::sync::atomic::AtomicUsize>);
+
+    #[async_trait::async_trait]
+    impl flowcore::Node for CountThenWait {
+        fn node_type(&self) -> &str {
+            "test.wait"
+        }
+
+        async fn execute(
+            &self,
+            ctx: flowcore::NodeContext,
+        ) -> Result<flowcore::NodeOutput, flowcore::NodeError> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if ctx.config.contains_key("wait") {
+                ctx.events.emit(flowcore::NodeEvent::WaitingForSignal {
+                    name: "go".to_string(),
+                    timeout_ms: None,
+                });
+                let payload = ctx.signals.wait("go").await;
+                ctx.events.emit(flowcore::NodeEvent::SignalReceived { name: "go".to_string() });
+                return Ok(flowcore::NodeOutput::new().with_output("payload", payload));
+            }
+            let token = ctx.inputs.get("token").and_then(Value::reveal_str).map(str::to_string);
+            Ok(flowcore::NodeOutput::new().with_output("out", token.map_or(Value::Number(1.0), Value::String)))
+        }
+    }
+
+    impl flowruntime::NodeFactory for CountThenWait {
+        fn create(
+            &self,
+            _config: &HashMap<String, Value>,
+        ) -> Result<Box<dyn flowcore::Node>, flowcore::NodeError> {
+            Ok(Box::new(CountThenWait(Arc::clone(&self.0))))
+        }
+
+        fn node_type(&self) -> &str {
+            "test.wait"
+        }
+    }
+
+    async fn wait_for_status(
+        runtime: &flowruntime::FlowRuntime,
+        execution_id: ExecutionId,
+        status: flowruntime::ExecutionStatus,
+    ) {
+        for _ in 0..200 {
+            if runtime.get_execution_status(execution_id).await.is_some_and(|s| s.status == status) {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("execution {} never became {:?}", execution_id, status);
+    }
+
+    #[tokio::test]
+    async fn test_waiting_execution_resumes_in_new_runtime() {
         let store = PersistentStore::in_memory().unwrap();
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let runtime = || {
+            let mut registry = flowruntime::NodeRegistry::new();
+            registry.register(Arc::new(CountThenWait(Arc::clone(&runs))));
+            let runtime = flowruntime::FlowRuntime::with_registry(Arc::new(registry), Default::default());
+            runtime.add_listener(Arc::new(store.clone()));
+            runtime
+        };
 
-        let outputs: HashMap<String, Value> =
-            [("result".to_string(), Value::Number(42.0))]
-                .into_iter()
-                .collect();
+        let mut workflow = Workflow::new("approval");
+        let first = workflow.add_node(flowcore::NodeSpec::new("test.wait"));
+        let second = workflow.add_node(flowcore::NodeSpec::new("test.wait").with_config("wait", true));
+        workflow.connect(first, "out", second, "in");
 
-        store
-            .cache_result("shell.exec", "config_hash", "input_hash", &outputs, Some(3600))
-            .unwrap();
+        // Waits, then the process "stops"
+        let before = runtime();
+        before.register_workflow(workflow.clone()).await;
+        let execution_id = before.start_workflow(workflow.id, HashMap::new()).await.unwrap();
+        wait_for_status(&before, execution_id, flowruntime::ExecutionStatus::Waiting).await;
+        let checkpoints = store.list_checkpoints().await.unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        assert!(checkpoints[0].completed.contains_key(&first));
+
+        let after = runtime();
+        after.register_workflow(workflow.clone()).await;
+        let checkpoint = checkpoints.into_iter().next().unwrap();
+        assert_eq!(after.restore_execution(workflow.clone(), checkpoint).await.unwrap(), execution_id);
+        wait_for_status(&after, execution_id, flowruntime::ExecutionStatus::Waiting).await;
+        assert!(after.send_signal(execution_id, "go", Value::String("approved".to_string())).await);
+        wait_for_status(&after, execution_id, flowruntime::ExecutionStatus::Completed).await;
+
+        // The first node ran once, the waiting one in each runtime
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 3);
+        let state = after.get_execution_status(execution_id).await.unwrap();
+        assert_eq!(state.outputs[&second]["payload"], Value::String("approved".to_string()));
+        assert!(store.list_checkpoints().await.unwrap().is_empty());
+        assert!(!after.send_signal(execution_id, "go", Value::Null).await);
+    }
 
-        let cached = store
-            .get_cached_result("shell.exec", "config_hash", "input_hash")
+    /// Keeps the checkpoints it is given
+    #[derive(Default)]
+    struct Checkpoints(std::sync::Mutex<Vec<ExecutionCheckpoint>>);
+
+    #[async_trait::async_trait]
+    impl flowruntime::ExecutionListener for Checkpoints {
+        async fn on_execution_finished(
+            &self,
+            _workflow: &Workflow,
+            _state: &flowruntime::ExecutionState,
+            _result: &Result<flowruntime::ExecutionResult, flowcore::FlowError>,
+        ) {
+        }
+
+        async fn on_execution_checkpoint(&self, checkpoint: &ExecutionCheckpoint) {
+            self.0.lock().unwrap().push(checkpoint.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_waiting_execution_with_secret_fails_on_restore() {
+        let store = PersistentStore::in_memory().unwrap();
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let runtime = || {
+            let mut registry = flowruntime::NodeRegistry::new();
+            registry.register(Arc::new(CountThenWait(Arc::clone(&runs))));
+            let runtime = flowruntime::FlowRuntime::with_registry(Arc::new(registry), Default::default());
+            runtime.add_listener(Arc::new(store.clone()));
+            runtime
+        };
+
+        let mut workflow = Workflow::new("approval with token");
+        let first = workflow.add_node(flowcore::NodeSpec::new("test.wait"));
+        let second = workflow.add_node(flowcore::NodeSpec::new("test.wait").with_config("wait", true));
+        workflow.connect(first, "out", second, "in");
+
+        let before = runtime();
+        let seen = Arc::new(Checkpoints::default());
+        before.add_listener(seen.clone());
+        before.register_workflow(workflow.clone()).await;
+        let token = Value::Secret(flowcore::Secret::new("s3cr3t-token"));
+        let inputs = HashMap::from([("token".to_string(), token)]);
+        let execution_id = before.start_workflow(workflow.id, inputs).await.unwrap();
+        wait_for_status(&before, execution_id, flowruntime::ExecutionStatus::Waiting).await;
+
+        // Listeners get the outputs as the node returned them, not the
+        // masked copy of its event
+        let checkpoint = seen.0.lock().unwrap().last().cloned().unwrap();
+        assert!(checkpoint.holds_secrets);
+        assert_eq!(checkpoint.completed[&first]["out"], Value::String("s3cr3t-token".to_string()));
+
+        // The store keeps none of it, so restoring fails the execution
+        let stored = store.list_checkpoints().await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert!(stored[0].holds_secrets);
+        assert!(stored[0].inputs.is_empty() && stored[0].completed.is_empty());
+
+        let after = runtime();
+        after.register_workflow(workflow.clone()).await;
+        let checkpoint = stored.into_iter().next().unwrap();
+        let error = after.restore_execution(workflow, checkpoint).await.unwrap_err();
+        assert!(error.to_string().contains("holds secrets"), "{}", error);
+        let record = store.get_execution(execution_id).await.unwrap().unwrap();
+        assert_eq!(record.status, flowruntime::ExecutionStatus::Failed);
+        assert!(store.list_checkpoints().await.unwrap().is_empty());
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_runtime_records_history() {
+        let store = PersistentStore::in_memory().unwrap();
+        let runtime = flowruntime::FlowRuntime::new();
+        runtime.add_listener(Arc::new(store.clone()));
+
+        let workflow = Workflow::new("empty");
+        runtime.register_workflow(workflow.clone()).await;
+        let result = runtime
+            .execute_workflow(workflow.id, HashMap::new())
+            .await;
+
+        let history = store
+            .get_execution_history(Some(workflow.id), 10)
+            .await
             .unwrap();
-        assert!(cached.is_some());
-        assert_eq!(
-            cached.unwrap().get("result"),
-            Some(&Value::Number(42.0))
-        );
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].success, result.is_ok());
+        assert_eq!(history[0].workflow_name, "empty");
     }
 
-    #[test]
-    fn test_cache_miss() {
+    #[tokio::test]
+    async fn test_finished_executions_only_in_store() {
         let store = PersistentStore::in_memory().unwrap();
-        let cached = store
-            .get_cached_result("shell.exec", "no_match", "no_match")
+        let runtime = flowruntime::FlowRuntime::with_config(flowruntime::RuntimeConfig {
+            max_finished_executions: 0,
+            ..Default::default()
+        });
+        runtime.add_listener(Arc::new(store.clone()));
+
+        let workflow = Workflow::new("evicted");
+        runtime.register_workflow(workflow.clone()).await;
+        let execution_id = runtime
+            .start_workflow(workflow.id, HashMap::new())
+            .await
             .unwrap();
-        assert!(cached.is_none());
+
+        for _ in 0..100 {
+            if runtime.get_execution_status(execution_id).await.is_none() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(runtime.list_executions().await.is_empty());
+        // Recorded before it was evicted from memory
+        assert!(store.get_execution(execution_id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_revisions_are_immutable() {
+        each_backend(async |store| {
+            let mut workflow = Workflow::new("v1");
+            assert_eq!(store.save_workflow(&workflow).await.unwrap(), 1);
+
+            workflow.name = "v2".to_string();
+            workflow.add_node(flowcore::NodeSpec::new("debug.log"));
+            assert_eq!(store.update_workflow(&workflow, 1).await.unwrap(), 2);
+
+            // Stale writer is rejected
+            let err = store.update_workflow(&workflow, 1).await.unwrap_err();
+            assert!(matches!(err, StoreError::Conflict { expected: 1, actual: 2 }));
+
+            let head = store.load_workflow(workflow.id).await.unwrap().unwrap();
+            assert_eq!((head.revision, head.name.as_str()), (2, "v2"));
+            let first = store.load_revision(workflow.id, 1).await.unwrap().unwrap();
+            assert_eq!((first.revision, first.name.as_str()), (1, "v1"));
+
+            let revisions = store.list_revisions(workflow.id).await.unwrap();
+            assert_eq!(
+                revisions.iter().map(|r| r.revision).collect::<Vec<_>>(),
+                vec![2, 1]
+            );
+
+            let diff = store.diff_revisions(workflow.id, 1, 2).await.unwrap().unwrap();
+            assert_eq!(diff.changed_fields, vec!["name".to_string()]);
+            assert_eq!(diff.nodes_added.len(), 1);
+
+            // Rollback appends rather than rewriting history
+            let restored = store
+                .rollback_workflow(workflow.id, 1, Some(2))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!((restored.revision, restored.name.as_str()), (3, "v1"));
+            assert!(store.diff_revisions(workflow.id, 1, 3).await.unwrap().unwrap().is_empty());
+        })
+        .await;
+    }
+
+    /// Only runs with a database to run it against: first saves on two
+    /// connections used to both pass the revision check, then one failed on
+    /// the primary key instead of with a conflict
+    #[cfg(feature = "postgres")]
+    #[tokio::test]
+    async fn test_postgres_concurrent_first_saves_conflict() {
+        let Some(backend) = TestBackend::postgres().await else {
+            return;
+        };
+        let Cleanup::Schema { url, .. } = &backend.cleanup else {
+            unreachable!()
+        };
+        let other = PersistentStore::connect(url).await.unwrap();
+
+        for _ in 0..20 {
+            let workflow = Workflow::new("raced");
+            let (a, b) = tokio::join!(
+                backend.store.update_workflow(&workflow, 0),
+                other.update_workflow(&workflow, 0)
+            );
+            let (saved, rejected) = if a.is_ok() { (a, b) } else { (b, a) };
+            assert_eq!(saved.unwrap(), 1);
+            assert!(
+                matches!(rejected, Err(StoreError::Conflict { expected: 0, actual: 1 })),
+                "{:?}",
+                rejected
+            );
+        }
+
+        drop(other);
+        backend.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn test_pre_revision_database_upgrades() {
+        let path = std::env::temp_dir().join(format!("flowpersist-{}.db", Uuid::new_v4()));
+        let workflow = Workflow::new("legacy");
+
+        // A database written before revisions existed
+        {
+            let conn = rusqlite::Connection::open(&path).unwrap();
+            conn.execute_batch(migrations::MIGRATIONS[0].sqlite).unwrap();
+            let mut json = serde_json::to_value(&workflow).unwrap();
+            json.as_object_mut().unwrap().remove("revision");
+            conn.execute(
+                "INSERT INTO workflows (id, name, description, definition_json, created_at, updated_at)
+                 VALUES (?1, ?2, NULL, ?3, ?4, ?4)",
+                rusqlite::params![
+                    workflow.id.to_string(),
+                    workflow.name,
+                    json.to_string(),
+                    Utc::now().to_rfc3339()
+                ],
+            )
+            .unwrap();
+        }
+
+        let store = PersistentStore::open(&path).unwrap();
+        let loaded = store.load_workflow(workflow.id).await.unwrap().unwrap();
+        assert_eq!(loaded.revision, 1);
+        assert_eq!(store.list_revisions(workflow.id).await.unwrap().len(), 1);
+        assert_eq!(store.update_workflow(&loaded, 1).await.unwrap(), 2);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_artifact_gc_keeps_stored_references() {
+        each_backend(async |store| {
+            let artifacts = flowcore::ArtifactStore::new(
+                std::env::temp_dir().join(format!("artifacts-{}", Uuid::new_v4())),
+            );
+
+            let template = artifacts.put(b"template", "text/plain").await.unwrap();
+            let report = artifacts.put(&[0, 159, 146, 150], "application/pdf").await.unwrap();
+            let orphan = artifacts.put(b"orphan", "text/plain").await.unwrap();
+
+            // Only the first revision still refers to the template
+            let mut workflow = Workflow::new("blobs");
+            workflow.add_node(NodeSpec::new("shell.exec").with_config("stdin", template.clone()));
+            store.save_workflow(&workflow).await.unwrap();
+            workflow.nodes[0].config.clear();
+            store.save_workflow(&workflow).await.unwrap();
+            let outputs = HashMap::from([(
+                "files".to_string(),
+                Value::Array(vec![Value::Blob(report.clone())]),
+            )]);
+            store
+                .cache_result("shell.exec", "config_hash", "input_hash", &outputs, None)
+                .await
+                .unwrap();
+
+            // Blob values survive the round trip through the database
+            let cached = store
+                .get_cached_result("shell.exec", "config_hash", "input_hash")
+                .await
+                .unwrap();
+            assert_eq!(cached, Some(outputs));
+
+            let referenced = store.referenced_blobs().await.unwrap();
+            assert_eq!(
+                referenced,
+                HashSet::from([template.hash.clone(), report.hash.clone()]),
+                "{}",
+                store.backend().backend_name()
+            );
+
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            let gc = artifacts.gc(&referenced, std::time::Duration::ZERO).await.unwrap();
+            assert_eq!((gc.removed, gc.kept, gc.freed_bytes), (1, 2, 6));
+            assert!(artifacts.load(&orphan).await.is_err());
+            assert_eq!(artifacts.load(&report).await.unwrap(), vec![0, 159, 146, 150]);
+            assert_eq!(artifacts.media_type(&report.hash).await.as_deref(), Some("application/pdf"));
+
+            let _ = std::fs::remove_dir_all(artifacts.root());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_credentials_sealed_and_resolved() {
+        each_backend(async |store| {
+            let vault = store.credentials(CredentialKey::generate());
+            let jira = CredentialData::BasicAuth {
+                username: "ops@example.com".to_string(),
+                password: "hunter22".to_string(),
+            };
+            let first = vault.put("jira", &jira).await.unwrap();
+            vault
+                .put("github", &CredentialData::Token { token: "ghp_0123".to_string() })
+                .await
+                .unwrap();
+
+            // Backends and listings only ever see ciphertext
+            let stored = store.backend().get_credential("jira").await.unwrap().unwrap();
+            assert!(stored.sealed.starts_with("v1:") && !stored.sealed.contains("hunter22"));
+            let listed = serde_json::to_string(&vault.list().await.unwrap()).unwrap();
+            assert!(!listed.contains("hunter22") && !listed.contains("v1:"), "{}", listed);
+            assert_eq!(vault.get("jira").await.unwrap(), Some(jira.clone()));
+
+            // Updates keep the creation time
+            let updated = vault.put("jira", &jira).await.unwrap();
+            assert_eq!(updated.created_at, first.created_at);
+
+            let config = HashMap::from([
+                ("token".to_string(), Value::Json(serde_json::json!({"$credential": "github"}))),
+                (
+                    "env".to_string(),
+                    Value::Json(serde_json::json!({
+                        "JIRA_USER": {"$credential": "jira", "field": "username"},
+                        "JIRA_TOKEN": {"$credential": "jira", "field": "password"},
+                    })),
+                ),
+            ]);
+            let resolved = flowcore::resolve_credentials(&config, Some(&vault)).await.unwrap();
+            assert_eq!(resolved["token"].reveal_str(), Some("ghp_0123"));
+            let env = &resolved["env"];
+            assert_eq!(env.pointer("/JIRA_USER"), Some(Value::String("ops@example.com".into())));
+            assert_eq!(env.pointer("/JIRA_TOKEN").unwrap().reveal_str(), Some("hunter22"));
+
+            let missing = HashMap::from([(
+                "token".to_string(),
+                Value::Json(serde_json::json!({"$credential": "gitlab"})),
+            )]);
+            assert!(flowcore::resolve_credentials(&missing, Some(&vault)).await.is_err());
+
+            // Another key, or the same ciphertext under another name, won't open
+            let other = store.credentials(CredentialKey::generate());
+            assert!(matches!(other.get("jira").await, Err(StoreError::Crypto(_))));
+            store
+                .backend()
+                .put_credential(&CredentialRecord { name: "copy".to_string(), ..stored })
+                .await
+                .unwrap();
+            assert!(matches!(vault.get("copy").await, Err(StoreError::Crypto(_))));
+
+            assert!(vault.delete("jira").await.unwrap());
+            assert_eq!(vault.get("jira").await.unwrap(), None);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_environments() {
+        each_backend(async |store| {
+            let prod = Environment::new("prod")
+                .with_variable("zypi_url", "https://zypi.prod.example.com")
+                .with_variable("memory_mb", 2048i64)
+                .with_variable("token", Value::Json(serde_json::json!({"$credential": "zypi"})));
+            store.put_environment(&prod).await.unwrap();
+            store.put_environment(&Environment::new("dev")).await.unwrap();
+            assert_eq!(store.get_environment("prod").await.unwrap(), Some(prod.clone()));
+
+            // Replacing keeps one profile per name
+            let prod = prod.with_variable("memory_mb", 4096i64);
+            store.put_environment(&prod).await.unwrap();
+            let names: Vec<_> = store
+                .list_environments()
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|e| e.name)
+                .collect();
+            assert_eq!(names, ["dev", "prod"]);
+            assert_eq!(
+                store.get_environment("prod").await.unwrap().unwrap().variables["memory_mb"],
+                Value::Integer(4096)
+            );
+
+            assert!(store.delete_environment("prod").await.unwrap());
+            assert!(!store.delete_environment("prod").await.unwrap());
+            assert_eq!(store.get_environment("prod").await.unwrap(), None);
+        })
+        .await;
     }
 }
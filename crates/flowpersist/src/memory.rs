@@ -0,0 +1,389 @@
+//! In-memory backend — nothing survives the process, useful for tests
+//! and throwaway servers
+
+use crate::store::{
+    CacheStore, CheckpointStore, CredentialStore, EnvironmentStore, ExecutionStore, ScheduleStore,
+    Store, SubscriptionStore, WorkflowStore,
+};
+use crate::{
+    CacheEntry, CredentialRecord, ExecutionQuery, ExecutionRecord, StoreError, StoreResult, TriggerSchedule,
+    WebhookSubscription, WorkflowRevision,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flowcore::{Environment, ExecutionId, Value, Workflow};
+use flowruntime::ExecutionCheckpoint;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+type CacheKey = (String, String, String);
+
+/// A saved definition and when it was saved
+type Revision = (Workflow, DateTime<Utc>);
+
+#[derive(Default)]
+pub struct MemoryStore {
+    /// Every revision of each workflow, oldest first (index = revision - 1)
+    workflows: RwLock<HashMap<Uuid, Vec<Revision>>>,
+    executions: RwLock<Vec<ExecutionRecord>>,
+    cache: RwLock<HashMap<CacheKey, CacheEntry>>,
+    schedules: RwLock<HashMap<Uuid, TriggerSchedule>>,
+    subscriptions: RwLock<HashMap<Uuid, WebhookSubscription>>,
+    checkpoints: RwLock<HashMap<ExecutionId, ExecutionCheckpoint>>,
+    credentials: RwLock<HashMap<String, CredentialRecord>>,
+    environments: RwLock<HashMap<String, Environment>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn cache_key(node_type: &str, config_hash: &str, input_hash: &str) -> CacheKey {
+    (
+        node_type.to_string(),
+        config_hash.to_string(),
+        input_hash.to_string(),
+    )
+}
+
+#[async_trait]
+impl WorkflowStore for MemoryStore {
+    async fn save_workflow(
+        &self,
+        workflow: &Workflow,
+        expected_revision: Option<u32>,
+    ) -> StoreResult<u32> {
+        let mut workflows = self.workflows.write().await;
+        let revisions = workflows.entry(workflow.id).or_default();
+        let current = revisions.len() as u32;
+
+        if let Some(expected) = expected_revision {
+            if expected != current {
+                return Err(StoreError::Conflict {
+                    expected,
+                    actual: current,
+                });
+            }
+        }
+
+        let mut stored = workflow.clone();
+        stored.revision = current + 1;
+        revisions.push((stored, Utc::now()));
+        Ok(current + 1)
+    }
+
+    async fn load_workflow(&self, id: Uuid) -> StoreResult<Option<Workflow>> {
+        let workflows = self.workflows.read().await;
+        Ok(workflows
+            .get(&id)
+            .and_then(|revisions| revisions.last())
+            .map(|(w, _)| w.clone()))
+    }
+
+    async fn load_revision(&self, id: Uuid, revision: u32) -> StoreResult<Option<Workflow>> {
+        let workflows = self.workflows.read().await;
+        Ok(workflows
+            .get(&id)
+            .and_then(|revisions| revisions.get((revision as usize).checked_sub(1)?))
+            .map(|(w, _)| w.clone()))
+    }
+
+    async fn all_revisions(&self) -> StoreResult<Vec<Workflow>> {
+        let workflows = self.workflows.read().await;
+        Ok(workflows
+            .values()
+            .flatten()
+            .map(|(w, _)| w.clone())
+            .collect())
+    }
+
+    async fn list_revisions(&self, id: Uuid) -> StoreResult<Vec<WorkflowRevision>> {
+        let workflows = self.workflows.read().await;
+        Ok(workflows
+            .get(&id)
+            .map(|revisions| {
+                revisions
+                    .iter()
+                    .rev()
+                    .map(|(w, created_at)| WorkflowRevision {
+                        workflow_id: id,
+                        revision: w.revision,
+                        name: w.name.clone(),
+                        created_at: *created_at,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn list_workflows(&self) -> StoreResult<Vec<(Uuid, String)>> {
+        let workflows = self.workflows.read().await;
+        let mut heads: Vec<_> = workflows.values().filter_map(|r| r.last()).collect();
+        heads.sort_by_key(|(_, updated)| std::cmp::Reverse(*updated));
+        Ok(heads
+            .into_iter()
+            .map(|(w, _)| (w.id, w.name.clone()))
+            .collect())
+    }
+
+    async fn delete_workflow(&self, id: Uuid) -> StoreResult<bool> {
+        self.schedules
+            .write()
+            .await
+            .retain(|_, s| s.workflow_id != id);
+        self.subscriptions
+            .write()
+            .await
+            .retain(|_, s| s.workflow_id != Some(id));
+        self.checkpoints
+            .write()
+            .await
+            .retain(|_, c| c.workflow_id != id);
+        Ok(self.workflows.write().await.remove(&id).is_some())
+    }
+}
+
+#[async_trait]
+impl ExecutionStore for MemoryStore {
+    async fn record_execution(&self, record: &ExecutionRecord) -> StoreResult<()> {
+        self.executions.write().await.push(record.clone());
+        Ok(())
+    }
+
+    async fn get_execution(&self, id: Uuid) -> StoreResult<Option<ExecutionRecord>> {
+        let executions = self.executions.read().await;
+        Ok(executions.iter().find(|r| r.id == id).cloned())
+    }
+
+    async fn query_executions(&self, query: &ExecutionQuery) -> StoreResult<Vec<ExecutionRecord>> {
+        let executions = self.executions.read().await;
+        let mut records: Vec<ExecutionRecord> = executions
+            .iter()
+            .filter(|r| query.workflow_id.is_none_or(|id| r.workflow_id == id))
+            .filter(|r| query.success.is_none_or(|s| r.success == s))
+            .filter(|r| query.status.as_ref().is_none_or(|s| r.status == *s))
+            .cloned()
+            .collect();
+        records.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+        Ok(records
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl CacheStore for MemoryStore {
+    async fn get_cached_result(
+        &self,
+        node_type: &str,
+        config_hash: &str,
+        input_hash: &str,
+    ) -> StoreResult<Option<HashMap<String, Value>>> {
+        let cache = self.cache.read().await;
+        Ok(cache
+            .get(&cache_key(node_type, config_hash, input_hash))
+            .filter(|entry| !crate::is_expired(entry.created_at, entry.ttl_seconds))
+            .map(|entry| entry.outputs.clone()))
+    }
+
+    async fn cache_result(
+        &self,
+        node_type: &str,
+        config_hash: &str,
+        input_hash: &str,
+        outputs: &HashMap<String, Value>,
+        ttl_seconds: Option<i64>,
+    ) -> StoreResult<()> {
+        self.cache.write().await.insert(
+            cache_key(node_type, config_hash, input_hash),
+            CacheEntry {
+                node_type: node_type.to_string(),
+                config_hash: config_hash.to_string(),
+                input_hash: input_hash.to_string(),
+                outputs: outputs.clone(),
+                created_at: Utc::now(),
+                ttl_seconds,
+            },
+        );
+        Ok(())
+    }
+
+    async fn invalidate_cache(&self, node_type: Option<&str>) -> StoreResult<usize> {
+        let mut cache = self.cache.write().await;
+        let before = cache.len();
+        match node_type {
+            Some(nt) => cache.retain(|_, entry| entry.node_type != nt),
+            None => cache.clear(),
+        }
+        Ok(before - cache.len())
+    }
+
+    async fn cache_stats(&self) -> StoreResult<(usize, String)> {
+        let cache = self.cache.read().await;
+        let newest = cache
+            .values()
+            .map(|entry| entry.created_at)
+            .max()
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_else(|| "never".to_string());
+        Ok((cache.len(), newest))
+    }
+
+    async fn cached_outputs(&self) -> StoreResult<Vec<HashMap<String, Value>>> {
+        Ok(self
+            .cache
+            .read()
+            .await
+            .values()
+            .filter(|entry| !crate::is_expired(entry.created_at, entry.ttl_seconds))
+            .map(|entry| entry.outputs.clone())
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ScheduleStore for MemoryStore {
+    async fn load_schedule(&self, trigger_id: Uuid) -> StoreResult<Option<TriggerSchedule>> {
+        Ok(self.schedules.read().await.get(&trigger_id).cloned())
+    }
+
+    async fn list_schedules(&self, workflow_id: Option<Uuid>) -> StoreResult<Vec<TriggerSchedule>> {
+        let schedules = self.schedules.read().await;
+        Ok(schedules
+            .values()
+            .filter(|s| workflow_id.is_none_or(|id| s.workflow_id == id))
+            .cloned()
+            .collect())
+    }
+
+    async fn put_schedule(&self, schedule: &TriggerSchedule) -> StoreResult<()> {
+        self.schedules
+            .write()
+            .await
+            .insert(schedule.trigger_id, schedule.clone());
+        Ok(())
+    }
+
+    async fn advance_schedule(
+        &self,
+        schedule: &TriggerSchedule,
+        expected_next: Option<DateTime<Utc>>,
+    ) -> StoreResult<bool> {
+        let mut schedules = self.schedules.write().await;
+        match schedules.get_mut(&schedule.trigger_id) {
+            Some(current) if current.next_fire_at == expected_next => {
+                *current = schedule.clone();
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+#[async_trait]
+impl SubscriptionStore for MemoryStore {
+    async fn list_subscriptions(&self) -> StoreResult<Vec<WebhookSubscription>> {
+        let mut subscriptions: Vec<_> = self.subscriptions.read().await.values().cloned().collect();
+        subscriptions.sort_by_key(|s| s.created_at);
+        Ok(subscriptions)
+    }
+
+    async fn put_subscription(&self, subscription: &WebhookSubscription) -> StoreResult<()> {
+        self.subscriptions
+            .write()
+            .await
+            .insert(subscription.id, subscription.clone());
+        Ok(())
+    }
+
+    async fn delete_subscription(&self, id: Uuid) -> StoreResult<bool> {
+        Ok(self.subscriptions.write().await.remove(&id).is_some())
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for MemoryStore {
+    async fn put_checkpoint(&self, checkpoint: &ExecutionCheckpoint) -> StoreResult<()> {
+        self.checkpoints
+            .write()
+            .await
+            .insert(checkpoint.execution_id, checkpoint.clone());
+        Ok(())
+    }
+
+    async fn delete_checkpoint(&self, execution_id: ExecutionId) -> StoreResult<bool> {
+        Ok(self.checkpoints.write().await.remove(&execution_id).is_some())
+    }
+
+    async fn list_checkpoints(&self) -> StoreResult<Vec<ExecutionCheckpoint>> {
+        let mut checkpoints: Vec<_> = self.checkpoints.read().await.values().cloned().collect();
+        checkpoints.sort_by_key(|c| c.started_at);
+        Ok(checkpoints)
+    }
+}
+
+#[async_trait]
+impl CredentialStore for MemoryStore {
+    async fn put_credential(&self, credential: &CredentialRecord) -> StoreResult<()> {
+        self.credentials
+            .write()
+            .await
+            .insert(credential.name.clone(), credential.clone());
+        Ok(())
+    }
+
+    async fn get_credential(&self, name: &str) -> StoreResult<Option<CredentialRecord>> {
+        Ok(self.credentials.read().await.get(name).cloned())
+    }
+
+    async fn list_credentials(&self) -> StoreResult<Vec<CredentialRecord>> {
+        let mut credentials: Vec<_> = self.credentials.read().await.values().cloned().collect();
+        credentials.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(credentials)
+    }
+
+    async fn delete_credential(&self, name: &str) -> StoreResult<bool> {
+        Ok(self.credentials.write().await.remove(name).is_some())
+    }
+}
+
+#[async_trait]
+impl EnvironmentStore for MemoryStore {
+    async fn put_environment(&self, environment: &Environment) -> StoreResult<()> {
+        self.environments
+            .write()
+            .await
+            .insert(environment.name.clone(), environment.clone());
+        Ok(())
+    }
+
+    async fn get_environment(&self, name: &str) -> StoreResult<Option<Environment>> {
+        Ok(self.environments.read().await.get(name).cloned())
+    }
+
+    async fn list_environments(&self) -> StoreResult<Vec<Environment>> {
+        let mut environments: Vec<_> = self.environments.read().await.values().cloned().collect();
+        environments.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(environments)
+    }
+
+    async fn delete_environment(&self, name: &str) -> StoreResult<bool> {
+        Ok(self.environments.write().await.remove(name).is_some())
+    }
+}
+
+#[async_trait]
+impl Store for MemoryStore {
+    fn backend_name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn schema_version(&self) -> StoreResult<u32> {
+        Ok(0)
+    }
+}
@@ -0,0 +1,317 @@
+//! Versioned schema migrations
+//!
+//! Each migration carries the DDL for every SQL backend. Backends record
+//! applied versions in a `schema_version` table and run the pending ones
+//! in order, each inside its own transaction. Never edit a migration that
+//! has shipped — append a new one instead.
+
+/// A single schema change
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub sqlite: &'static str,
+    pub postgres: &'static str,
+}
+
+/// Bookkeeping table, created before any migration runs
+pub(crate) const SCHEMA_VERSION_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS schema_version (
+        version INTEGER PRIMARY KEY,
+        description TEXT NOT NULL,
+        applied_at TEXT NOT NULL
+    );
+";
+
+/// All migrations, in ascending version order
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial schema",
+        // IF NOT EXISTS keeps databases created before versioning working
+        sqlite: "
+            CREATE TABLE IF NOT EXISTS workflows (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT,
+                definition_json TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS executions (
+                id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                workflow_name TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                completed_at TEXT,
+                success INTEGER NOT NULL DEFAULT 0,
+                duration_ms INTEGER NOT NULL DEFAULT 0,
+                completed_nodes INTEGER NOT NULL DEFAULT 0,
+                total_nodes INTEGER NOT NULL DEFAULT 0,
+                FOREIGN KEY (workflow_id) REFERENCES workflows(id)
+            );
+
+            CREATE TABLE IF NOT EXISTS node_cache (
+                id TEXT PRIMARY KEY,
+                node_type TEXT NOT NULL,
+                config_hash TEXT NOT NULL,
+                input_hash TEXT NOT NULL,
+                outputs_json TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                ttl_seconds INTEGER,
+                UNIQUE(node_type, config_hash, input_hash)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_executions_workflow
+                ON executions(workflow_id);
+            CREATE INDEX IF NOT EXISTS idx_executions_started
+                ON executions(started_at);
+            CREATE INDEX IF NOT EXISTS idx_node_cache_lookup
+                ON node_cache(node_type, config_hash, input_hash);
+        ",
+        // Executions may be recorded for ad-hoc workflows that were never
+        // saved, so there is no foreign key here.
+        postgres: "
+            CREATE TABLE IF NOT EXISTS workflows (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT,
+                definition_json TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS executions (
+                id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                workflow_name TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                completed_at TEXT,
+                success BOOLEAN NOT NULL DEFAULT FALSE,
+                duration_ms BIGINT NOT NULL DEFAULT 0,
+                completed_nodes BIGINT NOT NULL DEFAULT 0,
+                total_nodes BIGINT NOT NULL DEFAULT 0
+            );
+
+            CREATE TABLE IF NOT EXISTS node_cache (
+                id TEXT PRIMARY KEY,
+                node_type TEXT NOT NULL,
+                config_hash TEXT NOT NULL,
+                input_hash TEXT NOT NULL,
+                outputs_json TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                ttl_seconds BIGINT,
+                UNIQUE(node_type, config_hash, input_hash)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_executions_workflow
+                ON executions(workflow_id);
+            CREATE INDEX IF NOT EXISTS idx_executions_started
+                ON executions(started_at);
+        ",
+    },
+    Migration {
+        version: 2,
+        description: "keep execution history when a workflow is deleted",
+        // SQLite cannot drop a constraint, so rebuild the table without the
+        // foreign key to workflows
+        sqlite: "
+            CREATE TABLE executions_new (
+                id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                workflow_name TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                completed_at TEXT,
+                success INTEGER NOT NULL DEFAULT 0,
+                duration_ms INTEGER NOT NULL DEFAULT 0,
+                completed_nodes INTEGER NOT NULL DEFAULT 0,
+                total_nodes INTEGER NOT NULL DEFAULT 0
+            );
+            INSERT INTO executions_new SELECT * FROM executions;
+            DROP TABLE executions;
+            ALTER TABLE executions_new RENAME TO executions;
+
+            CREATE INDEX IF NOT EXISTS idx_executions_workflow
+                ON executions(workflow_id);
+            CREATE INDEX IF NOT EXISTS idx_executions_started
+                ON executions(started_at);
+        ",
+        // Never had the foreign key
+        postgres: "",
+    },
+    Migration {
+        version: 3,
+        description: "immutable workflow revisions",
+        // Existing definitions become revision 1
+        sqlite: "
+            ALTER TABLE workflows ADD COLUMN revision INTEGER NOT NULL DEFAULT 0;
+            UPDATE workflows SET revision = 1;
+
+            CREATE TABLE workflow_revisions (
+                workflow_id TEXT NOT NULL,
+                revision INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                definition_json TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (workflow_id, revision)
+            );
+            INSERT INTO workflow_revisions
+                SELECT id, 1, name, definition_json, updated_at FROM workflows;
+
+            ALTER TABLE executions ADD COLUMN workflow_revision INTEGER NOT NULL DEFAULT 0;
+        ",
+        postgres: "
+            ALTER TABLE workflows ADD COLUMN revision INTEGER NOT NULL DEFAULT 0;
+            UPDATE workflows SET revision = 1;
+
+            CREATE TABLE workflow_revisions (
+                workflow_id TEXT NOT NULL,
+                revision INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                definition_json TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (workflow_id, revision)
+            );
+            INSERT INTO workflow_revisions
+                SELECT id, 1, name, definition_json, updated_at FROM workflows;
+
+            ALTER TABLE executions ADD COLUMN workflow_revision INTEGER NOT NULL DEFAULT 0;
+        ",
+    },
+    Migration {
+        version: 4,
+        description: "cron trigger fire times",
+        sqlite: "
+            CREATE TABLE trigger_schedules (
+                trigger_id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                spec TEXT NOT NULL,
+                next_fire_at TEXT,
+                last_fire_at TEXT,
+                updated_at TEXT NOT NULL
+            );
+            CREATE INDEX idx_trigger_schedules_workflow
+                ON trigger_schedules(workflow_id);
+        ",
+        postgres: "
+            CREATE TABLE trigger_schedules (
+                trigger_id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                spec TEXT NOT NULL,
+                next_fire_at TEXT,
+                last_fire_at TEXT,
+                updated_at TEXT NOT NULL
+            );
+            CREATE INDEX idx_trigger_schedules_workflow
+                ON trigger_schedules(workflow_id);
+        ",
+    },
+    Migration {
+        version: 5,
+        description: "webhook subscriptions",
+        sqlite: "
+            CREATE TABLE webhook_subscriptions (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL,
+                events TEXT NOT NULL,
+                workflow_id TEXT,
+                secret TEXT,
+                created_at TEXT NOT NULL
+            );
+        ",
+        postgres: "
+            CREATE TABLE webhook_subscriptions (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL,
+                events TEXT NOT NULL,
+                workflow_id TEXT,
+                secret TEXT,
+                created_at TEXT NOT NULL
+            );
+        ",
+    },
+    Migration {
+        version: 6,
+        description: "execution checkpoints",
+        sqlite: "
+            CREATE TABLE execution_checkpoints (
+                execution_id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                checkpoint_json TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+        ",
+        postgres: "
+            CREATE TABLE execution_checkpoints (
+                execution_id TEXT PRIMARY KEY,
+                workflow_id TEXT NOT NULL,
+                checkpoint_json TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+        ",
+    },
+    Migration {
+        version: 7,
+        description: "encrypted credentials",
+        sqlite: "
+            CREATE TABLE credentials (
+                name TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                sealed TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+        ",
+        postgres: "
+            CREATE TABLE credentials (
+                name TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                sealed TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+        ",
+    },
+    Migration {
+        version: 8,
+        description: "environment profiles",
+        sqlite: "
+            CREATE TABLE environments (
+                name TEXT PRIMARY KEY,
+                variables_json TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+        ",
+        postgres: "
+            CREATE TABLE environments (
+                name TEXT PRIMARY KEY,
+                variables_json TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+        ",
+    },
+    Migration {
+        version: 9,
+        description: "execution status",
+        // Cancelled runs recorded before this read as failed
+        sqlite: "
+            ALTER TABLE executions ADD COLUMN status TEXT NOT NULL DEFAULT 'failed';
+            UPDATE executions SET status = 'completed' WHERE success != 0;
+        ",
+        postgres: "
+            ALTER TABLE executions ADD COLUMN status TEXT NOT NULL DEFAULT 'failed';
+            UPDATE executions SET status = 'completed' WHERE success;
+        ",
+    },
+];
+
+/// Migrations newer than `current`, in order
+pub(crate) fn pending(current: u32) -> impl Iterator<Item = &'static Migration> {
+    MIGRATIONS.iter().filter(move |m| m.version > current)
+}
+
+/// Version the schema will be at once every migration has run
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
@@ -0,0 +1,814 @@
+//! SQLite backend
+
+use crate::migrations::{self, SCHEMA_VERSION_TABLE};
+use crate::store::{
+    CacheStore, CheckpointStore, CredentialStore, EnvironmentStore, ExecutionStore, ScheduleStore,
+    Store, SubscriptionStore, WorkflowStore,
+};
+use crate::{
+    parse_timestamp, parse_workflow, CredentialRecord, ExecutionQuery, ExecutionRecord,
+    StoreError, StoreResult, TriggerSchedule, WebhookSubscription, WorkflowRevision,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flowcore::{Environment, ExecutionId, Value, Workflow};
+use flowruntime::{ExecutionCheckpoint, ExecutionStatus};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use uuid::Uuid;
+
+/// Store backed by a single SQLite connection
+pub struct SqliteStore {
+    db: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    /// Open or create a SQLite database and bring its schema up to date
+    pub fn open(path: impl AsRef<Path>) -> StoreResult<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Private in-memory database (for testing)
+    pub fn open_in_memory() -> StoreResult<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(mut conn: Connection) -> StoreResult<Self> {
+        Self::migrate(&mut conn)?;
+        Ok(Self {
+            db: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Run `f` against the connection on the blocking thread pool, so
+    /// rusqlite's synchronous disk I/O never stalls an async worker
+    async fn with_conn<T, F>(&self, f: F) -> StoreResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> StoreResult<T> + Send + 'static,
+    {
+        let db = Arc::clone(&self.db);
+        tokio::task::spawn_blocking(move || {
+            let mut conn = db.lock().unwrap_or_else(PoisonError::into_inner);
+            f(&mut conn)
+        })
+        .await
+        .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+    }
+
+    fn current_version(conn: &Connection) -> StoreResult<u32> {
+        let version: Option<i64> =
+            conn.query_row("SELECT MAX(version) FROM schema_version", [], |row| row.get(0))?;
+        Ok(version.unwrap_or(0) as u32)
+    }
+
+    fn migrate(conn: &mut Connection) -> StoreResult<()> {
+        conn.execute_batch(SCHEMA_VERSION_TABLE)?;
+        let current = Self::current_version(conn)?;
+
+        for migration in migrations::pending(current) {
+            tracing::info!(
+                "Applying SQLite migration {}: {}",
+                migration.version,
+                migration.description
+            );
+            let tx = conn.transaction()?;
+            tx.execute_batch(migration.sqlite)
+                .map_err(|e| StoreError::Migration {
+                    version: migration.version,
+                    message: e.to_string(),
+                })?;
+            tx.execute(
+                "INSERT INTO schema_version (version, description, applied_at) VALUES (?1, ?2, ?3)",
+                params![
+                    migration.version,
+                    migration.description,
+                    chrono::Utc::now().to_rfc3339(),
+                ],
+            )?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    fn row_to_record(row: &rusqlite::Row) -> rusqlite::Result<ExecutionRecord> {
+        Ok(ExecutionRecord {
+            id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap_or_default(),
+            workflow_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap_or_default(),
+            workflow_name: row.get(2)?,
+            started_at: parse_timestamp(&row.get::<_, String>(3)?).unwrap_or_default(),
+            completed_at: row
+                .get::<_, Option<String>>(4)?
+                .and_then(|s| parse_timestamp(&s)),
+            success: row.get::<_, i32>(5)? != 0,
+            duration_ms: row.get::<_, i64>(6)? as u64,
+            completed_nodes: row.get::<_, i64>(7)? as usize,
+            total_nodes: row.get::<_, i64>(8)? as usize,
+            workflow_revision: row.get(9)?,
+            status: ExecutionStatus::from_name(&row.get::<_, String>(10)?)
+                .unwrap_or(ExecutionStatus::Failed),
+        })
+    }
+
+    fn row_to_schedule(row: &rusqlite::Row) -> rusqlite::Result<TriggerSchedule> {
+        Ok(TriggerSchedule {
+            trigger_id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap_or_default(),
+            workflow_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap_or_default(),
+            spec: row.get(2)?,
+            next_fire_at: row
+                .get::<_, Option<String>>(3)?
+                .and_then(|s| parse_timestamp(&s)),
+            last_fire_at: row
+                .get::<_, Option<String>>(4)?
+                .and_then(|s| parse_timestamp(&s)),
+        })
+    }
+
+    fn row_to_subscription(row: &rusqlite::Row) -> rusqlite::Result<WebhookSubscription> {
+        Ok(WebhookSubscription {
+            id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap_or_default(),
+            url: row.get(1)?,
+            events: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or_default(),
+            workflow_id: row
+                .get::<_, Option<String>>(3)?
+                .and_then(|s| Uuid::parse_str(&s).ok()),
+            secret: row.get(4)?,
+            created_at: parse_timestamp(&row.get::<_, String>(5)?).unwrap_or_default(),
+        })
+    }
+
+    fn row_to_credential(row: &rusqlite::Row) -> rusqlite::Result<CredentialRecord> {
+        Ok(CredentialRecord {
+            name: row.get(0)?,
+            kind: row.get(1)?,
+            sealed: row.get(2)?,
+            created_at: parse_timestamp(&row.get::<_, String>(3)?).unwrap_or_default(),
+            updated_at: parse_timestamp(&row.get::<_, String>(4)?).unwrap_or_default(),
+        })
+    }
+}
+
+#[async_trait]
+impl WorkflowStore for SqliteStore {
+    async fn save_workflow(
+        &self,
+        workflow: &Workflow,
+        expected_revision: Option<u32>,
+    ) -> StoreResult<u32> {
+        let now = chrono::Utc::now().to_rfc3339();
+        let workflow = workflow.clone();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+
+            let current: u32 = tx
+                .query_row(
+                    "SELECT revision FROM workflows WHERE id = ?1",
+                    params![workflow.id.to_string()],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .unwrap_or(0);
+
+            if let Some(expected) = expected_revision {
+                if expected != current {
+                    return Err(StoreError::Conflict {
+                        expected,
+                        actual: current,
+                    });
+                }
+            }
+
+            let mut stored = workflow.clone();
+            stored.revision = current + 1;
+            let json = serde_json::to_string(&stored)?;
+
+            tx.execute(
+                "INSERT INTO workflow_revisions (workflow_id, revision, name, definition_json, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![workflow.id.to_string(), stored.revision, workflow.name, json, now],
+            )?;
+            tx.execute(
+                "INSERT OR REPLACE INTO workflows (id, name, description, definition_json, created_at, updated_at, revision)
+                 VALUES (?1, ?2, ?3, ?4, COALESCE((SELECT created_at FROM workflows WHERE id = ?1), ?5), ?5, ?6)",
+                params![
+                    workflow.id.to_string(),
+                    workflow.name,
+                    workflow.description,
+                    json,
+                    now,
+                    stored.revision,
+                ],
+            )?;
+            tx.commit()?;
+
+            Ok(stored.revision)
+        })
+        .await
+    }
+
+    async fn load_workflow(&self, id: Uuid) -> StoreResult<Option<Workflow>> {
+        self.with_conn(move |conn| {
+            let row: Option<(String, u32)> = conn
+                .query_row(
+                    "SELECT definition_json, revision FROM workflows WHERE id = ?1",
+                    params![id.to_string()],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+
+            row.map(|(json, revision)| parse_workflow(&json, revision))
+                .transpose()
+        })
+        .await
+    }
+
+    async fn load_revision(&self, id: Uuid, revision: u32) -> StoreResult<Option<Workflow>> {
+        self.with_conn(move |conn| {
+            let json: Option<String> = conn
+                .query_row(
+                    "SELECT definition_json FROM workflow_revisions WHERE workflow_id = ?1 AND revision = ?2",
+                    params![id.to_string(), revision],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            json.map(|json| parse_workflow(&json, revision)).transpose()
+        })
+        .await
+    }
+
+    async fn all_revisions(&self) -> StoreResult<Vec<Workflow>> {
+        self.with_conn(move |conn| {
+            let mut stmt =
+                conn.prepare("SELECT definition_json, revision FROM workflow_revisions")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?))
+            })?;
+
+            let mut workflows = Vec::new();
+            for row in rows {
+                let (json, revision) = row?;
+                workflows.push(parse_workflow(&json, revision)?);
+            }
+            Ok(workflows)
+        })
+        .await
+    }
+
+    async fn list_revisions(&self, id: Uuid) -> StoreResult<Vec<WorkflowRevision>> {
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT revision, name, created_at FROM workflow_revisions
+                 WHERE workflow_id = ?1 ORDER BY revision DESC",
+            )?;
+            let rows = stmt.query_map(params![id.to_string()], |row| {
+                Ok(WorkflowRevision {
+                    workflow_id: id,
+                    revision: row.get(0)?,
+                    name: row.get(1)?,
+                    created_at: parse_timestamp(&row.get::<_, String>(2)?).unwrap_or_default(),
+                })
+            })?;
+
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        })
+        .await
+    }
+
+    async fn list_workflows(&self) -> StoreResult<Vec<(Uuid, String)>> {
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare("SELECT id, name FROM workflows ORDER BY updated_at DESC")?;
+            let rows = stmt.query_map([], |row| {
+                let id: String = row.get(0)?;
+                let name: String = row.get(1)?;
+                Ok((Uuid::parse_str(&id).unwrap_or_default(), name))
+            })?;
+
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        })
+        .await
+    }
+
+    async fn delete_workflow(&self, id: Uuid) -> StoreResult<bool> {
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute(
+                "DELETE FROM workflow_revisions WHERE workflow_id = ?1",
+                params![id.to_string()],
+            )?;
+            tx.execute(
+                "DELETE FROM trigger_schedules WHERE workflow_id = ?1",
+                params![id.to_string()],
+            )?;
+            tx.execute(
+                "DELETE FROM webhook_subscriptions WHERE workflow_id = ?1",
+                params![id.to_string()],
+            )?;
+            tx.execute(
+                "DELETE FROM execution_checkpoints WHERE workflow_id = ?1",
+                params![id.to_string()],
+            )?;
+            let count = tx.execute("DELETE FROM workflows WHERE id = ?1", params![id.to_string()])?;
+            tx.commit()?;
+            Ok(count > 0)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl ExecutionStore for SqliteStore {
+    async fn record_execution(&self, record: &ExecutionRecord) -> StoreResult<()> {
+        let record = record.clone();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO executions (id, workflow_id, workflow_name, started_at, completed_at, success, duration_ms, completed_nodes, total_nodes, workflow_revision, status)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    record.id.to_string(),
+                    record.workflow_id.to_string(),
+                    record.workflow_name,
+                    record.started_at.to_rfc3339(),
+                    record.completed_at.map(|t| t.to_rfc3339()),
+                    record.success as i32,
+                    record.duration_ms as i64,
+                    record.completed_nodes as i64,
+                    record.total_nodes as i64,
+                    record.workflow_revision,
+                    record.status.as_str(),
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_execution(&self, id: Uuid) -> StoreResult<Option<ExecutionRecord>> {
+        self.with_conn(move |conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT id, workflow_id, workflow_name, started_at, completed_at, success, duration_ms, completed_nodes, total_nodes, workflow_revision, status
+                     FROM executions WHERE id = ?1",
+                    params![id.to_string()],
+                    Self::row_to_record,
+                )
+                .optional()?)
+        })
+        .await
+    }
+
+    async fn query_executions(&self, query: &ExecutionQuery) -> StoreResult<Vec<ExecutionRecord>> {
+        let query = query.clone();
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, workflow_id, workflow_name, started_at, completed_at, success, duration_ms, completed_nodes, total_nodes, workflow_revision, status
+                 FROM executions
+                 WHERE (?1 IS NULL OR workflow_id = ?1) AND (?2 IS NULL OR success = ?2)
+                   AND (?5 IS NULL OR status = ?5)
+                 ORDER BY started_at DESC LIMIT ?3 OFFSET ?4",
+            )?;
+
+            let rows = stmt.query_map(
+                params![
+                    query.workflow_id.map(|id| id.to_string()),
+                    query.success.map(|s| s as i32),
+                    query.limit as i64,
+                    query.offset as i64,
+                    query.status.as_ref().map(|s| s.as_str()),
+                ],
+                Self::row_to_record,
+            )?;
+
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl CacheStore for SqliteStore {
+    async fn get_cached_result(
+        &self,
+        node_type: &str,
+        config_hash: &str,
+        input_hash: &str,
+    ) -> StoreResult<Option<HashMap<String, Value>>> {
+        let (node_type, config_hash, input_hash) =
+            (node_type.to_string(), config_hash.to_string(), input_hash.to_string());
+        self.with_conn(move |conn| {
+            let row: Option<(String, String, Option<i64>)> = conn
+                .query_row(
+                    "SELECT outputs_json, created_at, ttl_seconds FROM node_cache
+                     WHERE node_type = ?1 AND config_hash = ?2 AND input_hash = ?3",
+                    params![node_type, config_hash, input_hash],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .optional()?;
+
+            let Some((json, created_at, ttl_seconds)) = row else {
+                return Ok(None);
+            };
+
+            if crate::is_expired(parse_timestamp(&created_at).unwrap_or_default(), ttl_seconds) {
+                return Ok(None);
+            }
+
+            Ok(Some(serde_json::from_str(&json)?))
+        })
+        .await
+    }
+
+    async fn cache_result(
+        &self,
+        node_type: &str,
+        config_hash: &str,
+        input_hash: &str,
+        outputs: &HashMap<String, Value>,
+        ttl_seconds: Option<i64>,
+    ) -> StoreResult<()> {
+        let id = Uuid::new_v4().to_string();
+        let json = serde_json::to_string(outputs)?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let (node_type, config_hash, input_hash) =
+            (node_type.to_string(), config_hash.to_string(), input_hash.to_string());
+        self.with_conn(move |conn| {
+
+            conn.execute(
+                "INSERT OR REPLACE INTO node_cache (id, node_type, config_hash, input_hash, outputs_json, created_at, ttl_seconds)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![id, node_type, config_hash, input_hash, json, now, ttl_seconds],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn invalidate_cache(&self, node_type: Option<&str>) -> StoreResult<usize> {
+        let node_type = node_type.map(str::to_string);
+        self.with_conn(move |conn| {
+            let count = if let Some(nt) = node_type {
+                conn.execute("DELETE FROM node_cache WHERE node_type = ?1", params![nt])?
+            } else {
+                conn.execute("DELETE FROM node_cache", [])?
+            };
+            Ok(count)
+        })
+        .await
+    }
+
+    async fn cache_stats(&self) -> StoreResult<(usize, String)> {
+        self.with_conn(move |conn| {
+            let count: i64 = conn.query_row("SELECT COUNT(*) FROM node_cache", [], |row| row.get(0))?;
+            let newest: String = conn
+                .query_row(
+                    "SELECT COALESCE(MAX(created_at), 'never') FROM node_cache",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap_or_else(|_| "unknown".to_string());
+            Ok((count as usize, newest))
+        })
+        .await
+    }
+
+    async fn cached_outputs(&self) -> StoreResult<Vec<HashMap<String, Value>>> {
+        self.with_conn(move |conn| {
+            let mut stmt =
+                conn.prepare("SELECT outputs_json, created_at, ttl_seconds FROM node_cache")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                ))
+            })?;
+
+            let mut outputs = Vec::new();
+            for row in rows {
+                let (json, created_at, ttl_seconds) = row?;
+                if !crate::is_expired(parse_timestamp(&created_at).unwrap_or_default(), ttl_seconds) {
+                    outputs.push(serde_json::from_str(&json)?);
+                }
+            }
+            Ok(outputs)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl ScheduleStore for SqliteStore {
+    async fn load_schedule(&self, trigger_id: Uuid) -> StoreResult<Option<TriggerSchedule>> {
+        self.with_conn(move |conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT trigger_id, workflow_id, spec, next_fire_at, last_fire_at
+                     FROM trigger_schedules WHERE trigger_id = ?1",
+                    params![trigger_id.to_string()],
+                    Self::row_to_schedule,
+                )
+                .optional()?)
+        })
+        .await
+    }
+
+    async fn list_schedules(&self, workflow_id: Option<Uuid>) -> StoreResult<Vec<TriggerSchedule>> {
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT trigger_id, workflow_id, spec, next_fire_at, last_fire_at
+                 FROM trigger_schedules WHERE ?1 IS NULL OR workflow_id = ?1",
+            )?;
+            let rows = stmt.query_map(
+                params![workflow_id.map(|id| id.to_string())],
+                Self::row_to_schedule,
+            )?;
+
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        })
+        .await
+    }
+
+    async fn put_schedule(&self, schedule: &TriggerSchedule) -> StoreResult<()> {
+        let schedule = schedule.clone();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO trigger_schedules (trigger_id, workflow_id, spec, next_fire_at, last_fire_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    schedule.trigger_id.to_string(),
+                    schedule.workflow_id.to_string(),
+                    schedule.spec,
+                    schedule.next_fire_at.map(|t| t.to_rfc3339()),
+                    schedule.last_fire_at.map(|t| t.to_rfc3339()),
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn advance_schedule(
+        &self,
+        schedule: &TriggerSchedule,
+        expected_next: Option<DateTime<Utc>>,
+    ) -> StoreResult<bool> {
+        let schedule = schedule.clone();
+        self.with_conn(move |conn| {
+            let count = conn.execute(
+                "UPDATE trigger_schedules
+                 SET spec = ?2, next_fire_at = ?3, last_fire_at = ?4, updated_at = ?5
+                 WHERE trigger_id = ?1 AND next_fire_at IS ?6",
+                params![
+                    schedule.trigger_id.to_string(),
+                    schedule.spec,
+                    schedule.next_fire_at.map(|t| t.to_rfc3339()),
+                    schedule.last_fire_at.map(|t| t.to_rfc3339()),
+                    Utc::now().to_rfc3339(),
+                    expected_next.map(|t| t.to_rfc3339()),
+                ],
+            )?;
+            Ok(count > 0)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl SubscriptionStore for SqliteStore {
+    async fn list_subscriptions(&self) -> StoreResult<Vec<WebhookSubscription>> {
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, url, events, workflow_id, secret, created_at
+                 FROM webhook_subscriptions ORDER BY created_at",
+            )?;
+            let rows = stmt.query_map([], Self::row_to_subscription)?;
+
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        })
+        .await
+    }
+
+    async fn put_subscription(&self, subscription: &WebhookSubscription) -> StoreResult<()> {
+        let subscription = subscription.clone();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO webhook_subscriptions (id, url, events, workflow_id, secret, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    subscription.id.to_string(),
+                    subscription.url,
+                    serde_json::to_string(&subscription.events)?,
+                    subscription.workflow_id.map(|id| id.to_string()),
+                    subscription.secret,
+                    subscription.created_at.to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delete_subscription(&self, id: Uuid) -> StoreResult<bool> {
+        self.with_conn(move |conn| {
+            let count = conn.execute(
+                "DELETE FROM webhook_subscriptions WHERE id = ?1",
+                params![id.to_string()],
+            )?;
+            Ok(count > 0)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for SqliteStore {
+    async fn put_checkpoint(&self, checkpoint: &ExecutionCheckpoint) -> StoreResult<()> {
+        let checkpoint = checkpoint.clone();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO execution_checkpoints (execution_id, workflow_id, checkpoint_json, updated_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    checkpoint.execution_id.to_string(),
+                    checkpoint.workflow_id.to_string(),
+                    serde_json::to_string(&checkpoint)?,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delete_checkpoint(&self, execution_id: ExecutionId) -> StoreResult<bool> {
+        self.with_conn(move |conn| {
+            let count = conn.execute(
+                "DELETE FROM execution_checkpoints WHERE execution_id = ?1",
+                params![execution_id.to_string()],
+            )?;
+            Ok(count > 0)
+        })
+        .await
+    }
+
+    async fn list_checkpoints(&self) -> StoreResult<Vec<ExecutionCheckpoint>> {
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT checkpoint_json FROM execution_checkpoints ORDER BY updated_at",
+            )?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+            let mut checkpoints = Vec::new();
+            for row in rows {
+                checkpoints.push(serde_json::from_str(&row?)?);
+            }
+            Ok(checkpoints)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl CredentialStore for SqliteStore {
+    async fn put_credential(&self, credential: &CredentialRecord) -> StoreResult<()> {
+        let credential = credential.clone();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO credentials (name, kind, sealed, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    credential.name,
+                    credential.kind,
+                    credential.sealed,
+                    credential.created_at.to_rfc3339(),
+                    credential.updated_at.to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_credential(&self, name: &str) -> StoreResult<Option<CredentialRecord>> {
+        let name = name.to_string();
+        self.with_conn(move |conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT name, kind, sealed, created_at, updated_at FROM credentials WHERE name = ?1",
+                    params![name],
+                    Self::row_to_credential,
+                )
+                .optional()?)
+        })
+        .await
+    }
+
+    async fn list_credentials(&self) -> StoreResult<Vec<CredentialRecord>> {
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT name, kind, sealed, created_at, updated_at FROM credentials ORDER BY name",
+            )?;
+            let rows = stmt.query_map([], Self::row_to_credential)?;
+            Ok(rows.collect::<Result<_, _>>()?)
+        })
+        .await
+    }
+
+    async fn delete_credential(&self, name: &str) -> StoreResult<bool> {
+        let name = name.to_string();
+        self.with_conn(move |conn| {
+            let count = conn.execute("DELETE FROM credentials WHERE name = ?1", params![name])?;
+            Ok(count > 0)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl EnvironmentStore for SqliteStore {
+    async fn put_environment(&self, environment: &Environment) -> StoreResult<()> {
+        let environment = environment.clone();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO environments (name, variables_json, updated_at)
+                 VALUES (?1, ?2, ?3)",
+                params![
+                    environment.name,
+                    serde_json::to_string(&environment.variables)?,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_environment(&self, name: &str) -> StoreResult<Option<Environment>> {
+        let name = name.to_string();
+        self.with_conn(move |conn| {
+            let json: Option<String> = conn
+                .query_row(
+                    "SELECT variables_json FROM environments WHERE name = ?1",
+                    params![name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            json.map(|json| {
+                Ok(Environment {
+                    name: name.to_string(),
+                    variables: serde_json::from_str(&json)?,
+                })
+            })
+            .transpose()
+        })
+        .await
+    }
+
+    async fn list_environments(&self) -> StoreResult<Vec<Environment>> {
+        self.with_conn(move |conn| {
+            let mut stmt =
+                conn.prepare("SELECT name, variables_json FROM environments ORDER BY name")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+
+            let mut environments = Vec::new();
+            for row in rows {
+                let (name, json) = row?;
+                environments.push(Environment {
+                    name,
+                    variables: serde_json::from_str(&json)?,
+                });
+            }
+            Ok(environments)
+        })
+        .await
+    }
+
+    async fn delete_environment(&self, name: &str) -> StoreResult<bool> {
+        let name = name.to_string();
+        self.with_conn(move |conn| {
+            let count = conn.execute("DELETE FROM environments WHERE name = ?1", params![name])?;
+            Ok(count > 0)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl Store for SqliteStore {
+    fn backend_name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn schema_version(&self) -> StoreResult<u32> {
+        self.with_conn(move |conn| {
+            Self::current_version(conn)
+        })
+        .await
+    }
+}
@@ -0,0 +1,180 @@
+//! Storage traits implemented by every backend
+//!
+//! `PersistentStore` only talks to these traits, so the server can run
+//! against SQLite locally and a shared Postgres database in production.
+
+use crate::{
+    CredentialRecord, ExecutionQuery, ExecutionRecord, StoreResult, TriggerSchedule,
+    WebhookSubscription, WorkflowRevision,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flowcore::{Environment, ExecutionId, Value, Workflow};
+use flowruntime::ExecutionCheckpoint;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Workflow definitions, kept as immutable revisions
+#[async_trait]
+pub trait WorkflowStore: Send + Sync {
+    /// Store `workflow` as a new revision and return its number. With
+    /// `expected_revision`, fail with [`StoreError::Conflict`] unless it
+    /// matches the current head (0 for a workflow that doesn't exist yet).
+    ///
+    /// [`StoreError::Conflict`]: crate::StoreError::Conflict
+    async fn save_workflow(
+        &self,
+        workflow: &Workflow,
+        expected_revision: Option<u32>,
+    ) -> StoreResult<u32>;
+
+    /// Latest revision
+    async fn load_workflow(&self, id: Uuid) -> StoreResult<Option<Workflow>>;
+
+    async fn load_revision(&self, id: Uuid, revision: u32) -> StoreResult<Option<Workflow>>;
+
+    /// All revisions of a workflow, newest first
+    async fn list_revisions(&self, id: Uuid) -> StoreResult<Vec<WorkflowRevision>>;
+
+    /// Every revision of every workflow, in no particular order
+    async fn all_revisions(&self) -> StoreResult<Vec<Workflow>>;
+
+    /// `(id, name)` pairs, most recently updated first
+    async fn list_workflows(&self) -> StoreResult<Vec<(Uuid, String)>>;
+
+    /// Remove the workflow, all of its revisions, its trigger schedules and
+    /// the webhook subscriptions limited to it
+    async fn delete_workflow(&self, id: Uuid) -> StoreResult<bool>;
+}
+
+/// Execution history
+#[async_trait]
+pub trait ExecutionStore: Send + Sync {
+    async fn record_execution(&self, record: &ExecutionRecord) -> StoreResult<()>;
+
+    async fn get_execution(&self, id: Uuid) -> StoreResult<Option<ExecutionRecord>>;
+
+    /// Matching executions, most recent first
+    async fn query_executions(&self, query: &ExecutionQuery) -> StoreResult<Vec<ExecutionRecord>>;
+}
+
+/// Node-level result cache keyed by content fingerprint
+#[async_trait]
+pub trait CacheStore: Send + Sync {
+    /// Returns `None` on a miss or when the entry's TTL has expired
+    async fn get_cached_result(
+        &self,
+        node_type: &str,
+        config_hash: &str,
+        input_hash: &str,
+    ) -> StoreResult<Option<HashMap<String, Value>>>;
+
+    async fn cache_result(
+        &self,
+        node_type: &str,
+        config_hash: &str,
+        input_hash: &str,
+        outputs: &HashMap<String, Value>,
+        ttl_seconds: Option<i64>,
+    ) -> StoreResult<()>;
+
+    /// Drop cached results for one node type, or everything when `None`
+    async fn invalidate_cache(&self, node_type: Option<&str>) -> StoreResult<usize>;
+
+    /// `(entry count, newest created_at)`
+    async fn cache_stats(&self) -> StoreResult<(usize, String)>;
+
+    /// Outputs of every entry whose TTL has not expired
+    async fn cached_outputs(&self) -> StoreResult<Vec<HashMap<String, Value>>>;
+}
+
+/// Fire times of cron triggers, shared by every scheduler using the store
+#[async_trait]
+pub trait ScheduleStore: Send + Sync {
+    async fn load_schedule(&self, trigger_id: Uuid) -> StoreResult<Option<TriggerSchedule>>;
+
+    /// Schedules of one workflow, or of all workflows when `None`
+    async fn list_schedules(&self, workflow_id: Option<Uuid>) -> StoreResult<Vec<TriggerSchedule>>;
+
+    /// Insert or replace unconditionally
+    async fn put_schedule(&self, schedule: &TriggerSchedule) -> StoreResult<()>;
+
+    /// Replace the stored schedule only if its `next_fire_at` is still
+    /// `expected_next`. Returns false if another scheduler advanced it
+    /// first, in which case the caller must not fire.
+    async fn advance_schedule(
+        &self,
+        schedule: &TriggerSchedule,
+        expected_next: Option<DateTime<Utc>>,
+    ) -> StoreResult<bool>;
+}
+
+/// Outbound webhooks for execution lifecycle events
+#[async_trait]
+pub trait SubscriptionStore: Send + Sync {
+    async fn list_subscriptions(&self) -> StoreResult<Vec<WebhookSubscription>>;
+
+    /// Insert or replace
+    async fn put_subscription(&self, subscription: &WebhookSubscription) -> StoreResult<()>;
+
+    async fn delete_subscription(&self, id: Uuid) -> StoreResult<bool>;
+}
+
+/// Checkpoints of executions waiting for a signal, one per execution
+#[async_trait]
+pub trait CheckpointStore: Send + Sync {
+    /// Insert or replace the execution's checkpoint
+    async fn put_checkpoint(&self, checkpoint: &ExecutionCheckpoint) -> StoreResult<()>;
+
+    async fn delete_checkpoint(&self, execution_id: ExecutionId) -> StoreResult<bool>;
+
+    async fn list_checkpoints(&self) -> StoreResult<Vec<ExecutionCheckpoint>>;
+}
+
+/// Sealed credentials, keyed by name. Backends never see the contents;
+/// see [`CredentialVault`](crate::CredentialVault).
+#[async_trait]
+pub trait CredentialStore: Send + Sync {
+    /// Insert or replace
+    async fn put_credential(&self, credential: &CredentialRecord) -> StoreResult<()>;
+
+    async fn get_credential(&self, name: &str) -> StoreResult<Option<CredentialRecord>>;
+
+    /// All credentials, by name
+    async fn list_credentials(&self) -> StoreResult<Vec<CredentialRecord>>;
+
+    async fn delete_credential(&self, name: &str) -> StoreResult<bool>;
+}
+
+/// Environment profiles, keyed by name
+#[async_trait]
+pub trait EnvironmentStore: Send + Sync {
+    /// Insert or replace
+    async fn put_environment(&self, environment: &Environment) -> StoreResult<()>;
+
+    async fn get_environment(&self, name: &str) -> StoreResult<Option<Environment>>;
+
+    /// All environments, by name
+    async fn list_environments(&self) -> StoreResult<Vec<Environment>>;
+
+    async fn delete_environment(&self, name: &str) -> StoreResult<bool>;
+}
+
+/// A complete storage backend
+#[async_trait]
+pub trait Store:
+    WorkflowStore
+    + ExecutionStore
+    + CacheStore
+    + ScheduleStore
+    + SubscriptionStore
+    + CheckpointStore
+    + CredentialStore
+    + EnvironmentStore
+{
+    /// Short backend name for logging (e.g. "sqlite", "postgres")
+    fn backend_name(&self) -> &'static str;
+
+    /// Highest applied migration version (0 for backends without a schema)
+    async fn schema_version(&self) -> StoreResult<u32>;
+}
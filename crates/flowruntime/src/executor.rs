@@ -1,6 +1,7 @@
 use flowcore::{
-    ExecutionEvent, EventBus, FlowError, Node, NodeContext, NodeId, 
-    Value, Workflow, WorkflowError, ExecutionId,
+    resolve_credentials, ArtifactStore, CredentialResolver, EventEmitter, ExecutionEvent, EventBus,
+    FlowError, Node, NodeContext, NodeId, Secret, SecretMask, Signals, Value, Workflow, WorkflowError,
+    ExecutionId,
 };
 use crate::registry::NodeRegistry;
 use chrono::Utc;
@@ -8,18 +9,103 @@ use futures::stream::{FuturesUnordered, StreamExt};
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::algo::toposort;
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
+use tokio::sync::mpsc;
 use tokio::time::{timeout, Duration};
 
+/// Outputs of the nodes that are done, unmasked: those that completed,
+/// those given in [`ExecutionOptions::completed`] and, with
+/// `ContinueOnError`, failed ones (with no outputs)
+pub type CompletedOutputs = Arc<std::sync::Mutex<HashMap<NodeId, HashMap<String, Value>>>>;
+
+/// How an execution starts and how it can be reached while it runs
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionOptions {
+    /// Generated if `None`
+    pub execution_id: Option<ExecutionId>,
+    /// Stops the execution, and the nodes running in it, when cancelled
+    pub cancel_token: Option<tokio_util::sync::CancellationToken>,
+    /// Signals for the execution's nodes
+    pub signals: Signals,
+    /// While `true`, no further nodes are started; running ones finish
+    pub pause: Option<tokio::sync::watch::Receiver<bool>>,
+    /// Set when continuing an execution from a checkpoint: outputs of the
+    /// nodes that already ran, which are not run again
+    pub completed: Option<HashMap<NodeId, HashMap<String, Value>>>,
+    /// Overrides of the workflow's variables, e.g. an environment's
+    pub variables: HashMap<String, Value>,
+    /// Given each node's outputs as it completes, before its masked
+    /// `NodeCompleted` event is emitted
+    pub outputs: Option<CompletedOutputs>,
+    /// Also sent every event of the execution, including those its nodes
+    /// emit. Unlike a bus subscriber it never falls behind and misses any.
+    pub events: Option<mpsc::UnboundedSender<ExecutionEvent>>,
+}
+
+/// Where the events of an execution go
+struct Events<'a> {
+    bus: &'a EventBus,
+    tap: Option<mpsc::UnboundedSender<ExecutionEvent>>,
+}
+
+impl Events<'_> {
+    fn emit(&self, event: ExecutionEvent) {
+        if let Some(tap) = &self.tap {
+            let _ = tap.send(event.clone());
+        }
+        self.bus.emit(event);
+    }
+    
+    fn create_emitter(&self, execution_id: ExecutionId, node_id: NodeId) -> EventEmitter {
+        let emitter = self.bus.create_emitter(execution_id, node_id);
+        match &self.tap {
+            Some(tap) => emitter.with_tap(tap.clone()),
+            None => emitter,
+        }
+    }
+}
+
 /// Executes workflows as DAGs with parallel execution
 pub struct WorkflowExecutor {
     max_parallel: usize,
+    artifacts: ArtifactStore,
+    workspace_root: Option<PathBuf>,
+    credentials: Option<Arc<dyn CredentialResolver>>,
 }
 
 impl WorkflowExecutor {
     pub fn new(max_parallel: usize) -> Self {
-        Self { max_parallel }
+        Self {
+            max_parallel,
+            artifacts: ArtifactStore::default(),
+            workspace_root: None,
+            credentials: None,
+        }
+    }
+    
+    /// Give nodes this store for blob values
+    pub fn with_artifacts(mut self, artifacts: ArtifactStore) -> Self {
+        self.artifacts = artifacts;
+        self
+    }
+    
+    /// Give every execution a workspace directory `<root>/<execution id>`
+    pub fn with_workspace_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.workspace_root = Some(root.into());
+        self
+    }
+    
+    /// Resolve `{"$credential": ...}` references in node config with `credentials`
+    pub fn with_credentials(mut self, credentials: Arc<dyn CredentialResolver>) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+    
+    /// Workspace directory of an execution, if executions get one
+    pub fn workspace(&self, execution_id: ExecutionId) -> Option<PathBuf> {
+        self.workspace_root.as_ref().map(|root| root.join(execution_id.to_string()))
     }
     
     /// Execute a workflow and return results.
@@ -34,24 +120,52 @@ impl WorkflowExecutor {
         execution_id: Option<ExecutionId>,
         cancel_token: Option<tokio_util::sync::CancellationToken>,
     ) -> Result<ExecutionResult, FlowError> {
-        let execution_id = execution_id.unwrap_or_else(ExecutionId::new_v4);
+        let options = ExecutionOptions {
+            execution_id,
+            cancel_token,
+            ..Default::default()
+        };
+        self.execute_with(workflow, registry, event_bus, initial_inputs, options).await
+    }
+    
+    /// Execute a workflow with full control over how it starts
+    pub async fn execute_with(
+        &self,
+        workflow: &Workflow,
+        registry: &NodeRegistry,
+        event_bus: &EventBus,
+        initial_inputs: HashMap<String, Value>,
+        options: ExecutionOptions,
+    ) -> Result<ExecutionResult, FlowError> {
+        let execution_id = options.execution_id.unwrap_or_else(ExecutionId::new_v4);
         let start_time = Instant::now();
+        let events = &Events {
+            bus: event_bus,
+            tap: options.events,
+        };
         
-        // Emit workflow started event
-        event_bus.emit(ExecutionEvent::WorkflowStarted {
-            execution_id,
-            workflow_id: workflow.id,
-            timestamp: Utc::now(),
-        });
+        if options.completed.is_none() {
+            // Emit workflow started event
+            events.emit(ExecutionEvent::WorkflowStarted {
+                execution_id,
+                workflow_id: workflow.id,
+                timestamp: Utc::now(),
+            });
+            tracing::info!("Starting workflow execution: {}", workflow.id);
+        } else {
+            tracing::info!("Resuming execution {} of workflow {}", execution_id, workflow.id);
+        }
         
-        tracing::info!("Starting workflow execution: {}", workflow.id);
+        // Apply variables before anything reads node config
+        let workflow = &workflow.with_variables(&options.variables)?;
         
         // Build dependency graph
         let graph = self.build_graph(workflow)?;
         
         // Create node instances
         let mut node_instances = HashMap::new();
-        for node_spec in &workflow.nodes {
+        let completed = options.completed.unwrap_or_default();
+        for node_spec in workflow.nodes.iter().filter(|n| !completed.contains_key(&n.id)) {
             let mut node = registry.create_node(&node_spec.node_type, &node_spec.config)?;
             
             // Initialize node
@@ -63,22 +177,42 @@ impl WorkflowExecutor {
             node_instances.insert(node_spec.id, node);
         }
         
+        // Kept when continuing from a checkpoint, so files written before
+        // a restart are still there
+        let workspace = self.workspace(execution_id);
+        if let Some(ref dir) = workspace {
+            tokio::fs::create_dir_all(dir).await.map_err(|e| {
+                FlowError::Execution(format!("Failed to create workspace {}: {}", dir.display(), e))
+            })?;
+        }
+        
         // Execute the DAG
         let result = self.execute_dag(
             workflow,
             graph,
             node_instances,
-            event_bus,
+            events,
             execution_id,
             initial_inputs,
-            cancel_token,
+            completed,
+            options.cancel_token,
+            options.signals,
+            options.pause,
+            options.outputs,
+            workspace.clone(),
         ).await;
         
+        if let Some(ref dir) = workspace {
+            if let Err(e) = tokio::fs::remove_dir_all(dir).await {
+                tracing::warn!("Failed to remove workspace {}: {}", dir.display(), e);
+            }
+        }
+        
         let duration_ms = start_time.elapsed().as_millis() as u64;
         let success = result.is_ok();
         
         // Emit workflow completed event
-        event_bus.emit(ExecutionEvent::WorkflowCompleted {
+        events.emit(ExecutionEvent::WorkflowCompleted {
             execution_id,
             success,
             duration_ms,
@@ -118,19 +252,44 @@ impl WorkflowExecutor {
     }
     
     /// Execute the DAG with parallelism
+    #[allow(clippy::too_many_arguments)]
     async fn execute_dag(
         &self,
         workflow: &Workflow,
         graph: DiGraph<NodeId, ()>,
         mut node_instances: HashMap<NodeId, Box<dyn Node>>,
-        event_bus: &EventBus,
+        events: &Events<'_>,
         execution_id: ExecutionId,
         initial_inputs: HashMap<String, Value>,
+        already_completed: HashMap<NodeId, HashMap<String, Value>>,
         cancel_token: Option<tokio_util::sync::CancellationToken>,
+        signals: Signals,
+        mut pause: Option<tokio::sync::watch::Receiver<bool>>,
+        shared_outputs: Option<CompletedOutputs>,
+        workspace: Option<PathBuf>,
     ) -> Result<ExecutionResult, FlowError> {
-        let mut completed = HashSet::new();
-        let mut node_outputs: HashMap<NodeId, HashMap<String, Value>> = HashMap::new();
+        // Secrets of this execution, masked out of every event it emits;
+        // secrets nodes output are added as they complete
+        let mut mask = SecretMask::new(
+            workflow
+                .nodes
+                .iter()
+                .flat_map(|n| n.config.values())
+                .chain(initial_inputs.values())
+                .chain(already_completed.values().flat_map(|outputs| outputs.values()))
+                .flat_map(Value::secrets),
+        );
+        if let Some(ref shared) = shared_outputs {
+            shared.lock().unwrap().extend(already_completed.clone());
+        }
+        let mut completed: HashSet<NodeId> = already_completed.keys().copied().collect();
+        let mut node_outputs: HashMap<NodeId, HashMap<String, Value>> = already_completed;
         let mut running = FuturesUnordered::new();
+        // Cancelled when the execution stops early, along with every node
+        let stop = cancel_token
+            .as_ref()
+            .map(|token| token.child_token())
+            .unwrap_or_default();
         let node_to_index: HashMap<NodeId, NodeIndex> = graph
             .node_indices()
             .map(|idx| (*graph.node_weight(idx).unwrap(), idx))
@@ -145,35 +304,45 @@ impl WorkflowExecutor {
             node_outputs.insert(NodeId::nil(), initial_map);
         }
         
-        loop {
+        let outcome = 'dag: loop {
             // Check for cancellation
             if let Some(ref token) = cancel_token {
                 if token.is_cancelled() {
                     tracing::warn!("Execution {} cancelled", execution_id);
-                    event_bus.emit(ExecutionEvent::WorkflowCompleted {
+                    events.emit(ExecutionEvent::WorkflowCompleted {
                         execution_id,
                         success: false,
                         duration_ms: 0,
                         timestamp: Utc::now(),
                     });
-                    return Err(FlowError::Execution("Workflow cancelled".to_string()));
+                    break Err(FlowError::Execution("Workflow cancelled".to_string()));
                 }
             }
             
-            // Find nodes ready to execute (all dependencies completed)
-            let ready_nodes = self.find_ready_nodes(&graph, &node_to_index, &completed);
+            // Find nodes ready to execute (all dependencies completed).
+            // Nodes already spawned no longer have an instance to take.
+            let ready_nodes: Vec<NodeId> = self
+                .find_ready_nodes(&graph, &node_to_index, &completed)
+                .into_iter()
+                .filter(|node_id| node_instances.contains_key(node_id))
+                .collect();
+            let paused = pause.as_ref().is_some_and(|p| *p.borrow());
+            let has_ready = !ready_nodes.is_empty();
             
             // Spawn tasks for ready nodes up to parallel limit
             for node_id in ready_nodes {
+                if paused {
+                    break;
+                }
                 if running.len() >= self.max_parallel {
                     break;
                 }
                 
-                let node_spec = workflow.find_node(node_id)
-                    .ok_or_else(|| WorkflowError::NodeNotFound(node_id.to_string()))?;
-                
-                let node = node_instances.remove(&node_id)
-                    .ok_or_else(|| WorkflowError::NodeNotFound(node_id.to_string()))?;
+                let (Some(node_spec), Some(node)) =
+                    (workflow.find_node(node_id), node_instances.remove(&node_id))
+                else {
+                    break 'dag Err(WorkflowError::NodeNotFound(node_id.to_string()).into());
+                };
                 
                 // Collect inputs from predecessor nodes
                 let inputs = self.collect_node_inputs(
@@ -184,29 +353,54 @@ impl WorkflowExecutor {
                     &node_outputs,
                 );
                 
-                let ctx = NodeContext {
-                    node_id,
-                    inputs,
-                    config: node_spec.config.clone(),
-                    state: Arc::new(tokio::sync::RwLock::new(flowcore::NodeState::default())),
-                    events: event_bus.create_emitter(execution_id, node_id),
-                    cancellation: tokio_util::sync::CancellationToken::new(),
-                };
-                
                 // Emit node started event
-                event_bus.emit(ExecutionEvent::NodeStarted {
+                events.emit(ExecutionEvent::NodeStarted {
                     execution_id,
                     node_id,
                     node_type: node_spec.node_type.clone(),
                     timestamp: Utc::now(),
                 });
                 
+                let config = node_spec.config.clone();
+                let credentials = self.credentials.clone();
+                let mut node_mask = mask.clone();
+                let emitter = events.create_emitter(execution_id, node_id);
+                let cancellation = stop.child_token();
+                let signals = signals.clone();
+                let artifacts = self.artifacts.clone();
+                let workspace = workspace.clone();
+                let variables = workflow.variables.clone();
+                
                 // Get retry policy from node spec
                 let retry_policy = node_spec.retry_policy.clone();
 
-                // Spawn execution task with retry
+                // Spawn execution task with retry. Besides the result it
+                // returns the secrets its config resolved to, for masking.
                 let task = async move {
-                    let mut last_error = None;
+                    // Looked up as late as possible, so a rotated credential
+                    // is picked up by the next node that uses it, and in the
+                    // node's task, so a slow lookup holds up no other node
+                    let config = match resolve_credentials(&config, credentials.as_deref()).await {
+                        Ok(config) => config,
+                        Err(e) => return (node_id, Err(e), 0, Vec::new()),
+                    };
+                    let secrets: Vec<Secret> = config.values().flat_map(Value::secrets).cloned().collect();
+                    node_mask.add(&secrets);
+                    
+                    let ctx = NodeContext {
+                        node_id,
+                        inputs,
+                        config,
+                        state: Arc::new(tokio::sync::RwLock::new(flowcore::NodeState::default())),
+                        events: emitter.with_mask(node_mask),
+                        cancellation,
+                        signals,
+                        artifacts,
+                        workspace,
+                        variables,
+                    };
+                    
+                    let mut last_error: Option<flowcore::NodeError> = None;
                     let max_attempts = retry_policy.as_ref()
                         .map(|r| r.max_attempts)
                         .unwrap_or(1);
@@ -220,6 +414,12 @@ impl WorkflowExecutor {
                                 "Retrying node {} (attempt {}/{}) after {}ms",
                                 node_id, attempt + 1, max_attempts, delay_ms
                             );
+                            ctx.events.emit(flowcore::NodeEvent::Retrying {
+                                attempt: attempt + 1,
+                                max_attempts,
+                                delay_ms,
+                                error: last_error.as_ref().map(ToString::to_string).unwrap_or_default(),
+                            });
                             tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
                         }
 
@@ -228,7 +428,7 @@ impl WorkflowExecutor {
                         let duration_ms = start.elapsed().as_millis() as u64;
 
                         match result {
-                            Ok(output) => return (node_id, Ok(output), duration_ms),
+                            Ok(output) => return (node_id, Ok(output), duration_ms, secrets),
                             Err(e) => {
                                 let is_timeout = matches!(e, flowcore::NodeError::Timeout { .. });
                                 let retry_on_timeout = retry_policy.as_ref()
@@ -236,14 +436,14 @@ impl WorkflowExecutor {
                                     .unwrap_or(true);
 
                                 if is_timeout && !retry_on_timeout {
-                                    return (node_id, Err(e), duration_ms);
+                                    return (node_id, Err(e), duration_ms, secrets);
                                 }
                                 last_error = Some(e);
                             }
                         }
                     }
 
-                    (node_id, Err(last_error.unwrap()), 0)
+                    (node_id, Err(last_error.unwrap()), 0, secrets)
                 };
                 
                 // Apply timeout if specified
@@ -256,7 +456,7 @@ impl WorkflowExecutor {
                                 // Timeout occurred
                                 (node_id, Err(flowcore::NodeError::Timeout { 
                                     seconds: timeout_ms / 1000 
-                                }), timeout_ms)
+                                }), timeout_ms, Vec::new())
                             }
                         }
                     };
@@ -267,24 +467,66 @@ impl WorkflowExecutor {
                 }
             }
             
-            // If nothing is running and nothing is ready, we're done
+            let cancelled = async {
+                match &cancel_token {
+                    Some(token) => token.cancelled().await,
+                    None => std::future::pending().await,
+                }
+            };
+            let pause_changed = async {
+                match &mut pause {
+                    Some(pause) => {
+                        if pause.changed().await.is_err() {
+                            std::future::pending::<()>().await;
+                        }
+                    }
+                    None => std::future::pending().await,
+                }
+            };
+            
             if running.is_empty() {
-                break;
+                // If nothing is running and nothing is ready, we're done
+                if !(paused && has_ready) {
+                    break Ok(());
+                }
+                // Paused with nodes ready to go: wait to be resumed
+                tokio::select! {
+                    _ = pause_changed => {}
+                    _ = cancelled => {}
+                }
+                continue;
             }
             
-            // Wait for next task to complete
-            if let Some(result) = running.next().await {
-                let (node_id, exec_result, duration_ms) = result
-                    .map_err(|e| FlowError::Execution(format!("Task join error: {}", e)))?;
+            // Wait for next task to complete, for cancellation, or for
+            // being resumed
+            let next = tokio::select! {
+                next = running.next() => next,
+                _ = cancelled => continue,
+                _ = pause_changed => continue,
+            };
+            if let Some(result) = next {
+                let (node_id, exec_result, duration_ms, secrets) = match result {
+                    Ok(result) => result,
+                    Err(e) => break Err(FlowError::Execution(format!("Task join error: {}", e))),
+                };
+                mask.add(&secrets);
                 
                 match exec_result {
                     Ok(output) => {
                         tracing::info!("Node {} completed in {}ms", node_id, duration_ms);
                         
-                        event_bus.emit(ExecutionEvent::NodeCompleted {
+                        mask.add(output.outputs.values().flat_map(Value::secrets));
+                        if let Some(ref shared) = shared_outputs {
+                            shared.lock().unwrap().insert(node_id, output.outputs.clone());
+                        }
+                        events.emit(ExecutionEvent::NodeCompleted {
                             execution_id,
                             node_id,
-                            outputs: output.outputs.clone(),
+                            outputs: output
+                                .outputs
+                                .iter()
+                                .map(|(port, value)| (port.clone(), mask.mask_value(value)))
+                                .collect(),
                             duration_ms,
                             timestamp: Utc::now(),
                         });
@@ -293,29 +535,33 @@ impl WorkflowExecutor {
                         completed.insert(node_id);
                     }
                     Err(e) => {
+                        let e = mask.mask(&e.to_string());
                         tracing::error!("Node {} failed: {}", node_id, e);
                         
-                        event_bus.emit(ExecutionEvent::NodeFailed {
+                        events.emit(ExecutionEvent::NodeFailed {
                             execution_id,
                             node_id,
-                            error: e.to_string(),
+                            error: e.clone(),
                             timestamp: Utc::now(),
                         });
                         
                         // Handle error based on workflow settings
                         match workflow.settings.on_error {
                             flowcore::ErrorHandling::StopWorkflow => {
-                                return Err(FlowError::Execution(format!(
+                                break Err(FlowError::Execution(format!(
                                     "Node {} failed: {}",
                                     node_id, e
                                 )));
                             }
                             flowcore::ErrorHandling::ContinueOnError => {
+                                if let Some(ref shared) = shared_outputs {
+                                    shared.lock().unwrap().insert(node_id, HashMap::new());
+                                }
                                 completed.insert(node_id);
                             }
                             flowcore::ErrorHandling::RetryWorkflow { .. } => {
                                 // TODO: Implement workflow retry logic
-                                return Err(FlowError::Execution(format!(
+                                break Err(FlowError::Execution(format!(
                                     "Node {} failed: {}",
                                     node_id, e
                                 )));
@@ -324,11 +570,33 @@ impl WorkflowExecutor {
                     }
                 }
             }
+        };
+        
+        if let Err(e) = outcome {
+            // Dropping the handles would leave the nodes running, still
+            // using the workspace the caller removes next
+            stop.cancel();
+            for task in running.iter() {
+                task.abort();
+            }
+            while running.next().await.is_some() {}
+            return Err(e);
         }
         
+        // The result leaves the executor, so it gets the same masking as events
+        let outputs = node_outputs
+            .into_iter()
+            .map(|(node_id, outputs)| {
+                let outputs = outputs
+                    .iter()
+                    .map(|(port, value)| (port.clone(), mask.mask_value(value)))
+                    .collect();
+                (node_id, outputs)
+            })
+            .collect();
         Ok(ExecutionResult {
             execution_id,
-            outputs: node_outputs,
+            outputs,
             completed_nodes: completed.len(),
             total_nodes: workflow.nodes.len(),
         })
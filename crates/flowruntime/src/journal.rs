@@ -0,0 +1,79 @@
+use flowcore::ExecutionEvent;
+use serde::Serialize;
+use std::collections::VecDeque;
+use tokio::sync::broadcast;
+
+/// An execution event numbered in the order it was recorded
+#[derive(Debug, Clone, Serialize)]
+pub struct SequencedEvent {
+    /// Starts at 1 and increases by one per event of the same execution
+    pub seq: u64,
+    pub event: ExecutionEvent,
+}
+
+/// Recent events of one execution, for clients that join late or reconnect
+pub(crate) struct ExecutionJournal {
+    events: VecDeque<SequencedEvent>,
+    capacity: usize,
+    next_seq: u64,
+    /// Dropped once the execution finishes so followers see the end
+    sender: Option<broadcast::Sender<SequencedEvent>>,
+}
+
+/// Events to replay followed by the live feed (`None` once finished)
+pub struct ExecutionFollow {
+    pub replay: Vec<SequencedEvent>,
+    pub live: Option<broadcast::Receiver<SequencedEvent>>,
+}
+
+impl ExecutionJournal {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self {
+            events: VecDeque::new(),
+            capacity,
+            next_seq: 1,
+            sender: Some(sender),
+        }
+    }
+
+    pub(crate) fn record(&mut self, event: ExecutionEvent) {
+        let event = SequencedEvent {
+            seq: self.next_seq,
+            event,
+        };
+        self.next_seq += 1;
+
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(event.clone());
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    /// Events still held, oldest first
+    pub(crate) fn events(&self) -> impl Iterator<Item = &ExecutionEvent> {
+        self.events.iter().map(|e| &e.event)
+    }
+
+    pub(crate) fn finish(&mut self) {
+        self.sender = None;
+    }
+
+    /// Recorded events after `after` (all of them if `None`) and a
+    /// receiver for the ones that follow
+    pub(crate) fn follow(&self, after: Option<u64>) -> ExecutionFollow {
+        let after = after.unwrap_or(0);
+        ExecutionFollow {
+            replay: self
+                .events
+                .iter()
+                .filter(|e| e.seq > after)
+                .cloned()
+                .collect(),
+            live: self.sender.as_ref().map(|s| s.subscribe()),
+        }
+    }
+}
@@ -4,9 +4,18 @@
 //! manages the node registry, and handles DAG-based parallel execution.
 
 mod executor;
+mod journal;
+mod lint;
 mod registry;
 mod runtime;
+mod schedule;
 
-pub use executor::{WorkflowExecutor, ExecutionResult, ExecutionHandle};
-pub use registry::{NodeFactory, NodeMetadata, PortDefinition, NodeRegistry};
-pub use runtime::{FlowRuntime, RuntimeConfig, ExecutionState, ExecutionStatus, NodeExecutionState};
+pub use executor::{WorkflowExecutor, CompletedOutputs, ExecutionOptions, ExecutionResult, ExecutionHandle};
+pub use journal::{ExecutionFollow, SequencedEvent};
+pub use lint::{Diagnostic, Severity};
+pub use registry::{ConfigDefinition, NodeFactory, NodeMetadata, PortDefinition, NodeRegistry};
+pub use schedule::{CronSchedule, MAX_BACKFILL, MISFIRE_GRACE};
+pub use runtime::{
+    FlowRuntime, RuntimeConfig, ExecutionCheckpoint, ExecutionListener, ExecutionState,
+    ExecutionStatus, NodeExecutionState, NodeStatus,
+};
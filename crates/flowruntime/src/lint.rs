@@ -0,0 +1,428 @@
+//! Workflow linting
+//!
+//! [`NodeRegistry::lint_workflow`] reports every problem it finds. Errors
+//! keep a workflow from running, and [`NodeRegistry::validate_workflow`]
+//! fails with the first of them. Warnings only make a workflow behave
+//! differently than it reads: ports a node doesn't have, inputs connected
+//! twice, config keys nothing reads. Checks against
+//! [`NodeMetadata`] only apply to what a node type declares.
+
+use crate::registry::{check_trigger, NodeMetadata, NodeRegistry};
+use flowcore::{NodeId, NodeSpec, Workflow};
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::Dfs;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The workflow can't be saved or run
+    Error,
+    /// The workflow runs, probably not as intended
+    Warning,
+}
+
+/// A problem found in a workflow
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Which check failed, e.g. `unknown-port`
+    pub code: &'static str,
+    pub message: String,
+    /// Node the problem is in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<NodeId>,
+    /// Index of the connection in `connections`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection: Option<usize>,
+    /// Index of the trigger in `triggers`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<usize>,
+    /// Config key (`config.<key>`), port or setting the problem is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, code: &'static str, message: String) -> Self {
+        Self {
+            severity,
+            code,
+            message,
+            node: None,
+            connection: None,
+            trigger: None,
+            field: None,
+        }
+    }
+
+    pub fn error(code: &'static str, message: String) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    pub fn warning(code: &'static str, message: String) -> Self {
+        Self::new(Severity::Warning, code, message)
+    }
+
+    pub fn at_node(mut self, node: NodeId) -> Self {
+        self.node = Some(node);
+        self
+    }
+
+    pub fn at_connection(mut self, index: usize) -> Self {
+        self.connection = Some(index);
+        self
+    }
+
+    pub fn at_field(mut self, field: impl Into<String>) -> Self {
+        self.field = Some(field.into());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+/// How messages refer to a node: its name, or its id if it has none
+fn label(node: &NodeSpec) -> String {
+    match &node.name {
+        Some(name) => format!("'{}'", name),
+        None => node.id.to_string(),
+    }
+}
+
+/// `names` for a message, e.g. `a, b, c`
+fn list(names: impl IntoIterator<Item = String>) -> String {
+    names.into_iter().collect::<Vec<_>>().join(", ")
+}
+
+impl NodeRegistry {
+    /// Every problem in `workflow`, errors and warnings, in document order.
+    /// [`NodeRegistry::validate_workflow`] fails with the first error.
+    pub fn lint_workflow(&self, workflow: &Workflow) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut nodes: HashMap<NodeId, (&NodeSpec, Option<NodeMetadata>)> = HashMap::new();
+
+        for node in &workflow.nodes {
+            if nodes.contains_key(&node.id) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "duplicate-node-id",
+                        format!("node id {} is used more than once", node.id),
+                    )
+                    .at_node(node.id),
+                );
+                continue;
+            }
+            let metadata = self.get_metadata(&node.node_type);
+            match &metadata {
+                None => diagnostics.push(
+                    Diagnostic::error(
+                        "unknown-node-type",
+                        format!("node {} has unknown type '{}'", label(node), node.node_type),
+                    )
+                    .at_node(node.id)
+                    .at_field("node_type"),
+                ),
+                Some(metadata) => self.lint_config(node, metadata, &mut diagnostics),
+            }
+            nodes.insert(node.id, (node, metadata));
+        }
+
+        // Connections between existing nodes, as graph edges
+        let mut graph = DiGraph::<NodeId, ()>::new();
+        let mut indices: HashMap<NodeId, NodeIndex> = HashMap::new();
+        for node in &workflow.nodes {
+            indices
+                .entry(node.id)
+                .or_insert_with(|| graph.add_node(node.id));
+        }
+        let mut targets: BTreeMap<(usize, &str), Vec<usize>> = BTreeMap::new();
+
+        for (index, conn) in workflow.connections.iter().enumerate() {
+            let mut dangling = false;
+            for (end, field, id) in [
+                ("source", "from_node", conn.from_node),
+                ("target", "to_node", conn.to_node),
+            ] {
+                if !nodes.contains_key(&id) {
+                    dangling = true;
+                    diagnostics.push(
+                        Diagnostic::error(
+                            "dangling-connection",
+                            format!("{} node {} of the connection doesn't exist", end, id),
+                        )
+                        .at_connection(index)
+                        .at_field(field),
+                    );
+                }
+            }
+            if dangling {
+                continue;
+            }
+            let (from, from_meta) = &nodes[&conn.from_node];
+            let (to, to_meta) = &nodes[&conn.to_node];
+
+            if let Some(meta) = from_meta.as_ref().filter(|m| !m.outputs.is_empty()) {
+                if !meta.outputs.iter().any(|p| p.matches(&conn.from_port)) {
+                    diagnostics.push(
+                        Diagnostic::warning(
+                            "unknown-port",
+                            format!(
+                                "{} has no output '{}'; {} outputs are: {}",
+                                label(from),
+                                conn.from_port,
+                                from.node_type,
+                                list(meta.outputs.iter().map(|p| p.name.clone()))
+                            ),
+                        )
+                        .at_connection(index)
+                        .at_field("from_port"),
+                    );
+                }
+            }
+            if let Some(meta) = to_meta.as_ref().filter(|m| !m.inputs.is_empty()) {
+                if !meta.inputs.iter().any(|p| p.matches(&conn.to_port)) {
+                    diagnostics.push(
+                        Diagnostic::warning(
+                            "unknown-port",
+                            format!(
+                                "{} has no input '{}'; {} inputs are: {}",
+                                label(to),
+                                conn.to_port,
+                                to.node_type,
+                                list(meta.inputs.iter().map(|p| p.name.clone()))
+                            ),
+                        )
+                        .at_connection(index)
+                        .at_field("to_port"),
+                    );
+                }
+            }
+
+            let position = workflow.nodes.iter().position(|n| n.id == conn.to_node);
+            targets
+                .entry((position.unwrap_or_default(), conn.to_port.as_str()))
+                .or_default()
+                .push(index);
+            graph.add_edge(indices[&conn.from_node], indices[&conn.to_node], ());
+        }
+
+        // The executor keeps one value per input port
+        for ((position, port), connections) in &targets {
+            let Some((&last, earlier)) = connections.split_last() else { continue };
+            if earlier.is_empty() {
+                continue;
+            }
+            let node = &workflow.nodes[*position];
+            diagnostics.push(
+                Diagnostic::warning(
+                    "duplicate-input",
+                    format!(
+                        "input '{}' of {} is connected {} times; only one value is \
+                         kept, the last one produced in connection order",
+                        port,
+                        label(node),
+                        connections.len()
+                    ),
+                )
+                .at_node(node.id)
+                .at_connection(last)
+                .at_field(port.to_string()),
+            );
+        }
+
+        self.lint_graph(workflow, &graph, &nodes, &mut diagnostics);
+
+        for (index, trigger) in workflow.triggers.iter().enumerate() {
+            if let Err(e) = check_trigger(&trigger.trigger_type) {
+                let mut diagnostic = Diagnostic::error("invalid-trigger", e.to_string());
+                diagnostic.trigger = Some(index);
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        if workflow.settings.on_failure == Some(workflow.id) {
+            diagnostics.push(
+                Diagnostic::error(
+                    "invalid-settings",
+                    "a workflow cannot be its own on_failure workflow".to_string(),
+                )
+                .at_field("settings.on_failure"),
+            );
+        }
+
+        diagnostics
+    }
+
+    /// Required config that is missing, config the factory rejects and
+    /// config nothing reads
+    fn lint_config(
+        &self,
+        node: &NodeSpec,
+        metadata: &NodeMetadata,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let mut missing = false;
+        for definition in metadata.config.iter().filter(|c| c.required) {
+            if !node.config.contains_key(&definition.name) {
+                missing = true;
+                diagnostics.push(
+                    Diagnostic::error(
+                        "missing-config",
+                        format!(
+                            "{} ({}) needs config '{}': {}",
+                            label(node),
+                            node.node_type,
+                            definition.name,
+                            definition.description
+                        ),
+                    )
+                    .at_node(node.id)
+                    .at_field(format!("config.{}", definition.name)),
+                );
+            }
+        }
+        // Factories mostly fail on the same missing keys; don't repeat them
+        if !missing {
+            if let Err(e) = self.create_node(&node.node_type, &node.config) {
+                diagnostics.push(
+                    Diagnostic::error("invalid-config", format!("{}: {}", label(node), e))
+                        .at_node(node.id),
+                );
+            }
+        }
+
+        if metadata.config.is_empty() {
+            return;
+        }
+        let mut keys: Vec<_> = node.config.keys().collect();
+        keys.sort();
+        for key in keys {
+            if !metadata.config.iter().any(|c| &c.name == key) {
+                diagnostics.push(
+                    Diagnostic::warning(
+                        "unused-config",
+                        format!(
+                            "{} doesn't read config '{}'; {} config is: {}",
+                            label(node),
+                            key,
+                            node.node_type,
+                            list(metadata.config.iter().map(|c| c.name.clone()))
+                        ),
+                    )
+                    .at_node(node.id)
+                    .at_field(format!("config.{}", key)),
+                );
+            }
+        }
+    }
+
+    /// Cycles, nodes that can't run because of them, nodes connected to
+    /// nothing and required inputs left unconnected
+    fn lint_graph(
+        &self,
+        workflow: &Workflow,
+        graph: &DiGraph<NodeId, ()>,
+        nodes: &HashMap<NodeId, (&NodeSpec, Option<NodeMetadata>)>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let mut in_cycle = HashSet::new();
+        for component in tarjan_scc(graph) {
+            let cyclic = component.len() > 1
+                || graph.contains_edge(component[0], component[0]);
+            if !cyclic {
+                continue;
+            }
+            let mut members: Vec<_> = component.iter().map(|i| nodes[&graph[*i]].0).collect();
+            members.sort_by_key(|n| workflow.nodes.iter().position(|m| m.id == n.id));
+            diagnostics.push(
+                Diagnostic::error(
+                    "cycle",
+                    format!(
+                        "nodes {} depend on each other",
+                        list(members.iter().map(|n| label(n)))
+                    ),
+                )
+                .at_node(members[0].id),
+            );
+            in_cycle.extend(component);
+        }
+
+        // Everything downstream of a cycle waits for it forever
+        let mut blocked = HashSet::new();
+        for start in &in_cycle {
+            let mut dfs = Dfs::new(graph, *start);
+            while let Some(index) = dfs.next(graph) {
+                if !in_cycle.contains(&index) {
+                    blocked.insert(graph[index]);
+                }
+            }
+        }
+
+        let connected: HashSet<NodeId> = graph
+            .edge_indices()
+            .filter_map(|e| graph.edge_endpoints(e))
+            .flat_map(|(a, b)| [graph[a], graph[b]])
+            .collect();
+
+        for index in graph.node_indices() {
+            let id = graph[index];
+            let (node, metadata) = &nodes[&id];
+            if blocked.contains(&id) {
+                diagnostics.push(
+                    Diagnostic::warning(
+                        "unreachable-node",
+                        format!("{} never runs: it depends on a cycle", label(node)),
+                    )
+                    .at_node(id),
+                );
+            }
+            if graph.node_count() > 1 && !connected.contains(&id) {
+                diagnostics.push(
+                    Diagnostic::warning(
+                        "disconnected-node",
+                        format!(
+                            "{} isn't connected to any other node; it runs on the \
+                             execution's inputs alone",
+                            label(node)
+                        ),
+                    )
+                    .at_node(id),
+                );
+            }
+
+            // Nodes with predecessors don't get the execution's inputs, so a
+            // required input must come from a connection
+            let incoming: HashSet<&str> = workflow
+                .connections
+                .iter()
+                .filter(|c| c.to_node == id)
+                .map(|c| c.to_port.as_str())
+                .collect();
+            if incoming.is_empty() {
+                continue;
+            }
+            let Some(metadata) = metadata else { continue };
+            for port in metadata.inputs.iter().filter(|p| p.required) {
+                if !incoming.iter().any(|name| port.matches(name)) {
+                    diagnostics.push(
+                        Diagnostic::warning(
+                            "missing-input",
+                            format!(
+                                "required input '{}' of {} isn't connected",
+                                port.name,
+                                label(node)
+                            ),
+                        )
+                        .at_node(id)
+                        .at_field(port.name.clone()),
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use flowcore::{Node, NodeError, Value, WorkflowError};
+use crate::schedule::CronSchedule;
+use flowcore::{Node, NodeError, TriggerType, Value, Workflow, WorkflowError};
+use crate::lint::Diagnostic;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -21,8 +23,13 @@ pub trait NodeFactory: Send + Sync {
 pub struct NodeMetadata {
     pub description: String,
     pub category: String,
+    /// Ports a node reads. A name with a `<placeholder>`, like
+    /// `file:<path>`, stands for every port it matches.
     pub inputs: Vec<PortDefinition>,
     pub outputs: Vec<PortDefinition>,
+    /// Config keys a node reads; types that declare none aren't checked
+    /// for unused keys
+    pub config: Vec<ConfigDefinition>,
 }
 
 impl Default for NodeMetadata {
@@ -32,6 +39,7 @@ impl Default for NodeMetadata {
             category: "general".to_string(),
             inputs: Vec::new(),
             outputs: Vec::new(),
+            config: Vec::new(),
         }
     }
 }
@@ -43,6 +51,45 @@ pub struct PortDefinition {
     pub required: bool,
 }
 
+impl PortDefinition {
+    pub fn new(name: impl Into<String>, description: impl Into<String>, required: bool) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            required,
+        }
+    }
+
+    /// Whether `port` is this port, or one of the ports a
+    /// `<placeholder>` name stands for
+    pub fn matches(&self, port: &str) -> bool {
+        match self.name.find('<') {
+            Some(start) if self.name.ends_with('>') => {
+                port.len() > start && port.starts_with(&self.name[..start])
+            }
+            _ => self.name == port,
+        }
+    }
+}
+
+/// A key of a node's config
+#[derive(Debug, Clone)]
+pub struct ConfigDefinition {
+    pub name: String,
+    pub description: String,
+    pub required: bool,
+}
+
+impl ConfigDefinition {
+    pub fn new(name: impl Into<String>, description: impl Into<String>, required: bool) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            required,
+        }
+    }
+}
+
 /// Registry of available node types
 pub struct NodeRegistry {
     factories: HashMap<String, Arc<dyn NodeFactory>>,
@@ -84,6 +131,76 @@ impl NodeRegistry {
     pub fn get_metadata(&self, node_type: &str) -> Option<NodeMetadata> {
         self.factories.get(node_type).map(|f| f.metadata())
     }
+    
+    /// Check that a workflow could be executed with this registry: the
+    /// first error [`NodeRegistry::lint_workflow`] reports, if any. Lint
+    /// warnings don't fail validation.
+    pub fn validate_workflow(&self, workflow: &Workflow) -> Result<(), WorkflowError> {
+        let Some(error) = self.lint_workflow(workflow).into_iter().find(Diagnostic::is_error) else {
+            return Ok(());
+        };
+        let node = |id| workflow.nodes.iter().find(|n| Some(n.id) == id);
+        Err(match error.code {
+            "cycle" => WorkflowError::CyclicDependency,
+            "unknown-node-type" => match node(error.node) {
+                Some(node) => WorkflowError::UnknownNodeType(node.node_type.clone()),
+                None => WorkflowError::Invalid(error.message),
+            },
+            "dangling-connection" => {
+                let conn = error.connection.map(|i| &workflow.connections[i]);
+                match (conn, error.field.as_deref()) {
+                    (Some(conn), Some("from_node")) => {
+                        WorkflowError::NodeNotFound(conn.from_node.to_string())
+                    }
+                    (Some(conn), _) => WorkflowError::NodeNotFound(conn.to_node.to_string()),
+                    (None, _) => WorkflowError::Invalid(error.message),
+                }
+            }
+            _ => WorkflowError::Invalid(error.message),
+        })
+    }
+}
+
+/// Check that a trigger is complete: cron expressions and globs parse and
+/// required fields are set
+pub(crate) fn check_trigger(trigger_type: &TriggerType) -> Result<(), WorkflowError> {
+    if let Some(Err(e)) = CronSchedule::from_trigger(trigger_type) {
+        return Err(e);
+    }
+    match trigger_type {
+        TriggerType::Webhook { path, .. } if path.trim_matches('/').is_empty() => {
+            return Err(WorkflowError::Invalid("webhook trigger needs a path".to_string()));
+        }
+        TriggerType::Event { event_type, inputs, .. } => {
+            if event_type.is_empty() {
+                return Err(WorkflowError::Invalid("event trigger needs an event_type".to_string()));
+            }
+            if let Some((input, pointer)) = inputs
+                .iter()
+                .find(|(_, p)| !p.is_empty() && !p.starts_with('/'))
+            {
+                return Err(WorkflowError::Invalid(format!(
+                    "event trigger input '{}': '{}' is not a JSON Pointer",
+                    input, pointer
+                )));
+            }
+        }
+        TriggerType::FileWatch { path, glob, events, .. } => {
+            if path.is_empty() {
+                return Err(WorkflowError::Invalid("file-watch trigger needs a path".to_string()));
+            }
+            if events.is_empty() {
+                return Err(WorkflowError::Invalid("file-watch trigger needs at least one event".to_string()));
+            }
+            if let Some(glob) = glob {
+                globset::Glob::new(glob).map_err(|e| {
+                    WorkflowError::Invalid(format!("invalid glob '{}': {}", glob, e))
+                })?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
 }
 
 impl Default for NodeRegistry {
@@ -1,9 +1,16 @@
-use crate::{registry::NodeRegistry, WorkflowExecutor, ExecutionResult};
-use flowcore::{EventBus, FlowError, Value, Workflow, ExecutionId, NodeId};
+use crate::{registry::NodeRegistry, WorkflowExecutor, CompletedOutputs, ExecutionOptions, ExecutionResult};
+use crate::journal::{ExecutionFollow, ExecutionJournal};
+use async_trait::async_trait;
+use flowcore::{
+    ArtifactStore, CredentialResolver, Environment, EventBus, ExecutionEvent, FlowError,
+    NodeEvent, Signals, Value, Workflow, WorkflowError, ExecutionId, NodeId,
+};
 use chrono::{Utc, DateTime};
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock as StdRwLock};
+use tokio::sync::{watch, RwLock};
 use tokio_util::sync::CancellationToken;
 
 /// Tracked state for a workflow execution
@@ -11,36 +18,196 @@ use tokio_util::sync::CancellationToken;
 pub struct ExecutionState {
     pub execution_id: ExecutionId,
     pub workflow_id: uuid::Uuid,
+    /// Revision of the workflow definition this execution is pinned to
+    pub workflow_revision: u32,
     pub status: ExecutionStatus,
     pub started_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
     pub node_progress: HashMap<NodeId, NodeExecutionState>,
+    /// Node outputs, filled in once the execution completes successfully
+    pub outputs: HashMap<NodeId, HashMap<String, Value>>,
+    /// Error message if the execution failed or was cancelled
+    pub error: Option<String>,
     pub cancel_token: CancellationToken,
+    pub signals: Signals,
+    /// Holds back further nodes while `true`
+    pub pause: Arc<watch::Sender<bool>>,
+    /// When it finished by the Tokio clock, which the retention TTL is
+    /// measured against
+    finished: Option<tokio::time::Instant>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ExecutionStatus {
     Running,
+    /// Every unfinished node that has started waits for a signal
+    Waiting,
+    /// No further nodes are started until the execution is resumed
+    Paused,
     Completed,
     Failed,
     Cancelled,
 }
 
-#[derive(Debug, Clone)]
+impl ExecutionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionStatus::Running => "running",
+            ExecutionStatus::Waiting => "waiting",
+            ExecutionStatus::Paused => "paused",
+            ExecutionStatus::Completed => "completed",
+            ExecutionStatus::Failed => "failed",
+            ExecutionStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// Status named by [`ExecutionStatus::as_str`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "running" => Some(ExecutionStatus::Running),
+            "waiting" => Some(ExecutionStatus::Waiting),
+            "paused" => Some(ExecutionStatus::Paused),
+            "completed" => Some(ExecutionStatus::Completed),
+            "failed" => Some(ExecutionStatus::Failed),
+            "cancelled" => Some(ExecutionStatus::Cancelled),
+            _ => None,
+        }
+    }
+    
+    pub fn is_finished(&self) -> bool {
+        !matches!(
+            self,
+            ExecutionStatus::Running | ExecutionStatus::Waiting | ExecutionStatus::Paused
+        )
+    }
+}
+
+/// Lifecycle of a single node within an execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeStatus {
+    /// Waiting for its dependencies
+    Pending,
+    Running,
+    /// Failed at least once and is being retried
+    Retrying,
+    /// Paused until a signal arrives
+    Waiting,
+    Succeeded,
+    Failed,
+    /// Never finished because the execution stopped first
+    Skipped,
+}
+
+impl NodeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeStatus::Pending => "pending",
+            NodeStatus::Running => "running",
+            NodeStatus::Retrying => "retrying",
+            NodeStatus::Waiting => "waiting",
+            NodeStatus::Succeeded => "succeeded",
+            NodeStatus::Failed => "failed",
+            NodeStatus::Skipped => "skipped",
+        }
+    }
+    
+    pub fn is_finished(&self) -> bool {
+        matches!(self, NodeStatus::Succeeded | NodeStatus::Failed | NodeStatus::Skipped)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct NodeExecutionState {
-    pub status: ExecutionStatus,
+    pub status: NodeStatus,
+    /// Number of attempts started so far (0 while pending)
+    pub attempts: u32,
     pub started_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Duration of the final attempt
     pub duration_ms: Option<u64>,
+    /// Most recent failure, kept while retrying
+    pub error: Option<String>,
+}
+
+impl NodeExecutionState {
+    fn pending() -> Self {
+        Self {
+            status: NodeStatus::Pending,
+            attempts: 0,
+            started_at: None,
+            completed_at: None,
+            duration_ms: None,
+            error: None,
+        }
+    }
+}
+
+/// Everything needed to continue a waiting execution in another process
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionCheckpoint {
+    pub execution_id: ExecutionId,
+    pub workflow_id: uuid::Uuid,
+    pub workflow_revision: u32,
+    pub started_at: DateTime<Utc>,
+    pub inputs: HashMap<String, Value>,
+    /// Outputs of the nodes that have finished
+    pub completed: HashMap<NodeId, HashMap<String, Value>>,
+    /// Signals sent but not taken yet
+    pub signals: HashMap<String, Vec<Value>>,
+    /// Variable overrides the execution started with, e.g. its environment's
+    #[serde(default)]
+    pub variables: HashMap<String, Value>,
+    /// Inputs, outputs, variables or signals hold secrets, which serialize
+    /// as placeholders, so a stored copy can't be continued
+    #[serde(default)]
+    pub holds_secrets: bool,
+}
+
+/// Hook notified when a tracked execution finishes (e.g. to persist history)
+#[async_trait]
+pub trait ExecutionListener: Send + Sync {
+    async fn on_execution_finished(
+        &self,
+        workflow: &Workflow,
+        state: &ExecutionState,
+        result: &Result<ExecutionResult, FlowError>,
+    );
+    
+    /// Called when an execution starts waiting for a signal and whenever it
+    /// makes progress after that, so it can be continued after a restart
+    /// with [`FlowRuntime::restore_execution`]. The last checkpoint is stale
+    /// once the execution finishes.
+    async fn on_execution_checkpoint(&self, _checkpoint: &ExecutionCheckpoint) {}
+}
+
+/// What a tracked execution has done so far
+struct ExecutionProgress {
+    checkpoint: ExecutionCheckpoint,
+    /// Listeners were given a checkpoint, which must now be kept current
+    saved: bool,
+    /// Unmasked outputs the executor hands over as nodes complete; the
+    /// nodes a checkpoint lists as completed
+    outputs: CompletedOutputs,
 }
 
-/// Main runtime for executing workflows
+/// Main runtime for executing workflows. Cloning is cheap and clones share
+/// all state.
+#[derive(Clone)]
 pub struct FlowRuntime {
     registry: Arc<NodeRegistry>,
     executor: Arc<WorkflowExecutor>,
     event_bus: Arc<EventBus>,
     workflows: Arc<RwLock<HashMap<uuid::Uuid, Workflow>>>,
+    /// Environment profiles executions can select by name
+    environments: Arc<RwLock<HashMap<String, Environment>>>,
     executions: Arc<RwLock<HashMap<ExecutionId, ExecutionState>>>,
+    journals: Arc<RwLock<HashMap<ExecutionId, ExecutionJournal>>>,
+    /// Checkpoint data of unfinished executions
+    progress: Arc<RwLock<HashMap<ExecutionId, ExecutionProgress>>>,
+    listeners: Arc<StdRwLock<Vec<Arc<dyn ExecutionListener>>>>,
+    config: RuntimeConfig,
 }
 
 impl FlowRuntime {
@@ -57,16 +224,29 @@ impl FlowRuntime {
     
     /// Create a new runtime with a pre-configured registry
     pub fn with_registry(registry: Arc<NodeRegistry>, config: RuntimeConfig) -> Self {
-        let executor = Arc::new(WorkflowExecutor::new(config.max_parallel_nodes));
+        let mut executor = WorkflowExecutor::new(config.max_parallel_nodes)
+            .with_artifacts(config.artifacts.clone())
+            .with_workspace_root(&config.workspace_root);
+        if let Some(credentials) = &config.credentials {
+            executor = executor.with_credentials(Arc::clone(credentials));
+        }
+        let executor = Arc::new(executor);
         let event_bus = Arc::new(EventBus::new(config.event_buffer_size));
         
-        Self {
+        let runtime = Self {
             registry,
             executor,
             event_bus,
             workflows: Arc::new(RwLock::new(HashMap::new())),
+            environments: Arc::new(RwLock::new(HashMap::new())),
             executions: Arc::new(RwLock::new(HashMap::new())),
-        }
+            journals: Arc::new(RwLock::new(HashMap::new())),
+            progress: Arc::new(RwLock::new(HashMap::new())),
+            listeners: Arc::new(StdRwLock::new(Vec::new())),
+            config,
+        };
+        runtime.spawn_eviction_sweep();
+        runtime
     }
     
     /// Get access to the node registry for registering node types
@@ -80,66 +260,589 @@ impl FlowRuntime {
         workflows.insert(workflow.id, workflow);
     }
     
-    /// Execute a workflow by ID
+    /// Remove a registered workflow, returning it if it existed
+    pub async fn unregister_workflow(&self, workflow_id: uuid::Uuid) -> Option<Workflow> {
+        self.workflows.write().await.remove(&workflow_id)
+    }
+    
+    /// Snapshot of every registered workflow
+    pub async fn list_workflows(&self) -> Vec<Workflow> {
+        self.workflows.read().await.values().cloned().collect()
+    }
+    
+    /// Register an environment profile, replacing one of the same name
+    pub async fn register_environment(&self, environment: Environment) {
+        self.environments
+            .write()
+            .await
+            .insert(environment.name.clone(), environment);
+    }
+    
+    /// Remove an environment profile, returning it if it existed
+    pub async fn unregister_environment(&self, name: &str) -> Option<Environment> {
+        self.environments.write().await.remove(name)
+    }
+    
+    pub async fn get_environment(&self, name: &str) -> Option<Environment> {
+        self.environments.read().await.get(name).cloned()
+    }
+    
+    /// Snapshot of every registered environment
+    pub async fn list_environments(&self) -> Vec<Environment> {
+        self.environments.read().await.values().cloned().collect()
+    }
+    
+    /// Register a listener called after every execution started through
+    /// [`FlowRuntime::execute_workflow`] or [`FlowRuntime::start_workflow`]
+    pub fn add_listener(&self, listener: Arc<dyn ExecutionListener>) {
+        self.listeners.write().unwrap().push(listener);
+    }
+    
+    /// Execute a workflow by ID, waiting for it to finish
     pub async fn execute_workflow(
         &self,
         workflow_id: uuid::Uuid,
         inputs: HashMap<String, Value>,
     ) -> Result<ExecutionResult, FlowError> {
-        let workflows = self.workflows.read().await;
-        let workflow = workflows
+        self.execute_workflow_in(workflow_id, inputs, None).await
+    }
+    
+    /// Execute a workflow by ID with the variables of a registered
+    /// environment, waiting for it to finish
+    pub async fn execute_workflow_in(
+        &self,
+        workflow_id: uuid::Uuid,
+        inputs: HashMap<String, Value>,
+        environment: Option<&str>,
+    ) -> Result<ExecutionResult, FlowError> {
+        let (workflow, options) = self.prepare_execution(workflow_id, environment).await?;
+        self.run_tracked(workflow, inputs, options).await
+    }
+    
+    /// Start a workflow in the background and return its execution id
+    /// immediately. Progress is available through
+    /// [`FlowRuntime::get_execution_status`] and the event bus.
+    pub async fn start_workflow(
+        &self,
+        workflow_id: uuid::Uuid,
+        inputs: HashMap<String, Value>,
+    ) -> Result<ExecutionId, FlowError> {
+        self.start_workflow_in(workflow_id, inputs, None).await
+    }
+    
+    /// [`FlowRuntime::start_workflow`] with the variables of a registered
+    /// environment
+    pub async fn start_workflow_in(
+        &self,
+        workflow_id: uuid::Uuid,
+        inputs: HashMap<String, Value>,
+        environment: Option<&str>,
+    ) -> Result<ExecutionId, FlowError> {
+        let (workflow, options) = self.prepare_execution(workflow_id, environment).await?;
+        Ok(self.spawn_tracked(workflow, inputs, options))
+    }
+    
+    /// Continue an execution from a checkpoint an earlier process handed to
+    /// [`ExecutionListener::on_execution_checkpoint`], e.g. after a server
+    /// restart. `workflow` must be the revision the execution is pinned to.
+    /// Nodes that had finished are not run again; nodes that were waiting
+    /// start waiting again. An execution whose checkpoint holds secrets
+    /// can't be continued: it is recorded as failed and an error returned.
+    pub async fn restore_execution(
+        &self,
+        workflow: Workflow,
+        checkpoint: ExecutionCheckpoint,
+    ) -> Result<ExecutionId, FlowError> {
+        let execution_id = checkpoint.execution_id;
+        if self.executions.read().await.contains_key(&execution_id) {
+            return Err(FlowError::Execution(format!(
+                "Execution {} is already tracked",
+                execution_id
+            )));
+        }
+        
+        if checkpoint.holds_secrets {
+            let error = format!(
+                "Execution {} cannot be restored: it holds secrets, which checkpoints don't keep; start it again",
+                execution_id
+            );
+            self.track(&workflow, execution_id, checkpoint.started_at, &checkpoint.completed, Signals::new())
+                .await;
+            self.finish_tracked(&workflow, execution_id, &Err(FlowError::Execution(error.clone())))
+                .await;
+            return Err(FlowError::Execution(error));
+        }
+        
+        let signals = Signals::with_pending(checkpoint.signals.clone());
+        let options = ExecutionOptions {
+            completed: Some(checkpoint.completed.clone()),
+            variables: checkpoint.variables.clone(),
+            ..self
+                .track(&workflow, execution_id, checkpoint.started_at, &checkpoint.completed, signals)
+                .await
+        };
+        let inputs = checkpoint.inputs.clone();
+        self.progress.write().await.insert(
+            execution_id,
+            ExecutionProgress { checkpoint, saved: true, outputs: CompletedOutputs::default() },
+        );
+        
+        Ok(self.spawn_tracked(workflow, inputs, options))
+    }
+    
+    fn spawn_tracked(
+        &self,
+        workflow: Workflow,
+        inputs: HashMap<String, Value>,
+        options: ExecutionOptions,
+    ) -> ExecutionId {
+        let execution_id = options.execution_id.unwrap_or_default();
+        let runtime = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = runtime.run_tracked(workflow, inputs, options).await {
+                tracing::warn!("Execution {} finished with error: {}", execution_id, e);
+            }
+        });
+        execution_id
+    }
+    
+    /// Snapshot the workflow and start tracking a new execution of it
+    async fn prepare_execution(
+        &self,
+        workflow_id: uuid::Uuid,
+        environment: Option<&str>,
+    ) -> Result<(Workflow, ExecutionOptions), FlowError> {
+        // Snapshot the definition so updates made while this runs don't
+        // affect it (and don't have to wait for it)
+        let workflow = self.workflows
+            .read()
+            .await
             .get(&workflow_id)
+            .cloned()
             .ok_or_else(|| FlowError::Workflow(
                 flowcore::WorkflowError::NotFound(workflow_id.to_string())
             ))?;
         
-        let execution_id = ExecutionId::new_v4();
+        let variables = match environment {
+            Some(name) => self
+                .get_environment(name)
+                .await
+                .ok_or_else(|| {
+                    FlowError::Workflow(WorkflowError::Invalid(format!(
+                        "Unknown environment '{}'",
+                        name
+                    )))
+                })?
+                .variables,
+            None => HashMap::new(),
+        };
+        // Unknown variables are reported before the execution is tracked
+        workflow.with_variables(&variables)?;
+        
+        let options = ExecutionOptions {
+            variables,
+            ..self
+                .track(&workflow, ExecutionId::new_v4(), Utc::now(), &HashMap::new(), Signals::new())
+                .await
+        };
+        Ok((workflow, options))
+    }
+    
+    /// Start tracking an execution BEFORE running it (so cancel_execution
+    /// can find it), with `completed` nodes already done. Returns the
+    /// options to run it with.
+    async fn track(
+        &self,
+        workflow: &Workflow,
+        execution_id: ExecutionId,
+        started_at: DateTime<Utc>,
+        completed: &HashMap<NodeId, HashMap<String, Value>>,
+        signals: Signals,
+    ) -> ExecutionOptions {
         let cancel_token = CancellationToken::new();
+        let (pause, paused) = watch::channel(false);
         
-        // Track execution state BEFORE running (so cancel_execution can find it)
-        {
-            let mut executions = self.executions.write().await;
-            executions.insert(execution_id, ExecutionState {
-                execution_id,
-                workflow_id,
-                status: ExecutionStatus::Running,
-                started_at: Utc::now(),
-                completed_at: None,
-                node_progress: HashMap::new(),
-                cancel_token: cancel_token.clone(),
+        self.executions.write().await.insert(execution_id, ExecutionState {
+            execution_id,
+            workflow_id: workflow.id,
+            workflow_revision: workflow.revision,
+            status: ExecutionStatus::Running,
+            started_at,
+            completed_at: None,
+            node_progress: workflow.nodes
+                .iter()
+                .map(|node| {
+                    let mut progress = NodeExecutionState::pending();
+                    if completed.contains_key(&node.id) {
+                        progress.status = NodeStatus::Succeeded;
+                    }
+                    (node.id, progress)
+                })
+                .collect(),
+            outputs: HashMap::new(),
+            error: None,
+            cancel_token: cancel_token.clone(),
+            signals: signals.clone(),
+            pause: Arc::new(pause),
+            finished: None,
+        });
+        
+        self.journals.write().await.insert(
+            execution_id,
+            ExecutionJournal::new(self.config.event_buffer_size),
+        );
+        
+        ExecutionOptions {
+            execution_id: Some(execution_id),
+            cancel_token: Some(cancel_token),
+            signals,
+            pause: Some(paused),
+            completed: None,
+            variables: HashMap::new(),
+            outputs: None,
+            events: None,
+        }
+    }
+    
+    async fn run_tracked(
+        &self,
+        workflow: Workflow,
+        inputs: HashMap<String, Value>,
+        mut options: ExecutionOptions,
+    ) -> Result<ExecutionResult, FlowError> {
+        let execution_id = options.execution_id.unwrap_or_default();
+        if let Some(state) = self.executions.read().await.get(&execution_id) {
+            let mut progress = self.progress.write().await;
+            let progress = progress.entry(execution_id).or_insert_with(|| ExecutionProgress {
+                checkpoint: ExecutionCheckpoint {
+                    execution_id,
+                    workflow_id: workflow.id,
+                    workflow_revision: workflow.revision,
+                    started_at: state.started_at,
+                    inputs: inputs.clone(),
+                    completed: HashMap::new(),
+                    signals: HashMap::new(),
+                    variables: options.variables.clone(),
+                    holds_secrets: inputs
+                        .values()
+                        .chain(options.variables.values())
+                        .any(|v| !v.secrets().is_empty()),
+                },
+                saved: false,
+                outputs: CompletedOutputs::default(),
             });
+            options.outputs = Some(Arc::clone(&progress.outputs));
         }
         
-        let result = self.executor
-            .execute(
-                workflow,
-                &self.registry,
-                &self.event_bus,
-                inputs,
-                Some(execution_id),
-                Some(cancel_token),
-            )
-            .await;
+        // Follow this execution's events while it runs so per-node progress
+        // is visible through get_execution_status and late subscribers can
+        // catch up through follow_execution. Its own channel rather than a
+        // bus subscription, which drops events when it falls behind.
+        let (sender, mut events) = tokio::sync::mpsc::unbounded_channel();
+        options.events = Some(sender);
+        let execution = self.executor.execute_with(
+            &workflow,
+            &self.registry,
+            &self.event_bus,
+            inputs,
+            options,
+        );
+        tokio::pin!(execution);
         
+        let result = loop {
+            tokio::select! {
+                result = &mut execution => break result,
+                Some(event) = events.recv() => {
+                    self.record_event(execution_id, event).await;
+                }
+            }
+        };
+        while let Ok(event) = events.try_recv() {
+            self.record_event(execution_id, event).await;
+        }
+        
+        self.finish_tracked(&workflow, execution_id, &result).await;
+        result
+    }
+    
+    /// Record how a tracked execution ended and tell listeners
+    async fn finish_tracked(
+        &self,
+        workflow: &Workflow,
+        execution_id: ExecutionId,
+        result: &Result<ExecutionResult, FlowError>,
+    ) {
         // Update execution state on completion
-        {
+        let final_state = {
             let mut executions = self.executions.write().await;
-            if let Some(state) = executions.get_mut(&execution_id) {
+            executions.get_mut(&execution_id).map(|state| {
                 match &result {
-                    Ok(_) => state.status = ExecutionStatus::Completed,
+                    Ok(r) => {
+                        state.status = ExecutionStatus::Completed;
+                        state.outputs = r.outputs.clone();
+                    }
                     Err(e) => {
-                        state.status = if e.to_string().contains("cancelled") {
+                        state.status = if state.cancel_token.is_cancelled() {
                             ExecutionStatus::Cancelled
                         } else {
                             ExecutionStatus::Failed
                         };
+                        state.error = Some(e.to_string());
                     }
                 }
                 state.completed_at = Some(Utc::now());
+                state.finished = Some(tokio::time::Instant::now());
+                for progress in state.node_progress.values_mut() {
+                    if !progress.status.is_finished() {
+                        progress.status = NodeStatus::Skipped;
+                    }
+                }
+                state.clone()
+            })
+        };
+        
+        if let Some(journal) = self.journals.write().await.get_mut(&execution_id) {
+            journal.finish();
+        }
+        self.progress.write().await.remove(&execution_id);
+        
+        if let Some(state) = final_state {
+            let listeners = self.listeners.read().unwrap().clone();
+            for listener in listeners {
+                listener.on_execution_finished(workflow, &state, result).await;
             }
         }
         
-        result
+        // Listeners have seen it (and persisted it, if configured), so it
+        // may now be dropped from memory
+        self.evict_finished().await;
+    }
+    
+    /// Journal an event of the tracked execution and apply it to its progress
+    async fn record_event(&self, execution_id: ExecutionId, event: ExecutionEvent) {
+        let changed = self.track_node_event(execution_id, &event).await;
+        if let Some(journal) = self.journals.write().await.get_mut(&execution_id) {
+            journal.record(event);
+        }
+        if changed {
+            self.save_checkpoint(execution_id).await;
+        }
+    }
+    
+    /// Apply a node lifecycle event to the tracked execution's progress.
+    /// Returns true if listeners should get a new checkpoint.
+    async fn track_node_event(&self, execution_id: ExecutionId, event: &ExecutionEvent) -> bool {
+        let mut executions = self.executions.write().await;
+        let Some(state) = executions.get_mut(&execution_id) else {
+            return false;
+        };
+        let mut progress = self.progress.write().await;
+        let progress = progress.get_mut(&execution_id);
+        let mut finished_node = false;
+        
+        match event {
+            ExecutionEvent::NodeStarted { node_id, timestamp, .. } => {
+                let progress = state.node_progress.entry(*node_id).or_insert_with(NodeExecutionState::pending);
+                progress.status = NodeStatus::Running;
+                progress.attempts = 1;
+                progress.started_at = Some(*timestamp);
+            }
+            ExecutionEvent::NodeEvent {
+                node_id,
+                event: NodeEvent::Retrying { attempt, error, .. },
+                ..
+            } => {
+                let progress = state.node_progress.entry(*node_id).or_insert_with(NodeExecutionState::pending);
+                progress.status = NodeStatus::Retrying;
+                progress.attempts = *attempt;
+                progress.error = Some(error.clone());
+            }
+            ExecutionEvent::NodeCompleted { node_id, duration_ms, timestamp, .. } => {
+                let progress = state.node_progress.entry(*node_id).or_insert_with(NodeExecutionState::pending);
+                progress.status = NodeStatus::Succeeded;
+                progress.completed_at = Some(*timestamp);
+                progress.duration_ms = Some(*duration_ms);
+                progress.error = None;
+                finished_node = true;
+            }
+            ExecutionEvent::NodeFailed { node_id, error, timestamp, .. } => {
+                let progress = state.node_progress.entry(*node_id).or_insert_with(NodeExecutionState::pending);
+                progress.status = NodeStatus::Failed;
+                progress.completed_at = Some(*timestamp);
+                progress.duration_ms = progress.started_at
+                    .map(|started| (*timestamp - started).num_milliseconds().max(0) as u64);
+                progress.error = Some(error.clone());
+                finished_node = true;
+            }
+            ExecutionEvent::NodeEvent {
+                node_id,
+                event: NodeEvent::WaitingForSignal { .. },
+                ..
+            } => {
+                state.node_progress.entry(*node_id).or_insert_with(NodeExecutionState::pending).status =
+                    NodeStatus::Waiting;
+            }
+            ExecutionEvent::NodeEvent {
+                node_id,
+                event: NodeEvent::SignalReceived { .. },
+                ..
+            } => {
+                state.node_progress.entry(*node_id).or_insert_with(NodeExecutionState::pending).status =
+                    NodeStatus::Running;
+            }
+            _ => {}
+        }
+        
+        let Some(progress) = progress else {
+            return false;
+        };
+        if let ExecutionEvent::NodeCompleted { node_id, outputs: masked, .. } = event {
+            // The event's copy has text taken from secrets masked
+            let outputs = progress.outputs.lock().unwrap();
+            if let Some(outputs) = outputs.get(node_id) {
+                if outputs != masked || outputs.values().any(|v| !v.secrets().is_empty()) {
+                    progress.checkpoint.holds_secrets = true;
+                }
+            }
+        }
+        
+        if state.status.is_finished() {
+            return false;
+        }
+        if state.status == ExecutionStatus::Paused {
+            return progress.saved && finished_node;
+        }
+        let was_waiting = state.status == ExecutionStatus::Waiting;
+        state.status = unpaused_status(state);
+        
+        if state.status == ExecutionStatus::Waiting && !was_waiting {
+            progress.saved = true;
+            return true;
+        }
+        progress.saved && finished_node
+    }
+    
+    /// Hand listeners the current checkpoint of an execution
+    async fn save_checkpoint(&self, execution_id: ExecutionId) {
+        let Some(signals) = self.executions.read().await.get(&execution_id).map(|s| s.signals.clone()) else {
+            return;
+        };
+        let Some(mut checkpoint) = self.progress.read().await.get(&execution_id).map(|p| {
+            let mut checkpoint = p.checkpoint.clone();
+            // What the executor has finished, whichever events were seen
+            checkpoint.completed = p.outputs.lock().unwrap().clone();
+            checkpoint
+        }) else {
+            return;
+        };
+        checkpoint.signals = signals.pending();
+        checkpoint.holds_secrets |= checkpoint
+            .signals
+            .values()
+            .flatten()
+            .any(|v| !v.secrets().is_empty());
+        
+        let listeners = self.listeners.read().unwrap().clone();
+        for listener in listeners {
+            listener.on_execution_checkpoint(&checkpoint).await;
+        }
+    }
+    
+    /// Send a signal to an unfinished execution; nodes waiting for `name`
+    /// continue with `payload`. Returns false if the execution is unknown or
+    /// has already finished.
+    pub async fn send_signal(&self, execution_id: ExecutionId, name: &str, payload: Value) -> bool {
+        let signals = match self.executions.read().await.get(&execution_id) {
+            Some(state) if !state.status.is_finished() => state.signals.clone(),
+            _ => return false,
+        };
+        signals.send(name, payload);
+        
+        // Keep signals nothing waits for yet in the saved checkpoint
+        let saved = self.progress.read().await.get(&execution_id).is_some_and(|p| p.saved);
+        if saved {
+            self.save_checkpoint(execution_id).await;
+        }
+        true
+    }
+    
+    /// Drop finished executions beyond the configured retention
+    async fn evict_finished(&self) {
+        Self::evict(&self.executions, &self.journals, &self.config).await;
+    }
+    
+    /// Enforce `finished_execution_ttl` on an idle runtime too, where no
+    /// execution finishes to trigger eviction. Needs a Tokio runtime; the
+    /// sweep ends once every clone of this runtime is dropped.
+    fn spawn_eviction_sweep(&self) {
+        let Some(ttl) = self.config.finished_execution_ttl else {
+            return;
+        };
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let executions = Arc::downgrade(&self.executions);
+        let journals = Arc::downgrade(&self.journals);
+        let config = self.config.clone();
+        let period = (ttl / 2).clamp(
+            std::time::Duration::from_secs(1),
+            std::time::Duration::from_secs(60),
+        );
+        
+        handle.spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let (Some(executions), Some(journals)) = (executions.upgrade(), journals.upgrade())
+                else {
+                    break;
+                };
+                Self::evict(&executions, &journals, &config).await;
+            }
+        });
+    }
+    
+    async fn evict(
+        executions: &RwLock<HashMap<ExecutionId, ExecutionState>>,
+        journals: &RwLock<HashMap<ExecutionId, ExecutionJournal>>,
+        config: &RuntimeConfig,
+    ) {
+        let mut executions = executions.write().await;
+        
+        if let Some(ttl) = config.finished_execution_ttl {
+            let now = tokio::time::Instant::now();
+            executions.retain(|_, e| e.finished.is_none_or(|at| now.duration_since(at) <= ttl));
+        }
+        
+        let mut finished: Vec<_> = executions
+            .values()
+            .filter_map(|e| e.completed_at.map(|at| (at, e.execution_id)))
+            .collect();
+        if finished.len() > config.max_finished_executions {
+            finished.sort();
+            let excess = finished.len() - config.max_finished_executions;
+            for (_, id) in &finished[..excess] {
+                executions.remove(id);
+            }
+        }
+        
+        journals.write().await.retain(|id, _| executions.contains_key(id));
+    }
+    
+    /// All tracked executions, most recently started first
+    pub async fn list_executions(&self) -> Vec<ExecutionState> {
+        let mut executions: Vec<_> = self.executions.read().await.values().cloned().collect();
+        executions.sort_by_key(|e| std::cmp::Reverse(e.started_at));
+        executions
+    }
+    
+    /// Events recorded for a tracked execution after sequence number
+    /// `after` (all retained ones if `None`), plus a receiver for the
+    /// events still to come. `None` if the execution is unknown or has been
+    /// evicted.
+    pub async fn follow_execution(&self, execution_id: ExecutionId, after: Option<u64>) -> Option<ExecutionFollow> {
+        self.journals.read().await.get(&execution_id).map(|j| j.follow(after))
     }
     
     /// Get the status of an execution
@@ -147,13 +850,64 @@ impl FlowRuntime {
         self.executions.read().await.get(&execution_id).cloned()
     }
     
-    /// Cancel a running execution
+    /// Stop starting further nodes of an execution; nodes already running
+    /// finish. Returns false if it is unknown, finished or already paused.
+    pub async fn pause_execution(&self, execution_id: ExecutionId) -> bool {
+        let mut executions = self.executions.write().await;
+        match executions.get_mut(&execution_id) {
+            Some(state) if !state.status.is_finished() && state.status != ExecutionStatus::Paused => {
+                state.pause.send_replace(true);
+                state.status = ExecutionStatus::Paused;
+            }
+            _ => return false,
+        }
+        drop(executions);
+        self.announce(ExecutionEvent::WorkflowPaused {
+            execution_id,
+            timestamp: Utc::now(),
+        })
+        .await;
+        true
+    }
+    
+    /// Let a paused execution start nodes again. Returns false if it is
+    /// unknown or not paused.
+    pub async fn resume_execution(&self, execution_id: ExecutionId) -> bool {
+        let mut executions = self.executions.write().await;
+        match executions.get_mut(&execution_id) {
+            Some(state) if state.status == ExecutionStatus::Paused => {
+                state.pause.send_replace(false);
+                state.status = unpaused_status(state);
+            }
+            _ => return false,
+        }
+        drop(executions);
+        self.announce(ExecutionEvent::WorkflowResumed {
+            execution_id,
+            timestamp: Utc::now(),
+        })
+        .await;
+        true
+    }
+    
+    /// Emit an event the runtime rather than the executor produces, and
+    /// journal it
+    async fn announce(&self, event: ExecutionEvent) {
+        if let Some(journal) = self.journals.write().await.get_mut(&event.execution_id()) {
+            journal.record(event.clone());
+        }
+        self.event_bus.emit(event);
+    }
+    
+    /// Cancel a running execution. Returns false if it is unknown or
+    /// has already finished.
     pub async fn cancel_execution(&self, execution_id: ExecutionId) -> bool {
-        if let Some(state) = self.executions.read().await.get(&execution_id) {
-            state.cancel_token.cancel();
-            true
-        } else {
-            false
+        match self.executions.read().await.get(&execution_id) {
+            Some(state) if !state.status.is_finished() => {
+                state.cancel_token.cancel();
+                true
+            }
+            _ => false,
         }
     }
     
@@ -168,11 +922,92 @@ impl FlowRuntime {
             .await
     }
     
+    /// [`FlowRuntime::execute`] with `variables`, e.g. an environment's,
+    /// overriding the workflow's
+    pub async fn execute_with_variables(
+        &self,
+        workflow: &Workflow,
+        inputs: HashMap<String, Value>,
+        variables: HashMap<String, Value>,
+    ) -> Result<ExecutionResult, FlowError> {
+        let options = ExecutionOptions {
+            variables,
+            ..Default::default()
+        };
+        self.executor
+            .execute_with(workflow, &self.registry, &self.event_bus, inputs, options)
+            .await
+    }
+    
     /// Subscribe to execution events
     pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<flowcore::ExecutionEvent> {
         self.event_bus.subscribe()
     }
     
+    /// Store holding the blobs of this runtime's executions
+    pub fn artifacts(&self) -> &ArtifactStore {
+        &self.config.artifacts
+    }
+    
+    /// Remove workspaces left behind by executions this runtime does not
+    /// track, e.g. those of a process that stopped mid-run. Returns how
+    /// many were removed.
+    pub async fn prune_workspaces(&self) -> std::io::Result<usize> {
+        let mut entries = match tokio::fs::read_dir(&self.config.workspace_root).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+        
+        let mut removed = 0;
+        while let Some(entry) = entries.next_entry().await? {
+            let tracked = match entry.file_name().to_str().map(ExecutionId::parse_str) {
+                Some(Ok(id)) => self.executions.read().await.contains_key(&id),
+                // Not an execution workspace
+                _ => true,
+            };
+            if !tracked && entry.file_type().await?.is_dir() {
+                tokio::fs::remove_dir_all(entry.path()).await?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+    
+    /// Hashes of the blobs tracked executions refer to: in their inputs,
+    /// node outputs and recorded events
+    pub async fn referenced_blobs(&self) -> HashSet<String> {
+        let mut values: Vec<Value> = Vec::new();
+        for state in self.executions.read().await.values() {
+            values.extend(state.outputs.values().flat_map(|ports| ports.values().cloned()));
+        }
+        for progress in self.progress.read().await.values() {
+            values.extend(progress.checkpoint.inputs.values().cloned());
+            let outputs = progress.outputs.lock().unwrap();
+            values.extend(outputs.values().flat_map(|ports| ports.values().cloned()));
+        }
+        for journal in self.journals.read().await.values() {
+            for event in journal.events() {
+                match event {
+                    ExecutionEvent::NodeCompleted { outputs, .. } => {
+                        values.extend(outputs.values().cloned())
+                    }
+                    ExecutionEvent::NodeEvent {
+                        event: NodeEvent::Data { value, .. } | NodeEvent::Custom { payload: value, .. },
+                        ..
+                    } => values.push(value.clone()),
+                    _ => {}
+                }
+            }
+        }
+        
+        values
+            .iter()
+            .flat_map(Value::blob_refs)
+            .map(|blob| blob.hash.clone())
+            .collect()
+    }
+    
     /// Get the event bus for direct access
     pub fn event_bus(&self) -> &Arc<EventBus> {
         &self.event_bus
@@ -186,10 +1021,39 @@ impl Default for FlowRuntime {
 }
 
 /// Configuration for the runtime
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct RuntimeConfig {
     pub max_parallel_nodes: usize,
+    /// Capacity of the event bus and of each execution's replay journal
     pub event_buffer_size: usize,
+    /// Finished executions kept in memory; the oldest are evicted first.
+    /// Use 0 to keep finished executions only in persistent storage (via an
+    /// [`ExecutionListener`]).
+    pub max_finished_executions: usize,
+    /// How long a finished execution stays in memory (`None` = no limit).
+    /// Enforced when executions finish and by a periodic sweep.
+    pub finished_execution_ttl: Option<std::time::Duration>,
+    /// Where nodes keep blob values
+    pub artifacts: ArtifactStore,
+    /// Parent of the per-execution workspace directories
+    pub workspace_root: PathBuf,
+    /// Where `{"$credential": ...}` references in node config are looked
+    /// up; without one, nodes using a reference fail
+    pub credentials: Option<Arc<dyn CredentialResolver>>,
+}
+
+impl std::fmt::Debug for RuntimeConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RuntimeConfig")
+            .field("max_parallel_nodes", &self.max_parallel_nodes)
+            .field("event_buffer_size", &self.event_buffer_size)
+            .field("max_finished_executions", &self.max_finished_executions)
+            .field("finished_execution_ttl", &self.finished_execution_ttl)
+            .field("artifacts", &self.artifacts)
+            .field("workspace_root", &self.workspace_root)
+            .field("credentials", &self.credentials.is_some())
+            .finish()
+    }
 }
 
 impl Default for RuntimeConfig {
@@ -197,6 +1061,23 @@ impl Default for RuntimeConfig {
         Self {
             max_parallel_nodes: 10,
             event_buffer_size: 1000,
+            max_finished_executions: 1000,
+            finished_execution_ttl: None,
+            artifacts: ArtifactStore::default(),
+            workspace_root: std::env::temp_dir().join("flowengine-workspaces"),
+            credentials: None,
         }
     }
 }
+
+/// Status of an unfinished, unpaused execution from its nodes' progress
+fn unpaused_status(state: &ExecutionState) -> ExecutionStatus {
+    let waiting = state.node_progress.values().any(|p| p.status == NodeStatus::Waiting);
+    let busy = state.node_progress.values()
+        .any(|p| matches!(p.status, NodeStatus::Running | NodeStatus::Retrying));
+    if waiting && !busy {
+        ExecutionStatus::Waiting
+    } else {
+        ExecutionStatus::Running
+    }
+}
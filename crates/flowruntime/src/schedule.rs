@@ -0,0 +1,258 @@
+//! Cron expressions evaluated in a timezone
+
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use flowcore::{CatchUpPolicy, TriggerType, WorkflowError};
+use std::str::FromStr;
+
+/// Most missed runs [`CatchUpPolicy::All`] will backfill at once
+pub const MAX_BACKFILL: usize = 100;
+
+/// How late a fire time may be handled and still count as on time rather
+/// than missed (covers scheduler ticks and short restarts)
+pub const MISFIRE_GRACE: chrono::Duration = chrono::Duration::seconds(60);
+
+/// A parsed cron trigger
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    schedule: cron::Schedule,
+    timezone: Tz,
+    spec: String,
+}
+
+impl CronSchedule {
+    /// Parse a 5-field (`min hour dom mon dow`) or 6/7-field (with seconds
+    /// and year) expression, evaluated in `timezone` (UTC if `None`)
+    pub fn parse(expression: &str, timezone: Option<&str>) -> Result<Self, WorkflowError> {
+        let expression = expression.trim();
+        let normalized = if expression.split_whitespace().count() == 5 {
+            format!("0 {}", expression)
+        } else {
+            expression.to_string()
+        };
+        let schedule = cron::Schedule::from_str(&normalized).map_err(|e| {
+            WorkflowError::Invalid(format!("invalid cron expression '{}': {}", expression, e))
+        })?;
+        let timezone = match timezone {
+            Some(tz) => tz
+                .parse::<Tz>()
+                .map_err(|_| WorkflowError::Invalid(format!("unknown timezone '{}'", tz)))?,
+            None => Tz::UTC,
+        };
+
+        Ok(Self {
+            spec: format!("{} {}", timezone.name(), expression),
+            schedule,
+            timezone,
+        })
+    }
+
+    /// The schedule of a cron trigger, `None` for other trigger types
+    pub fn from_trigger(trigger: &TriggerType) -> Option<Result<Self, WorkflowError>> {
+        match trigger {
+            TriggerType::Cron { expression, timezone, .. } => {
+                Some(Self::parse(expression, timezone.as_deref()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Timezone and expression; changes whenever either does
+    pub fn spec(&self) -> &str {
+        &self.spec
+    }
+
+    /// First fire time strictly after `after`, `None` if there is none.
+    /// A local time that occurs twice when clocks go back fires once, at
+    /// its first occurrence; one skipped when they go forward doesn't fire.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // Within a repeated hour the iterator yields either occurrence,
+        // which may not be after `after`
+        self.schedule
+            .after(&after.with_timezone(&self.timezone))
+            .map(|t| t.with_timezone(&Utc))
+            .find(|t| *t > after && self.first_occurrence(t))
+    }
+
+    /// Whether `t` is the first occurrence of its local time
+    fn first_occurrence(&self, t: &DateTime<Utc>) -> bool {
+        let local = t.with_timezone(&self.timezone).naive_local();
+        self.timezone
+            .from_local_datetime(&local)
+            .earliest()
+            .is_some_and(|first| first == *t)
+    }
+
+    /// Which fire times from `next_fire_at` up to `now` to run, oldest
+    /// first. Fire times older than [`MISFIRE_GRACE`] are missed: `Skip`
+    /// drops them and runs at most the latest on-time one, `Latest` runs
+    /// only the most recent fire time and `All` runs every one.
+    pub fn due_runs(
+        &self,
+        next_fire_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+        policy: CatchUpPolicy,
+    ) -> Vec<DateTime<Utc>> {
+        if next_fire_at > now {
+            return Vec::new();
+        }
+
+        // Walk backwards from now so a long outage costs at most
+        // MAX_BACKFILL steps, whatever the expression. Start just past now
+        // since stepping back is exclusive. Stepping back yields both
+        // occurrences of a repeated local time; keep the one next_after does.
+        let start = now + chrono::Duration::seconds(1);
+        let mut due: Vec<DateTime<Utc>> = self
+            .schedule
+            .after(&start.with_timezone(&self.timezone))
+            .rev()
+            .map(|t| t.with_timezone(&Utc))
+            .filter(|t| self.first_occurrence(t))
+            .skip_while(|t| *t > now)
+            .take_while(|t| *t >= next_fire_at)
+            .take(MAX_BACKFILL)
+            .collect();
+        due.reverse();
+
+        match policy {
+            CatchUpPolicy::Skip => {
+                due.drain(..due.len().saturating_sub(1));
+                due.retain(|t| now - *t <= MISFIRE_GRACE);
+            }
+            CatchUpPolicy::Latest => {
+                due.drain(..due.len().saturating_sub(1));
+            }
+            CatchUpPolicy::All => {}
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    fn fire_times(schedule: &CronSchedule, after: &str, count: usize) -> Vec<DateTime<Utc>> {
+        let mut after = at(after);
+        (0..count)
+            .map(|_| {
+                after = schedule.next_after(after).unwrap();
+                after
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let five = CronSchedule::parse("*/5 * * * *", None).unwrap();
+        assert_eq!(five.spec(), "UTC */5 * * * *");
+        assert_eq!(five.next_after(at("2024-01-15T10:02:30Z")), Some(at("2024-01-15T10:05:00Z")));
+
+        let seconds = CronSchedule::parse("30 * * * * *", None).unwrap();
+        assert_eq!(seconds.next_after(at("2024-01-15T10:02:30Z")), Some(at("2024-01-15T10:03:30Z")));
+
+        assert!(CronSchedule::parse("not cron", None).is_err());
+        assert!(CronSchedule::parse("0 9 * * *", Some("Mars/Olympus")).is_err());
+    }
+
+    #[test]
+    fn test_next_after_in_timezone() {
+        let nine = CronSchedule::parse("0 9 * * *", Some("Europe/Berlin")).unwrap();
+        assert_eq!(nine.spec(), "Europe/Berlin 0 9 * * *");
+        // 09:00 CET in winter, 09:00 CEST in summer
+        assert_eq!(nine.next_after(at("2024-01-15T00:00:00Z")), Some(at("2024-01-15T08:00:00Z")));
+        assert_eq!(nine.next_after(at("2024-07-15T00:00:00Z")), Some(at("2024-07-15T07:00:00Z")));
+        // Strictly after
+        assert_eq!(nine.next_after(at("2024-01-15T08:00:00Z")), Some(at("2024-01-16T08:00:00Z")));
+    }
+
+    #[test]
+    fn test_next_after_across_dst() {
+        let schedule = CronSchedule::parse("30 2 * * *", Some("Europe/Berlin")).unwrap();
+
+        // 02:30 doesn't exist on 2024-03-31, when clocks go from 02:00 to 03:00
+        assert_eq!(
+            fire_times(&schedule, "2024-03-30T12:00:00Z", 2),
+            [at("2024-04-01T00:30:00Z"), at("2024-04-02T00:30:00Z")]
+        );
+
+        // 02:30 happens twice on 2024-10-27, when clocks go from 03:00 back
+        // to 02:00; it fires once
+        assert_eq!(
+            fire_times(&schedule, "2024-10-26T12:00:00Z", 2),
+            [at("2024-10-27T00:30:00Z"), at("2024-10-28T01:30:00Z")]
+        );
+        // Also when asked during the repeated hour
+        assert_eq!(
+            schedule.next_after(at("2024-10-27T01:10:00Z")),
+            Some(at("2024-10-28T01:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_due_runs_policies() {
+        let schedule = CronSchedule::parse("*/5 * * * *", None).unwrap();
+        let next_fire_at = at("2024-01-15T10:00:00Z");
+
+        // Down from 10:00 until 10:32:10: 10:00 through 10:30 were missed
+        let now = at("2024-01-15T10:32:10Z");
+        let all = schedule.due_runs(next_fire_at, now, CatchUpPolicy::All);
+        assert_eq!(all.len(), 7);
+        assert_eq!(all[0], next_fire_at);
+        assert_eq!(all[6], at("2024-01-15T10:30:00Z"));
+        assert_eq!(
+            schedule.due_runs(next_fire_at, now, CatchUpPolicy::Latest),
+            [at("2024-01-15T10:30:00Z")]
+        );
+        assert!(schedule.due_runs(next_fire_at, now, CatchUpPolicy::Skip).is_empty());
+
+        // Within MISFIRE_GRACE the latest run is on time, not missed
+        let now = at("2024-01-15T10:30:45Z");
+        assert_eq!(
+            schedule.due_runs(next_fire_at, now, CatchUpPolicy::Skip),
+            [at("2024-01-15T10:30:00Z")]
+        );
+
+        // Nothing before the next fire time
+        assert!(schedule
+            .due_runs(next_fire_at, at("2024-01-15T09:59:59Z"), CatchUpPolicy::All)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_due_runs_backfill_is_bounded() {
+        let schedule = CronSchedule::parse("* * * * *", None).unwrap();
+        let now = at("2024-01-15T12:00:30Z");
+
+        // A day of missed minutes only backfills the most recent ones
+        let all = schedule.due_runs(now - chrono::Duration::days(1), now, CatchUpPolicy::All);
+        assert_eq!(all.len(), MAX_BACKFILL);
+        assert_eq!(all.last(), Some(&at("2024-01-15T12:00:00Z")));
+        assert!(all.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_due_runs_across_dst() {
+        let schedule = CronSchedule::parse("*/20 2 * * *", Some("Europe/Berlin")).unwrap();
+
+        // Missed all of the repeated hour: each local time runs once
+        let all = schedule.due_runs(
+            at("2024-10-26T23:30:00Z"),
+            at("2024-10-27T03:00:00Z"),
+            CatchUpPolicy::All,
+        );
+        assert_eq!(
+            all,
+            [
+                at("2024-10-27T00:00:00Z"),
+                at("2024-10-27T00:20:00Z"),
+                at("2024-10-27T00:40:00Z"),
+            ]
+        );
+        assert_eq!(all.last().copied(), fire_times(&schedule, "2024-10-26T23:30:00Z", 3).pop());
+    }
+}
@@ -0,0 +1,52 @@
+//! Garbage collection of the artifact store
+//!
+//! A blob stays while a running or recently finished execution, a workflow
+//! definition, a cache entry or a checkpoint refers to it. Blobs written
+//! within the grace period are kept too, since the node that stored them
+//! may not have reported its outputs yet.
+
+use flowpersist::PersistentStore;
+use flowruntime::FlowRuntime;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+/// Time between collections
+const INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Minimum age of a blob before it can be collected
+const GRACE: Duration = Duration::from_secs(60 * 60);
+
+/// Start collecting unreferenced blobs in the background
+pub fn spawn(runtime: Arc<FlowRuntime>, store: PersistentStore) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            collect(&runtime, &store).await;
+        }
+    })
+}
+
+async fn collect(runtime: &FlowRuntime, store: &PersistentStore) {
+    let mut referenced = match store.referenced_blobs().await {
+        Ok(referenced) => referenced,
+        Err(e) => {
+            // Without the stored references anything could look unused
+            error!("Skipping artifact gc: {}", e);
+            return;
+        }
+    };
+    referenced.extend(runtime.referenced_blobs().await);
+
+    match runtime.artifacts().gc(&referenced, GRACE).await {
+        Ok(report) if report.removed > 0 => info!(
+            "🧹 Removed {} unreferenced artifacts ({} bytes), kept {}",
+            report.removed, report.freed_bytes, report.kept
+        ),
+        Ok(_) => {}
+        Err(e) => error!("Artifact gc failed: {}", e),
+    }
+}
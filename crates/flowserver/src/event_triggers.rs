@@ -0,0 +1,562 @@
+//! Event triggers: workflows started by other workflows finishing or by
+//! custom events emitted with `event.emit`
+//!
+//! Events come from this server's executions and, when `IGGY_CONNECTION`
+//! is set, from the Iggy topic engines share, where this server publishes
+//! its own. Each execution a trigger starts is one link further down the
+//! chain that started it, and events from executions [`MAX_CHAIN_DEPTH`]
+//! links deep are dropped, so workflows that trigger each other cannot
+//! loop forever. The depth travels with published events, so this holds
+//! across engines too.
+
+use async_trait::async_trait;
+use flowcore::events::{BusEvent, IggyEventBus, IggyEventBusConfig};
+use flowcore::{
+    ExecutionEvent, ExecutionId, FlowError, NodeEvent, NodeId, TriggerType, Value, Workflow,
+};
+use flowruntime::{ExecutionListener, ExecutionResult, ExecutionState, FlowRuntime};
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+/// Longest chain of executions triggering each other
+pub const MAX_CHAIN_DEPTH: u32 = 8;
+
+const IGGY_CONSUMER: &str = "flowserver-event-triggers";
+const IGGY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Local and remote executions each remembered for Iggy events
+const REMEMBERED_EXECUTIONS: usize = 10_000;
+
+/// Something that happened, as event triggers see it
+struct TriggerEvent {
+    event_type: String,
+    /// Workflow the event came from, if known
+    workflow_id: Option<Uuid>,
+    payload: serde_json::Value,
+    /// Links in the trigger chain that led to the event
+    depth: u32,
+}
+
+pub struct EventTriggers {
+    runtime: FlowRuntime,
+    /// Chain depth of unfinished executions started by a trigger. Held
+    /// while starting one so it can't finish before it is recorded.
+    depths: Mutex<HashMap<ExecutionId, u32>>,
+    /// Executions this server started, so their events coming back from
+    /// Iggy are skipped even once the runtime has forgotten them
+    local: Mutex<Recent<()>>,
+    /// Workflow and chain depth of remote executions seen on Iggy
+    remote: Mutex<Recent<(Uuid, u32)>>,
+}
+
+/// Executions remembered in the order they were seen, forgetting the
+/// oldest past [`REMEMBERED_EXECUTIONS`], e.g. remote ones whose
+/// completion never arrived
+struct Recent<V> {
+    entries: HashMap<ExecutionId, V>,
+    order: VecDeque<ExecutionId>,
+}
+
+impl<V> Recent<V> {
+    fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn insert(&mut self, execution_id: ExecutionId, value: V) {
+        if self.entries.insert(execution_id, value).is_none() {
+            self.order.push_back(execution_id);
+        }
+        while self.order.len() > REMEMBERED_EXECUTIONS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    fn get(&self, execution_id: &ExecutionId) -> Option<&V> {
+        self.entries.get(execution_id)
+    }
+
+    fn contains(&self, execution_id: &ExecutionId) -> bool {
+        self.entries.contains_key(execution_id)
+    }
+
+    fn remove(&mut self, execution_id: &ExecutionId) -> Option<V> {
+        self.entries.remove(execution_id)
+    }
+}
+
+impl EventTriggers {
+    pub fn new(runtime: FlowRuntime) -> Arc<Self> {
+        Arc::new(Self {
+            runtime,
+            depths: Mutex::new(HashMap::new()),
+            local: Mutex::new(Recent::new()),
+            remote: Mutex::new(Recent::new()),
+        })
+    }
+
+    /// Watch this server's executions for custom events
+    pub fn spawn(self: &Arc<Self>) -> JoinHandle<()> {
+        let triggers = Arc::clone(self);
+        let mut events = self.runtime.subscribe_events();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(ExecutionEvent::WorkflowStarted { execution_id, .. }) => {
+                        triggers.local.lock().await.insert(execution_id, ());
+                    }
+                    Ok(ExecutionEvent::NodeEvent {
+                        execution_id,
+                        node_id,
+                        event: NodeEvent::Custom { name, payload },
+                        ..
+                    }) => {
+                        let workflow_id = triggers
+                            .runtime
+                            .get_execution_status(execution_id)
+                            .await
+                            .map(|s| s.workflow_id);
+                        let depth = triggers.depths.lock().await.get(&execution_id).copied();
+                        triggers
+                            .dispatch(custom_event(
+                                name,
+                                workflow_id,
+                                execution_id,
+                                node_id,
+                                &payload,
+                                "local",
+                                depth.unwrap_or(0),
+                            ))
+                            .await;
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(n)) => {
+                        warn!("Event triggers fell behind and missed {} events", n)
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Also react to events from the Iggy topic, and publish the ones of
+    /// this server's executions there
+    pub fn spawn_iggy(self: &Arc<Self>, config: IggyEventBusConfig) -> JoinHandle<()> {
+        let triggers = Arc::clone(self);
+        let events = self.runtime.subscribe_events();
+        tokio::spawn(async move {
+            let bus = match IggyEventBus::new(config).await {
+                Ok(bus) => Arc::new(bus),
+                Err(e) => {
+                    error!("Event triggers cannot connect to Iggy: {}", e);
+                    return;
+                }
+            };
+            let subscription = match bus.subscribe(IGGY_CONSUMER.to_string()).await {
+                Ok(subscription) => subscription,
+                Err(e) => {
+                    error!("Event triggers cannot subscribe to Iggy: {}", e);
+                    return;
+                }
+            };
+            tokio::spawn(Arc::clone(&triggers).publish(bus, events));
+            info!("📨 Event triggers listening on Iggy");
+
+            let mut interval = tokio::time::interval(IGGY_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                match subscription.poll_with_depth().await {
+                    Ok(events) => {
+                        for event in events {
+                            triggers.remote_event(event).await;
+                        }
+                    }
+                    Err(e) => warn!("Polling Iggy for trigger events failed: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Publish the events triggers react to, with the chain depth of the
+    /// execution they belong to
+    async fn publish(
+        self: Arc<Self>,
+        bus: Arc<IggyEventBus>,
+        mut events: tokio::sync::broadcast::Receiver<ExecutionEvent>,
+    ) {
+        loop {
+            let event = match events.recv().await {
+                Ok(
+                    event @ (ExecutionEvent::WorkflowStarted { .. }
+                    | ExecutionEvent::WorkflowCompleted { .. }
+                    | ExecutionEvent::NodeEvent {
+                        event: NodeEvent::Custom { .. },
+                        ..
+                    }),
+                ) => event,
+                Ok(_) => continue,
+                Err(RecvError::Lagged(n)) => {
+                    warn!("Publishing to Iggy fell behind and missed {} events", n);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            let depth = self.depths.lock().await.get(&event.execution_id()).copied();
+            if let Err(e) = bus.publish_at_depth(event, depth.unwrap_or(0)).await {
+                warn!("Publishing trigger event to Iggy failed: {}", e);
+            }
+        }
+    }
+
+    async fn remote_event(&self, published: BusEvent) {
+        let BusEvent { event, chain_depth } = published;
+        let execution_id = event.execution_id();
+        if self.local.lock().await.contains(&execution_id)
+            || self.runtime.get_execution_status(execution_id).await.is_some()
+        {
+            // One of ours, already handled locally
+            return;
+        }
+
+        let trigger_event = match event {
+            ExecutionEvent::WorkflowStarted { workflow_id, .. } => {
+                self.remote
+                    .lock()
+                    .await
+                    .insert(execution_id, (workflow_id, chain_depth));
+                return;
+            }
+            ExecutionEvent::WorkflowCompleted {
+                success,
+                duration_ms,
+                ..
+            } => {
+                // Completions may be published after the engine dropped
+                // their depth, so the one the execution started at counts
+                let started = self.remote.lock().await.remove(&execution_id);
+                let workflow_id = started.map(|(id, _)| id);
+                let status = if success { "completed" } else { "failed" };
+                TriggerEvent {
+                    event_type: format!("workflow.{}", status),
+                    workflow_id,
+                    payload: json!({
+                        "workflow_id": workflow_id,
+                        "execution_id": execution_id,
+                        "status": status,
+                        "duration_ms": duration_ms,
+                        "origin": "iggy",
+                    }),
+                    depth: started.map_or(chain_depth, |(_, depth)| depth),
+                }
+            }
+            ExecutionEvent::NodeEvent {
+                node_id,
+                event: NodeEvent::Custom { name, payload },
+                ..
+            } => {
+                custom_event(
+                    name,
+                    self.remote.lock().await.get(&execution_id).map(|(id, _)| *id),
+                    execution_id,
+                    node_id,
+                    &payload,
+                    "iggy",
+                    chain_depth,
+                )
+            }
+            _ => return,
+        };
+        self.dispatch(trigger_event).await;
+    }
+
+    /// Start every workflow with a matching enabled event trigger
+    async fn dispatch(&self, event: TriggerEvent) {
+        if event.depth >= MAX_CHAIN_DEPTH {
+            warn!(
+                "Dropping {} event from workflow {:?}: {} workflows already triggered each other in a row",
+                event.event_type, event.workflow_id, event.depth
+            );
+            return;
+        }
+
+        for workflow in self.runtime.list_workflows().await {
+            for trigger in workflow.triggers.iter().filter(|t| t.enabled) {
+                let TriggerType::Event {
+                    event_type,
+                    source_workflow,
+                    inputs,
+                } = &trigger.trigger_type
+                else {
+                    continue;
+                };
+                if *event_type != event.event_type
+                    || source_workflow.is_some_and(|id| event.workflow_id != Some(id))
+                {
+                    continue;
+                }
+
+                let mut mapped = HashMap::from([(
+                    "event".to_string(),
+                    Value::Json(event.payload.clone()),
+                )]);
+                for (input, pointer) in inputs {
+                    match event.payload.pointer(pointer) {
+                        Some(value) => {
+                            mapped.insert(input.clone(), Value::Json(value.clone()));
+                        }
+                        None => debug!(
+                            "Event trigger {}: nothing at '{}' for input '{}'",
+                            trigger.id, pointer, input
+                        ),
+                    }
+                }
+
+                let mut depths = self.depths.lock().await;
+                match self.runtime.start_workflow(workflow.id, mapped).await {
+                    Ok(execution_id) => {
+                        depths.insert(execution_id, event.depth + 1);
+                        info!(
+                            "{} event started execution {} of workflow {}",
+                            event.event_type, execution_id, workflow.id
+                        );
+                    }
+                    Err(e) => error!(
+                        "Event trigger {} failed to start workflow {}: {}",
+                        trigger.id, workflow.id, e
+                    ),
+                }
+            }
+        }
+    }
+}
+
+fn custom_event(
+    name: String,
+    workflow_id: Option<Uuid>,
+    execution_id: ExecutionId,
+    node_id: NodeId,
+    payload: &Value,
+    origin: &str,
+    depth: u32,
+) -> TriggerEvent {
+    TriggerEvent {
+        payload: json!({
+            "workflow_id": workflow_id,
+            "execution_id": execution_id,
+            "node_id": node_id,
+            "payload": payload.to_json(),
+            "origin": origin,
+        }),
+        event_type: name,
+        workflow_id,
+        depth,
+    }
+}
+
+/// Outputs keyed by node name, or by id where names are missing or shared
+fn outputs_by_name(workflow: &Workflow, state: &ExecutionState) -> serde_json::Value {
+    let mut names: HashMap<&str, usize> = HashMap::new();
+    for name in workflow.nodes.iter().filter_map(|n| n.name.as_deref()) {
+        *names.entry(name).or_default() += 1;
+    }
+
+    let mut outputs = serde_json::Map::new();
+    for (node_id, ports) in &state.outputs {
+        let key = workflow
+            .nodes
+            .iter()
+            .find(|n| n.id == *node_id)
+            .and_then(|n| n.name.as_deref())
+            .filter(|name| !name.is_empty() && names[name] == 1)
+            .map(str::to_string)
+            .unwrap_or_else(|| node_id.to_string());
+        let ports = ports
+            .iter()
+            .map(|(port, value)| (port.clone(), value.to_json()))
+            .collect::<serde_json::Map<_, _>>();
+        outputs.insert(key, ports.into());
+    }
+    outputs.into()
+}
+
+#[async_trait]
+impl ExecutionListener for EventTriggers {
+    async fn on_execution_finished(
+        &self,
+        workflow: &Workflow,
+        state: &ExecutionState,
+        _result: &Result<ExecutionResult, FlowError>,
+    ) {
+        let depth = self
+            .depths
+            .lock()
+            .await
+            .remove(&state.execution_id)
+            .unwrap_or(0);
+        let status = state.status.as_str();
+
+        self.dispatch(TriggerEvent {
+            event_type: format!("workflow.{}", status),
+            workflow_id: Some(workflow.id),
+            payload: json!({
+                "workflow_id": workflow.id,
+                "workflow_name": workflow.name,
+                "execution_id": state.execution_id,
+                "status": status,
+                "error": state.error,
+                "outputs": outputs_by_name(workflow, state),
+                "origin": "local",
+            }),
+            depth,
+        })
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use flowcore::TriggerSpec;
+
+    fn on_event(workflow: &mut Workflow, event_type: &str, source_workflow: Option<Uuid>) {
+        workflow.triggers.push(TriggerSpec {
+            id: Uuid::new_v4(),
+            trigger_type: TriggerType::Event {
+                event_type: event_type.to_string(),
+                source_workflow,
+                inputs: HashMap::new(),
+            },
+            enabled: true,
+        });
+    }
+
+    fn triggers() -> (FlowRuntime, Arc<EventTriggers>) {
+        let runtime = FlowRuntime::new();
+        let triggers = EventTriggers::new(runtime.clone());
+        runtime.add_listener(triggers.clone());
+        (runtime, triggers)
+    }
+
+    /// Executions of `workflow_id` once they stop changing
+    async fn settled_runs(runtime: &FlowRuntime, workflow_id: Uuid) -> usize {
+        let mut last = usize::MAX;
+        let mut stable = 0;
+        for _ in 0..500 {
+            let executions = runtime.list_executions().await;
+            let runs = executions.iter().filter(|e| e.workflow_id == workflow_id).count();
+            let busy = executions.iter().any(|e| !e.status.is_finished());
+            stable = if runs == last && !busy { stable + 1 } else { 0 };
+            if stable == 10 {
+                return runs;
+            }
+            last = runs;
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("executions of {} never settled", workflow_id);
+    }
+
+    fn remote(event: ExecutionEvent, chain_depth: u32) -> BusEvent {
+        BusEvent { event, chain_depth }
+    }
+
+    fn custom(execution_id: ExecutionId, name: &str) -> ExecutionEvent {
+        ExecutionEvent::NodeEvent {
+            execution_id,
+            node_id: Uuid::new_v4(),
+            event: NodeEvent::Custom {
+                name: name.to_string(),
+                payload: Value::Null,
+            },
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_self_triggering_workflow_stops_at_max_depth() {
+        let (runtime, _triggers) = triggers();
+        let mut workflow = Workflow::new("again");
+        let itself = Some(workflow.id);
+        on_event(&mut workflow, "workflow.completed", itself);
+        runtime.register_workflow(workflow.clone()).await;
+
+        runtime.start_workflow(workflow.id, HashMap::new()).await.unwrap();
+
+        // The one started by hand, then one per link of the chain
+        let runs = settled_runs(&runtime, workflow.id).await;
+        assert_eq!(runs, MAX_CHAIN_DEPTH as usize + 1);
+    }
+
+    #[tokio::test]
+    async fn test_chain_depth_carries_over_from_remote_events() {
+        let (runtime, triggers) = triggers();
+        let mut ordered = Workflow::new("on order");
+        on_event(&mut ordered, "order.created", None);
+        let mut followup = Workflow::new("after order");
+        on_event(&mut followup, "workflow.completed", Some(ordered.id));
+        runtime.register_workflow(ordered.clone()).await;
+        runtime.register_workflow(followup.clone()).await;
+
+        // Two links left: the remote event starts one, its completion the next
+        triggers
+            .remote_event(remote(custom(Uuid::new_v4(), "order.created"), MAX_CHAIN_DEPTH - 2))
+            .await;
+        assert_eq!(settled_runs(&runtime, ordered.id).await, 1);
+        assert_eq!(settled_runs(&runtime, followup.id).await, 1);
+
+        // One link left: only the first starts
+        triggers
+            .remote_event(remote(custom(Uuid::new_v4(), "order.created"), MAX_CHAIN_DEPTH - 1))
+            .await;
+        assert_eq!(settled_runs(&runtime, ordered.id).await, 2);
+        assert_eq!(settled_runs(&runtime, followup.id).await, 1);
+
+        // None left
+        triggers
+            .remote_event(remote(custom(Uuid::new_v4(), "order.created"), MAX_CHAIN_DEPTH))
+            .await;
+        assert_eq!(settled_runs(&runtime, ordered.id).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_remote_completion_keeps_the_depth_it_started_at() {
+        let (runtime, triggers) = triggers();
+        let source = Uuid::new_v4();
+        let mut followup = Workflow::new("after remote");
+        on_event(&mut followup, "workflow.completed", Some(source));
+        runtime.register_workflow(followup.clone()).await;
+
+        let completed = |execution_id| ExecutionEvent::WorkflowCompleted {
+            execution_id,
+            success: true,
+            duration_ms: 5,
+            timestamp: Utc::now(),
+        };
+        let started = |execution_id| ExecutionEvent::WorkflowStarted {
+            execution_id,
+            workflow_id: source,
+            timestamp: Utc::now(),
+        };
+
+        // Started at the limit; its completion is published without a depth
+        let deep = Uuid::new_v4();
+        triggers.remote_event(remote(started(deep), MAX_CHAIN_DEPTH)).await;
+        triggers.remote_event(remote(completed(deep), 0)).await;
+        assert_eq!(settled_runs(&runtime, followup.id).await, 0);
+
+        let shallow = Uuid::new_v4();
+        triggers.remote_event(remote(started(shallow), 0)).await;
+        triggers.remote_event(remote(completed(shallow), 0)).await;
+        assert_eq!(settled_runs(&runtime, followup.id).await, 1);
+    }
+}
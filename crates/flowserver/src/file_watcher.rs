@@ -0,0 +1,460 @@
+//! File-watch triggers: workflows started by changes under a directory
+//!
+//! Watches follow the registered workflows: they are added, replaced and
+//! dropped as file-watch triggers come, change and go. Changes are
+//! collected until the directory has been quiet for the trigger's debounce
+//! period, then the whole batch starts one run with the inputs `files`
+//! (path, relative path, event, size and modification time of each file),
+//! `count` and `directory`.
+
+use chrono::{DateTime, Utc};
+use flowcore::{FileEvent, TriggerType, Value};
+use flowruntime::FlowRuntime;
+use globset::{GlobBuilder, GlobMatcher};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// A batch starts its run at the latest this many debounce periods after
+/// its first change, even if changes keep coming
+const MAX_BATCH_PERIODS: u32 = 20;
+
+/// How often watches are brought in line with the registered workflows
+const RECONCILE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Start maintaining watches for file-watch triggers in the background
+pub fn spawn(runtime: Arc<FlowRuntime>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut watches: HashMap<Uuid, Watch> = HashMap::new();
+        // Failing watches already logged, by spec
+        let mut reported = HashSet::new();
+        let mut interval = tokio::time::interval(RECONCILE_INTERVAL);
+        loop {
+            interval.tick().await;
+            reconcile(&runtime, &mut watches, &mut reported).await;
+        }
+    })
+}
+
+/// An active watch; dropping it stops watching
+struct Watch {
+    /// Workflow and trigger definition it was started from
+    key: String,
+    _watcher: RecommendedWatcher,
+    batcher: JoinHandle<()>,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        self.batcher.abort();
+    }
+}
+
+async fn reconcile(
+    runtime: &Arc<FlowRuntime>,
+    watches: &mut HashMap<Uuid, Watch>,
+    reported: &mut HashSet<String>,
+) {
+    let mut wanted = HashMap::new();
+    for workflow in runtime.list_workflows().await {
+        for trigger in workflow.triggers.iter().filter(|t| t.enabled) {
+            if let TriggerType::FileWatch { .. } = &trigger.trigger_type {
+                let key = format!(
+                    "{} {}",
+                    workflow.id,
+                    serde_json::to_string(&trigger.trigger_type).unwrap_or_default()
+                );
+                wanted.insert(trigger.id, (key, workflow.id, trigger.trigger_type.clone()));
+            }
+        }
+    }
+
+    watches.retain(|trigger_id, watch| {
+        let keep = wanted.get(trigger_id).is_some_and(|(key, ..)| *key == watch.key);
+        if !keep {
+            info!("Stopped file watch for trigger {}", trigger_id);
+        }
+        keep
+    });
+
+    for (trigger_id, (key, workflow_id, trigger_type)) in wanted {
+        if watches.contains_key(&trigger_id) {
+            continue;
+        }
+        match WatchSpec::new(workflow_id, trigger_id, &trigger_type)
+            .and_then(|spec| spec.start(runtime, key.clone()))
+        {
+            Ok(watch) => {
+                info!("👀 Watching files for trigger {} of workflow {}", trigger_id, workflow_id);
+                reported.remove(&key);
+                watches.insert(trigger_id, watch);
+            }
+            Err(e) => {
+                // Retried every pass, e.g. until the directory exists
+                if reported.insert(key) {
+                    warn!("File watch for trigger {} of workflow {} failed: {}", trigger_id, workflow_id, e);
+                }
+            }
+        }
+    }
+}
+
+struct WatchSpec {
+    workflow_id: Uuid,
+    trigger_id: Uuid,
+    root: PathBuf,
+    glob: Option<GlobMatcher>,
+    events: Vec<FileEvent>,
+    debounce: Duration,
+}
+
+impl WatchSpec {
+    fn new(workflow_id: Uuid, trigger_id: Uuid, trigger: &TriggerType) -> Result<Self, String> {
+        let TriggerType::FileWatch {
+            path,
+            glob,
+            events,
+            debounce_ms,
+        } = trigger
+        else {
+            return Err("not a file-watch trigger".to_string());
+        };
+
+        let root = std::fs::canonicalize(path).map_err(|e| format!("{}: {}", path, e))?;
+        let glob = glob
+            .as_deref()
+            .map(|g| {
+                GlobBuilder::new(g)
+                    .literal_separator(true)
+                    .build()
+                    .map(|g| g.compile_matcher())
+                    .map_err(|e| format!("invalid glob '{}': {}", g, e))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            workflow_id,
+            trigger_id,
+            root,
+            glob,
+            events: events.clone(),
+            debounce: debounce_ms.map(Duration::from_millis).unwrap_or(DEFAULT_DEBOUNCE),
+        })
+    }
+
+    fn start(self, runtime: &Arc<FlowRuntime>, key: String) -> Result<Watch, String> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .map_err(|e| e.to_string())?;
+        watcher
+            .watch(&self.root, RecursiveMode::Recursive)
+            .map_err(|e| format!("{}: {}", self.root.display(), e))?;
+
+        let runtime = Arc::clone(runtime);
+        Ok(Watch {
+            key,
+            _watcher: watcher,
+            batcher: tokio::spawn(self.batch(runtime, rx)),
+        })
+    }
+
+    /// Collect changes into batches and start a run for each
+    async fn batch(
+        self,
+        runtime: Arc<FlowRuntime>,
+        mut rx: mpsc::UnboundedReceiver<notify::Result<notify::Event>>,
+    ) {
+        loop {
+            let mut batch = BTreeMap::new();
+            let Some(first) = rx.recv().await else {
+                return;
+            };
+            self.add(&mut batch, first);
+            if batch.is_empty() {
+                continue;
+            }
+
+            let deadline = Instant::now() + self.debounce * MAX_BATCH_PERIODS;
+            loop {
+                let quiet = Instant::now() + self.debounce;
+                match tokio::time::timeout_at(quiet.min(deadline), rx.recv()).await {
+                    Ok(Some(event)) => self.add(&mut batch, event),
+                    Ok(None) => return,
+                    Err(_) => break,
+                }
+            }
+            self.fire(&runtime, batch).await;
+        }
+    }
+
+    fn add(&self, batch: &mut BTreeMap<PathBuf, FileEvent>, event: notify::Result<notify::Event>) {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                warn!("File watch for trigger {}: {}", self.trigger_id, e);
+                return;
+            }
+        };
+
+        let kind = match event.kind {
+            EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                FileEvent::Created
+            }
+            EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                FileEvent::Removed
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                // Paths are [from, to]
+                let mut paths = event.paths.into_iter();
+                if let Some(from) = paths.next() {
+                    self.record(batch, from, FileEvent::Removed);
+                }
+                if let Some(to) = paths.next() {
+                    self.record(batch, to, FileEvent::Created);
+                }
+                return;
+            }
+            // Permission and timestamp changes don't change contents
+            EventKind::Modify(ModifyKind::Metadata(_)) => return,
+            EventKind::Modify(_) => FileEvent::Modified,
+            _ => return,
+        };
+        for path in event.paths {
+            self.record(batch, path, kind);
+        }
+    }
+
+    /// Merge a change into the batch, keeping one entry per file
+    fn record(&self, batch: &mut BTreeMap<PathBuf, FileEvent>, path: PathBuf, kind: FileEvent) {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return;
+        };
+        if self.glob.as_ref().is_some_and(|g| !g.is_match(relative)) {
+            return;
+        }
+
+        let merged = match (batch.get(&path), kind) {
+            (Some(FileEvent::Created), FileEvent::Modified) => FileEvent::Created,
+            // Came and went within the batch
+            (Some(FileEvent::Created), FileEvent::Removed) => {
+                batch.remove(&path);
+                return;
+            }
+            (Some(FileEvent::Removed), FileEvent::Created) => FileEvent::Modified,
+            (_, kind) => kind,
+        };
+        batch.insert(path, merged);
+    }
+
+    async fn fire(&self, runtime: &FlowRuntime, batch: BTreeMap<PathBuf, FileEvent>) {
+        let files: Vec<Value> = batch
+            .into_iter()
+            .filter(|(path, kind)| self.events.contains(kind) && !path.is_dir())
+            .map(|(path, kind)| self.file_info(&path, kind))
+            .collect();
+        if files.is_empty() {
+            return;
+        }
+
+        let count = files.len();
+        let inputs = HashMap::from([
+            ("files".to_string(), Value::Array(files)),
+            ("count".to_string(), Value::Number(count as f64)),
+            (
+                "directory".to_string(),
+                Value::String(self.root.display().to_string()),
+            ),
+        ]);
+        match runtime.start_workflow(self.workflow_id, inputs).await {
+            Ok(execution_id) => info!(
+                "File watch trigger {} started execution {} of workflow {} for {} file(s)",
+                self.trigger_id, execution_id, self.workflow_id, count
+            ),
+            Err(e) => error!(
+                "File watch trigger {} failed to start workflow {}: {}",
+                self.trigger_id, self.workflow_id, e
+            ),
+        }
+    }
+
+    fn file_info(&self, path: &Path, kind: FileEvent) -> Value {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let mut info = HashMap::from([
+            ("path".to_string(), Value::String(path.display().to_string())),
+            (
+                "relative_path".to_string(),
+                Value::String(relative.display().to_string()),
+            ),
+            ("event".to_string(), Value::String(format!("{:?}", kind))),
+        ]);
+        if let Ok(metadata) = std::fs::metadata(path) {
+            info.insert("size".to_string(), Value::Number(metadata.len() as f64));
+            if let Ok(modified) = metadata.modified() {
+                let modified: DateTime<Utc> = modified.into();
+                info.insert("modified".to_string(), Value::String(modified.to_rfc3339()));
+            }
+        }
+        Value::Object(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flowcore::{NodeSpec, TriggerSpec, Workflow};
+    use flowruntime::{ExecutionStatus, NodeRegistry, RuntimeConfig};
+
+    fn file_watch(path: &Path) -> TriggerType {
+        TriggerType::FileWatch {
+            path: path.display().to_string(),
+            glob: Some("*.csv".to_string()),
+            events: vec![FileEvent::Created, FileEvent::Modified],
+            debounce_ms: Some(200),
+        }
+    }
+
+    /// Relative path and event of each file a run was started for
+    fn changed_files(inputs: &HashMap<String, Value>) -> Vec<(String, String)> {
+        let Some(Value::Array(files)) = inputs.get("files") else {
+            panic!("no files in {:?}", inputs);
+        };
+        files
+            .iter()
+            .map(|file| {
+                let Value::Object(info) = file else {
+                    panic!("not a file: {:?}", file);
+                };
+                let text = |key: &str| info[key].as_str().unwrap().to_string();
+                (text("relative_path"), text("event"))
+            })
+            .collect()
+    }
+
+    /// Inputs of the `nth` run of `workflow_id`, passed through by its
+    /// only node, once it has completed
+    async fn run_inputs(runtime: &FlowRuntime, workflow_id: Uuid, nth: usize) -> HashMap<String, Value> {
+        for _ in 0..500 {
+            let mut runs: Vec<_> = runtime
+                .list_executions()
+                .await
+                .into_iter()
+                .filter(|e| e.workflow_id == workflow_id)
+                .collect();
+            runs.sort_by_key(|e| e.started_at);
+            if let Some(run) = runs.get(nth).filter(|r| r.status == ExecutionStatus::Completed) {
+                return run.outputs.values().next().cloned().unwrap_or_default();
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("run {} of workflow {} never completed", nth, workflow_id);
+    }
+
+    async fn runs(runtime: &FlowRuntime, workflow_id: Uuid) -> usize {
+        let executions = runtime.list_executions().await;
+        executions.iter().filter(|e| e.workflow_id == workflow_id).count()
+    }
+
+    #[test]
+    fn test_changes_merge_per_file() {
+        let root = std::env::temp_dir();
+        let spec = WatchSpec::new(Uuid::new_v4(), Uuid::new_v4(), &file_watch(&root)).unwrap();
+        let mut batch = BTreeMap::new();
+        let path = |name: &str| spec.root.join(name);
+
+        spec.record(&mut batch, path("new.csv"), FileEvent::Created);
+        spec.record(&mut batch, path("new.csv"), FileEvent::Modified);
+        spec.record(&mut batch, path("brief.csv"), FileEvent::Created);
+        spec.record(&mut batch, path("brief.csv"), FileEvent::Removed);
+        spec.record(&mut batch, path("replaced.csv"), FileEvent::Removed);
+        spec.record(&mut batch, path("replaced.csv"), FileEvent::Created);
+        spec.record(&mut batch, path("gone.csv"), FileEvent::Removed);
+        // Outside the glob or the watched directory
+        spec.record(&mut batch, path("notes.txt"), FileEvent::Created);
+        spec.record(&mut batch, path("nested/deep.csv"), FileEvent::Created);
+        spec.record(&mut batch, PathBuf::from("/elsewhere/other.csv"), FileEvent::Created);
+
+        assert_eq!(
+            batch.into_iter().collect::<Vec<_>>(),
+            vec![
+                (path("gone.csv"), FileEvent::Removed),
+                (path("new.csv"), FileEvent::Created),
+                (path("replaced.csv"), FileEvent::Modified),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quick_changes_start_one_run_with_the_batch() {
+        let dir = std::env::temp_dir().join(format!("flowserver-watch-{}", Uuid::new_v4()));
+        std::fs::create_dir(&dir).unwrap();
+
+        let mut registry = NodeRegistry::new();
+        flownodes::register_all(&mut registry);
+        let runtime = Arc::new(FlowRuntime::with_registry(Arc::new(registry), RuntimeConfig::default()));
+        let mut workflow = Workflow::new("watched");
+        workflow.add_node(NodeSpec::new("time.delay").with_config("delay_ms", Value::Number(0.0)));
+        let trigger = TriggerSpec {
+            id: Uuid::new_v4(),
+            trigger_type: file_watch(&dir),
+            enabled: true,
+        };
+        let spec = WatchSpec::new(workflow.id, trigger.id, &trigger.trigger_type).unwrap();
+        let root = spec.root.clone();
+        workflow.triggers.push(trigger);
+        let workflow_id = workflow.id;
+        runtime.register_workflow(workflow).await;
+        let _watch = spec.start(&runtime, String::new()).unwrap();
+
+        // Well within one debounce period of each other
+        std::fs::write(dir.join("a.csv"), "1,2").unwrap();
+        std::fs::write(dir.join("b.csv"), "x").unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+        std::fs::write(dir.join("brief.csv"), "gone again").unwrap();
+        std::fs::remove_file(dir.join("brief.csv")).unwrap();
+        std::fs::write(dir.join("a.csv"), "1,2,3").unwrap();
+
+        let inputs = run_inputs(&runtime, workflow_id, 0).await;
+        assert_eq!(
+            changed_files(&inputs),
+            vec![
+                ("a.csv".to_string(), "Created".to_string()),
+                ("b.csv".to_string(), "Created".to_string()),
+            ]
+        );
+        assert_eq!(inputs["count"].as_f64(), Some(2.0));
+        assert_eq!(inputs["directory"].as_str(), Some(root.display().to_string().as_str()));
+        let Value::Array(files) = &inputs["files"] else { unreachable!() };
+        let Value::Object(a) = &files[0] else { unreachable!() };
+        assert_eq!(a["path"].as_str(), Some(root.join("a.csv").display().to_string().as_str()));
+        assert_eq!(a["size"].as_f64(), Some(5.0));
+        assert!(a["modified"].as_str().is_some());
+
+        // Quiet from here on: no second run for the same changes
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert_eq!(runs(&runtime, workflow_id).await, 1);
+
+        // Removals aren't among the trigger's events
+        std::fs::write(dir.join("b.csv"), "y").unwrap();
+        std::fs::remove_file(dir.join("a.csv")).unwrap();
+        let inputs = run_inputs(&runtime, workflow_id, 1).await;
+        assert_eq!(
+            changed_files(&inputs),
+            vec![("b.csv".to_string(), "Modified".to_string())]
+        );
+        assert_eq!(inputs["count"].as_f64(), Some(1.0));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
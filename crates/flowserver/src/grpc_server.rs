@@ -5,18 +5,24 @@
 //!
 //! RPCs:
 //!   - CreateWorkflow — registers a new workflow
+//!   - UpdateWorkflow / PatchWorkflow — replace or partially edit a workflow
 //!   - ExecuteWorkflow — runs a workflow, streams events back
 //!   - GetWorkflowStatus — query execution status
 //!   - CancelWorkflow — stop a running execution
+//!   - PauseWorkflow / ResumeWorkflow — hold back and release further nodes
+//!   - SendSignal — continue nodes waiting for a signal
 //!   - Health — service health check
 
+// tonic::Status is large, but it is what every handler and conversion returns
+#![allow(clippy::result_large_err)]
+
 use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 use tracing::{info, error};
 
-use flowcore::{self, Workflow, Value};
+use flowcore::{self, BlobRef, Secret, Workflow, WorkflowEdit, Value, REDACTED};
 use flowruntime;
 
 use flowproto::flowengine::v1::{
@@ -25,6 +31,7 @@ use flowproto::flowengine::v1::{
 };
 
 use super::AppState;
+use crate::workflow_service::SaveError;
 
 /// Wraps the app state for the gRPC service
 pub struct FlowEngineGrpcServer {
@@ -40,60 +47,38 @@ impl FlowEngineGrpcServer {
     pub fn into_service(self) -> FlowEngineServiceServer<Self> {
         FlowEngineServiceServer::new(self)
     }
+
+    /// Status of an execution held in memory, or "not_found"
+    async fn current_status(&self, execution_id: uuid::Uuid) -> &'static str {
+        match self.state.runtime.get_execution_status(execution_id).await {
+            Some(state) => state.status.as_str(),
+            None => "not_found",
+        }
+    }
 }
 
 // ── Type Conversions ───────────────────────────────────────────────────────
 
 /// Convert proto Workflow → flowcore Workflow
 fn proto_to_workflow(pb: pb::Workflow) -> Result<Workflow, Status> {
-    let id = pb
-        .id
-        .parse::<uuid::Uuid>()
-        .map_err(|e| Status::invalid_argument(format!("invalid workflow id: {}", e)))?;
+    let id = parse_uuid(&pb.id, "workflow id")?;
 
     let nodes = pb
         .nodes
         .into_iter()
-        .map(|n| {
-            let node_id = n.id.parse::<uuid::Uuid>().map_err(|e| {
-                Status::invalid_argument(format!("invalid node id: {}", e))
-            })?;
-            Ok(flowcore::NodeSpec {
-                id: node_id,
-                node_type: n.node_type,
-                name: n.name,
-                config: n
-                    .config
-                    .into_iter()
-                    .map(|(k, v)| proto_to_value(v).map(|val| (k, val)))
-                    .collect::<Result<HashMap<_, _>, Status>>()?,
-                position: n.position.map(|p| flowcore::Position { x: p.x, y: p.y }),
-                retry_policy: n.retry_policy.map(|rp| flowcore::RetryPolicy {
-                    max_attempts: rp.max_attempts,
-                    delay_ms: rp.delay_ms,
-                    backoff_multiplier: rp.backoff_multiplier,
-                    max_delay_ms: rp.max_delay_ms,
-                    retry_on_timeout: rp.retry_on_timeout,
-                }),
-            })
-        })
+        .map(proto_to_node)
         .collect::<Result<Vec<_>, Status>>()?;
 
     let connections = pb
         .connections
         .into_iter()
-        .map(|c| {
-            Ok(flowcore::Connection {
-                from_node: c.from_node.parse().map_err(|e| {
-                    Status::invalid_argument(format!("invalid from_node: {}", e))
-                })?,
-                from_port: c.from_port,
-                to_node: c.to_node.parse().map_err(|e| {
-                    Status::invalid_argument(format!("invalid to_node: {}", e))
-                })?,
-                to_port: c.to_port,
-            })
-        })
+        .map(proto_to_connection)
+        .collect::<Result<Vec<_>, Status>>()?;
+
+    let triggers = pb
+        .triggers
+        .into_iter()
+        .map(proto_to_trigger)
         .collect::<Result<Vec<_>, Status>>()?;
 
     let settings = pb.settings.unwrap_or_default();
@@ -106,26 +91,217 @@ fn proto_to_workflow(pb: pb::Workflow) -> Result<Workflow, Status> {
 
     Ok(Workflow {
         id,
+        revision: pb.revision,
         name: pb.name,
         description: pb.description,
         nodes,
         connections,
-        triggers: vec![],
+        triggers,
+        variables: proto_to_config(pb.variables)?,
         settings: flowcore::WorkflowSettings {
             max_execution_time_ms: settings.max_execution_time_ms,
             max_parallel_nodes: settings.max_parallel_nodes as usize,
             on_error,
+            on_failure: settings
+                .on_failure
+                .map(|id| parse_uuid(&id, "on_failure workflow id"))
+                .transpose()?,
+        },
+    })
+}
+
+fn parse_uuid(s: &str, what: &str) -> Result<uuid::Uuid, Status> {
+    s.parse::<uuid::Uuid>()
+        .map_err(|e| Status::invalid_argument(format!("invalid {}: {}", what, e)))
+}
+
+/// Convert proto WorkflowNode → flowcore NodeSpec
+fn proto_to_node(n: pb::WorkflowNode) -> Result<flowcore::NodeSpec, Status> {
+    Ok(flowcore::NodeSpec {
+        id: parse_uuid(&n.id, "node id")?,
+        node_type: n.node_type,
+        name: n.name,
+        config: proto_to_config(n.config)?,
+        position: n.position.map(|p| flowcore::Position { x: p.x, y: p.y }),
+        retry_policy: n.retry_policy.map(|rp| flowcore::RetryPolicy {
+            max_attempts: rp.max_attempts,
+            delay_ms: rp.delay_ms,
+            backoff_multiplier: rp.backoff_multiplier,
+            max_delay_ms: rp.max_delay_ms,
+            retry_on_timeout: rp.retry_on_timeout,
+        }),
+    })
+}
+
+fn proto_to_config(config: HashMap<String, pb::Value>) -> Result<HashMap<String, Value>, Status> {
+    config
+        .into_iter()
+        .map(|(k, v)| proto_to_value(v).map(|val| (k, val)))
+        .collect()
+}
+
+/// Convert proto WorkflowConnection → flowcore Connection
+fn proto_to_connection(c: pb::WorkflowConnection) -> Result<flowcore::Connection, Status> {
+    Ok(flowcore::Connection {
+        from_node: parse_uuid(&c.from_node, "from_node")?,
+        from_port: c.from_port,
+        to_node: parse_uuid(&c.to_node, "to_node")?,
+        to_port: c.to_port,
+    })
+}
+
+/// Convert proto WorkflowTrigger → flowcore TriggerSpec
+fn proto_to_trigger(t: pb::WorkflowTrigger) -> Result<flowcore::TriggerSpec, Status> {
+    use pb::workflow_trigger::TriggerType as Pb;
+    let trigger_type = match t.trigger_type {
+        Some(Pb::Manual(_)) | None => flowcore::TriggerType::Manual,
+        Some(Pb::Cron(c)) => flowcore::TriggerType::Cron {
+            expression: c.expression,
+            timezone: Some(c.timezone).filter(|tz| !tz.is_empty()),
+            catch_up: match c.catch_up.as_str() {
+                "" | "Skip" => flowcore::CatchUpPolicy::Skip,
+                "Latest" => flowcore::CatchUpPolicy::Latest,
+                "All" => flowcore::CatchUpPolicy::All,
+                other => {
+                    return Err(Status::invalid_argument(format!(
+                        "unknown catch_up policy: {}",
+                        other
+                    )))
+                }
+            },
+        },
+        Some(Pb::Webhook(w)) => flowcore::TriggerType::Webhook {
+            path: w.path,
+            secret: Some(w.secret).filter(|s| !s.is_empty()),
+            signature_header: Some(w.signature_header).filter(|h| !h.is_empty()),
+            response: match w.response.as_str() {
+                "" | "Immediate" => flowcore::WebhookResponse::Immediate,
+                "ResponseNode" => flowcore::WebhookResponse::ResponseNode,
+                other => {
+                    return Err(Status::invalid_argument(format!(
+                        "unknown webhook response mode: {}",
+                        other
+                    )))
+                }
+            },
+        },
+        Some(Pb::Event(e)) => flowcore::TriggerType::Event {
+            event_type: e.event_type,
+            source_workflow: if e.source_workflow_id.is_empty() {
+                None
+            } else {
+                Some(parse_uuid(&e.source_workflow_id, "source workflow id")?)
+            },
+            inputs: e.inputs,
+        },
+        Some(Pb::FileWatch(f)) => flowcore::TriggerType::FileWatch {
+            path: f.path,
+            glob: Some(f.glob).filter(|g| !g.is_empty()),
+            events: if f.events.is_empty() {
+                vec![flowcore::FileEvent::Created, flowcore::FileEvent::Modified]
+            } else {
+                f.events
+                    .iter()
+                    .map(|e| match e.as_str() {
+                        "Created" => Ok(flowcore::FileEvent::Created),
+                        "Modified" => Ok(flowcore::FileEvent::Modified),
+                        "Removed" => Ok(flowcore::FileEvent::Removed),
+                        other => Err(Status::invalid_argument(format!(
+                            "unknown file event: {}",
+                            other
+                        ))),
+                    })
+                    .collect::<Result<_, _>>()?
+            },
+            debounce_ms: Some(f.debounce_ms).filter(|ms| *ms > 0),
         },
+    };
+    Ok(flowcore::TriggerSpec {
+        id: parse_uuid(&t.id, "trigger id")?,
+        trigger_type,
+        enabled: t.enabled,
     })
 }
 
+/// Convert proto WorkflowEdit → flowcore WorkflowEdit
+fn proto_to_edit(e: pb::WorkflowEdit) -> Result<WorkflowEdit, Status> {
+    use pb::workflow_edit::Edit;
+    let connection = |c: Option<pb::WorkflowConnection>| {
+        c.ok_or_else(|| Status::invalid_argument("connection is required"))
+            .and_then(proto_to_connection)
+    };
+
+    match e.edit {
+        Some(Edit::AddNode(add)) => Ok(WorkflowEdit::AddNode {
+            node: proto_to_node(
+                add.node
+                    .ok_or_else(|| Status::invalid_argument("add_node.node is required"))?,
+            )?,
+        }),
+        Some(Edit::RemoveNode(remove)) => Ok(WorkflowEdit::RemoveNode {
+            node_id: parse_uuid(&remove.node_id, "node id")?,
+        }),
+        Some(Edit::MoveNode(mv)) => {
+            let position = mv
+                .position
+                .ok_or_else(|| Status::invalid_argument("move_node.position is required"))?;
+            Ok(WorkflowEdit::MoveNode {
+                node_id: parse_uuid(&mv.node_id, "node id")?,
+                position: flowcore::Position {
+                    x: position.x,
+                    y: position.y,
+                },
+            })
+        }
+        Some(Edit::UpdateNode(update)) => Ok(WorkflowEdit::UpdateNode {
+            node_id: parse_uuid(&update.node_id, "node id")?,
+            name: update.name,
+            config: proto_to_config(update.config)?,
+        }),
+        Some(Edit::Connect(c)) => Ok(WorkflowEdit::Connect {
+            connection: connection(c.connection)?,
+        }),
+        Some(Edit::Disconnect(c)) => Ok(WorkflowEdit::Disconnect {
+            connection: connection(c.connection)?,
+        }),
+        Some(Edit::Rewire(r)) => Ok(WorkflowEdit::Rewire {
+            from: connection(r.from)?,
+            to: connection(r.to)?,
+        }),
+        None => Err(Status::invalid_argument("edit is required")),
+    }
+}
+
+impl From<SaveError> for Status {
+    fn from(e: SaveError) -> Self {
+        match &e {
+            SaveError::NotFound(_) => Status::not_found(e.to_string()),
+            SaveError::Exists(_) => Status::already_exists(e.to_string()),
+            SaveError::Invalid(_) => Status::invalid_argument(e.to_string()),
+            SaveError::Store(flowpersist::StoreError::Conflict { .. }) => {
+                Status::failed_precondition(e.to_string())
+            }
+            SaveError::Store(_) => Status::internal(e.to_string()),
+        }
+    }
+}
+
 /// Convert proto Value → flowcore Value
 fn proto_to_value(pb: pb::Value) -> Result<Value, Status> {
     match pb.kind {
         Some(pb::value::Kind::NullValue(_)) => Ok(Value::Null),
         Some(pb::value::Kind::BoolValue(b)) => Ok(Value::Bool(b)),
         Some(pb::value::Kind::NumberValue(n)) => Ok(Value::Number(n)),
+        Some(pb::value::Kind::IntegerValue(i)) => Ok(Value::Integer(i)),
+        Some(pb::value::Kind::TimestampValue(t)) => chrono::DateTime::parse_from_rfc3339(&t)
+            .map(|t| Value::Timestamp(t.with_timezone(&chrono::Utc)))
+            .map_err(|e| Status::invalid_argument(format!("invalid timestamp value: {}", e))),
+        Some(pb::value::Kind::DecimalValue(d)) => d
+            .parse()
+            .map(Value::Decimal)
+            .map_err(|e| Status::invalid_argument(format!("invalid decimal value: {}", e))),
         Some(pb::value::Kind::StringValue(s)) => Ok(Value::String(s)),
+        Some(pb::value::Kind::SecretValue(s)) => Ok(Value::Secret(Secret::new(s))),
         Some(pb::value::Kind::BytesValue(b)) => Ok(Value::Bytes(b)),
         Some(pb::value::Kind::JsonValue(j)) => {
             let json: serde_json::Value =
@@ -150,6 +326,11 @@ fn proto_to_value(pb: pb::Value) -> Result<Value, Status> {
                 .collect::<Result<_, _>>()?;
             Ok(Value::Object(map))
         }
+        Some(pb::value::Kind::BlobValue(blob)) => Ok(Value::Blob(BlobRef {
+            hash: blob.hash,
+            size: blob.size,
+            media_type: blob.media_type,
+        })),
         None => Ok(Value::Null),
     }
 }
@@ -160,7 +341,11 @@ fn value_to_proto(v: &Value) -> pb::Value {
         Value::Null => pb::value::Kind::NullValue(pb::NullValue {}),
         Value::Bool(b) => pb::value::Kind::BoolValue(*b),
         Value::Number(n) => pb::value::Kind::NumberValue(*n),
+        Value::Integer(i) => pb::value::Kind::IntegerValue(*i),
+        Value::Timestamp(t) => pb::value::Kind::TimestampValue(t.to_rfc3339()),
+        Value::Decimal(d) => pb::value::Kind::DecimalValue(d.to_string()),
         Value::String(s) => pb::value::Kind::StringValue(s.clone()),
+        Value::Secret(_) => pb::value::Kind::SecretValue(REDACTED.to_string()),
         Value::Bytes(b) => pb::value::Kind::BytesValue(b.clone()),
         Value::Json(j) => pb::value::Kind::JsonValue(j.to_string()),
         Value::Array(arr) => {
@@ -174,6 +359,11 @@ fn value_to_proto(v: &Value) -> pb::Value {
                 .collect();
             pb::value::Kind::ObjectValue(pb::ValueMap { entries })
         }
+        Value::Blob(blob) => pb::value::Kind::BlobValue(pb::BlobRef {
+            hash: blob.hash.clone(),
+            size: blob.size,
+            media_type: blob.media_type.clone(),
+        }),
     };
     pb::Value { kind: Some(kind) }
 }
@@ -212,17 +402,64 @@ impl FlowEngineService for FlowEngineGrpcServer {
 
         info!("[gRPC] Creating workflow: {} ({})", workflow.name, workflow_id);
 
-        self.state
-            .workflows
-            .write()
-            .await
-            .insert(workflow.id, workflow.clone());
-
-        self.state.runtime.register_workflow(workflow).await;
+        let workflow = self.state.create_workflow(workflow).await?;
 
         Ok(Response::new(pb::CreateWorkflowResponse {
             workflow_id,
             message: "Workflow created successfully".to_string(),
+            revision: workflow.revision,
+        }))
+    }
+
+    /// Replace a workflow definition
+    async fn update_workflow(
+        &self,
+        request: Request<pb::UpdateWorkflowRequest>,
+    ) -> Result<Response<pb::UpdateWorkflowResponse>, Status> {
+        let req = request.into_inner();
+        let pb_workflow = req
+            .workflow
+            .ok_or_else(|| Status::invalid_argument("workflow is required"))?;
+
+        let workflow = proto_to_workflow(pb_workflow)?;
+        info!("[gRPC] Updating workflow: {} ({})", workflow.name, workflow.id);
+
+        let workflow = self
+            .state
+            .update_workflow(workflow, req.expected_revision)
+            .await?;
+
+        Ok(Response::new(pb::UpdateWorkflowResponse {
+            workflow_id: workflow.id.to_string(),
+            revision: workflow.revision,
+            message: "Workflow updated successfully".to_string(),
+        }))
+    }
+
+    /// Apply partial edits to a workflow
+    async fn patch_workflow(
+        &self,
+        request: Request<pb::PatchWorkflowRequest>,
+    ) -> Result<Response<pb::UpdateWorkflowResponse>, Status> {
+        let req = request.into_inner();
+        let workflow_id = parse_uuid(&req.workflow_id, "workflow id")?;
+        let edits = req
+            .edits
+            .into_iter()
+            .map(proto_to_edit)
+            .collect::<Result<Vec<_>, Status>>()?;
+
+        info!("[gRPC] Applying {} edits to workflow {}", edits.len(), workflow_id);
+
+        let workflow = self
+            .state
+            .patch_workflow(workflow_id, &edits, req.expected_revision)
+            .await?;
+
+        Ok(Response::new(pb::UpdateWorkflowResponse {
+            workflow_id: workflow.id.to_string(),
+            revision: workflow.revision,
+            message: "Workflow updated successfully".to_string(),
         }))
     }
 
@@ -251,7 +488,9 @@ impl FlowEngineService for FlowEngineGrpcServer {
 
         // Execute the workflow
         let runtime = self.state.runtime.clone();
-        let exec_result = runtime.execute_workflow(workflow_id, inputs).await;
+        let exec_result = runtime
+            .execute_workflow_in(workflow_id, inputs, req.env.as_deref())
+            .await;
 
         // Stream events
         let (tx, rx) = tokio::sync::mpsc::channel(128);
@@ -308,27 +547,62 @@ impl FlowEngineService for FlowEngineGrpcServer {
         
         match self.state.runtime.get_execution_status(exec_id).await {
             Some(state) => {
-                let status_str = match state.status {
-                    flowruntime::ExecutionStatus::Running => "running",
-                    flowruntime::ExecutionStatus::Completed => "completed",
-                    flowruntime::ExecutionStatus::Failed => "failed",
-                    flowruntime::ExecutionStatus::Cancelled => "cancelled",
-                };
+                let completed_nodes = state
+                    .node_progress
+                    .values()
+                    .filter(|n| n.status == flowruntime::NodeStatus::Succeeded)
+                    .count();
+                let nodes = state
+                    .node_progress
+                    .iter()
+                    .map(|(node_id, n)| pb::NodeExecutionStatus {
+                        node_id: node_id.to_string(),
+                        status: n.status.as_str().to_string(),
+                        attempts: n.attempts,
+                        started_at: n.started_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                        completed_at: n.completed_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                        duration_ms: n.duration_ms.unwrap_or_default(),
+                        error: n.error.clone().unwrap_or_default(),
+                    })
+                    .collect();
+                let duration_ms = state
+                    .completed_at
+                    .map(|end| (end - state.started_at).num_milliseconds().max(0) as u64)
+                    .unwrap_or_default();
                 Ok(Response::new(pb::WorkflowStatusResponse {
                     execution_id: state.execution_id.to_string(),
                     workflow_id: state.workflow_id.to_string(),
-                    status: status_str.to_string(),
-                    completed_nodes: 0,   // TODO: track node count in state
-                    total_nodes: 0,
-                    duration_ms: 0,        // TODO: track duration in state
+                    status: state.status.as_str().to_string(),
+                    completed_nodes: completed_nodes as u32,
+                    total_nodes: state.node_progress.len() as u32,
+                    duration_ms,
                     started_at: state.started_at.to_rfc3339(),
                     completed_at: state.completed_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                    nodes,
                 }))
             }
-            None => Err(Status::not_found(format!(
-                "Execution {} not found",
-                execution_id
-            ))),
+            // Not tracked in memory (e.g. after a restart) — fall back to history
+            None => match self.state.store.get_execution(execution_id).await {
+                Ok(Some(record)) => Ok(Response::new(pb::WorkflowStatusResponse {
+                    execution_id: record.id.to_string(),
+                    workflow_id: record.workflow_id.to_string(),
+                    status: record.status.as_str().to_string(),
+                    completed_nodes: record.completed_nodes as u32,
+                    total_nodes: record.total_nodes as u32,
+                    duration_ms: record.duration_ms,
+                    started_at: record.started_at.to_rfc3339(),
+                    completed_at: record
+                        .completed_at
+                        .map(|t| t.to_rfc3339())
+                        .unwrap_or_default(),
+                    nodes: Vec::new(),
+                })),
+                Ok(None) => Err(Status::not_found(format!(
+                    "Execution {} not found",
+                    execution_id
+                ))),
+                Err(e) => Err(Status::internal(e.to_string())),
+            },
         }
     }
 
@@ -344,15 +618,84 @@ impl FlowEngineService for FlowEngineGrpcServer {
             .map_err(|e| Status::invalid_argument(format!("invalid execution_id: {}", e)))?;
 
         let exec_id = flowcore::ExecutionId::from(execution_id);
-        let cancelled = self.state.runtime.cancel_execution(exec_id).await;
+        let status = if self.state.runtime.cancel_execution(exec_id).await {
+            "cancelled"
+        } else {
+            // Already finished executions report their final status
+            match self.state.runtime.get_execution_status(exec_id).await {
+                Some(state) => state.status.as_str(),
+                None => "not_found",
+            }
+        };
 
         Ok(Response::new(pb::CancelWorkflowResponse {
             execution_id: execution_id.to_string(),
-            status: if cancelled {
-                "cancelled".to_string()
-            } else {
-                "not_found".to_string()
-            },
+            status: status.to_string(),
+        }))
+    }
+
+    /// Pause a running execution
+    async fn pause_workflow(
+        &self,
+        request: Request<pb::PauseWorkflowRequest>,
+    ) -> Result<Response<pb::PauseWorkflowResponse>, Status> {
+        let execution_id = parse_uuid(&request.into_inner().execution_id, "execution_id")?;
+        if self.state.runtime.pause_execution(execution_id).await {
+            info!("gRPC PauseWorkflow: {}", execution_id);
+        }
+        Ok(Response::new(pb::PauseWorkflowResponse {
+            execution_id: execution_id.to_string(),
+            status: self.current_status(execution_id).await.to_string(),
+        }))
+    }
+
+    /// Resume a paused execution
+    async fn resume_workflow(
+        &self,
+        request: Request<pb::ResumeWorkflowRequest>,
+    ) -> Result<Response<pb::ResumeWorkflowResponse>, Status> {
+        let execution_id = parse_uuid(&request.into_inner().execution_id, "execution_id")?;
+        if self.state.runtime.resume_execution(execution_id).await {
+            info!("gRPC ResumeWorkflow: {}", execution_id);
+        }
+        Ok(Response::new(pb::ResumeWorkflowResponse {
+            execution_id: execution_id.to_string(),
+            status: self.current_status(execution_id).await.to_string(),
+        }))
+    }
+
+    /// Send a signal to a waiting (or soon waiting) execution
+    async fn send_signal(
+        &self,
+        request: Request<pb::SendSignalRequest>,
+    ) -> Result<Response<pb::SendSignalResponse>, Status> {
+        let req = request.into_inner();
+        let execution_id = req
+            .execution_id
+            .parse::<uuid::Uuid>()
+            .map_err(|e| Status::invalid_argument(format!("invalid execution_id: {}", e)))?;
+        if req.name.is_empty() {
+            return Err(Status::invalid_argument("signal name is required"));
+        }
+        let payload = req.payload.map(proto_to_value).transpose()?.unwrap_or(Value::Null);
+
+        let delivered = self
+            .state
+            .runtime
+            .send_signal(execution_id, &req.name, payload)
+            .await;
+        if delivered {
+            info!("gRPC SendSignal: '{}' to execution {}", req.name, execution_id);
+        }
+        let status = match self.state.runtime.get_execution_status(execution_id).await {
+            Some(state) => state.status.as_str(),
+            None => "not_found",
+        };
+
+        Ok(Response::new(pb::SendSignalResponse {
+            execution_id: execution_id.to_string(),
+            status: status.to_string(),
+            delivered,
         }))
     }
 }
@@ -389,6 +732,28 @@ fn convert_event(event: &flowcore::events::ExecutionEvent) -> pb::WorkflowEvent
                 },
             )),
         },
+        ExecutionEvent::WorkflowPaused {
+            execution_id,
+            timestamp,
+        } => pb::WorkflowEvent {
+            event: Some(pb::workflow_event::Event::WorkflowPaused(
+                pb::WorkflowPausedEvent {
+                    execution_id: execution_id.to_string(),
+                    timestamp: timestamp.to_rfc3339(),
+                },
+            )),
+        },
+        ExecutionEvent::WorkflowResumed {
+            execution_id,
+            timestamp,
+        } => pb::WorkflowEvent {
+            event: Some(pb::workflow_event::Event::WorkflowResumed(
+                pb::WorkflowResumedEvent {
+                    execution_id: execution_id.to_string(),
+                    timestamp: timestamp.to_rfc3339(),
+                },
+            )),
+        },
         ExecutionEvent::NodeStarted {
             execution_id,
             node_id,
@@ -477,12 +842,40 @@ fn convert_event(event: &flowcore::events::ExecutionEvent) -> pb::WorkflowEvent
                         message: message.clone().unwrap_or_default(),
                     })
                 }
+                NodeEvent::Retrying { attempt, max_attempts, delay_ms, error } => {
+                    // Map Retrying to Info for gRPC (proto has no Retrying variant)
+                    pb::node_event_message::Detail::Info(pb::NodeInfo {
+                        message: format!(
+                            "↻ retrying (attempt {}/{}) in {}ms: {}",
+                            attempt, max_attempts, delay_ms, error
+                        ),
+                    })
+                }
                 NodeEvent::Data { port, value } => {
                     // Map Data to Info with serialized value
                     pb::node_event_message::Detail::Info(pb::NodeInfo {
                         message: format!("Data[{}]: {:?}", port, value),
                     })
                 }
+                NodeEvent::Custom { name, payload } => {
+                    // Map Custom to Info (proto has no Custom variant)
+                    pb::node_event_message::Detail::Info(pb::NodeInfo {
+                        message: format!("Event[{}]: {}", name, payload.to_string()),
+                    })
+                }
+                NodeEvent::WaitingForSignal { name, timeout_ms } => {
+                    pb::node_event_message::Detail::Info(pb::NodeInfo {
+                        message: match timeout_ms {
+                            Some(ms) => format!("⏸ waiting for signal '{}' (timeout {}ms)", name, ms),
+                            None => format!("⏸ waiting for signal '{}'", name),
+                        },
+                    })
+                }
+                NodeEvent::SignalReceived { name } => {
+                    pb::node_event_message::Detail::Info(pb::NodeInfo {
+                        message: format!("▶ received signal '{}'", name),
+                    })
+                }
             };
             pb::WorkflowEvent {
                 event: Some(pb::workflow_event::Event::NodeEvent(
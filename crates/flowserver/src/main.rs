@@ -1,25 +1,148 @@
 use actix_cors::Cors;
 use actix_web::{
-    delete, get, post, web, App, HttpResponse, HttpServer, Responder, Result as ActixResult,
+    delete, get, http::header, patch, post, put, web, App, HttpRequest, HttpResponse, HttpServer,
+    Responder, Result as ActixResult,
+};
+use chrono::{DateTime, Utc};
+use flowcore::{
+    Environment, ExecutionEvent, FlowError, NodeId, Value, Workflow, WorkflowEdit, WorkflowFormat,
+};
+use flowpersist::{
+    CredentialData, CredentialKey, CredentialVault, ExecutionQuery, ExecutionRecord,
+    PersistentStore, StoreError, WebhookSubscription,
+};
+use flowruntime::{
+    ExecutionFollow, ExecutionState, ExecutionStatus, FlowRuntime, NodeExecutionState,
+    SequencedEvent,
 };
-use actix_ws::Message;
-use flowcore::{Value, Workflow, WorkflowId};
-use flowruntime::FlowRuntime;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use utoipa::{OpenApi, ToSchema};
 use utoipa_swagger_ui::SwaggerUi;
 use uuid::Uuid;
 
+mod artifacts;
+mod event_triggers;
+mod file_watcher;
 mod grpc_server;
+mod notifications;
+mod scheduler;
+mod webhooks;
+mod websocket;
+mod workflow_service;
+
+use workflow_service::SaveError;
 
 /// Application state shared across handlers
 pub struct AppState {
     runtime: Arc<FlowRuntime>,
-    workflows: Arc<RwLock<HashMap<WorkflowId, Workflow>>>,
+    store: PersistentStore,
+    /// Credentials, when a `CREDENTIAL_KEY` is configured
+    credentials: Option<CredentialVault>,
+}
+
+/// Query parameters for execution history
+#[derive(Debug, Deserialize)]
+struct HistoryQuery {
+    /// Maximum number of records to return (default 50)
+    limit: Option<usize>,
+}
+
+/// Query parameters for exporting a workflow
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+    /// Document format: yaml (default), toml or json
+    format: Option<String>,
+}
+
+/// Query parameters for comparing revisions
+#[derive(Debug, Deserialize)]
+struct DiffQuery {
+    /// Base revision
+    from: u32,
+    /// Target revision (defaults to the current one)
+    to: Option<u32>,
+}
+
+/// Request body for rolling back to an earlier revision
+#[derive(Debug, Deserialize, ToSchema)]
+struct RollbackRequest {
+    /// Revision to restore
+    #[schema(example = 2)]
+    revision: u32,
+}
+
+/// Request body for subscribing to execution lifecycle events
+#[derive(Debug, Deserialize, ToSchema)]
+struct SubscriptionRequest {
+    /// URL events are POSTed to
+    #[schema(example = "https://hooks.example.com/flowengine")]
+    url: String,
+    /// Event types to deliver (default: all)
+    #[serde(default)]
+    #[schema(example = json!(["execution.failed"]))]
+    events: Vec<String>,
+    /// Only events of this workflow's executions
+    workflow_id: Option<Uuid>,
+    /// Sign deliveries with HMAC-SHA256 using this key
+    secret: Option<String>,
+}
+
+/// Request body for storing a credential
+#[derive(Debug, Deserialize, ToSchema)]
+struct CredentialRequest {
+    /// Name node config refers to it by, as `{"$credential": name}`
+    #[schema(example = "github")]
+    name: String,
+    /// `type` (token, basic_auth or oauth2_client_credentials) and its fields
+    #[serde(flatten)]
+    #[schema(value_type = Object, example = json!({"type": "token", "token": "ghp_..."}))]
+    data: CredentialData,
+}
+
+/// An environment profile
+#[derive(Debug, Serialize, ToSchema)]
+struct EnvironmentResponse {
+    #[schema(example = "prod")]
+    name: String,
+    /// Variable names and values
+    #[schema(value_type = Object, example = json!({"zypi_url": "https://zypi.prod.example.com", "memory_mb": 2048}))]
+    variables: serde_json::Value,
+}
+
+/// Query parameters for workflow execution
+#[derive(Debug, Deserialize)]
+struct ExecuteQuery {
+    /// Return 202 with the execution id instead of waiting for completion
+    #[serde(rename = "async", default)]
+    run_async: bool,
+    /// Environment profile whose variables override the workflow's
+    env: Option<String>,
+}
+
+/// Query parameters for listing executions
+#[derive(Debug, Deserialize)]
+struct ExecutionListQuery {
+    /// Only executions of this workflow
+    workflow_id: Option<Uuid>,
+    /// Only executions in this status (running, waiting, paused, completed, failed, cancelled)
+    status: Option<String>,
+    /// Maximum number of executions to return (default 50)
+    limit: Option<usize>,
+    /// Number of executions to skip
+    offset: Option<usize>,
+}
+
+/// Query parameters for following an execution
+#[derive(Debug, Deserialize)]
+struct StreamQuery {
+    /// Comma-separated event types to send, e.g. `StdoutLine,NodeFailed`.
+    /// `NodeEvent` selects every node event.
+    types: Option<String>,
+    /// Resume after this event id, for clients that can't set `Last-Event-ID`
+    last_event_id: Option<u64>,
 }
 
 /// Request body for workflow execution
@@ -34,286 +157,1824 @@ struct ExecuteRequest {
 struct WorkflowResponse {
     /// UUID of the created workflow
     id: Uuid,
+    /// Revision number of the stored definition
+    #[schema(example = 1)]
+    revision: u32,
     /// Human-readable confirmation
     #[schema(example = "Workflow created successfully")]
     message: String,
 }
 
-/// Response for workflow execution
-#[derive(Debug, Serialize, ToSchema)]
-struct ExecutionResponse {
-    /// UUID of this execution run
-    execution_id: Uuid,
-    /// Number of nodes that completed successfully
-    #[schema(example = 2)]
-    completed_nodes: usize,
-    /// Total nodes in the workflow
-    #[schema(example = 2)]
-    total_nodes: usize,
-}
+/// Response for workflow execution
+#[derive(Debug, Serialize, ToSchema)]
+struct ExecutionResponse {
+    /// UUID of this execution run
+    execution_id: Uuid,
+    /// Number of nodes that completed successfully
+    #[schema(example = 2)]
+    completed_nodes: usize,
+    /// Total nodes in the workflow
+    #[schema(example = 2)]
+    total_nodes: usize,
+    /// Output values keyed by node id, then port name
+    #[schema(value_type = Object)]
+    outputs: HashMap<NodeId, HashMap<String, Value>>,
+}
+
+/// Response for an execution started with `?async=true`
+#[derive(Debug, Serialize, ToSchema)]
+struct ExecutionStarted {
+    /// UUID of this execution run
+    execution_id: Uuid,
+    /// Always "running"
+    #[schema(example = "running")]
+    status: String,
+}
+
+/// Status, progress and outputs of one execution
+#[derive(Debug, Serialize, ToSchema)]
+struct ExecutionDetails {
+    execution_id: Uuid,
+    workflow_id: Uuid,
+    /// Revision of the workflow that was executed
+    #[schema(example = 1)]
+    workflow_revision: u32,
+    /// running, waiting, paused, completed, failed or cancelled
+    #[schema(example = "completed")]
+    status: String,
+    #[schema(value_type = String, format = DateTime)]
+    started_at: DateTime<Utc>,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    completed_at: Option<DateTime<Utc>>,
+    duration_ms: Option<u64>,
+    /// Per-node status and timings keyed by node id. Only available while
+    /// the execution is held in memory.
+    #[schema(value_type = Object)]
+    node_progress: HashMap<NodeId, NodeExecutionState>,
+    /// Output values keyed by node id, then port name. Only available while
+    /// the execution is held in memory.
+    #[schema(value_type = Object)]
+    outputs: HashMap<NodeId, HashMap<String, Value>>,
+    /// Failure or cancellation reason
+    error: Option<String>,
+}
+
+impl From<ExecutionState> for ExecutionDetails {
+    fn from(state: ExecutionState) -> Self {
+        Self {
+            execution_id: state.execution_id,
+            workflow_id: state.workflow_id,
+            workflow_revision: state.workflow_revision,
+            status: state.status.as_str().to_string(),
+            started_at: state.started_at,
+            completed_at: state.completed_at,
+            duration_ms: state
+                .completed_at
+                .map(|end| (end - state.started_at).num_milliseconds().max(0) as u64),
+            node_progress: state.node_progress,
+            outputs: state.outputs,
+            error: state.error,
+        }
+    }
+}
+
+impl From<ExecutionRecord> for ExecutionDetails {
+    fn from(record: ExecutionRecord) -> Self {
+        Self {
+            execution_id: record.id,
+            workflow_id: record.workflow_id,
+            workflow_revision: record.workflow_revision,
+            status: record.status.as_str().to_string(),
+            started_at: record.started_at,
+            completed_at: record.completed_at,
+            duration_ms: Some(record.duration_ms),
+            node_progress: HashMap::new(),
+            outputs: HashMap::new(),
+            error: None,
+        }
+    }
+}
+
+/// Error response for any 4xx/5xx
+#[derive(Debug, Serialize, ToSchema)]
+struct ErrorResponse {
+    /// Human-readable error description
+    #[schema(example = "Workflow not found")]
+    error: String,
+}
+
+/// Map a storage failure to a response (412 for revision conflicts, else 500)
+fn storage_error(e: StoreError) -> HttpResponse {
+    if let StoreError::Conflict { .. } = e {
+        return HttpResponse::PreconditionFailed().json(ErrorResponse {
+            error: e.to_string(),
+        });
+    }
+    error!("Storage error: {}", e);
+    HttpResponse::InternalServerError().json(ErrorResponse {
+        error: e.to_string(),
+    })
+}
+
+/// Map a rejected create/update to a response
+fn save_error(e: SaveError) -> HttpResponse {
+    match e {
+        SaveError::NotFound(id) => not_found(id),
+        SaveError::Exists(_) => HttpResponse::Conflict().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+        SaveError::Invalid(e) => HttpResponse::BadRequest().json(ErrorResponse {
+            error: e.to_string(),
+        }),
+        SaveError::Store(e) => storage_error(e),
+    }
+}
+
+fn execution_not_found(execution_id: Uuid) -> HttpResponse {
+    HttpResponse::NotFound().json(ErrorResponse {
+        error: format!("Execution {} not found", execution_id),
+    })
+}
+
+/// 409 describing the execution's current status, which didn't allow what
+/// was asked, or 404 if the execution is unknown
+async fn execution_conflict(
+    data: &AppState,
+    execution_id: Uuid,
+    describe: impl FnOnce(&str) -> String,
+) -> HttpResponse {
+    let status = match data.runtime.get_execution_status(execution_id).await {
+        Some(state) => Some(state.status.as_str()),
+        None => match data.store.get_execution(execution_id).await {
+            Ok(record) => record.map(|r| r.status.as_str()),
+            Err(e) => return storage_error(e),
+        },
+    };
+    match status {
+        Some(status) => HttpResponse::Conflict().json(ErrorResponse {
+            error: describe(status),
+        }),
+        None => execution_not_found(execution_id),
+    }
+}
+
+/// Map a failure to run a workflow to a response (404 for unknown workflows)
+fn execution_error(workflow_id: Uuid, e: FlowError) -> HttpResponse {
+    match e {
+        FlowError::Workflow(flowcore::WorkflowError::NotFound(_)) => return not_found(workflow_id),
+        // e.g. a reference to a variable neither the workflow nor the
+        // environment defines
+        FlowError::Workflow(flowcore::WorkflowError::Invalid(_)) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: e.to_string(),
+            })
+        }
+        _ => {}
+    }
+    error!("Workflow {} execution failed: {}", workflow_id, e);
+    HttpResponse::InternalServerError().json(ErrorResponse {
+        error: e.to_string(),
+    })
+}
+
+fn not_found(workflow_id: Uuid) -> HttpResponse {
+    HttpResponse::NotFound().json(ErrorResponse {
+        error: format!("Workflow {} not found", workflow_id),
+    })
+}
+
+/// An execution input as sent by a client: a tagged
+/// `{"type": "Secret", "value": ...}` is read as a secret, anything else
+/// is passed on as JSON
+fn input_value(json: serde_json::Value) -> Value {
+    match serde_json::from_value(json.clone()) {
+        Ok(secret @ Value::Secret(_)) => secret,
+        _ => Value::Json(json),
+    }
+}
+
+/// Workflow from a request body: JSON, or YAML/TOML by `Content-Type`, with
+/// nodes keyed by name or listed with plain or tagged config
+fn workflow_body(req: &HttpRequest, body: &[u8]) -> Result<Workflow, HttpResponse> {
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let invalid = |error: String| HttpResponse::BadRequest().json(ErrorResponse { error });
+    let text = std::str::from_utf8(body).map_err(|e| invalid(e.to_string()))?;
+    Workflow::parse(text, WorkflowFormat::from_content_type(content_type))
+        .map_err(|e| invalid(e.to_string()))
+}
+
+/// ETag for a workflow revision
+fn etag(revision: u32) -> String {
+    format!("\"{}\"", revision)
+}
+
+/// Revision required by an `If-Match` header (`"3"`, `W/"3"` or `3`).
+/// `*` and a missing header impose no constraint.
+fn if_match(req: &HttpRequest) -> Result<Option<u32>, HttpResponse> {
+    let Some(value) = req.headers().get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let value = value.to_str().unwrap_or_default().trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    value
+        .trim_start_matches("W/")
+        .trim_matches('"')
+        .parse()
+        .map(Some)
+        .map_err(|_| {
+            HttpResponse::BadRequest().json(ErrorResponse {
+                error: format!("If-Match must be a revision number, got {}", value),
+            })
+        })
+}
+
+/// Summary of a registered node type
+#[derive(Debug, Serialize, ToSchema)]
+struct NodeTypeInfo {
+    /// Node type identifier (e.g., "zypi.exec", "shell.exec")
+    #[schema(example = "zypi.exec")]
+    r#type: String,
+    /// Human-readable description
+    #[schema(example = "Execute command in Firecracker microVM")]
+    description: String,
+    /// Category for grouping (e.g., "zypi", "shell", "docker")
+    #[schema(example = "zypi")]
+    category: String,
+}
+
+/// Health check — returns service status
+#[utoipa::path(
+    get,
+    path = "/health",
+    responses(
+        (status = 200, description = "Service is healthy", body = serde_json::Value)
+    )
+)]
+#[get("/health")]
+async fn health_check() -> impl Responder {
+    HttpResponse::Ok().json(serde_json::json!({
+        "status": "healthy",
+        "version": env!("CARGO_PKG_VERSION"),
+        "service": "flowengine"
+    }))
+}
+
+/// List all registered workflows (summary only — no full definitions)
+#[utoipa::path(
+    get,
+    path = "/api/workflows",
+    responses(
+        (status = 200, description = "List of workflow summaries", body = Vec<serde_json::Value>)
+    )
+)]
+#[get("/api/workflows")]
+async fn list_workflows(data: web::Data<AppState>) -> ActixResult<impl Responder> {
+    let ids = match data.store.list_workflows().await {
+        Ok(ids) => ids,
+        Err(e) => return Ok(storage_error(e)),
+    };
+
+    let mut workflow_list = Vec::with_capacity(ids.len());
+    for (id, _) in ids {
+        match data.store.load_workflow(id).await {
+            Ok(Some(w)) => workflow_list.push(serde_json::json!({
+                "id": w.id,
+                "name": w.name,
+                "description": w.description,
+                "nodes": w.nodes.len(),
+                "connections": w.connections.len(),
+            })),
+            // Deleted between list and load
+            Ok(None) => {}
+            Err(e) => return Ok(storage_error(e)),
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(workflow_list))
+}
+
+/// Create a new workflow from a FlowEngine workflow definition.
+///
+/// Node config may be plain JSON or tagged values, and nodes may be keyed by
+/// name with `node.port` connections; send `Content-Type: application/yaml`
+/// or `application/toml` for a YAML or TOML definition.
+#[utoipa::path(
+    post,
+    path = "/api/workflows",
+    request_body = serde_json::Value,
+    responses(
+        (status = 201, description = "Workflow created", body = WorkflowResponse),
+        (status = 400, description = "Invalid workflow JSON/YAML or unknown node types", body = ErrorResponse),
+        (status = 409, description = "A workflow with this id already exists", body = ErrorResponse),
+        (status = 500, description = "Workflow could not be saved", body = ErrorResponse)
+    )
+)]
+#[post("/api/workflows")]
+async fn create_workflow(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    body: web::Bytes,
+) -> ActixResult<impl Responder> {
+    let workflow = match workflow_body(&req, &body) {
+        Ok(workflow) => workflow,
+        Err(response) => return Ok(response),
+    };
+    let workflow_id = workflow.id;
+
+    info!("Creating workflow: {} ({})", workflow.name, workflow_id);
+
+    let revision = match data.create_workflow(workflow).await {
+        Ok(workflow) => workflow.revision,
+        Err(e) => return Ok(save_error(e)),
+    };
+
+    Ok(HttpResponse::Created()
+        .insert_header((header::ETAG, etag(revision)))
+        .json(WorkflowResponse {
+            id: workflow_id,
+            revision,
+            message: "Workflow created successfully".to_string(),
+        }))
+}
+
+/// Lint a workflow definition without saving it.
+///
+/// Accepts the same bodies as `POST /api/workflows` and reports unknown node
+/// types, dangling connections, undefined ports, duplicate inputs, cycles and
+/// unused or missing config against the server's node registry.
+#[utoipa::path(
+    post,
+    path = "/api/workflows/lint",
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, description = "Lint diagnostics (`valid` is false when any has error severity)", body = serde_json::Value),
+        (status = 400, description = "Body is not a workflow definition", body = ErrorResponse)
+    )
+)]
+#[post("/api/workflows/lint")]
+async fn lint_workflow(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    body: web::Bytes,
+) -> ActixResult<impl Responder> {
+    let workflow = match workflow_body(&req, &body) {
+        Ok(workflow) => workflow,
+        Err(response) => return Ok(response),
+    };
+    let diagnostics = data.runtime.registry().lint_workflow(&workflow);
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "valid": !diagnostics.iter().any(|d| d.is_error()),
+        "diagnostics": diagnostics,
+    })))
+}
+
+/// Get a specific workflow by ID (full definition including nodes and connections)
+#[utoipa::path(
+    get,
+    path = "/api/workflows/{id}",
+    params(
+        ("id" = Uuid, description = "Workflow UUID")
+    ),
+    responses(
+        (status = 200, description = "Workflow definition (ETag carries the revision)", body = serde_json::Value),
+        (status = 404, description = "Workflow not found", body = ErrorResponse)
+    )
+)]
+#[get("/api/workflows/{id}")]
+async fn get_workflow(
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+) -> ActixResult<impl Responder> {
+    let workflow_id = path.into_inner();
+
+    match data.store.load_workflow(workflow_id).await {
+        Ok(Some(workflow)) => Ok(HttpResponse::Ok()
+            .insert_header((header::ETAG, etag(workflow.revision)))
+            .json(workflow)),
+        Ok(None) => Ok(not_found(workflow_id)),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Replace a workflow definition, creating a new revision.
+///
+/// Send `If-Match: "<revision>"` (or include the `revision` you loaded in the
+/// body) to reject the update if someone else saved in the meantime.
+#[utoipa::path(
+    put,
+    path = "/api/workflows/{id}",
+    params(
+        ("id" = Uuid, description = "Workflow UUID"),
+        ("If-Match" = Option<String>, Header, description = "Expected current revision")
+    ),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, description = "New revision saved", body = WorkflowResponse),
+        (status = 400, description = "Definition fails validation", body = ErrorResponse),
+        (status = 404, description = "Workflow not found", body = ErrorResponse),
+        (status = 412, description = "Workflow was modified since the expected revision", body = ErrorResponse)
+    )
+)]
+#[put("/api/workflows/{id}")]
+async fn update_workflow(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+    body: web::Bytes,
+) -> ActixResult<impl Responder> {
+    let workflow_id = path.into_inner();
+    let mut workflow = match workflow_body(&req, &body) {
+        Ok(workflow) => workflow,
+        Err(response) => return Ok(response),
+    };
+    workflow.id = workflow_id;
+
+    let expected = match if_match(&req) {
+        Ok(Some(revision)) => Some(revision),
+        Ok(None) => (workflow.revision > 0).then_some(workflow.revision),
+        Err(response) => return Ok(response),
+    };
+
+    let revision = match data.update_workflow(workflow, expected).await {
+        Ok(workflow) => workflow.revision,
+        Err(e) => return Ok(save_error(e)),
+    };
+
+    info!("Updated workflow {} to revision {}", workflow_id, revision);
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, etag(revision)))
+        .json(WorkflowResponse {
+            id: workflow_id,
+            revision,
+            message: "Workflow updated successfully".to_string(),
+        }))
+}
+
+/// Apply partial edits to a workflow, creating a new revision.
+///
+/// The body is a list of operations applied in order, all or nothing:
+/// `add_node`, `remove_node`, `move_node`, `update_node`, `connect`,
+/// `disconnect` and `rewire`. `If-Match` works as for `PUT`.
+#[utoipa::path(
+    patch,
+    path = "/api/workflows/{id}",
+    params(
+        ("id" = Uuid, description = "Workflow UUID"),
+        ("If-Match" = Option<String>, Header, description = "Expected current revision")
+    ),
+    request_body = Vec<serde_json::Value>,
+    responses(
+        (status = 200, description = "Edits applied", body = WorkflowResponse),
+        (status = 400, description = "An edit or the resulting definition is invalid", body = ErrorResponse),
+        (status = 404, description = "Workflow not found", body = ErrorResponse),
+        (status = 412, description = "Workflow was modified since the expected revision", body = ErrorResponse)
+    )
+)]
+#[patch("/api/workflows/{id}")]
+async fn patch_workflow(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+    edits: web::Json<Vec<WorkflowEdit>>,
+) -> ActixResult<impl Responder> {
+    let workflow_id = path.into_inner();
+
+    let expected = match if_match(&req) {
+        Ok(expected) => expected,
+        Err(response) => return Ok(response),
+    };
+
+    let revision = match data.patch_workflow(workflow_id, &edits, expected).await {
+        Ok(workflow) => workflow.revision,
+        Err(e) => return Ok(save_error(e)),
+    };
+
+    info!(
+        "Applied {} edits to workflow {} (revision {})",
+        edits.len(),
+        workflow_id,
+        revision
+    );
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, etag(revision)))
+        .json(WorkflowResponse {
+            id: workflow_id,
+            revision,
+            message: "Workflow updated successfully".to_string(),
+        }))
+}
+
+/// List the stored revisions of a workflow, newest first
+#[utoipa::path(
+    get,
+    path = "/api/workflows/{id}/revisions",
+    params(
+        ("id" = Uuid, description = "Workflow UUID")
+    ),
+    responses(
+        (status = 200, description = "Revision summaries", body = Vec<serde_json::Value>),
+        (status = 404, description = "Workflow not found", body = ErrorResponse)
+    )
+)]
+#[get("/api/workflows/{id}/revisions")]
+async fn list_revisions(
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+) -> ActixResult<impl Responder> {
+    let workflow_id = path.into_inner();
+
+    match data.store.list_revisions(workflow_id).await {
+        Ok(revisions) if revisions.is_empty() => Ok(not_found(workflow_id)),
+        Ok(revisions) => Ok(HttpResponse::Ok().json(revisions)),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Next and last fire times of a workflow's cron triggers
+#[utoipa::path(
+    get,
+    path = "/api/workflows/{id}/schedules",
+    params(
+        ("id" = Uuid, description = "Workflow UUID")
+    ),
+    responses(
+        (status = 200, description = "Trigger schedules", body = Vec<serde_json::Value>),
+        (status = 404, description = "Workflow not found", body = ErrorResponse)
+    )
+)]
+#[get("/api/workflows/{id}/schedules")]
+async fn list_schedules(
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+) -> ActixResult<impl Responder> {
+    let workflow_id = path.into_inner();
+
+    match data.store.load_workflow(workflow_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Ok(not_found(workflow_id)),
+        Err(e) => return Ok(storage_error(e)),
+    }
+    match data.store.list_schedules(Some(workflow_id)).await {
+        Ok(schedules) => Ok(HttpResponse::Ok().json(schedules)),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Get the definition stored at a specific revision
+#[utoipa::path(
+    get,
+    path = "/api/workflows/{id}/revisions/{revision}",
+    params(
+        ("id" = Uuid, description = "Workflow UUID"),
+        ("revision" = u32, description = "Revision number")
+    ),
+    responses(
+        (status = 200, description = "Workflow definition", body = serde_json::Value),
+        (status = 404, description = "Revision not found", body = ErrorResponse)
+    )
+)]
+#[get("/api/workflows/{id}/revisions/{revision}")]
+async fn get_revision(
+    data: web::Data<AppState>,
+    path: web::Path<(Uuid, u32)>,
+) -> ActixResult<impl Responder> {
+    let (workflow_id, revision) = path.into_inner();
+
+    match data.store.load_revision(workflow_id, revision).await {
+        Ok(Some(workflow)) => Ok(HttpResponse::Ok()
+            .insert_header((header::ETAG, etag(revision)))
+            .json(workflow)),
+        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("Workflow {} has no revision {}", workflow_id, revision),
+        })),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Current revision as a hand-editable document, with nodes keyed by name
+/// and `node.port` connections; it can be posted back as is
+#[utoipa::path(
+    get,
+    path = "/api/workflows/{id}/export",
+    params(
+        ("id" = Uuid, description = "Workflow UUID"),
+        ("format" = Option<String>, Query, description = "yaml (default), toml or json")
+    ),
+    responses(
+        (status = 200, description = "Workflow document in the requested format", body = String),
+        (status = 400, description = "Unknown format, or the workflow can't be written in it", body = ErrorResponse),
+        (status = 404, description = "Workflow not found", body = ErrorResponse)
+    )
+)]
+#[get("/api/workflows/{id}/export")]
+async fn export_workflow(
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+    query: web::Query<ExportQuery>,
+) -> ActixResult<impl Responder> {
+    let workflow_id = path.into_inner();
+
+    let name = query.format.as_deref().unwrap_or("yaml");
+    let Some(format) = WorkflowFormat::from_name(name) else {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Unknown format '{}': expected yaml, toml or json", name),
+        }));
+    };
+
+    let workflow = match data.store.load_workflow(workflow_id).await {
+        Ok(Some(workflow)) => workflow,
+        Ok(None) => return Ok(not_found(workflow_id)),
+        Err(e) => return Ok(storage_error(e)),
+    };
+
+    match format.render(&workflow.to_named_document()) {
+        Ok(text) => Ok(HttpResponse::Ok()
+            .content_type(format.content_type())
+            .insert_header((header::ETAG, etag(workflow.revision)))
+            .body(text)),
+        Err(e) => Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            error: e.to_string(),
+        })),
+    }
+}
+
+/// Structural diff between two revisions (nodes and connections added,
+/// removed or changed)
+#[utoipa::path(
+    get,
+    path = "/api/workflows/{id}/diff",
+    params(
+        ("id" = Uuid, description = "Workflow UUID"),
+        ("from" = u32, Query, description = "Base revision"),
+        ("to" = Option<u32>, Query, description = "Target revision (default: current)")
+    ),
+    responses(
+        (status = 200, description = "Differences between the revisions", body = serde_json::Value),
+        (status = 404, description = "Workflow or revision not found", body = ErrorResponse)
+    )
+)]
+#[get("/api/workflows/{id}/diff")]
+async fn diff_revisions(
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+    query: web::Query<DiffQuery>,
+) -> ActixResult<impl Responder> {
+    let workflow_id = path.into_inner();
+
+    let to = match query.to {
+        Some(to) => to,
+        None => match data.store.load_workflow(workflow_id).await {
+            Ok(Some(current)) => current.revision,
+            Ok(None) => return Ok(not_found(workflow_id)),
+            Err(e) => return Ok(storage_error(e)),
+        },
+    };
+
+    match data.store.diff_revisions(workflow_id, query.from, to).await {
+        Ok(Some(diff)) => Ok(HttpResponse::Ok().json(diff)),
+        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            error: format!(
+                "Workflow {} has no revision {} or {}",
+                workflow_id, query.from, to
+            ),
+        })),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Restore an earlier revision. The old definition is saved again as a new
+/// revision, so history is never rewritten.
+#[utoipa::path(
+    post,
+    path = "/api/workflows/{id}/rollback",
+    params(
+        ("id" = Uuid, description = "Workflow UUID"),
+        ("If-Match" = Option<String>, Header, description = "Expected current revision")
+    ),
+    request_body = RollbackRequest,
+    responses(
+        (status = 200, description = "Revision restored", body = WorkflowResponse),
+        (status = 404, description = "Revision not found", body = ErrorResponse),
+        (status = 412, description = "Workflow was modified since the expected revision", body = ErrorResponse)
+    )
+)]
+#[post("/api/workflows/{id}/rollback")]
+async fn rollback_workflow(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+    body: web::Json<RollbackRequest>,
+) -> ActixResult<impl Responder> {
+    let workflow_id = path.into_inner();
+    let target = body.revision;
+
+    let expected = match if_match(&req) {
+        Ok(expected) => expected,
+        Err(response) => return Ok(response),
+    };
+
+    let workflow = match data
+        .store
+        .rollback_workflow(workflow_id, target, expected)
+        .await
+    {
+        Ok(Some(workflow)) => workflow,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                error: format!("Workflow {} has no revision {}", workflow_id, target),
+            }))
+        }
+        Err(e) => return Ok(storage_error(e)),
+    };
+    let revision = workflow.revision;
+
+    info!(
+        "Rolled back workflow {} to revision {} (now revision {})",
+        workflow_id, target, revision
+    );
+    data.runtime.register_workflow(workflow).await;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, etag(revision)))
+        .json(WorkflowResponse {
+            id: workflow_id,
+            revision,
+            message: format!("Restored revision {}", target),
+        }))
+}
+
+/// Delete a workflow by ID
+#[utoipa::path(
+    delete,
+    path = "/api/workflows/{id}",
+    params(
+        ("id" = Uuid, description = "Workflow UUID")
+    ),
+    responses(
+        (status = 200, description = "Workflow deleted", body = serde_json::Value),
+        (status = 404, description = "Workflow not found", body = ErrorResponse)
+    )
+)]
+#[delete("/api/workflows/{id}")]
+async fn delete_workflow(
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+) -> ActixResult<impl Responder> {
+    let workflow_id = path.into_inner();
+
+    match data.store.delete_workflow(workflow_id).await {
+        Ok(true) => {
+            data.runtime.unregister_workflow(workflow_id).await;
+            info!("Deleted workflow: {}", workflow_id);
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "message": "Workflow deleted successfully"
+            })))
+        }
+        Ok(false) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("Workflow {} not found", workflow_id),
+        })),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// List webhook subscriptions
+#[utoipa::path(
+    get,
+    path = "/api/subscriptions",
+    responses(
+        (status = 200, description = "Webhook subscriptions", body = Vec<serde_json::Value>)
+    )
+)]
+#[get("/api/subscriptions")]
+async fn list_subscriptions(data: web::Data<AppState>) -> ActixResult<impl Responder> {
+    match data.store.list_subscriptions().await {
+        Ok(subscriptions) => Ok(HttpResponse::Ok().json(subscriptions)),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Subscribe a URL to execution lifecycle events
+#[utoipa::path(
+    post,
+    path = "/api/subscriptions",
+    request_body = SubscriptionRequest,
+    responses(
+        (status = 201, description = "Subscription created", body = serde_json::Value),
+        (status = 400, description = "Invalid subscription", body = ErrorResponse),
+        (status = 404, description = "Workflow not found", body = ErrorResponse)
+    )
+)]
+#[post("/api/subscriptions")]
+async fn create_subscription(
+    data: web::Data<AppState>,
+    req: web::Json<SubscriptionRequest>,
+) -> ActixResult<impl Responder> {
+    let req = req.into_inner();
+
+    let bad_request = |error: String| Ok(HttpResponse::BadRequest().json(ErrorResponse { error }));
+    if !req.url.starts_with("http://") && !req.url.starts_with("https://") {
+        return bad_request(format!("'{}' is not an http(s) URL", req.url));
+    }
+    if let Some(event) = req
+        .events
+        .iter()
+        .find(|e| !notifications::EVENT_TYPES.contains(&e.as_str()))
+    {
+        return bad_request(format!(
+            "unknown event '{}', expected one of {}",
+            event,
+            notifications::EVENT_TYPES.join(", ")
+        ));
+    }
+    if let Some(workflow_id) = req.workflow_id {
+        match data.store.load_workflow(workflow_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return Ok(not_found(workflow_id)),
+            Err(e) => return Ok(storage_error(e)),
+        }
+    }
+
+    let subscription = WebhookSubscription {
+        id: Uuid::new_v4(),
+        url: req.url,
+        events: req.events,
+        workflow_id: req.workflow_id,
+        secret: req.secret.filter(|s| !s.is_empty()),
+        created_at: Utc::now(),
+    };
+    match data.store.put_subscription(&subscription).await {
+        Ok(()) => {
+            info!("Subscribed {} to execution events", subscription.url);
+            Ok(HttpResponse::Created().json(subscription))
+        }
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Remove a webhook subscription
+#[utoipa::path(
+    delete,
+    path = "/api/subscriptions/{id}",
+    params(
+        ("id" = Uuid, description = "Subscription UUID")
+    ),
+    responses(
+        (status = 200, description = "Subscription deleted"),
+        (status = 404, description = "Subscription not found", body = ErrorResponse)
+    )
+)]
+#[delete("/api/subscriptions/{id}")]
+async fn delete_subscription(
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+) -> ActixResult<impl Responder> {
+    let id = path.into_inner();
+
+    match data.store.delete_subscription(id).await {
+        Ok(true) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "message": "Subscription deleted successfully"
+        }))),
+        Ok(false) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("Subscription {} not found", id),
+        })),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// The credential vault, or the response explaining it is disabled
+fn credential_vault(data: &AppState) -> Result<&CredentialVault, HttpResponse> {
+    data.credentials.as_ref().ok_or_else(|| {
+        HttpResponse::ServiceUnavailable().json(ErrorResponse {
+            error: "credentials are disabled; set CREDENTIAL_KEY to a base64-encoded 32-byte key"
+                .to_string(),
+        })
+    })
+}
+
+/// Why `name` can't name a credential or environment (`kind`), if it can't
+fn invalid_name(kind: &str, name: &str) -> Option<String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Some(format!(
+            "invalid {} name '{}': use letters, digits, '-', '_' and '.'",
+            kind, name
+        ));
+    }
+    None
+}
+
+/// Why a credential can't be stored under `name`, if it can't
+fn invalid_credential(name: &str, credential: &CredentialData) -> Option<String> {
+    if let Some(error) = invalid_name("credential", name) {
+        return Some(error);
+    }
+    credential.missing_field().map(|field| {
+        format!(
+            "{} credential '{}' needs a non-empty '{}'",
+            credential.kind(),
+            name,
+            field
+        )
+    })
+}
+
+/// List credentials, without their contents
+#[utoipa::path(
+    get,
+    path = "/api/credentials",
+    responses(
+        (status = 200, description = "Credential names, types and timestamps", body = Vec<serde_json::Value>),
+        (status = 503, description = "No credential key configured", body = ErrorResponse)
+    )
+)]
+#[get("/api/credentials")]
+async fn list_credentials(data: web::Data<AppState>) -> ActixResult<impl Responder> {
+    let vault = match credential_vault(&data) {
+        Ok(vault) => vault,
+        Err(response) => return Ok(response),
+    };
+    match vault.list().await {
+        Ok(credentials) => Ok(HttpResponse::Ok().json(credentials)),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Store a new credential, encrypted
+#[utoipa::path(
+    post,
+    path = "/api/credentials",
+    request_body = CredentialRequest,
+    responses(
+        (status = 201, description = "Credential stored; the response omits its contents", body = serde_json::Value),
+        (status = 400, description = "Invalid credential", body = ErrorResponse),
+        (status = 409, description = "A credential of that name exists", body = ErrorResponse),
+        (status = 503, description = "No credential key configured", body = ErrorResponse)
+    )
+)]
+#[post("/api/credentials")]
+async fn create_credential(
+    data: web::Data<AppState>,
+    req: web::Json<CredentialRequest>,
+) -> ActixResult<impl Responder> {
+    let vault = match credential_vault(&data) {
+        Ok(vault) => vault,
+        Err(response) => return Ok(response),
+    };
+    let req = req.into_inner();
+    if let Some(error) = invalid_credential(&req.name, &req.data) {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse { error }));
+    }
+    match vault.record(&req.name).await {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return Ok(HttpResponse::Conflict().json(ErrorResponse {
+                error: format!("Credential '{}' already exists; PUT to replace it", req.name),
+            }))
+        }
+        Err(e) => return Ok(storage_error(e)),
+    }
+    match vault.put(&req.name, &req.data).await {
+        Ok(record) => {
+            info!("Stored {} credential '{}'", record.kind, record.name);
+            Ok(HttpResponse::Created().json(record))
+        }
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Get a credential's metadata. Its contents are never returned.
+#[utoipa::path(
+    get,
+    path = "/api/credentials/{name}",
+    params(
+        ("name" = String, description = "Credential name")
+    ),
+    responses(
+        (status = 200, description = "Credential name, type and timestamps", body = serde_json::Value),
+        (status = 404, description = "Credential not found", body = ErrorResponse),
+        (status = 503, description = "No credential key configured", body = ErrorResponse)
+    )
+)]
+#[get("/api/credentials/{name}")]
+async fn get_credential(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> ActixResult<impl Responder> {
+    let vault = match credential_vault(&data) {
+        Ok(vault) => vault,
+        Err(response) => return Ok(response),
+    };
+    let name = path.into_inner();
+    match vault.record(&name).await {
+        Ok(Some(record)) => Ok(HttpResponse::Ok().json(record)),
+        Ok(None) => Ok(credential_not_found(&name)),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Create or replace a credential, e.g. to rotate a token
+#[utoipa::path(
+    put,
+    path = "/api/credentials/{name}",
+    params(
+        ("name" = String, description = "Credential name")
+    ),
+    request_body(content = Object, description = "`type` and its fields", example = json!({"type": "basic_auth", "username": "ops@example.com", "password": "..."})),
+    responses(
+        (status = 200, description = "Credential stored; the response omits its contents", body = serde_json::Value),
+        (status = 400, description = "Invalid credential", body = ErrorResponse),
+        (status = 503, description = "No credential key configured", body = ErrorResponse)
+    )
+)]
+#[put("/api/credentials/{name}")]
+async fn put_credential(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    req: web::Json<CredentialData>,
+) -> ActixResult<impl Responder> {
+    let vault = match credential_vault(&data) {
+        Ok(vault) => vault,
+        Err(response) => return Ok(response),
+    };
+    let name = path.into_inner();
+    let credential = req.into_inner();
+    if let Some(error) = invalid_credential(&name, &credential) {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse { error }));
+    }
+    match vault.put(&name, &credential).await {
+        Ok(record) => {
+            info!("Stored {} credential '{}'", record.kind, record.name);
+            Ok(HttpResponse::Ok().json(record))
+        }
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Delete a credential. Workflows referring to it fail until it is
+/// stored again.
+#[utoipa::path(
+    delete,
+    path = "/api/credentials/{name}",
+    params(
+        ("name" = String, description = "Credential name")
+    ),
+    responses(
+        (status = 200, description = "Credential deleted"),
+        (status = 404, description = "Credential not found", body = ErrorResponse),
+        (status = 503, description = "No credential key configured", body = ErrorResponse)
+    )
+)]
+#[delete("/api/credentials/{name}")]
+async fn delete_credential(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> ActixResult<impl Responder> {
+    let vault = match credential_vault(&data) {
+        Ok(vault) => vault,
+        Err(response) => return Ok(response),
+    };
+    let name = path.into_inner();
+    match vault.delete(&name).await {
+        Ok(true) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "message": "Credential deleted successfully"
+        }))),
+        Ok(false) => Ok(credential_not_found(&name)),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+fn credential_not_found(name: &str) -> HttpResponse {
+    HttpResponse::NotFound().json(ErrorResponse {
+        error: format!("Credential '{}' not found", name),
+    })
+}
+
+/// An environment as the API shows it: variables as plain values
+fn environment_response(environment: &Environment) -> EnvironmentResponse {
+    EnvironmentResponse {
+        name: environment.name.clone(),
+        variables: environment.to_document(),
+    }
+}
+
+/// List environment profiles
+#[utoipa::path(
+    get,
+    path = "/api/environments",
+    responses(
+        (status = 200, description = "Environments and their variables", body = Vec<EnvironmentResponse>)
+    )
+)]
+#[get("/api/environments")]
+async fn list_environments(data: web::Data<AppState>) -> ActixResult<impl Responder> {
+    match data.store.list_environments().await {
+        Ok(environments) => Ok(HttpResponse::Ok().json(
+            environments
+                .iter()
+                .map(environment_response)
+                .collect::<Vec<_>>(),
+        )),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Get an environment profile
+#[utoipa::path(
+    get,
+    path = "/api/environments/{name}",
+    params(
+        ("name" = String, description = "Environment name")
+    ),
+    responses(
+        (status = 200, description = "Environment and its variables", body = EnvironmentResponse),
+        (status = 404, description = "Environment not found", body = ErrorResponse)
+    )
+)]
+#[get("/api/environments/{name}")]
+async fn get_environment(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> ActixResult<impl Responder> {
+    let name = path.into_inner();
+    match data.store.get_environment(&name).await {
+        Ok(Some(environment)) => Ok(HttpResponse::Ok().json(environment_response(&environment))),
+        Ok(None) => Ok(environment_not_found(&name)),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+/// Create or replace an environment profile. The body maps variable names
+/// to values, which override the workflow's variables when executing with
+/// `?env={name}`.
+#[utoipa::path(
+    put,
+    path = "/api/environments/{name}",
+    params(
+        ("name" = String, description = "Environment name")
+    ),
+    request_body(content = Object, description = "Variable names and values", example = json!({"zypi_url": "https://zypi.prod.example.com", "memory_mb": 2048, "token": {"$credential": "zypi-prod"}})),
+    responses(
+        (status = 200, description = "Environment stored", body = EnvironmentResponse),
+        (status = 400, description = "Invalid environment", body = ErrorResponse)
+    )
+)]
+#[put("/api/environments/{name}")]
+async fn put_environment(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    req: web::Json<serde_json::Value>,
+) -> ActixResult<impl Responder> {
+    let name = path.into_inner();
+    if let Some(error) = invalid_name("environment", &name) {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse { error }));
+    }
+    let environment = match Environment::from_document(&name, req.into_inner()) {
+        Ok(environment) => environment,
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+                error: e.to_string(),
+            }))
+        }
+    };
+    // The store would only keep the redacted placeholder
+    if let Some((variable, _)) = environment
+        .variables
+        .iter()
+        .find(|(_, v)| !v.secrets().is_empty())
+    {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!(
+                "variable '{}' is a secret; refer to a credential with \
+                 {{\"$credential\": ...}} instead",
+                variable
+            ),
+        }));
+    }
+    if let Err(e) = data.store.put_environment(&environment).await {
+        return Ok(storage_error(e));
+    }
+    info!(
+        "Stored environment '{}' with {} variables",
+        name,
+        environment.variables.len()
+    );
+    let response = environment_response(&environment);
+    data.runtime.register_environment(environment).await;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Delete an environment profile
+#[utoipa::path(
+    delete,
+    path = "/api/environments/{name}",
+    params(
+        ("name" = String, description = "Environment name")
+    ),
+    responses(
+        (status = 200, description = "Environment deleted"),
+        (status = 404, description = "Environment not found", body = ErrorResponse)
+    )
+)]
+#[delete("/api/environments/{name}")]
+async fn delete_environment(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> ActixResult<impl Responder> {
+    let name = path.into_inner();
+    match data.store.delete_environment(&name).await {
+        Ok(true) => {
+            data.runtime.unregister_environment(&name).await;
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "message": "Environment deleted successfully"
+            })))
+        }
+        Ok(false) => Ok(environment_not_found(&name)),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
+
+fn environment_not_found(name: &str) -> HttpResponse {
+    HttpResponse::NotFound().json(ErrorResponse {
+        error: format!("Environment '{}' not found", name),
+    })
+}
+
+/// Download the contents of a blob value. Blobs never change, so the
+/// response may be cached forever.
+#[utoipa::path(
+    get,
+    path = "/api/artifacts/{hash}",
+    params(
+        ("hash" = String, description = "Hex SHA-256 of the blob")
+    ),
+    responses(
+        (status = 200, description = "Blob contents, with the media type it was stored with"),
+        (status = 400, description = "Not a SHA-256 hash", body = ErrorResponse),
+        (status = 404, description = "Blob not found", body = ErrorResponse)
+    )
+)]
+#[get("/api/artifacts/{hash}")]
+async fn download_artifact(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> ActixResult<impl Responder> {
+    let hash = path.into_inner();
+    let artifacts = data.runtime.artifacts();
+
+    let Some(file) = artifacts.path(&hash) else {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("'{}' is not a SHA-256 hash", hash),
+        }));
+    };
+    let contents = match tokio::fs::read(&file).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                error: format!("Artifact {} not found", hash),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to read artifact {}: {}", hash, e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to read artifact {}: {}", hash, e),
+            }));
+        }
+    };
+    let media_type = artifacts
+        .media_type(&hash)
+        .await
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    Ok(HttpResponse::Ok()
+        .content_type(media_type)
+        .insert_header((header::ETAG, format!("\"{}\"", hash)))
+        .insert_header((header::CACHE_CONTROL, "public, max-age=31536000, immutable"))
+        .body(contents))
+}
+
+/// Execute a previously created workflow with inputs.
+/// With `?async=true` the run continues in the background and its id is
+/// returned immediately; poll `GET /api/executions/{id}` for the result.
+#[utoipa::path(
+    post,
+    path = "/api/workflows/{id}/execute",
+    params(
+        ("id" = Uuid, description = "Workflow UUID"),
+        ("async" = Option<bool>, Query, description = "Return 202 without waiting for completion"),
+        ("env" = Option<String>, Query, description = "Environment whose variables override the workflow's")
+    ),
+    request_body = ExecuteRequest,
+    responses(
+        (status = 200, description = "Workflow executed successfully", body = ExecutionResponse),
+        (status = 202, description = "Execution started", body = ExecutionStarted),
+        (status = 400, description = "Config refers to an undefined variable", body = ErrorResponse),
+        (status = 404, description = "Workflow or environment not found", body = ErrorResponse),
+        (status = 500, description = "Execution failed", body = ErrorResponse)
+    )
+)]
+#[post("/api/workflows/{id}/execute")]
+async fn execute_workflow(
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+    query: web::Query<ExecuteQuery>,
+    req: web::Json<ExecuteRequest>,
+) -> ActixResult<impl Responder> {
+    let workflow_id = path.into_inner();
+    let inputs = req.into_inner().inputs;
+
+    let env = query.env.as_deref();
+    match env {
+        Some(name) => {
+            if data.runtime.get_environment(name).await.is_none() {
+                return Ok(environment_not_found(name));
+            }
+            info!("Executing workflow {} in environment '{}'", workflow_id, name);
+        }
+        None => info!("Executing workflow: {}", workflow_id),
+    }
 
-/// Error response for any 4xx/5xx
-#[derive(Debug, Serialize, ToSchema)]
-struct ErrorResponse {
-    /// Human-readable error description
-    #[schema(example = "Workflow not found")]
-    error: String,
-}
+    let converted_inputs: HashMap<String, Value> = inputs
+        .into_iter()
+        .map(|(k, v)| (k, input_value(v)))
+        .collect();
 
-/// Summary of a registered node type
-#[derive(Debug, Serialize, ToSchema)]
-struct NodeTypeInfo {
-    /// Node type identifier (e.g., "zypi.exec", "shell.exec")
-    #[schema(example = "zypi.exec")]
-    r#type: String,
-    /// Human-readable description
-    #[schema(example = "Execute command in Firecracker microVM")]
-    description: String,
-    /// Category for grouping (e.g., "zypi", "shell", "docker")
-    #[schema(example = "zypi")]
-    category: String,
+    if query.run_async {
+        return match data
+            .runtime
+            .start_workflow_in(workflow_id, converted_inputs, env)
+            .await
+        {
+            Ok(execution_id) => Ok(HttpResponse::Accepted()
+                .insert_header((header::LOCATION, format!("/api/executions/{}", execution_id)))
+                .json(ExecutionStarted {
+                    execution_id,
+                    status: ExecutionStatus::Running.as_str().to_string(),
+                })),
+            Err(e) => Ok(execution_error(workflow_id, e)),
+        };
+    }
+
+    match data
+        .runtime
+        .execute_workflow_in(workflow_id, converted_inputs, env)
+        .await
+    {
+        Ok(result) => {
+            info!(
+                "Workflow {} completed: {}/{} nodes",
+                workflow_id, result.completed_nodes, result.total_nodes
+            );
+
+            Ok(HttpResponse::Ok().json(ExecutionResponse {
+                execution_id: result.execution_id,
+                completed_nodes: result.completed_nodes,
+                total_nodes: result.total_nodes,
+                outputs: result.outputs,
+            }))
+        }
+        Err(e) => Ok(execution_error(workflow_id, e)),
+    }
 }
 
-/// Health check — returns service status
+/// Execution history for a workflow, most recent first
 #[utoipa::path(
     get,
-    path = "/health",
+    path = "/api/workflows/{id}/executions",
+    params(
+        ("id" = Uuid, description = "Workflow UUID"),
+        ("limit" = Option<usize>, Query, description = "Maximum records to return (default 50)")
+    ),
     responses(
-        (status = 200, description = "Service is healthy", body = serde_json::Value)
+        (status = 200, description = "Recorded executions", body = Vec<serde_json::Value>),
+        (status = 500, description = "History could not be read", body = ErrorResponse)
     )
 )]
-#[get("/health")]
-async fn health_check() -> impl Responder {
-    HttpResponse::Ok().json(serde_json::json!({
-        "status": "healthy",
-        "version": env!("CARGO_PKG_VERSION"),
-        "service": "flowengine"
-    }))
+#[get("/api/workflows/{id}/executions")]
+async fn workflow_executions(
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+    query: web::Query<HistoryQuery>,
+) -> ActixResult<impl Responder> {
+    let workflow_id = path.into_inner();
+    let limit = query.limit.unwrap_or(50);
+
+    match data
+        .store
+        .get_execution_history(Some(workflow_id), limit)
+        .await
+    {
+        Ok(history) => Ok(HttpResponse::Ok().json(history)),
+        Err(e) => Ok(storage_error(e)),
+    }
 }
 
-/// List all registered workflows (summary only — no full definitions)
+/// List executions, most recently started first. Running executions come
+/// from the runtime; finished ones also from the execution history.
 #[utoipa::path(
     get,
-    path = "/api/workflows",
+    path = "/api/executions",
+    params(
+        ("workflow_id" = Option<Uuid>, Query, description = "Only executions of this workflow"),
+        ("status" = Option<String>, Query, description = "running, waiting, paused, completed, failed or cancelled"),
+        ("limit" = Option<usize>, Query, description = "Maximum executions to return (default 50)"),
+        ("offset" = Option<usize>, Query, description = "Number of executions to skip")
+    ),
     responses(
-        (status = 200, description = "List of workflow summaries", body = Vec<serde_json::Value>)
+        (status = 200, description = "Matching executions", body = Vec<ExecutionDetails>),
+        (status = 400, description = "Unknown status filter", body = ErrorResponse),
+        (status = 500, description = "History could not be read", body = ErrorResponse)
     )
 )]
-#[get("/api/workflows")]
-async fn list_workflows(data: web::Data<AppState>) -> ActixResult<impl Responder> {
-    let workflows = data.workflows.read().await;
-    let workflow_list: Vec<_> = workflows
-        .values()
-        .map(|w| {
-            serde_json::json!({
-                "id": w.id,
-                "name": w.name,
-                "description": w.description,
-                "nodes": w.nodes.len(),
-                "connections": w.connections.len(),
-            })
-        })
+#[get("/api/executions")]
+async fn list_executions(
+    data: web::Data<AppState>,
+    query: web::Query<ExecutionListQuery>,
+) -> ActixResult<impl Responder> {
+    let query = query.into_inner();
+    let limit = query.limit.unwrap_or(50);
+    let offset = query.offset.unwrap_or(0);
+
+    let status = match query.status.as_deref() {
+        None => None,
+        Some(name) => match ExecutionStatus::from_name(name) {
+            Some(status) => Some(status),
+            None => {
+                return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+                    error: format!("Unknown execution status: {}", name),
+                }))
+            }
+        },
+    };
+
+    let mut executions: Vec<ExecutionDetails> = data
+        .runtime
+        .list_executions()
+        .await
+        .into_iter()
+        .filter(|e| query.workflow_id.is_none_or(|id| e.workflow_id == id))
+        .filter(|e| status.as_ref().is_none_or(|s| e.status == *s))
+        .map(ExecutionDetails::from)
         .collect();
 
-    Ok(HttpResponse::Ok().json(workflow_list))
+    // History only holds finished executions
+    if status.as_ref().is_none_or(|s| s.is_finished()) {
+        let history = data
+            .store
+            .query_executions(&ExecutionQuery {
+                workflow_id: query.workflow_id,
+                status,
+                limit: offset + limit,
+                ..Default::default()
+            })
+            .await;
+        match history {
+            Ok(records) => {
+                for record in records {
+                    if !executions.iter().any(|e| e.execution_id == record.id) {
+                        executions.push(record.into());
+                    }
+                }
+            }
+            Err(e) => return Ok(storage_error(e)),
+        }
+    }
+
+    executions.sort_by_key(|e| std::cmp::Reverse(e.started_at));
+    let page: Vec<_> = executions.into_iter().skip(offset).take(limit).collect();
+    Ok(HttpResponse::Ok().json(page))
 }
 
-/// Create a new workflow from a FlowEngine workflow definition
+/// Status, per-node progress and outputs of an execution
 #[utoipa::path(
-    post,
-    path = "/api/workflows",
-    request_body = serde_json::Value,
+    get,
+    path = "/api/executions/{id}",
+    params(
+        ("id" = Uuid, description = "Execution UUID")
+    ),
     responses(
-        (status = 201, description = "Workflow created", body = WorkflowResponse),
-        (status = 400, description = "Invalid workflow JSON", body = ErrorResponse)
+        (status = 200, description = "Execution details", body = ExecutionDetails),
+        (status = 404, description = "Execution not found", body = ErrorResponse),
+        (status = 500, description = "History could not be read", body = ErrorResponse)
     )
 )]
-#[post("/api/workflows")]
-async fn create_workflow(
+#[get("/api/executions/{id}")]
+async fn get_execution(
     data: web::Data<AppState>,
-    workflow: web::Json<Workflow>,
+    path: web::Path<Uuid>,
 ) -> ActixResult<impl Responder> {
-    let workflow = workflow.into_inner();
-    let workflow_id = workflow.id;
+    let execution_id = path.into_inner();
 
-    info!("Creating workflow: {} ({})", workflow.name, workflow_id);
+    if let Some(state) = data.runtime.get_execution_status(execution_id).await {
+        return Ok(HttpResponse::Ok().json(ExecutionDetails::from(state)));
+    }
+    // Not tracked in memory (e.g. after a restart) — fall back to history
+    match data.store.get_execution(execution_id).await {
+        Ok(Some(record)) => Ok(HttpResponse::Ok().json(ExecutionDetails::from(record))),
+        Ok(None) => Ok(execution_not_found(execution_id)),
+        Err(e) => Ok(storage_error(e)),
+    }
+}
 
-    data.workflows.write().await.insert(workflow_id, workflow);
+/// Request cancellation of a running execution
+#[utoipa::path(
+    post,
+    path = "/api/executions/{id}/cancel",
+    params(
+        ("id" = Uuid, description = "Execution UUID")
+    ),
+    responses(
+        (status = 202, description = "Cancellation requested", body = ExecutionDetails),
+        (status = 404, description = "Execution not found", body = ErrorResponse),
+        (status = 409, description = "Execution already finished", body = ErrorResponse)
+    )
+)]
+#[post("/api/executions/{id}/cancel")]
+async fn cancel_execution(
+    data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+) -> ActixResult<impl Responder> {
+    let execution_id = path.into_inner();
 
-    if let Some(workflow) = data.workflows.read().await.get(&workflow_id) {
-        data.runtime.register_workflow(workflow.clone()).await;
+    if data.runtime.cancel_execution(execution_id).await {
+        info!("Cancelling execution: {}", execution_id);
+        let state = data.runtime.get_execution_status(execution_id).await;
+        return Ok(HttpResponse::Accepted().json(state.map(ExecutionDetails::from)));
     }
 
-    Ok(HttpResponse::Created().json(WorkflowResponse {
-        id: workflow_id,
-        message: "Workflow created successfully".to_string(),
-    }))
+    Ok(execution_conflict(&data, execution_id, |status| {
+        format!("Execution {} already {}", execution_id, status)
+    })
+    .await)
 }
 
-/// Get a specific workflow by ID (full definition including nodes and connections)
+/// Pause an execution: no further nodes are started, while nodes that are
+/// already running finish
 #[utoipa::path(
-    get,
-    path = "/api/workflows/{id}",
+    post,
+    path = "/api/executions/{id}/pause",
     params(
-        ("id" = Uuid, description = "Workflow UUID")
+        ("id" = Uuid, description = "Execution UUID")
     ),
     responses(
-        (status = 200, description = "Workflow definition", body = serde_json::Value),
-        (status = 404, description = "Workflow not found", body = ErrorResponse)
+        (status = 202, description = "Execution paused", body = ExecutionDetails),
+        (status = 404, description = "Execution not found", body = ErrorResponse),
+        (status = 409, description = "Execution already paused or finished", body = ErrorResponse)
     )
 )]
-#[get("/api/workflows/{id}")]
-async fn get_workflow(
+#[post("/api/executions/{id}/pause")]
+async fn pause_execution(
     data: web::Data<AppState>,
     path: web::Path<Uuid>,
 ) -> ActixResult<impl Responder> {
-    let workflow_id = path.into_inner();
-    let workflows = data.workflows.read().await;
+    let execution_id = path.into_inner();
 
-    match workflows.get(&workflow_id) {
-        Some(workflow) => Ok(HttpResponse::Ok().json(workflow)),
-        None => Ok(HttpResponse::NotFound().json(ErrorResponse {
-            error: format!("Workflow {} not found", workflow_id),
-        })),
+    if data.runtime.pause_execution(execution_id).await {
+        info!("Paused execution: {}", execution_id);
+        let state = data.runtime.get_execution_status(execution_id).await;
+        return Ok(HttpResponse::Accepted().json(state.map(ExecutionDetails::from)));
     }
+
+    Ok(execution_conflict(&data, execution_id, |status| {
+        format!("Execution {} already {}", execution_id, status)
+    })
+    .await)
 }
 
-/// Delete a workflow by ID
+/// Resume a paused execution
 #[utoipa::path(
-    delete,
-    path = "/api/workflows/{id}",
+    post,
+    path = "/api/executions/{id}/resume",
     params(
-        ("id" = Uuid, description = "Workflow UUID")
+        ("id" = Uuid, description = "Execution UUID")
     ),
     responses(
-        (status = 200, description = "Workflow deleted", body = serde_json::Value),
-        (status = 404, description = "Workflow not found", body = ErrorResponse)
+        (status = 202, description = "Execution resumed", body = ExecutionDetails),
+        (status = 404, description = "Execution not found", body = ErrorResponse),
+        (status = 409, description = "Execution not paused", body = ErrorResponse)
     )
 )]
-#[delete("/api/workflows/{id}")]
-async fn delete_workflow(
+#[post("/api/executions/{id}/resume")]
+async fn resume_execution(
     data: web::Data<AppState>,
     path: web::Path<Uuid>,
 ) -> ActixResult<impl Responder> {
-    let workflow_id = path.into_inner();
-    let mut workflows = data.workflows.write().await;
+    let execution_id = path.into_inner();
 
-    match workflows.remove(&workflow_id) {
-        Some(_) => {
-            info!("Deleted workflow: {}", workflow_id);
-            Ok(HttpResponse::Ok().json(serde_json::json!({
-                "message": "Workflow deleted successfully"
-            })))
-        }
-        None => Ok(HttpResponse::NotFound().json(ErrorResponse {
-            error: format!("Workflow {} not found", workflow_id),
-        })),
+    if data.runtime.resume_execution(execution_id).await {
+        info!("Resumed execution: {}", execution_id);
+        let state = data.runtime.get_execution_status(execution_id).await;
+        return Ok(HttpResponse::Accepted().json(state.map(ExecutionDetails::from)));
     }
+
+    Ok(execution_conflict(&data, execution_id, |status| {
+        format!("Execution {} is not paused but {}", execution_id, status)
+    })
+    .await)
 }
 
-/// Execute a previously created workflow with inputs
+/// Send a named signal to an execution. `flow.wait_for_signal` nodes
+/// waiting for it continue with the JSON body (if any) as their `payload`
+/// output; a signal nothing waits for yet is kept until a node does.
 #[utoipa::path(
     post,
-    path = "/api/workflows/{id}/execute",
+    path = "/api/executions/{id}/signals/{name}",
     params(
-        ("id" = Uuid, description = "Workflow UUID")
+        ("id" = Uuid, description = "Execution UUID"),
+        ("name" = String, description = "Signal name")
     ),
-    request_body = ExecuteRequest,
+    request_body(content = Object, description = "Optional JSON payload", content_type = "application/json"),
     responses(
-        (status = 200, description = "Workflow executed successfully", body = ExecutionResponse),
-        (status = 404, description = "Workflow not found", body = ErrorResponse),
-        (status = 500, description = "Execution failed", body = ErrorResponse)
+        (status = 202, description = "Signal delivered", body = ExecutionDetails),
+        (status = 400, description = "Body is not JSON", body = ErrorResponse),
+        (status = 404, description = "Execution not found", body = ErrorResponse),
+        (status = 409, description = "Execution already finished", body = ErrorResponse)
     )
 )]
-#[post("/api/workflows/{id}/execute")]
-async fn execute_workflow(
+#[post("/api/executions/{id}/signals/{name}")]
+async fn send_signal(
     data: web::Data<AppState>,
-    path: web::Path<Uuid>,
-    req: web::Json<ExecuteRequest>,
+    path: web::Path<(Uuid, String)>,
+    body: web::Bytes,
 ) -> ActixResult<impl Responder> {
-    let workflow_id = path.into_inner();
-    let inputs = req.into_inner().inputs;
+    let (execution_id, name) = path.into_inner();
 
-    info!("Executing workflow: {}", workflow_id);
+    let payload = if body.is_empty() {
+        Value::Null
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(json) => Value::Json(json),
+            Err(e) => {
+                return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+                    error: format!("Signal payload must be JSON: {}", e),
+                }))
+            }
+        }
+    };
 
-    let converted_inputs: HashMap<String, Value> = inputs
-        .into_iter()
-        .map(|(k, v)| (k, Value::Json(v)))
-        .collect();
+    if data.runtime.send_signal(execution_id, &name, payload).await {
+        info!("Signal '{}' sent to execution {}", name, execution_id);
+        let state = data.runtime.get_execution_status(execution_id).await;
+        return Ok(HttpResponse::Accepted().json(state.map(ExecutionDetails::from)));
+    }
 
-    match data.runtime.execute_workflow(workflow_id, converted_inputs).await {
-        Ok(result) => {
-            info!(
-                "Workflow {} completed: {}/{} nodes",
-                workflow_id, result.completed_nodes, result.total_nodes
-            );
+    Ok(execution_conflict(&data, execution_id, |status| {
+        format!("Execution {} already {}", execution_id, status)
+    })
+    .await)
+}
 
-            Ok(HttpResponse::Ok().json(ExecutionResponse {
-                execution_id: result.execution_id,
-                completed_nodes: result.completed_nodes,
-                total_nodes: result.total_nodes,
-            }))
-        }
-        Err(e) => {
-            error!("Workflow {} execution failed: {}", workflow_id, e);
-            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
-                error: e.to_string(),
-            }))
+/// Name clients filter events by: the node event type for node events
+/// (`StdoutLine`, `Progress`, ...) and the execution event type otherwise
+fn event_name(event: &ExecutionEvent) -> &'static str {
+    match event {
+        ExecutionEvent::NodeEvent { event, .. } => event.type_name(),
+        _ => event.type_name(),
+    }
+}
+
+/// Whether `event` is one of `types`, by [`event_name`] or, so that
+/// `NodeEvent` selects all node events, by its execution event type
+fn event_matches(event: &ExecutionEvent, types: &HashSet<String>) -> bool {
+    types.contains(event_name(event)) || types.contains(event.type_name())
+}
+
+/// Send one event to an SSE client. Returns false once the stream should
+/// end, because the client went away or the workflow completed.
+async fn send_sse(
+    tx: &tokio::sync::mpsc::Sender<Result<web::Bytes, actix_web::Error>>,
+    types: &Option<HashSet<String>>,
+    event: &SequencedEvent,
+) -> bool {
+    let name = event_name(&event.event);
+    let wanted = types
+        .as_ref()
+        .is_none_or(|types| event_matches(&event.event, types));
+    if wanted {
+        let data = serde_json::to_string(&event.event).unwrap_or_default();
+        let frame = format!("id: {}\nevent: {}\ndata: {}\n\n", event.seq, name, data);
+        if tx.send(Ok(web::Bytes::from(frame))).await.is_err() {
+            return false;
         }
     }
+    !matches!(event.event, ExecutionEvent::WorkflowCompleted { .. })
 }
 
-/// WebSocket endpoint for real-time execution events.
-/// NOTE: not included in OpenAPI spec (WebSocket not modeled by OpenAPI 3.x).
-#[get("/api/events")]
-async fn websocket_events(
-    req: actix_web::HttpRequest,
-    stream: web::Payload,
+/// Follow one execution as Server-Sent Events. Events already emitted are
+/// replayed first (after `Last-Event-ID` when reconnecting), and the stream
+/// closes once the workflow completes.
+#[utoipa::path(
+    get,
+    path = "/api/executions/{id}/stream",
+    params(
+        ("id" = Uuid, description = "Execution UUID"),
+        ("types" = Option<String>, Query, description = "Comma-separated event types to send (e.g. StdoutLine,NodeFailed)"),
+        ("last_event_id" = Option<u64>, Query, description = "Resume after this event id (alternative to the Last-Event-ID header)"),
+        ("Last-Event-ID" = Option<u64>, Header, description = "Resume after this event id")
+    ),
+    responses(
+        (status = 200, description = "text/event-stream of execution events"),
+        (status = 400, description = "Invalid Last-Event-ID", body = ErrorResponse),
+        (status = 404, description = "Execution not found or no longer held in memory", body = ErrorResponse)
+    )
+)]
+#[get("/api/executions/{id}/stream")]
+async fn stream_execution(
     data: web::Data<AppState>,
+    path: web::Path<Uuid>,
+    query: web::Query<StreamQuery>,
+    req: HttpRequest,
 ) -> ActixResult<HttpResponse> {
-    let (res, mut session, mut msg_stream) = actix_ws::handle(&req, stream)?;
+    let execution_id = path.into_inner();
+    let query = query.into_inner();
 
-    info!("WebSocket client connected");
+    let after = match req.headers().get("Last-Event-ID") {
+        Some(value) => match value.to_str().unwrap_or_default().trim().parse() {
+            Ok(id) => Some(id),
+            Err(_) => {
+                return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+                    error: "Last-Event-ID must be an event id".to_string(),
+                }))
+            }
+        },
+        None => query.last_event_id,
+    };
+    let types: Option<HashSet<String>> = query.types.map(|types| {
+        types
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect()
+    });
 
-    let mut events = data.runtime.subscribe_events();
+    let Some(follow) = data.runtime.follow_execution(execution_id, after).await else {
+        return Ok(execution_not_found(execution_id));
+    };
 
+    let runtime = data.runtime.clone();
+    let (tx, rx) = tokio::sync::mpsc::channel(128);
     actix_web::rt::spawn(async move {
+        // Comments keep proxies from timing out quiet executions
+        let period = std::time::Duration::from_secs(15);
+        let mut keep_alive = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        let mut follow = follow;
+        let mut last = after;
         loop {
-            tokio::select! {
-                event = events.recv() => {
-                    match event {
-                        Ok(event) => {
-                            if let Ok(json) = serde_json::to_string(&event) {
-                                if session.text(json).await.is_err() {
-                                    break;
-                                }
-                            }
-                        }
-                        Err(_) => break,
-                    }
+            let ExecutionFollow { replay, live } = follow;
+            for event in &replay {
+                if !send_sse(&tx, &types, event).await {
+                    return;
                 }
+                last = Some(event.seq);
+            }
+            let Some(mut live) = live else {
+                return;
+            };
 
-                Some(Ok(msg)) = msg_stream.recv() => {
-                    match msg {
-                        Message::Ping(bytes) => {
-                            if session.pong(&bytes).await.is_err() {
-                                break;
+            loop {
+                tokio::select! {
+                    event = live.recv() => match event {
+                        Ok(event) => {
+                            if !send_sse(&tx, &types, &event).await {
+                                return;
                             }
+                            last = Some(event.seq);
+                        }
+                        // Fell behind the live feed; catch up from the journal
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => break,
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+                    },
+                    _ = keep_alive.tick() => {
+                        if tx.send(Ok(web::Bytes::from_static(b": keep-alive\n\n"))).await.is_err() {
+                            return;
                         }
-                        Message::Close(_) => break,
-                        _ => {}
                     }
                 }
+            }
 
-                else => break,
+            // Evicted meanwhile: end the stream, a reconnect gets the 404
+            match runtime.follow_execution(execution_id, last).await {
+                Some(next) => follow = next,
+                None => return,
             }
         }
-
-        info!("WebSocket client disconnected");
-        let _ = session.close(None).await;
     });
 
-    Ok(res)
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(tokio_stream::wrappers::ReceiverStream::new(rx)))
 }
 
 /// List all available node types that can be used in workflow definitions
@@ -351,16 +2012,52 @@ async fn list_node_types(data: web::Data<AppState>) -> ActixResult<impl Responde
         health_check,
         list_workflows,
         create_workflow,
+        lint_workflow,
         get_workflow,
+        update_workflow,
+        patch_workflow,
+        list_revisions,
+        get_revision,
+        export_workflow,
+        diff_revisions,
+        rollback_workflow,
+        list_schedules,
         delete_workflow,
         execute_workflow,
+        workflow_executions,
+        list_executions,
+        get_execution,
+        cancel_execution,
+        pause_execution,
+        resume_execution,
+        send_signal,
+        stream_execution,
+        list_subscriptions,
+        create_subscription,
+        delete_subscription,
+        list_credentials,
+        create_credential,
+        get_credential,
+        put_credential,
+        delete_credential,
+        list_environments,
+        get_environment,
+        put_environment,
+        delete_environment,
+        download_artifact,
         list_node_types,
     ),
     components(
         schemas(
             ExecuteRequest,
+            RollbackRequest,
+            SubscriptionRequest,
+            CredentialRequest,
+            EnvironmentResponse,
             WorkflowResponse,
             ExecutionResponse,
+            ExecutionStarted,
+            ExecutionDetails,
             ErrorResponse,
             NodeTypeInfo,
         )
@@ -515,6 +2212,18 @@ async fn openapi_spec() -> impl Responder {
     HttpResponse::Ok().json(ApiDoc::openapi())
 }
 
+/// Parse an optional numeric environment variable, ignoring bad values
+fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let value = std::env::var(name).ok()?;
+    match value.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            error!("Ignoring {}={}: not a number", name, value);
+            None
+        }
+    }
+}
+
 #[actix_web::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt()
@@ -526,18 +2235,138 @@ async fn main() -> anyhow::Result<()> {
     let mut registry = flowruntime::NodeRegistry::new();
     flownodes::register_all(&mut registry);
 
-    let runtime = FlowRuntime::with_registry(
-        Arc::new(registry),
-        flowruntime::RuntimeConfig::default(),
+    // Finished executions are persisted, so memory only needs recent ones
+    let mut config = flowruntime::RuntimeConfig::default();
+    if let Some(max) = env_parse("EXECUTION_RETENTION") {
+        config.max_finished_executions = max;
+    }
+    if let Some(secs) = env_parse("EXECUTION_TTL_SECS") {
+        config.finished_execution_ttl = Some(std::time::Duration::from_secs(secs));
+    }
+    config.artifacts = flowcore::ArtifactStore::new(
+        std::env::var("ARTIFACT_DIR").unwrap_or_else(|_| "artifacts".to_string()),
+    );
+    if let Ok(dir) = std::env::var("WORKSPACE_DIR") {
+        config.workspace_root = dir.into();
+    }
+
+    let database_path =
+        std::env::var("DATABASE_PATH").unwrap_or_else(|_| "flowengine.db".to_string());
+    let store = PersistentStore::connect(&database_path).await?;
+    info!(
+        "💾 Using {} store at {}",
+        store.backend().backend_name(),
+        database_path
     );
 
+    // Node config refers to credentials, which are resolved as nodes start
+    let credentials = match std::env::var("CREDENTIAL_KEY") {
+        Ok(key) => {
+            let vault = store.credentials(CredentialKey::from_base64(&key)?);
+            config.credentials = Some(Arc::new(vault.clone()));
+            info!("🔐 Credential store enabled");
+            Some(vault)
+        }
+        Err(_) => {
+            info!("Credential store disabled; set CREDENTIAL_KEY to enable it");
+            None
+        }
+    };
+
+    let runtime = FlowRuntime::with_registry(Arc::new(registry), config);
+
     info!("✅ Runtime initialized with standard nodes");
 
+    // Re-register everything saved by previous runs
+    let saved = store.list_workflows().await?;
+    for (id, name) in &saved {
+        match store.load_workflow(*id).await {
+            Ok(Some(workflow)) => runtime.register_workflow(workflow).await,
+            Ok(None) => {}
+            Err(e) => error!("Failed to load workflow {} ({}): {}", name, id, e),
+        }
+    }
+    info!("📂 Loaded {} saved workflows", saved.len());
+    let environments = store.list_environments().await?;
+    info!("📂 Loaded {} environments", environments.len());
+    for environment in environments {
+        runtime.register_environment(environment).await;
+    }
+
+    runtime.add_listener(Arc::new(store.clone()));
+
+    // Run on_failure workflows and notify webhook subscribers; aborts on drop
+    let notifier = notifications::Notifier::new(runtime.clone(), store.clone());
+    runtime.add_listener(notifier.clone());
+    let _notification_handle = notifier.spawn();
+
+    // Start workflows from other workflows' events; tasks abort on drop
+    let event_triggers = event_triggers::EventTriggers::new(runtime.clone());
+    runtime.add_listener(event_triggers.clone());
+    let _event_trigger_handle = event_triggers.spawn();
+    let _iggy_trigger_handle = std::env::var("IGGY_CONNECTION").ok().map(|connection_string| {
+        let defaults = flowcore::events::IggyEventBusConfig::default();
+        event_triggers.spawn_iggy(flowcore::events::IggyEventBusConfig {
+            connection_string,
+            stream_name: std::env::var("IGGY_STREAM").unwrap_or(defaults.stream_name),
+            topic_name: std::env::var("IGGY_TOPIC").unwrap_or(defaults.topic_name),
+            ..defaults
+        })
+    });
+
+    // Continue executions that were waiting for a signal when the last
+    // process stopped, now that every listener is in place
+    let checkpoints = store.list_checkpoints().await?;
+    for checkpoint in checkpoints {
+        let execution_id = checkpoint.execution_id;
+        let workflow = match store
+            .load_revision(checkpoint.workflow_id, checkpoint.workflow_revision)
+            .await
+        {
+            Ok(Some(workflow)) => workflow,
+            Ok(None) => {
+                warn!(
+                    "Dropping checkpoint of execution {}: workflow {} revision {} is gone",
+                    execution_id, checkpoint.workflow_id, checkpoint.workflow_revision
+                );
+                let _ = store.delete_checkpoint(execution_id).await;
+                continue;
+            }
+            Err(e) => {
+                error!("Failed to load workflow for execution {}: {}", execution_id, e);
+                continue;
+            }
+        };
+        match runtime.restore_execution(workflow, checkpoint).await {
+            Ok(_) => info!("⏯️  Resumed waiting execution {}", execution_id),
+            Err(e) => error!("Failed to resume execution {}: {}", execution_id, e),
+        }
+    }
+
+    // Workspaces of executions that were not resumed are of no use
+    match runtime.prune_workspaces().await {
+        Ok(0) => {}
+        Ok(removed) => info!("🧹 Removed {} stale execution workspaces", removed),
+        Err(e) => warn!("Failed to remove stale execution workspaces: {}", e),
+    }
+
     let app_state = web::Data::new(AppState {
         runtime: Arc::new(runtime),
-        workflows: Arc::new(RwLock::new(HashMap::new())),
+        store,
+        credentials,
     });
 
+    // Fire cron triggers; aborts on drop
+    let _scheduler_handle =
+        scheduler::spawn(Arc::clone(&app_state.runtime), app_state.store.clone());
+
+    // Watch directories of file-watch triggers; aborts on drop
+    let _file_watch_handle = file_watcher::spawn(Arc::clone(&app_state.runtime));
+
+    // Delete blobs nothing refers to any more; aborts on drop
+    let _artifact_gc_handle =
+        artifacts::spawn(Arc::clone(&app_state.runtime), app_state.store.clone());
+
     let rest_bind = std::env::var("BIND_ADDRESS").unwrap_or_else(|_| "0.0.0.0:3000".to_string());
     let grpc_bind = std::env::var("GRPC_BIND_ADDRESS").unwrap_or_else(|_| "0.0.0.0:3001".to_string());
 
@@ -546,10 +2375,11 @@ async fn main() -> anyhow::Result<()> {
     info!("📖 OpenAPI spec at http://{}/api-docs/openapi.json", rest_bind);
     info!("🔍 Swagger UI at http://{}/api-docs/", rest_bind);
 
-    // Clone state for gRPC server (shared workflows + runtime)
+    // Clone state for gRPC server (shared store + runtime)
     let grpc_state = Arc::new(AppState {
         runtime: Arc::clone(&app_state.runtime),
-        workflows: Arc::clone(&app_state.workflows),
+        store: app_state.store.clone(),
+        credentials: app_state.credentials.clone(),
     });
 
     let grpc_addr = grpc_bind.parse()?;
@@ -581,10 +2411,41 @@ async fn main() -> anyhow::Result<()> {
             .service(health_check)
             .service(list_workflows)
             .service(create_workflow)
+            .service(lint_workflow)
             .service(get_workflow)
+            .service(update_workflow)
+            .service(patch_workflow)
+            .service(list_revisions)
+            .service(get_revision)
+            .service(export_workflow)
+            .service(diff_revisions)
+            .service(rollback_workflow)
+            .service(list_schedules)
+            .service(web::resource("/hooks/{path:.*}").to(webhooks::receive))
             .service(delete_workflow)
             .service(execute_workflow)
-            .service(websocket_events)
+            .service(workflow_executions)
+            .service(list_executions)
+            .service(get_execution)
+            .service(cancel_execution)
+            .service(pause_execution)
+            .service(resume_execution)
+            .service(send_signal)
+            .service(stream_execution)
+            .service(list_subscriptions)
+            .service(create_subscription)
+            .service(delete_subscription)
+            .service(list_credentials)
+            .service(create_credential)
+            .service(get_credential)
+            .service(put_credential)
+            .service(delete_credential)
+            .service(list_environments)
+            .service(get_environment)
+            .service(put_environment)
+            .service(delete_environment)
+            .service(download_artifact)
+            .service(websocket::websocket_events)
             .service(list_node_types)
             .service(agent_card)
             .service(openapi_spec)
@@ -0,0 +1,412 @@
+//! Failure handling and outbound notifications
+//!
+//! When an execution fails, the workflow named by its `on_failure` setting
+//! runs with the failure context as inputs: `execution_id`, `workflow_id`,
+//! `workflow_name`, `failed_node`, `error` and the failed node's last
+//! `stderr` lines. Lifecycle events (`execution.started`, `.completed`,
+//! `.failed`, `.cancelled`) are also POSTed to every matching webhook
+//! subscription, signed like incoming webhooks when it has a secret.
+
+use async_trait::async_trait;
+use flowcore::{ExecutionEvent, ExecutionId, FlowError, NodeEvent, Value, Workflow};
+use flowpersist::{PersistentStore, WebhookSubscription};
+use flowruntime::{
+    ExecutionListener, ExecutionResult, ExecutionState, ExecutionStatus, FlowRuntime, NodeStatus,
+};
+use serde_json::json;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use super::webhooks;
+
+/// Event types subscriptions can ask for
+pub const EVENT_TYPES: &[&str] = &[
+    "execution.started",
+    "execution.completed",
+    "execution.failed",
+    "execution.cancelled",
+];
+
+/// Header naming the event type of a delivery
+const EVENT_HEADER: &str = "X-Flowengine-Event";
+
+/// Stderr lines of the failed node passed to the failure workflow
+const STDERR_TAIL: usize = 20;
+
+const DELIVERY_ATTEMPTS: u32 = 3;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct Notifier {
+    runtime: FlowRuntime,
+    store: PersistentStore,
+    client: reqwest::Client,
+    /// Unfinished executions started as failure workflows. Their own
+    /// failures are only logged, so failure workflows can't cascade.
+    handlers: Mutex<HashSet<ExecutionId>>,
+}
+
+impl Notifier {
+    pub fn new(runtime: FlowRuntime, store: PersistentStore) -> Arc<Self> {
+        Arc::new(Self {
+            runtime,
+            store,
+            client: reqwest::Client::new(),
+            handlers: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Notify subscribers of executions starting
+    pub fn spawn(self: &Arc<Self>) -> JoinHandle<()> {
+        let notifier = Arc::clone(self);
+        let mut events = self.runtime.subscribe_events();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(ExecutionEvent::WorkflowStarted {
+                        execution_id,
+                        workflow_id,
+                        timestamp,
+                    }) => {
+                        let workflow_name = notifier
+                            .runtime
+                            .list_workflows()
+                            .await
+                            .into_iter()
+                            .find(|w| w.id == workflow_id)
+                            .map(|w| w.name);
+                        notifier
+                            .notify(
+                                "execution.started",
+                                workflow_id,
+                                json!({
+                                    "execution_id": execution_id,
+                                    "workflow_id": workflow_id,
+                                    "workflow_name": workflow_name,
+                                    "started_at": timestamp,
+                                }),
+                            )
+                            .await;
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(n)) => {
+                        warn!("Notifications fell behind and missed {} events", n)
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Deliver an event to every subscription that wants it, in the background
+    async fn notify(&self, event_type: &str, workflow_id: Uuid, data: serde_json::Value) {
+        let subscriptions = match self.store.list_subscriptions().await {
+            Ok(subscriptions) => subscriptions,
+            Err(e) => {
+                error!("Failed to load webhook subscriptions: {}", e);
+                return;
+            }
+        };
+
+        let body = json!({
+            "event": event_type,
+            "timestamp": chrono::Utc::now(),
+            "data": data,
+        })
+        .to_string();
+        for subscription in subscriptions
+            .into_iter()
+            .filter(|s| s.wants(event_type, workflow_id))
+        {
+            tokio::spawn(deliver(
+                self.client.clone(),
+                subscription,
+                event_type.to_string(),
+                body.clone(),
+            ));
+        }
+    }
+
+    /// What went wrong, from the final state and the execution's journal
+    async fn failure_context(&self, workflow: &Workflow, state: &ExecutionState) -> serde_json::Value {
+        let failed = state
+            .node_progress
+            .iter()
+            .filter(|(_, p)| p.status == NodeStatus::Failed)
+            .min_by_key(|(_, p)| p.completed_at)
+            .map(|(id, p)| (*id, p.error.clone()));
+        let failed_node = failed.as_ref().map(|(id, _)| {
+            let spec = workflow.nodes.iter().find(|n| n.id == *id);
+            json!({
+                "id": id,
+                "name": spec.and_then(|n| n.name.clone()),
+                "node_type": spec.map(|n| n.node_type.clone()),
+            })
+        });
+
+        let mut stderr = VecDeque::new();
+        if let Some(follow) = self.runtime.follow_execution(state.execution_id, None).await {
+            for sequenced in follow.replay {
+                if let ExecutionEvent::NodeEvent {
+                    node_id,
+                    event: NodeEvent::StderrLine { line },
+                    ..
+                } = sequenced.event
+                {
+                    if failed.as_ref().is_none_or(|(id, _)| *id == node_id) {
+                        if stderr.len() == STDERR_TAIL {
+                            stderr.pop_front();
+                        }
+                        stderr.push_back(line);
+                    }
+                }
+            }
+        }
+
+        json!({
+            "execution_id": state.execution_id,
+            "workflow_id": workflow.id,
+            "workflow_name": workflow.name,
+            "failed_node": failed_node,
+            "error": failed.and_then(|(_, e)| e).or_else(|| state.error.clone()),
+            "stderr": Vec::from(stderr).join("\n"),
+        })
+    }
+
+    /// Start the workflow's `on_failure` workflow with the failure context
+    async fn run_failure_workflow(&self, workflow: &Workflow, handler_id: Uuid, context: &serde_json::Value) {
+        let inputs: HashMap<String, Value> = context
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| (key.clone(), Value::Json(value.clone())))
+            .collect();
+
+        let mut handlers = self.handlers.lock().await;
+        match self.runtime.start_workflow(handler_id, inputs).await {
+            Ok(execution_id) => {
+                handlers.insert(execution_id);
+                info!(
+                    "Failure of execution {} started execution {} of on_failure workflow {}",
+                    context["execution_id"].as_str().unwrap_or_default(),
+                    execution_id,
+                    handler_id
+                );
+            }
+            Err(e) => error!(
+                "Workflow {} failed and its on_failure workflow {} could not start: {}",
+                workflow.id, handler_id, e
+            ),
+        }
+    }
+}
+
+/// POST one event to a subscriber, retrying with backoff
+async fn deliver(client: reqwest::Client, subscription: WebhookSubscription, event_type: String, body: String) {
+    for attempt in 1..=DELIVERY_ATTEMPTS {
+        let mut request = client
+            .post(&subscription.url)
+            .timeout(DELIVERY_TIMEOUT)
+            .header("content-type", "application/json")
+            .header(EVENT_HEADER, &event_type);
+        if let Some(secret) = &subscription.secret {
+            request = request.header(
+                webhooks::DEFAULT_SIGNATURE_HEADER,
+                webhooks::sign(secret, body.as_bytes()),
+            );
+        }
+
+        match request.body(body.clone()).send().await {
+            Ok(response) if response.status().is_success() => return,
+            Ok(response) => warn!(
+                "Subscription {} answered {} to {} (attempt {}/{})",
+                subscription.id,
+                response.status(),
+                event_type,
+                attempt,
+                DELIVERY_ATTEMPTS
+            ),
+            Err(e) => warn!(
+                "Delivering {} to subscription {} failed (attempt {}/{}): {}",
+                event_type, subscription.id, attempt, DELIVERY_ATTEMPTS, e
+            ),
+        }
+        if attempt < DELIVERY_ATTEMPTS {
+            tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
+        }
+    }
+    error!(
+        "Gave up delivering {} to subscription {} at {}",
+        event_type, subscription.id, subscription.url
+    );
+}
+
+#[async_trait]
+impl ExecutionListener for Notifier {
+    async fn on_execution_finished(
+        &self,
+        workflow: &Workflow,
+        state: &ExecutionState,
+        _result: &Result<ExecutionResult, FlowError>,
+    ) {
+        let was_handler = self.handlers.lock().await.remove(&state.execution_id);
+
+        let mut data = match state.status {
+            ExecutionStatus::Failed => {
+                let context = self.failure_context(workflow, state).await;
+                match workflow.settings.on_failure {
+                    Some(_) if was_handler => warn!(
+                        "on_failure workflow {} failed itself; not starting another",
+                        workflow.id
+                    ),
+                    Some(handler_id) => self.run_failure_workflow(workflow, handler_id, &context).await,
+                    None => {}
+                }
+                context
+            }
+            _ => json!({
+                "execution_id": state.execution_id,
+                "workflow_id": workflow.id,
+                "workflow_name": workflow.name,
+                "error": state.error,
+            }),
+        };
+        data["status"] = json!(state.status.as_str());
+        data["started_at"] = json!(state.started_at);
+        data["completed_at"] = json!(state.completed_at);
+        self.notify(&format!("execution.{}", state.status.as_str()), workflow.id, data)
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flowcore::NodeSpec;
+    use flowruntime::{NodeRegistry, RuntimeConfig};
+
+    fn notifier() -> (FlowRuntime, Arc<Notifier>) {
+        let mut registry = NodeRegistry::new();
+        flownodes::register_all(&mut registry);
+        let runtime = FlowRuntime::with_registry(Arc::new(registry), RuntimeConfig::default());
+        let notifier = Notifier::new(runtime.clone(), PersistentStore::in_memory().unwrap());
+        runtime.add_listener(notifier.clone());
+        (runtime, notifier)
+    }
+
+    /// A workflow passing its inputs through as outputs
+    fn pass_through(name: &str) -> Workflow {
+        let mut workflow = Workflow::new(name);
+        workflow.add_node(NodeSpec::new("time.delay").with_config("delay_ms", Value::Number(0.0)));
+        workflow
+    }
+
+    fn shell(name: &str, command: &str, on_failure: Uuid) -> Workflow {
+        let mut workflow = Workflow::new(name);
+        workflow.add_node(
+            NodeSpec::new("shell.exec")
+                .with_name("build")
+                .with_config("command", command)
+                .with_config("shell", true),
+        );
+        workflow.settings.on_failure = Some(on_failure);
+        workflow
+    }
+
+    async fn runs_of(runtime: &FlowRuntime, workflow_id: Uuid) -> Vec<ExecutionState> {
+        let executions = runtime.list_executions().await;
+        executions.into_iter().filter(|e| e.workflow_id == workflow_id).collect()
+    }
+
+    /// The first run of `workflow_id`, once it has finished
+    async fn finished_run(runtime: &FlowRuntime, workflow_id: Uuid) -> ExecutionState {
+        for _ in 0..500 {
+            if let Some(run) = runs_of(runtime, workflow_id).await.into_iter().next() {
+                if run.completed_at.is_some() {
+                    return run;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("workflow {} never ran to the end", workflow_id);
+    }
+
+    #[tokio::test]
+    async fn test_failure_workflow_runs_only_on_failure() {
+        let (runtime, _notifier) = notifier();
+        let handler = pass_through("on failure");
+        let handler_id = handler.id;
+        runtime.register_workflow(handler).await;
+
+        // Listeners have run by the time an execution's result is back
+        let passing = shell("passing", "echo fine", handler_id);
+        let passing_id = passing.id;
+        runtime.register_workflow(passing).await;
+        runtime.execute_workflow(passing_id, HashMap::new()).await.unwrap();
+        assert!(runs_of(&runtime, handler_id).await.is_empty());
+
+        let mut slow = pass_through("slow");
+        slow.nodes[0].config.insert("delay_ms".to_string(), Value::Number(5000.0));
+        slow.settings.on_failure = Some(handler_id);
+        let slow_id = slow.id;
+        runtime.register_workflow(slow).await;
+        let execution_id = runtime.start_workflow(slow_id, HashMap::new()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(runtime.cancel_execution(execution_id).await);
+        assert_eq!(finished_run(&runtime, slow_id).await.status, ExecutionStatus::Cancelled);
+        assert!(runs_of(&runtime, handler_id).await.is_empty());
+
+        let failing = shell("failing", "echo first >&2; echo broken >&2; exit 3", handler_id);
+        let failing_id = failing.id;
+        let build = failing.nodes[0].id;
+        runtime.register_workflow(failing).await;
+        assert!(runtime.execute_workflow(failing_id, HashMap::new()).await.is_err());
+        let failed = finished_run(&runtime, failing_id).await;
+
+        let run = finished_run(&runtime, handler_id).await;
+        assert_eq!(run.status, ExecutionStatus::Completed);
+        let inputs = run.outputs.values().next().unwrap();
+        let input = |key: &str| match &inputs[key] {
+            Value::Json(json) => json.clone(),
+            other => panic!("{} is {:?}", key, other),
+        };
+        assert_eq!(input("execution_id"), json!(failed.execution_id));
+        assert_eq!(input("workflow_id"), json!(failing_id));
+        assert_eq!(input("workflow_name"), json!("failing"));
+        assert_eq!(
+            input("failed_node"),
+            json!({"id": build, "name": "build", "node_type": "shell.exec"})
+        );
+        let error = input("error");
+        assert!(
+            error.as_str().is_some_and(|e| e.contains("exited with code 3")),
+            "{}",
+            error
+        );
+        assert_eq!(input("stderr"), json!("first\nbroken"));
+        assert_eq!(runs_of(&runtime, handler_id).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failing_failure_workflow_starts_no_other() {
+        let (runtime, _notifier) = notifier();
+        // Its own failure handler
+        let mut handler = shell("on failure", "exit 1", Uuid::nil());
+        let handler_id = handler.id;
+        handler.settings.on_failure = Some(handler_id);
+        runtime.register_workflow(handler).await;
+        let failing = shell("failing", "exit 2", handler_id);
+        let failing_id = failing.id;
+        runtime.register_workflow(failing).await;
+
+        assert!(runtime.execute_workflow(failing_id, HashMap::new()).await.is_err());
+        assert_eq!(finished_run(&runtime, handler_id).await.status, ExecutionStatus::Failed);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(runs_of(&runtime, handler_id).await.len(), 1);
+    }
+}
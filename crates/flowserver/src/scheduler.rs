@@ -0,0 +1,202 @@
+//! Fires the cron triggers of registered workflows
+//!
+//! Fire times live in the store rather than in memory, so a restarted
+//! server knows which runs it missed, and every fire time is claimed with a
+//! compare-and-swap before anything starts, so two servers sharing a
+//! database never both fire it.
+
+use chrono::{DateTime, Utc};
+use flowcore::{CatchUpPolicy, TriggerSpec, TriggerType, Value, Workflow};
+use flowpersist::{PersistentStore, TriggerSchedule};
+use flowruntime::{CronSchedule, FlowRuntime, MISFIRE_GRACE};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// How often triggers are checked; also the finest resolution of
+/// expressions with a seconds field
+const TICK: Duration = Duration::from_secs(1);
+
+/// Start checking cron triggers in the background
+pub fn spawn(runtime: Arc<FlowRuntime>, store: PersistentStore) -> JoinHandle<()> {
+    let mut scheduler = Scheduler {
+        runtime,
+        store,
+        reported: HashSet::new(),
+    };
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            scheduler.tick(Utc::now()).await;
+        }
+    })
+}
+
+struct Scheduler {
+    runtime: Arc<FlowRuntime>,
+    store: PersistentStore,
+    /// Invalid triggers already logged, with the expression that failed
+    reported: HashSet<(Uuid, String)>,
+}
+
+impl Scheduler {
+    async fn tick(&mut self, now: DateTime<Utc>) {
+        for workflow in self.runtime.list_workflows().await {
+            for trigger in &workflow.triggers {
+                let TriggerType::Cron {
+                    expression,
+                    timezone,
+                    catch_up,
+                } = &trigger.trigger_type
+                else {
+                    continue;
+                };
+
+                let schedule = match CronSchedule::parse(expression, timezone.as_deref()) {
+                    Ok(schedule) => schedule,
+                    Err(e) => {
+                        if self.reported.insert((trigger.id, expression.clone())) {
+                            warn!("Cron trigger {} of workflow {} ignored: {}", trigger.id, workflow.id, e);
+                        }
+                        continue;
+                    }
+                };
+
+                if let Err(e) = self.check(&workflow, trigger, &schedule, *catch_up, now).await {
+                    error!("Cron trigger {} of workflow {}: {}", trigger.id, workflow.id, e);
+                }
+            }
+        }
+    }
+
+    async fn check(
+        &self,
+        workflow: &Workflow,
+        trigger: &TriggerSpec,
+        schedule: &CronSchedule,
+        catch_up: CatchUpPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<(), flowpersist::StoreError> {
+        let stored = self.store.load_schedule(trigger.id).await?;
+
+        // New trigger, or its expression or timezone changed: start
+        // counting from now rather than firing for the past
+        let stored = match stored {
+            Some(stored) if stored.spec == schedule.spec() => stored,
+            stored => {
+                let fresh = TriggerSchedule {
+                    trigger_id: trigger.id,
+                    workflow_id: workflow.id,
+                    spec: schedule.spec().to_string(),
+                    next_fire_at: schedule.next_after(now),
+                    last_fire_at: stored.and_then(|s| s.last_fire_at),
+                };
+                return self.store.put_schedule(&fresh).await;
+            }
+        };
+
+        let Some(next_fire_at) = stored.next_fire_at else {
+            return Ok(());
+        };
+        if next_fire_at > now {
+            return Ok(());
+        }
+
+        // Disabled triggers keep their schedule moving so that enabling
+        // them later doesn't count the disabled period as missed
+        let runs = if trigger.enabled {
+            if now - next_fire_at > MISFIRE_GRACE {
+                info!(
+                    "Cron trigger {} of workflow {} missed runs since {} (catch-up: {:?})",
+                    trigger.id, workflow.id, next_fire_at, catch_up
+                );
+            }
+            schedule.due_runs(next_fire_at, now, catch_up)
+        } else {
+            Vec::new()
+        };
+
+        let advanced = TriggerSchedule {
+            next_fire_at: schedule.next_after(now),
+            last_fire_at: runs.last().copied().or(stored.last_fire_at),
+            ..stored
+        };
+        if !self.store.advance_schedule(&advanced, Some(next_fire_at)).await? {
+            // Another scheduler claimed these runs
+            return Ok(());
+        }
+
+        for scheduled_at in runs {
+            let inputs = HashMap::from([(
+                "scheduled_at".to_string(),
+                Value::String(scheduled_at.to_rfc3339()),
+            )]);
+            match self.runtime.start_workflow(workflow.id, inputs).await {
+                Ok(execution_id) => info!(
+                    "Cron trigger {} started execution {} of workflow {} for {}",
+                    trigger.id, execution_id, workflow.id, scheduled_at
+                ),
+                Err(e) => error!(
+                    "Cron trigger {} failed to start workflow {}: {}",
+                    trigger.id, workflow.id, e
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_disabled_trigger_keeps_schedule_moving() {
+        let runtime = Arc::new(FlowRuntime::new());
+        let mut workflow = Workflow::new("every five minutes");
+        workflow.triggers.push(TriggerSpec {
+            id: Uuid::new_v4(),
+            trigger_type: TriggerType::Cron {
+                expression: "*/5 * * * *".to_string(),
+                timezone: None,
+                catch_up: CatchUpPolicy::All,
+            },
+            enabled: false,
+        });
+        let trigger_id = workflow.triggers[0].id;
+        runtime.register_workflow(workflow.clone()).await;
+        let mut scheduler = Scheduler {
+            runtime: Arc::clone(&runtime),
+            store: PersistentStore::in_memory().unwrap(),
+            reported: HashSet::new(),
+        };
+
+        scheduler.tick(at("2024-01-15T10:02:00Z")).await;
+        let stored = scheduler.store.load_schedule(trigger_id).await.unwrap().unwrap();
+        assert_eq!(stored.next_fire_at, Some(at("2024-01-15T10:05:00Z")));
+
+        // Half an hour disabled: nothing runs, the fire times still advance
+        scheduler.tick(at("2024-01-15T10:31:00Z")).await;
+        let stored = scheduler.store.load_schedule(trigger_id).await.unwrap().unwrap();
+        assert_eq!(stored.next_fire_at, Some(at("2024-01-15T10:35:00Z")));
+        assert_eq!(stored.last_fire_at, None);
+        assert!(runtime.list_executions().await.is_empty());
+
+        // Enabled again, the disabled period isn't caught up on
+        workflow.triggers[0].enabled = true;
+        runtime.register_workflow(workflow).await;
+        scheduler.tick(at("2024-01-15T10:35:10Z")).await;
+        let stored = scheduler.store.load_schedule(trigger_id).await.unwrap().unwrap();
+        assert_eq!(stored.last_fire_at, Some(at("2024-01-15T10:35:00Z")));
+        assert_eq!(runtime.list_executions().await.len(), 1);
+    }
+}
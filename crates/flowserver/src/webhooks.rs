@@ -0,0 +1,473 @@
+//! Webhook triggers: workflows reachable at `/hooks/{path}`
+//!
+//! Any HTTP method is accepted. The request becomes the workflow inputs
+//! `method`, `path`, `headers`, `query` and `body` (a blob when it is not
+//! text). Depending on the
+//! trigger the caller gets `202 Accepted` straight away or waits for the
+//! workflow's `webhook.respond` node.
+
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, HttpResponse};
+use flowcore::{
+    ArtifactStore, ExecutionEvent, ExecutionId, NodeId, TriggerType, Value, WebhookResponse, Workflow,
+    WorkflowError,
+};
+use flownodes::WEBHOOK_RESPOND;
+use flowruntime::FlowRuntime;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use super::{execution_error, AppState, ErrorResponse, ExecutionStarted};
+
+pub const DEFAULT_SIGNATURE_HEADER: &str = "X-Signature-256";
+
+/// How long a caller waits for a `webhook.respond` node
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A webhook trigger found for a request path
+struct Hook {
+    workflow: Workflow,
+    secret: Option<String>,
+    signature_header: Option<String>,
+    response: WebhookResponse,
+}
+
+fn normalize(path: &str) -> &str {
+    path.trim_matches('/')
+}
+
+/// Enabled webhook triggers of a workflow with their normalized paths
+fn webhook_paths(workflow: &Workflow) -> impl Iterator<Item = (&str, &TriggerType)> {
+    workflow
+        .triggers
+        .iter()
+        .filter(|t| t.enabled)
+        .filter_map(|t| match &t.trigger_type {
+            TriggerType::Webhook { path, .. } => Some((normalize(path), &t.trigger_type)),
+            _ => None,
+        })
+}
+
+/// Reject webhook triggers whose path another workflow already serves, or
+/// that wait for a response no node in the workflow can give
+pub async fn check_triggers(runtime: &FlowRuntime, workflow: &Workflow) -> Result<(), WorkflowError> {
+    let mut paths = HashSet::new();
+    for (path, trigger) in webhook_paths(workflow) {
+        if !paths.insert(path) {
+            return Err(WorkflowError::Invalid(format!(
+                "webhook path '{}' is used by more than one trigger",
+                path
+            )));
+        }
+        if let TriggerType::Webhook {
+            response: WebhookResponse::ResponseNode,
+            ..
+        } = trigger
+        {
+            if !workflow.nodes.iter().any(|n| n.node_type == WEBHOOK_RESPOND) {
+                return Err(WorkflowError::Invalid(format!(
+                    "webhook '{}' waits for a {} node but the workflow has none",
+                    path, WEBHOOK_RESPOND
+                )));
+            }
+        }
+    }
+
+    for other in runtime.list_workflows().await {
+        if other.id == workflow.id {
+            continue;
+        }
+        if let Some((path, _)) = webhook_paths(&other).find(|(p, _)| paths.contains(p)) {
+            return Err(WorkflowError::Invalid(format!(
+                "webhook path '{}' is already used by workflow {}",
+                path, other.id
+            )));
+        }
+    }
+    Ok(())
+}
+
+async fn find_hook(runtime: &FlowRuntime, path: &str) -> Option<Hook> {
+    let path = normalize(path);
+    for workflow in runtime.list_workflows().await {
+        let found = webhook_paths(&workflow)
+            .find(|(p, _)| *p == path)
+            .map(|(_, trigger)| trigger.clone());
+        if let Some(TriggerType::Webhook {
+            secret,
+            signature_header,
+            response,
+            ..
+        }) = found
+        {
+            return Some(Hook {
+                workflow,
+                secret,
+                signature_header,
+                response,
+            });
+        }
+    }
+    None
+}
+
+/// Check a hex HMAC-SHA256 of the body, optionally prefixed `sha256=`
+fn verify_signature(secret: &str, signature: &str, body: &[u8]) -> bool {
+    let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// `sha256=`-prefixed hex HMAC-SHA256 of the body, as checked above
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Workflow inputs describing the request; binary bodies go to the
+/// artifact store
+async fn request_inputs(
+    req: &HttpRequest,
+    path: &str,
+    body: &[u8],
+    artifacts: &ArtifactStore,
+) -> HashMap<String, Value> {
+    let headers = req
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+            (name.as_str().to_string(), Value::String(value))
+        })
+        .collect();
+
+    let query = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .map(|q| {
+            q.into_inner()
+                .into_iter()
+                .map(|(k, v)| (k, Value::String(v)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let content_type = req
+        .headers()
+        .get("content-type")
+        .and_then(|ct| ct.to_str().ok());
+    let is_json = content_type.is_some_and(|ct| ct.contains("json"));
+    let body = if body.is_empty() {
+        Value::Null
+    } else if let Some(json) = is_json
+        .then(|| serde_json::from_slice(body).ok())
+        .flatten()
+    {
+        Value::Json(json)
+    } else {
+        match std::str::from_utf8(body) {
+            Ok(text) => Value::String(text.to_string()),
+            Err(_) => {
+                let media_type = content_type.unwrap_or("application/octet-stream");
+                match artifacts.put(body, media_type).await {
+                    Ok(blob) => Value::Blob(blob),
+                    Err(e) => {
+                        warn!("Keeping webhook body inline, storing it failed: {}", e);
+                        Value::Bytes(body.to_vec())
+                    }
+                }
+            }
+        }
+    };
+
+    HashMap::from([
+        ("method".to_string(), Value::String(req.method().to_string())),
+        ("path".to_string(), Value::String(normalize(path).to_string())),
+        ("headers".to_string(), Value::Object(headers)),
+        ("query".to_string(), Value::Object(query)),
+        ("body".to_string(), body),
+    ])
+}
+
+/// Turn the outputs of a `webhook.respond` node into the HTTP response
+async fn node_response(outputs: &HashMap<String, Value>, artifacts: &ArtifactStore) -> HttpResponse {
+    let status = outputs
+        .get("status")
+        .and_then(Value::as_f64)
+        .and_then(|s| StatusCode::from_u16(s as u16).ok())
+        .unwrap_or(StatusCode::OK);
+    let mut response = HttpResponse::build(status);
+
+    let mut has_content_type = false;
+    if let Some(Value::Object(headers)) = outputs.get("headers") {
+        for (name, value) in headers {
+            // Framing is up to the server, whatever the workflow copied
+            if name.eq_ignore_ascii_case("content-length")
+                || name.eq_ignore_ascii_case("transfer-encoding")
+            {
+                continue;
+            }
+            has_content_type |= name.eq_ignore_ascii_case("content-type");
+            response.insert_header((name.as_str(), value.to_string()));
+        }
+    }
+
+    match outputs.get("body") {
+        None | Some(Value::Null) => response.finish(),
+        Some(Value::String(text)) => {
+            if !has_content_type {
+                response.content_type("text/plain; charset=utf-8");
+            }
+            response.body(text.clone())
+        }
+        Some(Value::Bytes(bytes)) => {
+            if !has_content_type {
+                response.content_type("application/octet-stream");
+            }
+            response.body(bytes.clone())
+        }
+        Some(Value::Blob(blob)) => match artifacts.load(blob).await {
+            Ok(bytes) => {
+                if !has_content_type {
+                    response.content_type(blob.media_type.as_str());
+                }
+                response.body(bytes)
+            }
+            Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to load response body {}: {}", blob.hash, e),
+            }),
+        },
+        Some(other) => {
+            if !has_content_type {
+                response.content_type("application/json");
+            }
+            response.body(other.to_json().to_string())
+        }
+    }
+}
+
+/// Wait until a `webhook.respond` node of the execution completes
+async fn await_response(
+    runtime: &FlowRuntime,
+    workflow: &Workflow,
+    execution_id: ExecutionId,
+) -> HttpResponse {
+    let responders: HashSet<NodeId> = workflow
+        .nodes
+        .iter()
+        .filter(|n| n.node_type == WEBHOOK_RESPOND)
+        .map(|n| n.id)
+        .collect();
+    let responded = |event: &ExecutionEvent| match event {
+        ExecutionEvent::NodeCompleted {
+            node_id, outputs, ..
+        } if responders.contains(node_id) => Some(outputs.clone()),
+        _ => None,
+    };
+
+    let wait = async {
+        let mut after = None;
+        loop {
+            let follow = runtime.follow_execution(execution_id, after).await?;
+            for e in &follow.replay {
+                if let Some(outputs) = responded(&e.event) {
+                    return Some(outputs);
+                }
+                after = Some(e.seq);
+            }
+            let mut live = follow.live?;
+            loop {
+                match live.recv().await {
+                    Ok(e) => {
+                        if let Some(outputs) = responded(&e.event) {
+                            return Some(outputs);
+                        }
+                        after = Some(e.seq);
+                    }
+                    // Fell behind the live feed; catch up from the journal
+                    Err(RecvError::Lagged(_)) => break,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }
+    };
+
+    match tokio::time::timeout(RESPONSE_TIMEOUT, wait).await {
+        Ok(Some(outputs)) => node_response(&outputs, runtime.artifacts()).await,
+        Ok(None) => {
+            let error = runtime
+                .get_execution_status(execution_id)
+                .await
+                .and_then(|s| s.error)
+                .unwrap_or_else(|| "no webhook.respond node ran".to_string());
+            warn!("Execution {} finished without responding: {}", execution_id, error);
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Execution {} finished without responding: {}", execution_id, error),
+            })
+        }
+        Err(_) => HttpResponse::GatewayTimeout().json(ErrorResponse {
+            error: format!(
+                "Execution {} did not respond within {}s",
+                execution_id,
+                RESPONSE_TIMEOUT.as_secs()
+            ),
+        }),
+    }
+}
+
+/// Entry point for every `/hooks/...` request.
+/// NOTE: not included in OpenAPI spec (paths are defined by workflows).
+pub async fn receive(
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Bytes,
+    data: web::Data<AppState>,
+) -> HttpResponse {
+    let path = path.into_inner();
+    let Some(hook) = find_hook(&data.runtime, &path).await else {
+        return HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("No webhook at /hooks/{}", normalize(&path)),
+        });
+    };
+
+    if let Some(secret) = &hook.secret {
+        let header = hook
+            .signature_header
+            .as_deref()
+            .unwrap_or(DEFAULT_SIGNATURE_HEADER);
+        let valid = req
+            .headers()
+            .get(header)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|signature| verify_signature(secret, signature, &body));
+        if !valid {
+            warn!("Rejected webhook /hooks/{}: bad or missing {}", normalize(&path), header);
+            return HttpResponse::Unauthorized().json(ErrorResponse {
+                error: format!("Missing or invalid {} header", header),
+            });
+        }
+    }
+
+    let workflow_id = hook.workflow.id;
+    let inputs = request_inputs(&req, &path, &body, data.runtime.artifacts()).await;
+    let execution_id = match data.runtime.start_workflow(workflow_id, inputs).await {
+        Ok(id) => id,
+        Err(e) => return execution_error(workflow_id, e),
+    };
+    info!(
+        "Webhook /hooks/{} started execution {} of workflow {}",
+        normalize(&path),
+        execution_id,
+        workflow_id
+    );
+
+    match hook.response {
+        WebhookResponse::Immediate => HttpResponse::Accepted().json(ExecutionStarted {
+            execution_id,
+            status: "running".to_string(),
+        }),
+        WebhookResponse::ResponseNode => {
+            await_response(&data.runtime, &hook.workflow, execution_id).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::App;
+    use flowcore::{NodeSpec, TriggerSpec};
+    use flowpersist::PersistentStore;
+    use flowruntime::{NodeRegistry, RuntimeConfig};
+    use std::sync::Arc;
+
+    const SECRET: &str = "It's a Secret to Everybody";
+    const BODY: &[u8] = br#"{"ref": "refs/heads/main"}"#;
+
+    #[test]
+    fn test_signature_of_the_body_verifies() {
+        let signature = sign(SECRET, BODY);
+        assert!(verify_signature(SECRET, &signature, BODY));
+        // The prefix is optional
+        assert!(verify_signature(SECRET, signature.trim_start_matches("sha256="), BODY));
+    }
+
+    #[test]
+    fn test_bad_signature_rejected() {
+        let signature = sign(SECRET, BODY);
+        assert!(!verify_signature("another secret", &signature, BODY));
+        assert!(!verify_signature(SECRET, "sha256=not-hex", BODY));
+        assert!(!verify_signature(SECRET, "", BODY));
+
+        let mut flipped = hex::decode(signature.trim_start_matches("sha256=")).unwrap();
+        flipped[0] ^= 1;
+        assert!(!verify_signature(SECRET, &hex::encode(flipped), BODY));
+    }
+
+    #[test]
+    fn test_tampered_body_rejected() {
+        let signature = sign(SECRET, BODY);
+        assert!(!verify_signature(SECRET, &signature, br#"{"ref": "refs/heads/evil"}"#));
+        assert!(!verify_signature(SECRET, &signature, &BODY[..BODY.len() - 1]));
+        assert!(!verify_signature(SECRET, &signature, b""));
+    }
+
+    #[actix_web::test]
+    async fn test_signed_hook_requires_its_header() {
+        let mut registry = NodeRegistry::new();
+        flownodes::register_all(&mut registry);
+        let runtime = FlowRuntime::with_registry(Arc::new(registry), RuntimeConfig::default());
+        let mut workflow = Workflow::new("signed");
+        workflow.add_node(NodeSpec::new("debug.log"));
+        workflow.triggers.push(TriggerSpec {
+            id: uuid::Uuid::new_v4(),
+            trigger_type: TriggerType::Webhook {
+                path: "deploy".to_string(),
+                secret: Some(SECRET.to_string()),
+                signature_header: Some("X-Hub-Signature-256".to_string()),
+                response: WebhookResponse::Immediate,
+            },
+            enabled: true,
+        });
+        runtime.register_workflow(workflow).await;
+
+        let state = web::Data::new(AppState {
+            runtime: Arc::new(runtime),
+            store: PersistentStore::in_memory().unwrap(),
+            credentials: None,
+        });
+        let app = init_service(
+            App::new()
+                .app_data(state)
+                .service(web::resource("/hooks/{path:.*}").to(receive)),
+        )
+        .await;
+        let request = || TestRequest::post().uri("/hooks/deploy").set_payload(BODY);
+
+        // No header, or the signature in the default header instead of the
+        // configured one
+        let response = call_service(&app, request().to_request()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let misplaced = request()
+            .insert_header((DEFAULT_SIGNATURE_HEADER, sign(SECRET, BODY)))
+            .to_request();
+        let response = call_service(&app, misplaced).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let signed = request()
+            .insert_header(("X-Hub-Signature-256", sign(SECRET, BODY)))
+            .to_request();
+        let response = call_service(&app, signed).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+    }
+}
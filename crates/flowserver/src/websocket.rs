@@ -0,0 +1,458 @@
+//! WebSocket event stream with a small JSON command protocol
+//!
+//! Without any subscription a connection receives every execution event,
+//! as it always has. Clients can instead subscribe to the executions,
+//! workflows or event types they care about, and start or cancel
+//! executions over the same socket. Every command may carry an `id` that
+//! is echoed back in its `ack` or `error` reply.
+
+use actix_web::{get, web, HttpRequest, HttpResponse, Result as ActixResult};
+use actix_ws::Message;
+use flowcore::{ExecutionEvent, ExecutionId};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::info;
+use uuid::Uuid;
+
+use super::{event_matches, input_value, AppState};
+
+/// Commands sent by the client
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    /// Receive events matching every given criterion
+    Subscribe {
+        id: Option<String>,
+        #[serde(flatten)]
+        filter: EventFilter,
+    },
+    /// Drop one subscription, or all of them if none is given, which sends
+    /// every event again as before the first subscribe
+    Unsubscribe {
+        id: Option<String>,
+        subscription: Option<u64>,
+    },
+    /// Start an execution in the background
+    Start {
+        id: Option<String>,
+        workflow_id: Uuid,
+        #[serde(default)]
+        inputs: HashMap<String, serde_json::Value>,
+        /// Environment whose variables override the workflow's
+        #[serde(default)]
+        env: Option<String>,
+        /// Also subscribe to the new execution's events (default true)
+        #[serde(default = "default_true")]
+        subscribe: bool,
+    },
+    /// Cancel a running execution
+    Cancel {
+        id: Option<String>,
+        execution_id: Uuid,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Replies to client commands
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Ack {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        subscription: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        execution_id: Option<ExecutionId>,
+    },
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        error: String,
+    },
+}
+
+impl ServerMessage {
+    fn ack(id: Option<String>) -> Self {
+        ServerMessage::Ack {
+            id,
+            subscription: None,
+            execution_id: None,
+        }
+    }
+
+    fn error(id: Option<String>, error: impl Into<String>) -> Self {
+        ServerMessage::Error {
+            id,
+            error: error.into(),
+        }
+    }
+}
+
+/// Which events a subscription selects; unset fields match anything
+#[derive(Debug, Default, Deserialize)]
+struct EventFilter {
+    execution_id: Option<ExecutionId>,
+    workflow_id: Option<Uuid>,
+    /// Event type names as accepted by the SSE `types` parameter
+    event_types: Option<HashSet<String>>,
+}
+
+impl EventFilter {
+    fn matches(&self, event: &ExecutionEvent, workflow_id: Option<Uuid>) -> bool {
+        self.execution_id.is_none_or(|id| event.execution_id() == id)
+            && self.workflow_id.is_none_or(|id| workflow_id == Some(id))
+            && self
+                .event_types
+                .as_ref()
+                .is_none_or(|types| event_matches(event, types))
+    }
+}
+
+/// Per-connection state
+struct Session {
+    state: web::Data<AppState>,
+    subscriptions: HashMap<u64, EventFilter>,
+    next_subscription: u64,
+    /// Set by subscribe and cleared by unsubscribing from everything;
+    /// while unset every event is sent
+    filtered: bool,
+    /// Workflow of each execution seen, for workflow filters
+    workflows: HashMap<ExecutionId, Uuid>,
+}
+
+impl Session {
+    fn new(state: web::Data<AppState>) -> Self {
+        Self {
+            state,
+            subscriptions: HashMap::new(),
+            next_subscription: 1,
+            filtered: false,
+            workflows: HashMap::new(),
+        }
+    }
+
+    fn subscribe(&mut self, filter: EventFilter) -> u64 {
+        let subscription = self.next_subscription;
+        self.next_subscription += 1;
+        self.subscriptions.insert(subscription, filter);
+        self.filtered = true;
+        subscription
+    }
+
+    async fn wants(&mut self, event: &ExecutionEvent) -> bool {
+        let execution_id = event.execution_id();
+        if let ExecutionEvent::WorkflowStarted { workflow_id, .. } = event {
+            self.workflows.insert(execution_id, *workflow_id);
+        }
+        if !self.filtered {
+            return true;
+        }
+
+        let mut workflow_id = self.workflows.get(&execution_id).copied();
+        if workflow_id.is_none() && self.subscriptions.values().any(|f| f.workflow_id.is_some()) {
+            // Started before this connection saw its WorkflowStarted
+            workflow_id = self
+                .state
+                .runtime
+                .get_execution_status(execution_id)
+                .await
+                .map(|s| s.workflow_id);
+            if let Some(id) = workflow_id {
+                self.workflows.insert(execution_id, id);
+            }
+        }
+
+        let wanted = self
+            .subscriptions
+            .values()
+            .any(|filter| filter.matches(event, workflow_id));
+        if let ExecutionEvent::WorkflowCompleted { .. } = event {
+            self.workflows.remove(&execution_id);
+        }
+        wanted
+    }
+
+    async fn handle(&mut self, text: &str) -> ServerMessage {
+        let message: ClientMessage = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(e) => return ServerMessage::error(None, format!("Invalid message: {}", e)),
+        };
+
+        match message {
+            ClientMessage::Subscribe { id, filter } => ServerMessage::Ack {
+                id,
+                subscription: Some(self.subscribe(filter)),
+                execution_id: None,
+            },
+            ClientMessage::Unsubscribe { id, subscription } => match subscription {
+                Some(subscription) => match self.subscriptions.remove(&subscription) {
+                    Some(_) => ServerMessage::ack(id),
+                    None => ServerMessage::error(
+                        id,
+                        format!("Unknown subscription {}", subscription),
+                    ),
+                },
+                None => {
+                    self.subscriptions.clear();
+                    self.filtered = false;
+                    ServerMessage::ack(id)
+                }
+            },
+            ClientMessage::Start {
+                id,
+                workflow_id,
+                inputs,
+                env,
+                subscribe,
+            } => {
+                let inputs = inputs
+                    .into_iter()
+                    .map(|(k, v)| (k, input_value(v)))
+                    .collect();
+                match self
+                    .state
+                    .runtime
+                    .start_workflow_in(workflow_id, inputs, env.as_deref())
+                    .await
+                {
+                    Ok(execution_id) => {
+                        info!("Execution {} started over WebSocket", execution_id);
+                        self.workflows.insert(execution_id, workflow_id);
+                        let subscription = subscribe.then(|| {
+                            self.subscribe(EventFilter {
+                                execution_id: Some(execution_id),
+                                ..Default::default()
+                            })
+                        });
+                        ServerMessage::Ack {
+                            id,
+                            subscription,
+                            execution_id: Some(execution_id),
+                        }
+                    }
+                    Err(e) => ServerMessage::error(id, e.to_string()),
+                }
+            }
+            ClientMessage::Cancel { id, execution_id } => {
+                if self.state.runtime.cancel_execution(execution_id).await {
+                    info!("Cancelling execution {} over WebSocket", execution_id);
+                    ServerMessage::Ack {
+                        id,
+                        subscription: None,
+                        execution_id: Some(execution_id),
+                    }
+                } else {
+                    ServerMessage::error(
+                        id,
+                        format!("Execution {} is not running", execution_id),
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// WebSocket endpoint for real-time execution events.
+/// NOTE: not included in OpenAPI spec (WebSocket not modeled by OpenAPI 3.x).
+#[get("/api/events")]
+pub async fn websocket_events(
+    req: HttpRequest,
+    stream: web::Payload,
+    data: web::Data<AppState>,
+) -> ActixResult<HttpResponse> {
+    let (res, mut session, mut msg_stream) = actix_ws::handle(&req, stream)?;
+
+    info!("WebSocket client connected");
+
+    let mut events = data.runtime.subscribe_events();
+    let mut client = Session::new(data);
+
+    actix_web::rt::spawn(async move {
+        loop {
+            tokio::select! {
+                event = events.recv() => {
+                    match event {
+                        Ok(event) => {
+                            if !client.wants(&event).await {
+                                continue;
+                            }
+                            if let Ok(json) = serde_json::to_string(&event) {
+                                if session.text(json).await.is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                }
+
+                Some(Ok(msg)) = msg_stream.recv() => {
+                    match msg {
+                        Message::Text(text) => {
+                            let reply = client.handle(&text).await;
+                            if let Ok(json) = serde_json::to_string(&reply) {
+                                if session.text(json).await.is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                        Message::Ping(bytes) if session.pong(&bytes).await.is_err() => break,
+                        Message::Close(_) => break,
+                        _ => {}
+                    }
+                }
+
+                else => break,
+            }
+        }
+
+        info!("WebSocket client disconnected");
+        let _ = session.close(None).await;
+    });
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use flowcore::{NodeSpec, Workflow};
+    use flowpersist::PersistentStore;
+    use flowruntime::{ExecutionStatus, FlowRuntime, NodeRegistry, RuntimeConfig};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn session() -> Session {
+        let mut registry = NodeRegistry::new();
+        flownodes::register_all(&mut registry);
+        Session::new(web::Data::new(AppState {
+            runtime: Arc::new(FlowRuntime::with_registry(
+                Arc::new(registry),
+                RuntimeConfig::default(),
+            )),
+            store: PersistentStore::in_memory().unwrap(),
+            credentials: None,
+        }))
+    }
+
+    /// The reply as the client sees it
+    async fn send(session: &mut Session, command: serde_json::Value) -> serde_json::Value {
+        serde_json::to_value(session.handle(&command.to_string()).await).unwrap()
+    }
+
+    fn started(execution_id: ExecutionId, workflow_id: Uuid) -> ExecutionEvent {
+        ExecutionEvent::WorkflowStarted {
+            execution_id,
+            workflow_id,
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn paused(execution_id: ExecutionId) -> ExecutionEvent {
+        ExecutionEvent::WorkflowPaused {
+            execution_id,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_malformed_messages_get_an_error() {
+        let mut session = session();
+        for text in [
+            "not json",
+            r#"{"type": "explode", "id": "1"}"#,
+            r#"{"type": "cancel", "id": "2"}"#,
+            r#"{"type": "subscribe", "execution_id": "not-a-uuid"}"#,
+            r#"{"id": "3"}"#,
+        ] {
+            let reply = serde_json::to_value(session.handle(text).await).unwrap();
+            assert_eq!(reply["type"], "error", "{}", text);
+            assert!(reply["error"].as_str().unwrap().starts_with("Invalid message"));
+            assert!(reply.get("id").is_none());
+        }
+
+        // Nothing was subscribed along the way
+        assert!(session.wants(&paused(Uuid::new_v4())).await);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_and_unsubscribe() {
+        let mut session = session();
+        let (watched, other) = (Uuid::new_v4(), Uuid::new_v4());
+        assert!(session.wants(&paused(other)).await);
+
+        let reply = send(&mut session, json!({"type": "subscribe", "id": "a", "execution_id": watched})).await;
+        assert_eq!(reply, json!({"type": "ack", "id": "a", "subscription": 1}));
+        assert!(session.wants(&paused(watched)).await);
+        assert!(!session.wants(&paused(other)).await);
+
+        // Subscriptions add up; each matches on all of its criteria
+        let workflow_id = Uuid::new_v4();
+        let reply = send(
+            &mut session,
+            json!({"type": "subscribe", "workflow_id": workflow_id, "event_types": ["WorkflowStarted"]}),
+        )
+        .await;
+        assert_eq!(reply, json!({"type": "ack", "subscription": 2}));
+        assert!(session.wants(&started(other, workflow_id)).await);
+        assert!(!session.wants(&paused(other)).await);
+        assert!(!session.wants(&started(Uuid::new_v4(), Uuid::new_v4())).await);
+
+        let reply = send(&mut session, json!({"type": "unsubscribe", "id": "b", "subscription": 1})).await;
+        assert_eq!(reply, json!({"type": "ack", "id": "b"}));
+        assert!(!session.wants(&paused(watched)).await);
+        let reply = send(&mut session, json!({"type": "unsubscribe", "id": "c", "subscription": 1})).await;
+        assert_eq!(reply, json!({"type": "error", "id": "c", "error": "Unknown subscription 1"}));
+
+        // Dropping them all sends every event again
+        let reply = send(&mut session, json!({"type": "unsubscribe"})).await;
+        assert_eq!(reply, json!({"type": "ack"}));
+        assert!(session.wants(&paused(other)).await);
+    }
+
+    #[tokio::test]
+    async fn test_start_subscribes_and_cancel_stops() {
+        let mut session = session();
+        let mut workflow = Workflow::new("waits");
+        workflow.add_node(NodeSpec::new("flow.wait_for_signal").with_config("signal", "go"));
+        session.state.runtime.register_workflow(workflow.clone()).await;
+
+        let reply = send(&mut session, json!({"type": "start", "id": "s", "workflow_id": workflow.id})).await;
+        assert_eq!(reply["type"], "ack", "{}", reply);
+        assert_eq!((&reply["id"], &reply["subscription"]), (&json!("s"), &json!(1)));
+        let execution_id: ExecutionId = serde_json::from_value(reply["execution_id"].clone()).unwrap();
+        assert!(session.wants(&paused(execution_id)).await);
+        assert!(!session.wants(&paused(Uuid::new_v4())).await);
+
+        let reply = send(&mut session, json!({"type": "cancel", "id": "x", "execution_id": execution_id})).await;
+        assert_eq!(reply, json!({"type": "ack", "id": "x", "execution_id": execution_id}));
+        let runtime = session.state.runtime.clone();
+        for _ in 0..200 {
+            if runtime.get_execution_status(execution_id).await.unwrap().status.is_finished() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let state = runtime.get_execution_status(execution_id).await.unwrap();
+        assert_eq!(state.status, ExecutionStatus::Cancelled);
+
+        // Finished or unknown executions can't be cancelled, unknown
+        // workflows can't be started
+        let reply = send(&mut session, json!({"type": "cancel", "id": "y", "execution_id": execution_id})).await;
+        assert_eq!(reply["type"], "error");
+        assert_eq!(reply["id"], "y");
+        let reply = send(&mut session, json!({"type": "cancel", "execution_id": Uuid::new_v4()})).await;
+        assert_eq!(reply["type"], "error");
+        let reply = send(
+            &mut session,
+            json!({"type": "start", "id": "z", "workflow_id": Uuid::new_v4(), "subscribe": false}),
+        )
+        .await;
+        assert_eq!((&reply["type"], &reply["id"]), (&json!("error"), &json!("z")));
+    }
+}
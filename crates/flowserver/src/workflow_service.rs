@@ -0,0 +1,193 @@
+//! Workflow create/update logic shared by the REST and gRPC front ends
+//!
+//! Every change is validated against the node registry (and its webhook
+//! paths against the other workflows), stored as a new revision and then
+//! registered with the runtime, in that order, so the runtime never runs a
+//! definition that isn't persisted. Node config may not hold secrets, which
+//! are never persisted.
+
+use flowcore::{Workflow, WorkflowEdit, WorkflowError};
+use flowpersist::StoreError;
+use uuid::Uuid;
+
+use super::{webhooks, AppState};
+
+/// Why a workflow change was rejected
+#[derive(Debug)]
+pub enum SaveError {
+    NotFound(Uuid),
+    /// Create was given the id of a stored workflow
+    Exists(Uuid),
+    Invalid(WorkflowError),
+    Store(StoreError),
+}
+
+impl std::fmt::Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveError::NotFound(id) => write!(f, "Workflow {} not found", id),
+            SaveError::Exists(id) => write!(
+                f,
+                "Workflow {} already exists; update it with PUT or PATCH, \
+                 or give the new one its own id",
+                id
+            ),
+            SaveError::Invalid(e) => write!(f, "{}", e),
+            SaveError::Store(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<StoreError> for SaveError {
+    fn from(e: StoreError) -> Self {
+        SaveError::Store(e)
+    }
+}
+
+impl AppState {
+    /// Store a new workflow as revision 1. Fails if its id is taken, e.g.
+    /// by another named document with the same name and no `id`.
+    pub async fn create_workflow(&self, workflow: Workflow) -> Result<Workflow, SaveError> {
+        let workflow_id = workflow.id;
+        self.commit(workflow, 0).await.map_err(|e| match e {
+            SaveError::Store(StoreError::Conflict { .. }) => SaveError::Exists(workflow_id),
+            e => e,
+        })
+    }
+
+    /// Replace an existing workflow. Without `expected_revision` the update
+    /// still fails if the workflow changes between loading and saving.
+    pub async fn update_workflow(
+        &self,
+        workflow: Workflow,
+        expected_revision: Option<u32>,
+    ) -> Result<Workflow, SaveError> {
+        let current = self.current_workflow(workflow.id).await?;
+        let expected = expected_revision.unwrap_or(current.revision);
+        self.commit(workflow, expected).await
+    }
+
+    /// Apply partial edits to the current revision
+    pub async fn patch_workflow(
+        &self,
+        workflow_id: Uuid,
+        edits: &[WorkflowEdit],
+        expected_revision: Option<u32>,
+    ) -> Result<Workflow, SaveError> {
+        let mut workflow = self.current_workflow(workflow_id).await?;
+        let base = workflow.revision;
+        if let Some(expected) = expected_revision {
+            if expected != base {
+                return Err(StoreError::Conflict {
+                    expected,
+                    actual: base,
+                }
+                .into());
+            }
+        }
+
+        workflow.apply_edits(edits).map_err(SaveError::Invalid)?;
+        self.commit(workflow, base).await
+    }
+
+    async fn current_workflow(&self, workflow_id: Uuid) -> Result<Workflow, SaveError> {
+        self.store
+            .load_workflow(workflow_id)
+            .await?
+            .ok_or(SaveError::NotFound(workflow_id))
+    }
+
+    async fn commit(
+        &self,
+        mut workflow: Workflow,
+        expected_revision: u32,
+    ) -> Result<Workflow, SaveError> {
+        // The store would only keep the redacted placeholder
+        let with_secret = workflow
+            .nodes
+            .iter()
+            .find(|n| n.config.values().any(|v| !v.secrets().is_empty()));
+        if let Some(node) = with_secret {
+            return Err(SaveError::Invalid(WorkflowError::Invalid(format!(
+                "node {} has a secret in its config; stored workflows cannot hold \
+                 secrets, pass them as execution inputs instead",
+                node.name.clone().unwrap_or_else(|| node.id.to_string())
+            ))));
+        }
+        if let Some((name, _)) = workflow
+            .variables
+            .iter()
+            .find(|(_, v)| !v.secrets().is_empty())
+        {
+            return Err(SaveError::Invalid(WorkflowError::Invalid(format!(
+                "variable '{}' is a secret; stored workflows cannot hold secrets, \
+                 refer to a credential with {{\"$credential\": ...}} instead",
+                name
+            ))));
+        }
+        self.runtime
+            .registry()
+            .validate_workflow(&workflow)
+            .map_err(SaveError::Invalid)?;
+        webhooks::check_triggers(&self.runtime, &workflow)
+            .await
+            .map_err(SaveError::Invalid)?;
+
+        workflow.revision = self
+            .store
+            .update_workflow(&workflow, expected_revision)
+            .await?;
+
+        self.runtime.register_workflow(workflow.clone()).await;
+        Ok(workflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flowcore::WorkflowFormat;
+    use flowpersist::PersistentStore;
+    use flowruntime::{FlowRuntime, NodeRegistry, RuntimeConfig};
+    use std::sync::Arc;
+
+    fn state() -> AppState {
+        let mut registry = NodeRegistry::new();
+        flownodes::register_all(&mut registry);
+        AppState {
+            runtime: Arc::new(FlowRuntime::with_registry(
+                Arc::new(registry),
+                RuntimeConfig::default(),
+            )),
+            store: PersistentStore::in_memory().unwrap(),
+            credentials: None,
+        }
+    }
+
+    fn named(document: &str) -> Workflow {
+        Workflow::parse(document, WorkflowFormat::Yaml).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_create_rejects_named_documents_sharing_a_name() {
+        let state = state();
+        let first = named("name: Report\nnodes:\n  log:\n    type: debug.log\n");
+        let second = named("name: Report\nnodes:\n  other:\n    type: debug.log\n");
+        assert_eq!(first.id, second.id);
+
+        state.create_workflow(first.clone()).await.unwrap();
+        match state.create_workflow(second).await {
+            Err(SaveError::Exists(id)) => assert_eq!(id, first.id),
+            other => panic!("expected Exists, got {:?}", other.map(|w| w.id)),
+        }
+        let stored = state.store.load_workflow(first.id).await.unwrap().unwrap();
+        assert_eq!(stored.nodes[0].id, first.nodes[0].id);
+
+        // An explicit id tells them apart
+        let third = named(&format!(
+            "name: Report\nid: {}\nnodes:\n  other:\n    type: debug.log\n",
+            Uuid::new_v4()
+        ));
+        state.create_workflow(third).await.unwrap();
+    }
+}